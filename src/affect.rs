@@ -0,0 +1,81 @@
+//! Cells affected by a move besides the one it was played at, e.g. for animation layers
+//!
+//! Check trait [`Affects`] for more information
+
+/// How a move affected a cell
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Effect {
+    /// A piece of the other player was removed from the board
+    Captured,
+    /// A piece of the other player was turned into a piece of the moving player
+    Flipped,
+    /// A hidden cell was revealed
+    Revealed,
+}
+
+/// A cell affected by a move, see [`Affects`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Affected {
+    pub row: usize,
+    pub col: usize,
+    pub effect: Effect,
+}
+
+/// A game reporting the cells its moves affect
+///
+/// The move methods ending in `_with_effects`, e.g. `Reversi::put_with_effects`, return the cells collected by the move itself, which is the cheaper way to follow a game being played. This trait is the fallback for states obtained otherwise, e.g. loaded or received: like the `diff` and `events` methods of the games, the cells are found by comparing the state right before a move with the state right after it, which scans the whole board and needs the state before the move kept around
+///
+/// Both report the same cells. The placement of a piece by the move itself is not reported, while every cell it captures, flips or reveals is, with captures being [`Effect::Captured`] in any game that removes pieces
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "reversi")]
+/// # fn reversi() {
+/// use gamie::{
+///     affect::{Affected, Affects, Effect},
+///     reversi::Reversi,
+/// };
+///
+/// let before = Reversi::new().unwrap();
+/// let mut after = before.clone();
+/// after.put(2, 4).unwrap();
+///
+/// assert!(before.affected(&after).eq([Affected {
+///     row: 3,
+///     col: 4,
+///     effect: Effect::Flipped,
+/// }]));
+/// # }
+/// ```
+pub trait Affects {
+    /// Iterate over the cells affected from this state to a later one, in row-major order
+    fn affected<'a>(&'a self, after: &'a Self) -> impl Iterator<Item = Affected> + 'a;
+}
+
+/// Iterate over the set bits of a row-major bitboard as the cells affected, in row-major order
+#[cfg(any(feature = "hasami_shogi", feature = "reversi"))]
+pub(crate) fn bitboard_cells(
+    mut bits: u128,
+    width: usize,
+    effect: Effect,
+) -> impl Iterator<Item = Affected> {
+    core::iter::from_fn(move || {
+        if bits == 0 {
+            return None;
+        }
+
+        let idx = bits.trailing_zeros() as usize;
+        bits &= bits - 1;
+
+        Some(Affected {
+            row: idx / width,
+            col: idx % width,
+            effect,
+        })
+    })
+}
@@ -0,0 +1,209 @@
+//! Canonical byte encodings of games, e.g. for cache keys and deduplication
+//!
+//! Check trait [`Canonical`] for more information
+
+#[cfg(any(feature = "minesweeper", feature = "serde"))]
+use alloc::vec::Vec;
+
+/// A game with a canonical byte encoding
+///
+/// Two games of the same kind have the same bytes exactly when they are equal, see the `PartialEq` implementation of each game. Unlike `Debug` or serde, the encoding is independent of features and stable across releases, so the bytes can be stored as cache keys. A change to the encoding of a game comes with a new version of it, and versions are never reused
+///
+/// The bytes start with the name of the game in ASCII, e.g. `tictactoe`, a zero byte, and the version of the encoding, `1` for every game currently. The fields of the game follow in order:
+///
+/// - a `bool` is a byte of `0` or `1`
+/// - an integer is little-endian, with `usize` and `isize` as 8 bytes
+/// - an enum is the index of its variant as a byte, followed by the fields of the variant, and an `Option` is an enum of `None` and `Some`
+/// - an array is its elements, while a `Vec` is its length followed by its elements
+///
+/// Comparing the bytes lexicographically gives a total order of games that is stable across releases
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "tictactoe")]
+/// # fn tictactoe() {
+/// use gamie::{canonical::Canonical, tictactoe::TicTacToe};
+///
+/// let mut game = TicTacToe::new().unwrap();
+/// game.put(1, 1).unwrap();
+///
+/// let mut bytes = Vec::new();
+/// game.write_canonical(&mut bytes);
+///
+/// assert!(bytes.starts_with(b"tictactoe\0\x01"));
+/// # }
+/// ```
+pub trait Canonical {
+    /// Write the canonical bytes of the game to `out`, e.g. a `Vec<u8>`
+    fn write_canonical<E: Extend<u8>>(&self, out: &mut E);
+}
+
+/// A part of a game with a canonical byte encoding, see [`Canonical`]
+// unused without any game
+#[allow(dead_code)]
+pub(crate) trait Encode {
+    fn encode<E: Extend<u8>>(&self, out: &mut E);
+}
+
+/// Write the name of a game and the version of its encoding
+// unused without any game
+#[allow(dead_code)]
+pub(crate) fn header<E: Extend<u8>>(out: &mut E, name: &str, version: u8) {
+    out.extend(name.bytes());
+    out.extend([0, version]);
+}
+
+macro_rules! encode_int {
+    ($($int:ty => $as:ty),*) => {
+        $(
+            impl Encode for $int {
+                fn encode<E: Extend<u8>>(&self, out: &mut E) {
+                    out.extend((*self as $as).to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+encode_int!(u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => u64, i64 => i64, isize => i64);
+
+impl Encode for bool {
+    fn encode<E: Extend<u8>>(&self, out: &mut E) {
+        out.extend([*self as u8]);
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode<E: Extend<u8>>(&self, out: &mut E) {
+        (**self).encode(out);
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode<E: Extend<u8>>(&self, out: &mut E) {
+        match self {
+            None => out.extend([0]),
+            Some(value) => {
+                out.extend([1]);
+                value.encode(out);
+            }
+        }
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode<E: Extend<u8>>(&self, out: &mut E) {
+        self.iter().for_each(|value| value.encode(out));
+    }
+}
+
+#[cfg(any(feature = "minesweeper", feature = "serde"))]
+impl<T: Encode> Encode for Vec<T> {
+    fn encode<E: Extend<u8>>(&self, out: &mut E) {
+        self.len().encode(out);
+        self.iter().for_each(|value| value.encode(out));
+    }
+}
+
+impl<A: Encode, B: Encode> Encode for (A, B) {
+    fn encode<E: Extend<u8>>(&self, out: &mut E) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+}
+
+/// Implement `Encode` for an enum, with the index of each variant and the names given to its fields
+macro_rules! encode_enum {
+    ($enum:ident { $($idx:literal => $variant:ident $(($($field:ident),+))?,)* }) => {
+        impl $crate::canonical::Encode for $enum {
+            fn encode<E: Extend<u8>>(&self, out: &mut E) {
+                match self {
+                    $(
+                        Self::$variant $(($($field),+))? => {
+                            out.extend([$idx]);
+                            $($($crate::canonical::Encode::encode($field, out);)+)?
+                        }
+                    )*
+                }
+            }
+        }
+    };
+}
+
+/// Implement `Encode` for a struct, with its fields in order
+// unused without any game
+#[allow(unused_macros)]
+macro_rules! encode_struct {
+    ($struct:ident { $($field:tt),* $(,)? }) => {
+        impl $crate::canonical::Encode for $struct {
+            fn encode<E: Extend<u8>>(&self, out: &mut E) {
+                $($crate::canonical::Encode::encode(&self.$field, out);)*
+            }
+        }
+    };
+}
+
+/// Implement [`Canonical`] for a game, with its name, the version of its encoding, and its fields in order
+// unused without any game
+#[allow(unused_macros)]
+macro_rules! canonical {
+    ($game:ident, $name:literal, $version:literal { $($field:ident),* $(,)? }) => {
+        $crate::canonical::canonical!([] $game, $name, $version { $($field),* });
+    };
+    ([$($generics:tt)*] $game:ty, $name:literal, $version:literal { $($field:ident),* $(,)? }) => {
+        impl<$($generics)*> $crate::canonical::Canonical for $game {
+            fn write_canonical<E: Extend<u8>>(&self, out: &mut E) {
+                $crate::canonical::header(out, $name, $version);
+                $crate::canonical::Encode::encode(self, out);
+            }
+        }
+
+        // a game within another one is encoded without the header
+        impl<$($generics)*> $crate::canonical::Encode for $game {
+            fn encode<E: Extend<u8>>(&self, out: &mut E) {
+                $($crate::canonical::Encode::encode(&self.$field, out);)*
+            }
+        }
+    };
+}
+
+pub(crate) use encode_enum;
+#[allow(unused_imports)]
+pub(crate) use {canonical, encode_struct};
+
+#[cfg(all(test, feature = "tictactoe", feature = "connect_four"))]
+mod tests {
+    extern crate std;
+
+    use crate::{canonical::*, connect_four::ConnectFour, tictactoe::TicTacToe};
+    use std::vec::Vec;
+
+    fn bytes(game: &impl Canonical) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        game.write_canonical(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn test() {
+        let mut game = TicTacToe::new().unwrap();
+        game.put(0, 2).unwrap();
+
+        let mut expected = b"tictactoe\0\x01".to_vec();
+        // `None`, `None`, `Some(Player0)` and six more `None`
+        expected.extend([0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+        expected.extend(1u64.to_le_bytes());
+        expected.extend([1, 0, 0, 0]);
+        assert_eq!(bytes(&game), expected);
+
+        assert_eq!(
+            bytes(&ConnectFour::from_moves(&[0, 1, 2, 3]).unwrap()),
+            bytes(&ConnectFour::from_moves(&[2, 3, 0, 1]).unwrap())
+        );
+        assert!(
+            bytes(&ConnectFour::from_moves(&[0, 1, 2, 3]).unwrap())
+                < bytes(&ConnectFour::from_moves(&[1, 0, 2, 3]).unwrap())
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Stable machine-readable codes of errors and statuses, e.g. for frontends to look up localized messages
+//!
+//! Check trait [`Code`] for more information
+
+/// A value with a stable machine-readable code
+///
+/// A code is the name of an enum variant in `snake_case`, e.g. `"position_occupied"` for `PositionOccupied`. Codes never change between versions with the variants they name, while the English messages of `Display` may, so frontends should match codes rather than messages
+///
+/// Data carried by a variant is not part of its code, e.g. the winner of `Status::Win(..)` is read from the status itself. The [`Error`](crate::Error) of the crate has the code of the module error it wraps
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "tictactoe")]
+/// # fn tictactoe() {
+/// use gamie::{code::Code, tictactoe::TicTacToe, Error};
+///
+/// let mut game = TicTacToe::new().unwrap();
+/// game.put(1, 1).unwrap();
+///
+/// let err: Error = game.put(1, 1).unwrap_err().into();
+///
+/// let message = match err.code() {
+///     "position_occupied" => "Cette case est déjà prise",
+///     _ => "Coup invalide",
+/// };
+///
+/// assert_eq!(message, "Cette case est déjà prise");
+/// assert_eq!(game.status().code(), "ongoing");
+/// # }
+/// ```
+pub trait Code {
+    /// Get the code
+    fn code(&self) -> &'static str;
+}
+
+/// Implement [`Code`] for an enum, naming the code of each variant
+///
+/// With the `ufmt` feature, `ufmt::uDisplay` is implemented as well, writing the code
+macro_rules! codes {
+    ($enum:ident { $($variant:ident => $code:literal,)* }) => {
+        impl $crate::code::Code for $enum {
+            fn code(&self) -> &'static str {
+                match self {
+                    $(Self::$variant { .. } => $code,)*
+                }
+            }
+        }
+
+        #[cfg(feature = "ufmt")]
+        impl ufmt::uDisplay for $enum {
+            fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+            where
+                W: ufmt::uWrite + ?Sized,
+            {
+                f.write_str($crate::code::Code::code(self))
+            }
+        }
+    };
+}
+
+pub(crate) use codes;
@@ -0,0 +1,639 @@
+//! Connect6
+//!
+//! Check struct [`Connect6`] for more information
+
+use crate::{
+    invariant::InvariantError,
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use core::{
+    convert::Infallible,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+use snafu::Snafu;
+
+pub(crate) const BOARD_WIDTH: usize = 19;
+pub(crate) const BOARD_HEIGHT: usize = 19;
+
+/// Connect6
+///
+/// The first player places one stone on the first turn, after that each player places two stones per turn. Six or more stones of a player in a row win the game
+///
+/// Stones can be placed one at a time with [`Connect6::put`], the turn passes once the player has placed all stones of the turn. A whole turn can also be applied at once with [`Connect6::apply`]
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::connect6::{Connect6, Move, Player};
+/// let mut game = Connect6::new().unwrap();
+///
+/// game.put(9, 9).unwrap();
+/// assert_eq!(game.next_player(), Player::Player1);
+///
+/// game.put(8, 8).unwrap();
+/// assert_eq!(game.stones_left(), 1);
+/// game.put(8, 10).unwrap();
+///
+/// game.apply(Move::Double {
+///     first: (10, 8),
+///     second: (10, 10),
+/// })
+/// .unwrap();
+/// assert_eq!(game.next_player(), Player::Player1);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Connect6 {
+    board: [[Option<Player>; BOARD_WIDTH]; BOARD_HEIGHT],
+    move_count: usize,
+    stones_left: usize,
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(Connect6);
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::canonical!(Connect6, "connect6", 1 {
+    board,
+    move_count,
+    stones_left,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+});
+
+/// A whole turn, each position being `(row, col)`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Move {
+    /// Place one stone, only on the first turn or when one stone of the turn is left
+    Single(usize, usize),
+    /// Place two stones
+    Double {
+        first: (usize, usize),
+        second: (usize, usize),
+    },
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    /// Positions are always in bounds
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let is_single = u.arbitrary()?;
+
+        let mut position = || -> arbitrary::Result<(usize, usize)> {
+            Ok((u.choose_index(BOARD_HEIGHT)?, u.choose_index(BOARD_WIDTH)?))
+        };
+
+        if is_single {
+            let (row, col) = position()?;
+            Ok(Self::Single(row, col))
+        } else {
+            Ok(Self::Double {
+                first: position()?,
+                second: position()?,
+            })
+        }
+    }
+}
+
+/// Errors that can occur when placing a stone onto the board
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Connect6Error {
+    #[snafu(display("position occupied"))]
+    PositionOccupied,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("position out of bounds"))]
+    OutOfBounds,
+    #[snafu(display("wrong number of stones for the turn"))]
+    WrongStoneCount,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+}
+
+crate::code::codes!(Connect6Error {
+    PositionOccupied => "position_occupied",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+    WrongStoneCount => "wrong_stone_count",
+    NoDrawOffer => "no_draw_offer",
+});
+
+impl Connect6 {
+    /// Create a new Connect6 game
+    pub const fn new() -> Result<Self, Infallible> {
+        Ok(Self {
+            board: [[None; BOARD_WIDTH]; BOARD_HEIGHT],
+            move_count: 0,
+            stones_left: 1,
+            next_player: Player::Player0,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+        })
+    }
+
+    /// Create a Connect6 game by replaying a list of stones from an empty board, one stone per entry
+    ///
+    /// Return the index of the first invalid stone and the error it caused on failure
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::connect6::{Connect6, Player};
+    /// let game = Connect6::from_moves(&[(9, 9), (8, 8), (8, 10)]).unwrap();
+    ///
+    /// assert_eq!(game.next_player(), Player::Player0);
+    /// assert!(Connect6::from_moves(&[(9, 9), (9, 9)]).is_err());
+    /// ```
+    pub fn from_moves(moves: &[(usize, usize)]) -> Result<Self, (usize, Connect6Error)> {
+        let mut game = Self::new().unwrap();
+
+        for (idx, (row, col)) in moves.iter().enumerate() {
+            if *row >= BOARD_HEIGHT || *col >= BOARD_WIDTH {
+                return Err((idx, Connect6Error::OutOfBounds));
+            }
+
+            game.put(*row, *col).map_err(|err| (idx, err))?;
+        }
+
+        Ok(game)
+    }
+
+    /// Get a stone at a position
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Option<Player> {
+        self.board[row][col]
+    }
+
+    /// Get a stone at a position without bounds checking, for the hot loops of a search that keep the positions in bounds already
+    ///
+    /// The bounds are still checked in debug builds
+    ///
+    /// # Safety
+    ///
+    /// The target position must be in bounds, see [`get`](Self::get)
+    pub const unsafe fn get_unchecked(&self, row: usize, col: usize) -> Option<Player> {
+        debug_assert!(row < self.board.len() && col < self.board[0].len());
+
+        // SAFETY: the caller keeps the position in bounds
+        unsafe { *(*self.board.as_ptr().add(row)).as_ptr().add(col) }
+    }
+
+    /// Place one stone of the next player
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn put(&mut self, row: usize, col: usize) -> Result<(), Connect6Error> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(Connect6Error::GameEnded);
+        }
+
+        if self.board[row][col].is_some() {
+            return Err(Connect6Error::PositionOccupied);
+        }
+
+        self.draw_offer = None;
+        self.board[row][col] = Some(self.next_player);
+        self.move_count += 1;
+        self.stones_left -= 1;
+
+        if self.is_six(row, col) {
+            self.status = Status::Win(self.next_player);
+        } else if self.move_count == BOARD_HEIGHT * BOARD_WIDTH {
+            self.status = Status::Draw;
+        }
+
+        if self.stones_left == 0 {
+            self.next_player = self.next_player.other();
+            self.stones_left = 2;
+        }
+
+        Ok(())
+    }
+
+    /// Check if a stone can be put at a position, same as [`put`](Self::put) succeeding but without changing the game
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn can_put(&self, row: usize, col: usize) -> bool {
+        !matches!(self.status, Status::Win(_) | Status::Draw) && self.board[row][col].is_none()
+    }
+
+    /// Apply a whole turn
+    ///
+    /// The stones of the move must match [`Connect6::stones_left`], and all positions are checked before any stone is placed. If the first stone wins the game, the second one is not placed
+    ///
+    /// Panic if a target position is out of bounds
+    pub fn apply(&mut self, mv: Move) -> Result<(), Connect6Error> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(Connect6Error::GameEnded);
+        }
+
+        match mv {
+            Move::Single(row, col) => {
+                if self.stones_left != 1 {
+                    return Err(Connect6Error::WrongStoneCount);
+                }
+
+                self.put(row, col)
+            }
+            Move::Double { first, second } => {
+                if self.stones_left != 2 {
+                    return Err(Connect6Error::WrongStoneCount);
+                }
+
+                if first == second
+                    || self.get(first.0, first.1).is_some()
+                    || self.get(second.0, second.1).is_some()
+                {
+                    return Err(Connect6Error::PositionOccupied);
+                }
+
+                self.put(first.0, first.1)?;
+
+                if self.status == Status::Ongoing {
+                    self.put(second.0, second.1)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    ///
+    /// The game holds no allocation, so this is a single copy of the game followed by [`apply`](Self::apply)
+    ///
+    /// Panic if a target position is out of bounds
+    pub fn preview(&self, mv: Move) -> Result<Self, Connect6Error> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, one or two positions as described in [`parse`](crate::parse), e.g. `9 9` or `9 10, 10 9`
+    pub fn parse_move(text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+        let first = words.position(BOARD_HEIGHT, BOARD_WIDTH)?;
+
+        let mv = if words.is_empty() {
+            Move::Single(first.0, first.1)
+        } else {
+            let second = words.position(BOARD_HEIGHT, BOARD_WIDTH)?;
+            Move::Double { first, second }
+        };
+
+        words.finish()?;
+        Ok(mv)
+    }
+
+    /// Get the number of stones the next player has yet to place in the turn
+    pub const fn stones_left(&self) -> usize {
+        self.stones_left
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The stone count must match the move count, which in turn decides the next player and the stones left of the turn, and a status ended by the rules must match the board
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let stones = self
+            .board
+            .iter()
+            .flatten()
+            .filter(|cell| cell.is_some())
+            .count();
+
+        if stones != self.move_count {
+            return Err(InvariantError::Counter);
+        }
+
+        // Player0 places the first stone alone, then each turn places two
+        let (next_player, stones_left) = match self.move_count {
+            0 => (Player::Player0, 1),
+            count => {
+                let turn = (count - 1) / 2 + 1;
+                let player = if turn % 2 == 1 {
+                    Player::Player1
+                } else {
+                    Player::Player0
+                };
+
+                (player, 2 - (count - 1) % 2)
+            }
+        };
+
+        // every stone placed counts down the turn, even the one ending the game
+        if (next_player, stones_left) != (self.next_player, self.stones_left) {
+            return Err(InvariantError::NextPlayer);
+        }
+
+        let has_six = |player| {
+            (0..BOARD_HEIGHT)
+                .flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+                .any(|(row, col)| self.board[row][col] == Some(player) && self.is_six(row, col))
+        };
+        let has_no_six = !has_six(Player::Player0) && !has_six(Player::Player1);
+        let is_full = self.move_count == BOARD_HEIGHT * BOARD_WIDTH;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => has_no_six && !is_full,
+            (Status::Ongoing, _) => false,
+            (Status::Win(player), EndReason::Normal) => {
+                has_six(*player) && !has_six(player.other())
+            }
+            (Status::Draw, EndReason::Normal) => has_no_six && is_full,
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), Connect6Error> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), Connect6Error> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), Connect6Error> {
+        if self.status != Status::Ongoing {
+            return Err(Connect6Error::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), Connect6Error> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(Connect6Error::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), Connect6Error> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), Connect6Error> {
+        if self.status != Status::Ongoing {
+            return Err(Connect6Error::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Check if the stone at a position is part of six or more stones in a row
+    fn is_six(&self, row: usize, col: usize) -> bool {
+        let player = self.board[row][col];
+
+        let count = |row_step: isize, col_step: isize| {
+            (1..)
+                .map(|distance| {
+                    (
+                        row as isize + row_step * distance,
+                        col as isize + col_step * distance,
+                    )
+                })
+                .take_while(|(row, col)| {
+                    (0..BOARD_HEIGHT as isize).contains(row)
+                        && (0..BOARD_WIDTH as isize).contains(col)
+                        && self.get(*row as usize, *col as usize) == player
+                })
+                .count()
+        };
+
+        [(0, 1), (1, 0), (1, 1), (1, -1)]
+            .into_iter()
+            .any(|(row_step, col_step)| {
+                count(row_step, col_step) + count(-row_step, -col_step) + 1 >= 6
+            })
+    }
+}
+
+impl Player {
+    /// Get the other player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match *self {
+            Self::Single(row, col) => write!(f, "{row} {col}"),
+            Self::Double {
+                first: (first_row, first_col),
+                second: (second_row, second_col),
+            } => write!(f, "{first_row} {first_col}, {second_row} {second_col}"),
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Connect6 {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    pub fn random_position<R: GameRng + ?Sized>(rng: &mut R, plies: usize) -> Self {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        game.play_random_position(rng, plies);
+        game
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for Connect6 {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let empties = || {
+            testing::positions(BOARD_HEIGHT, BOARD_WIDTH)
+                .filter(|&(row, col)| self.can_put(row, col))
+        };
+
+        let first = testing::choose(rng, empties())?;
+
+        if self.stones_left == 1 {
+            return Some(Move::Single(first.0, first.1));
+        }
+
+        // the second stone is picked among the other empty positions, so each pair is equally likely
+        testing::choose(rng, empties().filter(|position| *position != first))
+            .map(|second| Move::Double { first, second })
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::connect6::*;
+
+    #[test]
+    fn test() {
+        let mut game = Connect6::new().unwrap();
+
+        assert_eq!(
+            game.apply(Move::Double {
+                first: (0, 0),
+                second: (0, 1),
+            }),
+            Err(Connect6Error::WrongStoneCount)
+        );
+
+        game.apply(Move::Single(0, 0)).unwrap();
+
+        assert_eq!(
+            game.apply(Move::Double {
+                first: (0, 0),
+                second: (1, 1),
+            }),
+            Err(Connect6Error::PositionOccupied)
+        );
+        assert_eq!(game.get(1, 1), None);
+
+        for col in [1, 3] {
+            game.apply(Move::Double {
+                first: (5, col),
+                second: (7, col),
+            })
+            .unwrap();
+            game.apply(Move::Double {
+                first: (0, col),
+                second: (0, col + 1),
+            })
+            .unwrap();
+        }
+
+        game.apply(Move::Double {
+            first: (9, 1),
+            second: (9, 3),
+        })
+        .unwrap();
+
+        // the first stone connects six, so the second one is not placed
+        game.apply(Move::Double {
+            first: (0, 5),
+            second: (18, 18),
+        })
+        .unwrap();
+
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+        assert_eq!(game.get(18, 18), None);
+        assert_eq!(game.put(18, 18), Err(Connect6Error::GameEnded));
+    }
+}
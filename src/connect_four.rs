@@ -2,14 +2,31 @@
 //!
 //! Check struct [`ConnectFour`] for more information
 
+use crate::{
+    invariant::{self, InvariantError},
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+    util::{self, KInARow, LINES},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
 use core::{
     convert::Infallible,
-    fmt::{Debug, Formatter, Result as FmtResult},
+    fmt::{Debug, Display, Formatter, Result as FmtResult},
+    hash::{Hash, Hasher},
 };
 use snafu::Snafu;
 
-const BOARD_WIDTH: usize = 7;
-const BOARD_HEIGHT: usize = 6;
+pub(crate) const BOARD_WIDTH: usize = 7;
+pub(crate) const BOARD_HEIGHT: usize = 6;
+
+// the number of pieces in a row a player wins with
+const LINE_LENGTH: usize = 4;
+
+const CENTER_OUT_COLUMNS: [usize; BOARD_WIDTH] = [3, 2, 4, 1, 5, 0, 6];
 
 /// Connect Four
 ///
@@ -22,52 +39,192 @@ const BOARD_HEIGHT: usize = 6;
 /// game.put(2).unwrap();
 /// // ...
 /// ```
-#[derive(Clone)]
+#[derive(Clone, Eq, Hash, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct ConnectFour {
     columns: [Column; BOARD_WIDTH],
     move_count: usize,
     next_player: Player,
     status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+    is_flip_allowed: bool,
+    is_swapped: bool,
 }
 
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(ConnectFour);
+
 /// Player
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
 pub enum Player {
     Player0,
     Player1,
 }
 
 /// Game status
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
 pub enum Status {
     Ongoing,
+    /// The board is full, both players connect four by a flip, or the game was drawn otherwise, see [`ConnectFour::end_reason`]
+    ///
+    /// A piece can be put in every column not yet filled, so a full board is the only way the next player runs out of moves. Flipping does not count, as it never fills a position
     Draw,
     Win(Player),
 }
 
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::canonical!(ConnectFour, "connect_four", 1 {
+    columns,
+    move_count,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+    is_flip_allowed,
+    is_swapped,
+});
+
+/// A move
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Move {
+    /// Put a piece in a column
+    Put(usize),
+    /// Flip the board upside down, only allowed in the flip variant, see [`ConnectFour::with_flip`]
+    Flip,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    /// Positions are always in bounds
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // one choice for each column, and one for flipping
+        match u.choose_index(BOARD_WIDTH + 1)? {
+            BOARD_WIDTH => Ok(Self::Flip),
+            col => Ok(Self::Put(col)),
+        }
+    }
+}
+
+/// The change of a position between two game states
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CellDiff {
+    pub before: Option<Player>,
+    pub after: Option<Player>,
+}
+
+/// Differences between two game states, see [`ConnectFour::diff`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BoardDiff {
+    /// The change of each position, `None` if unchanged
+    pub cells: [[Option<CellDiff>; BOARD_WIDTH]; BOARD_HEIGHT],
+    /// The next player before and after, `None` if unchanged
+    pub next_player: Option<(Player, Player)>,
+    /// The game status before and after, `None` if unchanged
+    pub status: Option<(Status, Status)>,
+}
+
 /// Errors that can occur when putting a piece onto the board
 #[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
 pub enum ConnectFourError {
     #[snafu(display("column filled"))]
     ColumnFilled,
     #[snafu(display("game ended"))]
     GameEnded,
+    #[snafu(display("column out of bounds"))]
+    OutOfBounds,
+    #[snafu(display("flip not allowed"))]
+    FlipNotAllowed,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+    #[snafu(display("swap not allowed"))]
+    SwapNotAllowed,
 }
 
+crate::code::codes!(ConnectFourError {
+    ColumnFilled => "column_filled",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+    FlipNotAllowed => "flip_not_allowed",
+    NoDrawOffer => "no_draw_offer",
+    SwapNotAllowed => "swap_not_allowed",
+});
+
 /// Column in the board
 ///
 /// Since pieces are placed from the bottom, the column is represented as a grow-only stack
 /// `Option<Player>` is not needed since we are tracking the number of filled cells
 #[derive(Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 struct Column {
     cells: [Player; BOARD_HEIGHT],
     filled: usize,
 }
 
+// the cells above the filled ones are left over and do not tell columns apart
+impl PartialEq for Column {
+    fn eq(&self, other: &Self) -> bool {
+        self.filled == other.filled && self.pieces() == other.pieces()
+    }
+}
+
+impl Eq for Column {}
+
+impl Hash for Column {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.filled.hash(state);
+        self.pieces().hash(state);
+    }
+}
+
+impl Column {
+    /// Get the filled cells, from the top piece to the bottom
+    fn pieces(&self) -> &[Player] {
+        &self.cells[BOARD_HEIGHT.saturating_sub(self.filled)..]
+    }
+}
+
+impl crate::canonical::Encode for Column {
+    fn encode<E: Extend<u8>>(&self, out: &mut E) {
+        self.filled.encode(out);
+        self.pieces().iter().for_each(|piece| piece.encode(out));
+    }
+}
+
 struct LastMove {
     player: Player,
     row: usize,
@@ -77,7 +234,21 @@ struct LastMove {
 impl ConnectFour {
     /// Create a new Connect Four game
     pub const fn new() -> Result<Self, Infallible> {
-        Ok(Self {
+        Ok(Self::initial())
+    }
+
+    /// Create a new Connect Four game, the same as [`new`](Self::new) without the `Result`
+    pub const fn initial() -> Self {
+        Self::with_flip_allowed(false)
+    }
+
+    /// Create a new Connect Four game of the flip variant, where a player may spend a turn flipping the board upside down with [`ConnectFour::flip`]
+    pub const fn with_flip() -> Result<Self, Infallible> {
+        Ok(Self::with_flip_allowed(true))
+    }
+
+    const fn with_flip_allowed(is_flip_allowed: bool) -> Self {
+        Self {
             columns: [Column {
                 cells: [Player::Player0; BOARD_HEIGHT],
                 filled: 0,
@@ -85,7 +256,112 @@ impl ConnectFour {
             move_count: 0,
             next_player: Player::Player0,
             status: Status::Ongoing,
-        })
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+            is_flip_allowed,
+            is_swapped: false,
+        }
+    }
+
+    /// Create a Connect Four game by replaying a list of moves from an empty board, each move being the column to put a piece in
+    ///
+    /// Return the index of the first invalid move and the error it caused on failure
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::connect_four::{ConnectFour, Player};
+    /// let game = ConnectFour::from_moves(&[3, 2]).unwrap();
+    ///
+    /// assert_eq!(game.next_player(), Player::Player0);
+    /// assert!(ConnectFour::from_moves(&[3, 7]).is_err());
+    /// ```
+    pub const fn from_moves(moves: &[usize]) -> Result<Self, (usize, ConnectFourError)> {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        let mut idx = 0;
+
+        while idx < moves.len() {
+            let col = moves[idx];
+
+            if col >= BOARD_WIDTH {
+                return Err((idx, ConnectFourError::OutOfBounds));
+            }
+
+            if let Err(err) = game.put(col) {
+                return Err((idx, err));
+            }
+
+            idx += 1;
+        }
+
+        Ok(game)
+    }
+
+    /// Compose a Connect Four game from a board and the next player, e.g. for a hand-authored puzzle
+    ///
+    /// Row `0` is the top of the board, and the pieces of each column must rest on the bottom or another piece. The status is read from the board
+    ///
+    /// Return `Err(InvariantError::Board)` for a floating piece, or the error of [`validate`](Self::validate) if the position could not have been reached by playing
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::{connect_four::{ConnectFour, Player}, invariant::InvariantError};
+    /// let mut board = [[None; 7]; 6];
+    /// board[5][3] = Some(Player::Player0);
+    /// board[4][3] = Some(Player::Player1);
+    ///
+    /// let game = ConnectFour::from_board(board, Player::Player0).unwrap();
+    /// assert_eq!(game.get(4, 3), Some(Player::Player1));
+    ///
+    /// board[5][3] = None;
+    /// assert_eq!(
+    ///     ConnectFour::from_board(board, Player::Player0).unwrap_err(),
+    ///     InvariantError::Board
+    /// );
+    /// ```
+    pub fn from_board(
+        board: [[Option<Player>; BOARD_WIDTH]; BOARD_HEIGHT],
+        next_player: Player,
+    ) -> Result<Self, InvariantError> {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        for (col, column) in game.columns.iter_mut().enumerate() {
+            for row in (0..BOARD_HEIGHT).rev() {
+                match board[row][col] {
+                    Some(player) if column.filled == BOARD_HEIGHT - 1 - row => {
+                        column.cells[row] = player;
+                        column.filled += 1;
+                    }
+                    Some(_) => return Err(InvariantError::Board),
+                    None => {}
+                }
+            }
+
+            game.move_count += column.filled;
+        }
+
+        game.next_player = next_player;
+
+        game.status = if game.connects_four(Player::Player0) {
+            Status::Win(Player::Player0)
+        } else if game.connects_four(Player::Player1) {
+            Status::Win(Player::Player1)
+        } else if game.move_count == BOARD_HEIGHT * BOARD_WIDTH {
+            Status::Draw
+        } else {
+            Status::Ongoing
+        };
+
+        game.validate()?;
+        Ok(game)
     }
 
     /// Get a piece at a position
@@ -101,10 +377,31 @@ impl ConnectFour {
         }
     }
 
+    /// Get a piece at a position without bounds checking, for the hot loops of a search that keep the positions in bounds already
+    ///
+    /// The bounds are still checked in debug builds
+    ///
+    /// # Safety
+    ///
+    /// The target position must be in bounds, see [`get`](Self::get)
+    pub const unsafe fn get_unchecked(&self, row: usize, col: usize) -> Option<Player> {
+        debug_assert!(row < BOARD_HEIGHT && col < BOARD_WIDTH);
+
+        // SAFETY: the caller keeps the position in bounds
+        let column = unsafe { &*self.columns.as_ptr().add(col) };
+
+        if row >= BOARD_HEIGHT - column.filled {
+            // SAFETY: same as above
+            Some(unsafe { *column.cells.as_ptr().add(row) })
+        } else {
+            None
+        }
+    }
+
     /// Put a piece
     ///
     /// Panic if the target position is out of bounds
-    pub fn put(&mut self, col: usize) -> Result<(), ConnectFourError> {
+    pub const fn put(&mut self, col: usize) -> Result<(), ConnectFourError> {
         if matches!(self.status, Status::Win(_) | Status::Draw) {
             return Err(ConnectFourError::GameEnded);
         }
@@ -127,6 +424,7 @@ impl ConnectFour {
         };
 
         self.move_count += 1;
+        self.draw_offer = None;
         self.next_player = self.next_player.other();
 
         self.update_status(last_move);
@@ -134,6 +432,290 @@ impl ConnectFour {
         Ok(())
     }
 
+    /// Flip the board upside down, spending the turn of the next player
+    ///
+    /// All pieces fall to the other side, reversing their order in each column. Both players are then checked for four in a row: the game is won if only one of them connects four, and drawn if both do
+    ///
+    /// Only allowed in the flip variant, see [`ConnectFour::with_flip`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::connect_four::{ConnectFour, Player};
+    /// let mut game = ConnectFour::with_flip().unwrap();
+    /// game.put(3).unwrap();
+    /// game.put(3).unwrap();
+    ///
+    /// game.flip().unwrap();
+    ///
+    /// assert_eq!(game.get(5, 3), Some(Player::Player1));
+    /// assert_eq!(game.next_player(), Player::Player1);
+    /// ```
+    pub fn flip(&mut self) -> Result<(), ConnectFourError> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(ConnectFourError::GameEnded);
+        }
+
+        if !self.is_flip_allowed {
+            return Err(ConnectFourError::FlipNotAllowed);
+        }
+
+        for column in &mut self.columns {
+            column.cells[BOARD_HEIGHT - column.filled..].reverse();
+        }
+
+        self.draw_offer = None;
+        self.next_player = self.next_player.other();
+
+        self.status = match (
+            self.connects_four(Player::Player0),
+            self.connects_four(Player::Player1),
+        ) {
+            (true, true) => Status::Draw,
+            (true, false) => Status::Win(Player::Player0),
+            (false, true) => Status::Win(Player::Player1),
+            (false, false) => Status::Ongoing,
+        };
+
+        Ok(())
+    }
+
+    /// Check if a piece can be put into a column, same as [`put`](Self::put) succeeding but without changing the game
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn can_put(&self, col: usize) -> bool {
+        !matches!(self.status, Status::Win(_) | Status::Draw)
+            && self.columns[col].filled < BOARD_HEIGHT
+    }
+
+    /// Check if the board can be flipped, same as [`flip`](Self::flip) succeeding but without changing the game
+    pub const fn can_flip(&self) -> bool {
+        !matches!(self.status, Status::Win(_) | Status::Draw) && self.is_flip_allowed
+    }
+
+    /// Apply a move
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn apply(&mut self, mv: Move) -> Result<(), ConnectFourError> {
+        match mv {
+            Move::Put(col) => self.put(col),
+            Move::Flip => self.flip(),
+        }
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    ///
+    /// The game holds no allocation, so this is a single copy of the game followed by [`apply`](Self::apply)
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn preview(&self, mv: Move) -> Result<Self, ConnectFourError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, a column optionally after `col`, or `flip`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::connect_four::{ConnectFour, Move};
+    /// assert_eq!(ConnectFour::parse_move("col 4"), Ok(Move::Put(4)));
+    /// assert_eq!(ConnectFour::parse_move("Flip"), Ok(Move::Flip));
+    ///
+    /// assert_eq!(Move::Put(4).to_string(), "4");
+    /// ```
+    pub fn parse_move(text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+
+        let mv = if words.take("flip") {
+            Move::Flip
+        } else {
+            let _ = words.take("col") || words.take("column");
+            Move::Put(words.number(BOARD_WIDTH)?)
+        };
+
+        words.finish()?;
+        Ok(mv)
+    }
+
+    /// Check if the game is of the flip variant, see [`ConnectFour::with_flip`]
+    pub const fn is_flip_allowed(&self) -> bool {
+        self.is_flip_allowed
+    }
+
+    /// Swap sides by the pie rule, only allowed right after the first move
+    ///
+    /// [`Player::Player1`] takes over the first piece, and [`Player::Player0`] moves next
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::connect_four::{ConnectFour, Player};
+    /// let mut game = ConnectFour::new().unwrap();
+    /// game.put(3).unwrap();
+    ///
+    /// game.swap().unwrap();
+    ///
+    /// assert_eq!(game.get(5, 3), Some(Player::Player1));
+    /// assert_eq!(game.next_player(), Player::Player0);
+    /// ```
+    pub fn swap(&mut self) -> Result<(), ConnectFourError> {
+        if self.status != Status::Ongoing {
+            return Err(ConnectFourError::GameEnded);
+        }
+
+        if self.move_count != 1 || self.is_swapped {
+            return Err(ConnectFourError::SwapNotAllowed);
+        }
+
+        for column in &mut self.columns {
+            for cell in &mut column.cells[BOARD_HEIGHT - column.filled..] {
+                *cell = cell.other();
+            }
+        }
+
+        self.next_player = self.next_player.other();
+        self.draw_offer = None;
+        self.is_swapped = true;
+
+        Ok(())
+    }
+
+    /// Check if the sides were swapped by the pie rule, see [`swap`](Self::swap)
+    pub const fn is_swapped(&self) -> bool {
+        self.is_swapped
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// No column may be filled beyond the board, the piece counts must match the move count, and a status ended by the rules must match the board. Flipping spends a turn without a piece, so the turns are only checked when flipping is not allowed
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        if self
+            .columns
+            .iter()
+            .any(|column| column.filled > BOARD_HEIGHT)
+        {
+            return Err(InvariantError::Board);
+        }
+
+        let count = |player| {
+            self.columns
+                .iter()
+                .flat_map(|column| &column.cells[BOARD_HEIGHT - column.filled..])
+                .filter(|cell| **cell == player)
+                .count()
+        };
+
+        let (player0_count, player1_count) = (count(Player::Player0), count(Player::Player1));
+
+        if player0_count + player1_count != self.move_count
+            || self.is_swapped && self.move_count == 0
+        {
+            return Err(InvariantError::Counter);
+        }
+
+        // after a swap, Player1 owns the first piece
+        let (first_count, second_count, first_player) = if self.is_swapped {
+            (player1_count, player0_count, Player::Player1)
+        } else {
+            (player0_count, player1_count, Player::Player0)
+        };
+
+        if !self.is_flip_allowed {
+            invariant::check_turns(first_count, second_count, self.next_player == first_player)?;
+        }
+
+        let has_line = |player| self.connects_four(player);
+        let has_no_line = !has_line(Player::Player0) && !has_line(Player::Player1);
+        let is_full = self.move_count == BOARD_HEIGHT * BOARD_WIDTH;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            // a full board can still be flipped
+            (Status::Ongoing, EndReason::Normal) => {
+                has_no_line && (!is_full || self.is_flip_allowed)
+            }
+            (Status::Ongoing, _) => false,
+            (Status::Win(player), EndReason::Normal) => {
+                has_line(*player) && !has_line(player.other())
+            }
+            (Status::Draw, EndReason::Normal) => {
+                has_no_line && is_full
+                    || self.is_flip_allowed
+                        && has_line(Player::Player0)
+                        && has_line(Player::Player1)
+            }
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), ConnectFourError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), ConnectFourError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), ConnectFourError> {
+        if self.status != Status::Ongoing {
+            return Err(ConnectFourError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), ConnectFourError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(ConnectFourError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), ConnectFourError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
     /// Get the next player
     pub const fn next_player(&self) -> Player {
         self.next_player
@@ -144,63 +726,180 @@ impl ConnectFour {
         &self.status
     }
 
-    fn update_status(&mut self, last_move: LastMove) {
-        // to determine if the game is ended by the last move, 7 positions centered at the last move are checked on each direction
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), ConnectFourError> {
+        if self.status != Status::Ongoing {
+            return Err(ConnectFourError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Get the columns where the next player wins immediately by putting a piece
+    pub fn winning_columns(&self) -> impl Iterator<Item = usize> + '_ {
+        self.playable_columns()
+            .filter(|col| self.is_winning_put(*col, self.next_player))
+    }
 
-        let checking_row_range =
-            last_move.row.saturating_sub(3)..=(last_move.row + 3).min(BOARD_HEIGHT - 1);
-        let checking_col_range =
-            last_move.col.saturating_sub(3)..=(last_move.col + 3).min(BOARD_WIDTH - 1);
-        let mut continuous_player_pieces = 0;
+    /// Get the columns the next player must block, where the other player would win immediately by putting a piece
+    pub fn losing_if_ignored(&self) -> impl Iterator<Item = usize> + '_ {
+        self.playable_columns()
+            .filter(|col| self.is_winning_put(*col, self.next_player.other()))
+    }
 
-        // horizontal
-        for col in checking_col_range.clone() {
-            if self.get(last_move.row, col) == Some(last_move.player) {
-                continuous_player_pieces += 1;
-                if continuous_player_pieces == 4 {
-                    self.status = Status::Win(last_move.player);
-                    return;
-                }
-            }
-        }
+    /// Get the columns not yet filled, ordered from the center outwards
+    ///
+    /// Center columns take part in more lines, so trying them first is a simple but effective move ordering
+    pub fn columns_center_out(&self) -> impl Iterator<Item = usize> + '_ {
+        CENTER_OUT_COLUMNS
+            .into_iter()
+            .filter(|col| self.columns[*col].filled < BOARD_HEIGHT)
+    }
 
-        // vertical
-        continuous_player_pieces = 0;
+    /// Get the game with the board mirrored left to right, the rest of the state kept
+    ///
+    /// The pieces stack from the bottom, so this is the only transform keeping the board playable
+    pub fn mirror_horizontal(&self) -> Self {
+        let mut game = self.clone();
+        game.columns.reverse();
+        game
+    }
 
-        for row in checking_row_range.clone() {
-            if self.get(row, last_move.col) == Some(last_move.player) {
-                continuous_player_pieces += 1;
-                if continuous_player_pieces == 4 {
-                    self.status = Status::Win(last_move.player);
-                    return;
+    /// Compare the game with a later state of it
+    ///
+    /// The returned diff describes how to get from `self` to `other`
+    pub fn diff(&self, other: &Self) -> BoardDiff {
+        let mut cells = [[None; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        for (row, cells) in cells.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                let (before, after) = (self.get(row, col), other.get(row, col));
+
+                if before != after {
+                    *cell = Some(CellDiff { before, after });
                 }
             }
         }
 
-        // top-left to bottom-right diagonal
-        continuous_player_pieces = 0;
+        BoardDiff {
+            cells,
+            next_player: (self.next_player != other.next_player)
+                .then_some((self.next_player, other.next_player)),
+            status: (self.status != other.status)
+                .then(|| (self.status.clone(), other.status.clone())),
+        }
+    }
+
+    fn playable_columns(&self) -> impl Iterator<Item = usize> + '_ {
+        let is_ongoing = self.status == Status::Ongoing;
+
+        (0..BOARD_WIDTH).filter(move |col| is_ongoing && self.columns[*col].filled < BOARD_HEIGHT)
+    }
+
+    /// Check if putting a piece of the player in the column would connect four
+    fn is_winning_put(&self, col: usize, player: Player) -> bool {
+        let row = BOARD_HEIGHT - 1 - self.columns[col].filled;
+
+        util::k_in_a_row::<BOARD_HEIGHT, BOARD_WIDTH>(row, col, LINE_LENGTH, |row, col| {
+            self.get(row, col) == Some(player)
+        })
+    }
+
+    /// Check if the player has four in a row anywhere on the board
+    fn connects_four(&self, player: Player) -> bool {
+        let is_player = |row, col| self.get(row, col) == Some(player);
+
+        (0..BOARD_HEIGHT)
+            .flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+            .any(|(row, col)| {
+                is_player(row, col)
+                    && util::k_in_a_row::<BOARD_HEIGHT, BOARD_WIDTH>(
+                        row,
+                        col,
+                        LINE_LENGTH,
+                        is_player,
+                    )
+            })
+    }
+
+    /// Iterate over all lines of 4 positions a player can win with, each position being `(row, col)`
+    pub fn lines() -> impl Iterator<Item = [(usize, usize); LINE_LENGTH]> {
+        (0..BOARD_HEIGHT as isize)
+            .flat_map(|row| (0..BOARD_WIDTH as isize).map(move |col| (row, col)))
+            .flat_map(|(row, col)| {
+                LINES.into_iter().filter_map(move |(row_step, col_step)| {
+                    let end_row = row + row_step * (LINE_LENGTH as isize - 1);
+                    let end_col = col + col_step * (LINE_LENGTH as isize - 1);
+
+                    let is_in_bounds = (0..BOARD_HEIGHT as isize).contains(&end_row)
+                        && (0..BOARD_WIDTH as isize).contains(&end_col);
+
+                    is_in_bounds.then(|| {
+                        core::array::from_fn(|distance| {
+                            (
+                                (row + row_step * distance as isize) as usize,
+                                (col + col_step * distance as isize) as usize,
+                            )
+                        })
+                    })
+                })
+            })
+    }
+
+    /// Get the number of lines through each empty position that a player can still win with, which are the lines without a piece of the other player
+    ///
+    /// Occupied positions are `0`. See [`lines`](Self::lines)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::connect_four::{ConnectFour, Player};
+    /// let game = ConnectFour::new().unwrap();
+    ///
+    /// assert_eq!(ConnectFour::lines().count(), 69);
+    /// assert_eq!(game.heatmap(Player::Player0)[2][3], 13);
+    /// ```
+    pub fn heatmap(&self, player: Player) -> [[usize; BOARD_WIDTH]; BOARD_HEIGHT] {
+        let mut heatmap = [[0; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        let open_lines = Self::lines().filter(|line| {
+            line.iter()
+                .all(|(row, col)| self.get(*row, *col) != Some(player.other()))
+        });
 
-        for (row, col) in checking_row_range.clone().zip(checking_col_range.clone()) {
-            if self.get(row, col) == Some(last_move.player) {
-                continuous_player_pieces += 1;
-                if continuous_player_pieces == 4 {
-                    self.status = Status::Win(last_move.player);
-                    return;
+        for line in open_lines {
+            for (row, col) in line {
+                if self.get(row, col).is_none() {
+                    heatmap[row][col] += 1;
                 }
             }
         }
 
-        // top-right to bottom-left diagonal
-        continuous_player_pieces = 0;
+        heatmap
+    }
 
-        for (row, col) in checking_row_range.zip(checking_col_range.rev()) {
-            if self.get(row, col) == Some(last_move.player) {
-                continuous_player_pieces += 1;
-                if continuous_player_pieces == 4 {
-                    self.status = Status::Win(last_move.player);
-                    return;
-                }
-            }
+    /// Check if a position holds a piece of a player, as `==` is not available in const contexts
+    const fn is_piece_of(&self, row: usize, col: usize, player: Player) -> bool {
+        matches!(
+            (self.get(row, col), player),
+            (Some(Player::Player0), Player::Player0) | (Some(Player::Player1), Player::Player1)
+        )
+    }
+
+    const fn update_status(&mut self, last_move: LastMove) {
+        let player = last_move.player;
+        let mut scan =
+            KInARow::<BOARD_HEIGHT, BOARD_WIDTH>::new(last_move.row, last_move.col, LINE_LENGTH);
+
+        while let Some((row, col)) = scan.next() {
+            scan.record(self.is_piece_of(row, col, player));
+        }
+
+        if scan.is_found() {
+            self.status = Status::Win(player);
+            return;
         }
 
         // check draw
@@ -210,6 +909,29 @@ impl ConnectFour {
     }
 }
 
+impl Default for ConnectFour {
+    fn default() -> Self {
+        Self::initial()
+    }
+}
+
+impl BoardDiff {
+    /// Iterate over the changed positions as `(row, col, diff)`, in row-major order
+    pub fn changes(&self) -> impl Iterator<Item = (usize, usize, CellDiff)> + '_ {
+        self.cells.iter().enumerate().flat_map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .filter_map(move |(col, cell)| cell.map(|cell| (row, col, cell)))
+        })
+    }
+
+    /// Check if the two game states are identical
+    pub fn is_empty(&self) -> bool {
+        self.changes().next().is_none() && self.next_player.is_none() && self.status.is_none()
+    }
+}
+
 impl Player {
     /// Get the other player
     pub const fn other(self) -> Self {
@@ -220,15 +942,22 @@ impl Player {
     }
 }
 
-impl Debug for ConnectFour {
+impl Display for Move {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        let mut board = [[None; BOARD_HEIGHT]; BOARD_WIDTH];
+        match self {
+            Self::Put(col) => write!(f, "{col}"),
+            Self::Flip => write!(f, "flip"),
+        }
+    }
+}
 
-        for col in 0..BOARD_WIDTH {
-            let column = &self.columns[col];
+impl Debug for ConnectFour {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let mut board = [[None; BOARD_WIDTH]; BOARD_HEIGHT];
 
-            for row in 0..column.filled {
-                board[col][row] = Some(column.cells[row]);
+        for (row, cells) in board.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                *cell = self.get(row, col);
             }
         }
 
@@ -237,20 +966,58 @@ impl Debug for ConnectFour {
             .field("move_count", &self.move_count)
             .field("next_player", &self.next_player)
             .field("status", &self.status)
+            .field("is_flip_allowed", &self.is_flip_allowed)
+            .field("end_reason", &self.end_reason)
+            .field("draw_offer", &self.draw_offer)
+            .field("is_swapped", &self.is_swapped)
             .finish()
     }
 }
 
+#[cfg(feature = "testing")]
+impl ConnectFour {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    pub fn random_position<R: GameRng + ?Sized>(rng: &mut R, plies: usize) -> Self {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        game.play_random_position(rng, plies);
+        game
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for ConnectFour {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let puts = (0..BOARD_WIDTH)
+            .filter(|col| self.can_put(*col))
+            .map(Move::Put);
+        let flip = self.can_flip().then_some(Move::Flip);
+
+        testing::choose(rng, puts.chain(flip))
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::connect_four::*;
 
     #[test]
     fn test() {
-        let mut game = ConnectFour::new().unwrap();
+        assert_eq!(ConnectFour::default(), ConnectFour::new().unwrap());
+
+        let mut game = ConnectFour::initial();
 
         game.put(3).unwrap();
-        game.put(2).unwrap();
+        game.apply(Move::Put(2)).unwrap();
         game.put(2).unwrap();
         game.put(1).unwrap();
         game.put(1).unwrap();
@@ -266,5 +1033,100 @@ mod tests {
         game.put(0).unwrap();
 
         assert_eq!(game.status(), &Status::Win(Player::Player0));
+        assert!(!game.can_put(4));
+        assert!(!ConnectFour::from_moves(&[3; 6]).unwrap().can_put(3));
+        assert_eq!(
+            ConnectFour::from_moves(&[0, 1, 2, 3]).unwrap(),
+            ConnectFour::from_moves(&[2, 3, 0, 1]).unwrap()
+        );
+        assert_ne!(
+            ConnectFour::from_moves(&[0, 1, 2, 3]).unwrap(),
+            ConnectFour::from_moves(&[1, 0, 2, 3]).unwrap()
+        );
+
+        // a gap in the bottom row breaks the four
+        let gapped = ConnectFour::from_moves(&[0, 0, 1, 1, 4, 4, 3]).unwrap();
+        assert_eq!(gapped.status(), &Status::Ongoing);
+        assert_eq!(unsafe { gapped.get_unchecked(5, 4) }, Some(Player::Player0));
+        assert_eq!(unsafe { gapped.get_unchecked(3, 4) }, None);
+        assert!(ConnectFour::with_flip().unwrap().can_flip());
+        assert!(game.preview(Move::Put(4)).is_err());
+
+        let mut u = arbitrary::Unstructured::new(&[7, 6]);
+        assert_eq!(arbitrary::Arbitrary::arbitrary(&mut u), Ok(Move::Flip));
+        assert_eq!(arbitrary::Arbitrary::arbitrary(&mut u), Ok(Move::Put(6)));
+
+        let threatened = ConnectFour::from_moves(&[3, 3, 4, 4, 5]).unwrap();
+        assert!(threatened.winning_columns().eq([0; 0]));
+        assert!(threatened.losing_if_ignored().eq([2, 6]));
+        assert!(ConnectFour::from_moves(&[3; 6])
+            .unwrap()
+            .columns_center_out()
+            .eq([2, 4, 1, 5, 0, 6]));
+
+        let diff = ConnectFour::new().unwrap().diff(&game);
+        assert_eq!(diff.changes().count(), 15);
+        assert_eq!(
+            diff.status,
+            Some((Status::Ongoing, Status::Win(Player::Player0)))
+        );
+
+        assert_eq!(
+            ConnectFour::new().unwrap().flip(),
+            Err(ConnectFourError::FlipNotAllowed)
+        );
+        assert_eq!(
+            ConnectFour::from_moves(&[3, 3]).unwrap().swap(),
+            Err(ConnectFourError::SwapNotAllowed)
+        );
+
+        // Player1 has three on top of Player0's three in columns 0 to 2, and one more in column 3, so flipping connects four in the bottom row
+        let mut flipped = ConnectFour::with_flip().unwrap();
+        for col in [0, 0, 1, 1, 2, 2, 6, 3, 6] {
+            flipped.put(col).unwrap();
+        }
+        assert_eq!(flipped.status(), &Status::Ongoing);
+
+        flipped.apply(Move::Flip).unwrap();
+        assert_eq!(flipped.get(5, 0), Some(Player::Player1));
+        assert_eq!(flipped.status(), &Status::Win(Player::Player1));
+
+        assert_eq!(
+            ConnectFour::from_moves(&[0, 0, 0, 0, 0, 0, 0]).unwrap_err(),
+            (6, ConnectFourError::ColumnFilled)
+        );
+
+        const WON: ConnectFour = match ConnectFour::from_moves(&[3, 3, 2, 2, 1, 1, 0]) {
+            Ok(game) => game,
+            Err(_) => panic!(),
+        };
+        assert_eq!(WON.status(), &Status::Win(Player::Player0));
+
+        let game = ConnectFour::from_moves(&[3]).unwrap();
+        assert_eq!(game.heatmap(Player::Player0)[5][2], 5);
+        assert_eq!(game.heatmap(Player::Player1)[5][2], 2);
+        assert_eq!(game.heatmap(Player::Player1)[5][3], 0);
+
+        let mut game = ConnectFour::from_moves(&[3, 3, 4]).unwrap();
+        assert_eq!(game.validate(), Ok(()));
+
+        game.columns[3].filled = BOARD_HEIGHT + 1;
+        assert_eq!(game.validate(), Err(InvariantError::Board));
+
+        game.columns[3].filled = 1;
+        assert_eq!(game.validate(), Err(InvariantError::Counter));
+
+        let game = ConnectFour::from_moves(&[0, 1])
+            .unwrap()
+            .mirror_horizontal();
+        assert_eq!(game.get(5, 6), Some(Player::Player0));
+        assert_eq!(game.get(5, 5), Some(Player::Player1));
+
+        let game = ConnectFour::from_moves(&[3, 3, 4]).unwrap();
+        let mut value = serde_json::to_value(&game).unwrap();
+        assert!(serde_json::from_value::<ConnectFour>(value.clone()).is_ok());
+
+        value["columns"][3]["filled"] = (BOARD_HEIGHT + 1).into();
+        assert!(serde_json::from_value::<ConnectFour>(value).is_err());
     }
 }
@@ -0,0 +1,341 @@
+//! Dice for the chance-based games
+//!
+//! A [`Die`] rolls a number from `1` to its number of faces, a [`CustomDie`] rolls one of its own faces, and a [`Roll`] sums up dice with a modifier, as written in roll expressions like `2d6+1`
+//!
+//! Every roll draws from a [`GameRng`] with [`GameRng::gen_below`], so each face is equally likely with any generator implementing it without bias, e.g. the ones from the `rand` feature or [`FairRng`](https://docs.rs/gamie/*/gamie/fairness/struct.FairRng.html)
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "rand")]
+//! # fn dice() {
+//! use gamie::dice::{Die, Roll};
+//! use rand::rngs::ThreadRng;
+//!
+//! let mut rng = ThreadRng::default();
+//!
+//! let face = Die::D20.roll(&mut rng);
+//! assert!((1..=20).contains(&face));
+//!
+//! let roll: Roll = "2d6+1".parse().unwrap();
+//! assert!((3..=13).contains(&roll.roll(&mut rng)));
+//! # }
+//! # #[cfg(feature = "rand")]
+//! # dice();
+//! ```
+
+use crate::rng::GameRng;
+use core::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+use snafu::Snafu;
+
+/// A die with faces numbered from `1`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+// a die is stored as its number of faces, checked when deserialized
+#[cfg_attr(feature = "serde", serde(try_from = "usize", into = "usize"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Die {
+    faces: usize,
+}
+
+/// A die with faces of any kind, e.g. the `-`, blank and `+` faces of a Fudge die
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CustomDie<T, const N: usize> {
+    faces: [T; N],
+}
+
+/// A number of identical dice summed up with a modifier, e.g. `2d6+1`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Roll {
+    count: usize,
+    die: Die,
+    modifier: isize,
+}
+
+/// Errors that can occur when creating dice
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum DiceError {
+    #[snafu(display("die without faces"))]
+    NoFaces,
+    #[snafu(display("invalid roll expression"))]
+    InvalidExpression,
+}
+
+crate::code::codes!(DiceError {
+    NoFaces => "no_faces",
+    InvalidExpression => "invalid_expression",
+});
+
+impl Die {
+    /// A four-sided die
+    pub const D4: Self = Self { faces: 4 };
+    /// A six-sided die
+    pub const D6: Self = Self { faces: 6 };
+    /// An eight-sided die
+    pub const D8: Self = Self { faces: 8 };
+    /// A ten-sided die, numbered from `1` to `10`
+    pub const D10: Self = Self { faces: 10 };
+    /// A twelve-sided die
+    pub const D12: Self = Self { faces: 12 };
+    /// A twenty-sided die
+    pub const D20: Self = Self { faces: 20 };
+
+    /// Create a die with a number of faces
+    ///
+    /// Return `Err(DiceError::NoFaces)` if `faces` is `0`
+    pub const fn new(faces: usize) -> Result<Self, DiceError> {
+        if faces == 0 {
+            return Err(DiceError::NoFaces);
+        }
+
+        Ok(Self { faces })
+    }
+
+    /// Get the number of faces
+    pub const fn faces(self) -> usize {
+        self.faces
+    }
+
+    /// Roll the die, get a number from `1` to the number of faces
+    pub fn roll<R: GameRng + ?Sized>(self, rng: &mut R) -> usize {
+        rng.gen_below(self.faces) + 1
+    }
+}
+
+impl TryFrom<usize> for Die {
+    type Error = DiceError;
+
+    fn try_from(faces: usize) -> Result<Self, DiceError> {
+        Self::new(faces)
+    }
+}
+
+impl From<Die> for usize {
+    fn from(die: Die) -> Self {
+        die.faces
+    }
+}
+
+impl<T, const N: usize> CustomDie<T, N> {
+    /// Create a die with its faces
+    ///
+    /// Return `Err(DiceError::NoFaces)` if `faces` is empty
+    pub fn new(faces: [T; N]) -> Result<Self, DiceError> {
+        if N == 0 {
+            return Err(DiceError::NoFaces);
+        }
+
+        Ok(Self { faces })
+    }
+
+    /// Get the faces
+    pub const fn faces(&self) -> &[T; N] {
+        &self.faces
+    }
+
+    /// Roll the die, get one of its faces
+    pub fn roll<R: GameRng + ?Sized>(&self, rng: &mut R) -> &T {
+        &self.faces[rng.gen_below(N)]
+    }
+}
+
+impl Roll {
+    /// Create a roll of `count` dice plus a modifier
+    pub const fn new(count: usize, die: Die, modifier: isize) -> Self {
+        Self {
+            count,
+            die,
+            modifier,
+        }
+    }
+
+    /// Get the number of dice
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Get the die rolled
+    pub const fn die(&self) -> Die {
+        self.die
+    }
+
+    /// Get the modifier added to the sum of the dice
+    pub const fn modifier(&self) -> isize {
+        self.modifier
+    }
+
+    /// Get the lowest total, with every die showing `1`
+    ///
+    /// The total saturates at the bounds of `isize`, which only a roll created with [`Roll::new`] can reach, as parsed ones are checked to fit
+    pub const fn min(&self) -> isize {
+        saturate(self.count).saturating_add(self.modifier)
+    }
+
+    /// Get the highest total, with every die showing its highest face
+    ///
+    /// The total saturates at the bounds of `isize`, same as [`min`](Self::min)
+    pub const fn max(&self) -> isize {
+        saturate(self.count.saturating_mul(self.die.faces)).saturating_add(self.modifier)
+    }
+
+    /// Roll the dice, get the total with the modifier
+    ///
+    /// The total saturates at the bounds of `isize`, same as [`min`](Self::min)
+    pub fn roll<R: GameRng + ?Sized>(&self, rng: &mut R) -> isize {
+        let sum = self.rolls(rng).fold(0, usize::saturating_add);
+        saturate(sum).saturating_add(self.modifier)
+    }
+
+    /// Roll the dice one by one, get the face of each die without the modifier
+    ///
+    /// This is for games looking at the single dice, e.g. for doubles
+    pub fn rolls<'a, R: GameRng + ?Sized>(
+        &self,
+        rng: &'a mut R,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let die = self.die;
+        (0..self.count).map(move |_| die.roll(rng))
+    }
+}
+
+impl FromStr for Roll {
+    type Err = DiceError;
+
+    /// Parse a roll expression of the form `[count]d<faces>[+modifier|-modifier]`, e.g. `d20`, `2d6+1` or `4D4-2`
+    ///
+    /// Return `Err(DiceError::InvalidExpression)` if the expression is malformed or its highest total does not fit into an `isize`, or `Err(DiceError::NoFaces)` for a die with `0` faces
+    fn from_str(s: &str) -> Result<Self, DiceError> {
+        let (count, rest) = s
+            .split_once(['d', 'D'])
+            .ok_or(DiceError::InvalidExpression)?;
+
+        let count = match count {
+            "" => 1,
+            count => parse_number(count)?,
+        };
+
+        let (faces, modifier) = match rest.find(['+', '-']) {
+            Some(idx) => {
+                let modifier = isize::try_from(parse_number(&rest[idx + 1..])?)
+                    .map_err(|_| DiceError::InvalidExpression)?;
+
+                match &rest[idx..idx + 1] {
+                    "-" => (&rest[..idx], -modifier),
+                    _ => (&rest[..idx], modifier),
+                }
+            }
+            None => (rest, 0),
+        };
+
+        let die = Die::new(parse_number(faces)?)?;
+
+        // the lowest total lies between the modifier and the highest total, so it fits too
+        count
+            .checked_mul(die.faces)
+            .and_then(|sum| isize::try_from(sum).ok())
+            .and_then(|sum| sum.checked_add(modifier))
+            .ok_or(DiceError::InvalidExpression)?;
+
+        Ok(Self::new(count, die, modifier))
+    }
+}
+
+impl Display for Roll {
+    /// Format as a roll expression, see [`Roll::from_str`]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}d{}", self.count, self.die.faces)?;
+
+        match self.modifier {
+            0 => Ok(()),
+            modifier if modifier > 0 => write!(f, "+{modifier}"),
+            modifier => write!(f, "{modifier}"),
+        }
+    }
+}
+
+/// Convert a count to an `isize`, saturating at `isize::MAX`
+const fn saturate(n: usize) -> isize {
+    if n > isize::MAX as usize {
+        isize::MAX
+    } else {
+        n as isize
+    }
+}
+
+/// Parse a number of digits only, unlike `str::parse` allowing no sign
+fn parse_number(s: &str) -> Result<usize, DiceError> {
+    if s.is_empty() || !s.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(DiceError::InvalidExpression);
+    }
+
+    s.parse().map_err(|_| DiceError::InvalidExpression)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dice::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(Die::new(0), Err(DiceError::NoFaces));
+        assert_eq!(Die::new(6), Ok(Die::D6));
+        assert!((0..100).all(|_| (1..=4).contains(&Die::D4.roll(&mut rng))));
+
+        let fudge = CustomDie::new([-1, 0, 1]).unwrap();
+        assert!((0..100).all(|_| fudge.faces().contains(fudge.roll(&mut rng))));
+        assert_eq!(CustomDie::<u8, 0>::new([]), Err(DiceError::NoFaces));
+
+        let roll: Roll = "2d6+1".parse().unwrap();
+        assert_eq!(roll, Roll::new(2, Die::D6, 1));
+        assert_eq!((roll.min(), roll.max()), (3, 13));
+        assert!((0..100).all(|_| (3..=13).contains(&roll.roll(&mut rng))));
+        assert_eq!(roll.rolls(&mut rng).count(), 2);
+
+        assert_eq!("d20".parse(), Ok(Roll::new(1, Die::D20, 0)));
+        assert_eq!("4D4-2".parse(), Ok(Roll::new(4, Die::D4, -2)));
+        assert_eq!("2d0".parse::<Roll>(), Err(DiceError::NoFaces));
+
+        for invalid in [
+            "",
+            "2",
+            "2d",
+            "d+1",
+            "2d6+",
+            "2d6++1",
+            "-2d6",
+            "2d6 + 1",
+            "1d6+9223372036854775808",
+            "1d6-9223372036854775808",
+            "1d6+9223372036854775807",
+            "9223372036854775807d2",
+            "2d9223372036854775807",
+            "99999999999999999999d6",
+        ] {
+            assert_eq!(invalid.parse::<Roll>(), Err(DiceError::InvalidExpression));
+        }
+
+        let roll = Roll::new(usize::MAX, Die::new(usize::MAX).unwrap(), isize::MIN);
+        assert_eq!((roll.min(), roll.max()), (-1, -1));
+
+        for expression in ["1d20", "2d6+1", "4d4-2", "1d6-9223372036854775807"] {
+            let mut formatted = alloc::string::String::new();
+            core::fmt::write(
+                &mut formatted,
+                format_args!("{}", expression.parse::<Roll>().unwrap()),
+            )
+            .unwrap();
+            assert_eq!(formatted, expression);
+        }
+    }
+}
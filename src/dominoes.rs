@@ -0,0 +1,785 @@
+//! Block Dominoes
+//!
+//! Check struct [`Dominoes`] for more information
+
+#[cfg(feature = "testing")]
+use crate::testing::{self, RandomPlay};
+use crate::{
+    invariant::InvariantError,
+    outcome::{AdjudicationReason, EndReason},
+    rng::GameRng,
+};
+use core::convert::Infallible;
+use snafu::Snafu;
+
+const HAND_SIZE: usize = 7;
+const MAX_TILES: usize = TileSet::DoubleNine.tile_count();
+
+/// Block Dominoes for two players
+///
+/// Each player is dealt 7 tiles from a shuffled set, and the rest stay face down in the boneyard, unused in the Block game. The player holding the highest double leads, and the players take turns adding a tile from their hand to either end of the chain, matching the number of pips at that end. A player without a matching tile passes
+///
+/// The first player to play all their tiles wins. If neither player can play, the game is blocked, and the player with fewer pips left in hand wins, or it is a draw if both have the same number of pips. The winner scores the pips left in the hand of the other player
+///
+/// Both hands are part of the game state, so a server should not send the whole game to a player
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::dominoes::{Dominoes, End, Player, Tile, TileSet};
+/// let mut game = Dominoes::from_deal(
+///     TileSet::DoubleSix,
+///     [
+///         &[Tile::new(6, 6), Tile::new(2, 6)],
+///         &[Tile::new(1, 6), Tile::new(3, 4)],
+///     ],
+/// )
+/// .unwrap();
+///
+/// // the double six leads
+/// assert_eq!(game.next_player(), Player::Player0);
+///
+/// game.play(Tile::new(6, 6), End::Left).unwrap();
+/// game.play(Tile::new(6, 1), End::Right).unwrap();
+/// assert_eq!(game.ends(), Some([6, 1]));
+///
+/// game.play(Tile::new(2, 6), End::Left).unwrap();
+/// assert_eq!(game.score(), Some((Player::Player0, 7)));
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Dominoes {
+    set: TileSet,
+    // bit sets of the tiles, by index in the set
+    hands: [u64; 2],
+    chain: u64,
+    ends: Option<[u8; 2]>,
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(Dominoes);
+
+/// A set of tiles, with every pair of pips up to a double
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum TileSet {
+    /// 28 tiles, up to the double six
+    DoubleSix,
+    /// 55 tiles, up to the double nine
+    DoubleNine,
+}
+
+/// A tile with two ends, equal to the tile with its ends swapped
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+// a tile is stored as its pips, in order when deserialized
+#[cfg_attr(feature = "serde", serde(from = "[u8; 2]", into = "[u8; 2]"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Tile {
+    low: u8,
+    high: u8,
+}
+
+/// An end of the chain
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum End {
+    Left,
+    Right,
+}
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    /// A blocked game with the same number of pips left in both hands, or a draw by agreement or adjudication
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(TileSet {
+    0 => DoubleSix,
+    1 => DoubleNine,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::canonical!(Dominoes, "dominoes", 1 {
+    set,
+    hands,
+    chain,
+    ends,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+});
+
+/// A move of the next player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Move {
+    Play { tile: Tile, end: End },
+    Pass,
+}
+
+/// Errors that can occur when dealing or playing a tile
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum DominoesError {
+    #[snafu(display("tile not in hand"))]
+    TileNotInHand,
+    #[snafu(display("tile does not match the end"))]
+    TileMismatch,
+    #[snafu(display("cannot pass with a playable tile"))]
+    CannotPass,
+    #[snafu(display("invalid deal"))]
+    InvalidDeal,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+}
+
+crate::code::codes!(DominoesError {
+    TileNotInHand => "tile_not_in_hand",
+    TileMismatch => "tile_mismatch",
+    CannotPass => "cannot_pass",
+    InvalidDeal => "invalid_deal",
+    GameEnded => "game_ended",
+    NoDrawOffer => "no_draw_offer",
+});
+
+impl Dominoes {
+    /// Create a new game with a double-six set, shuffled and dealt with `rng`
+    pub fn new<R: GameRng + ?Sized>(rng: &mut R) -> Result<Self, Infallible> {
+        Self::with_set(TileSet::DoubleSix, rng)
+    }
+
+    /// Create a new game with a set, shuffled and dealt with `rng`
+    pub fn with_set<R: GameRng + ?Sized>(set: TileSet, rng: &mut R) -> Result<Self, Infallible> {
+        let mut tiles = [0; MAX_TILES];
+        let tiles = &mut tiles[..set.tile_count()];
+
+        for (idx, tile) in tiles.iter_mut().enumerate() {
+            *tile = idx;
+        }
+
+        rng.shuffle(tiles);
+
+        let deal = |tiles: &[usize]| tiles.iter().fold(0, |hand, idx| hand | 1 << idx);
+        let hands = [
+            deal(&tiles[..HAND_SIZE]),
+            deal(&tiles[HAND_SIZE..HAND_SIZE * 2]),
+        ];
+
+        Ok(Self::dealt(set, hands))
+    }
+
+    /// Create a new game from the hands dealt to [`Player::Player0`] and [`Player::Player1`], e.g. by a server shuffling on its own
+    ///
+    /// The hands may be of any size. Return `Err(DominoesError::InvalidDeal)` if a hand is empty, or a tile is not in the set or dealt twice
+    pub fn from_deal(set: TileSet, hands: [&[Tile]; 2]) -> Result<Self, DominoesError> {
+        let mut dealt = [0; 2];
+
+        for (player, tiles) in hands.into_iter().enumerate() {
+            for tile in tiles {
+                if !set.contains(*tile) || (dealt[0] | dealt[1]) & tile.bit() != 0 {
+                    return Err(DominoesError::InvalidDeal);
+                }
+
+                dealt[player] |= tile.bit();
+            }
+        }
+
+        if dealt.contains(&0) {
+            return Err(DominoesError::InvalidDeal);
+        }
+
+        Ok(Self::dealt(set, dealt))
+    }
+
+    fn dealt(set: TileSet, hands: [u64; 2]) -> Self {
+        // the highest double leads, or the first player if neither holds one
+        let leader = (0..=set.max_pips())
+            .rev()
+            .map(|pips| Tile::new(pips, pips).bit())
+            .find_map(|bit| match (hands[0] & bit != 0, hands[1] & bit != 0) {
+                (true, _) => Some(Player::Player0),
+                (_, true) => Some(Player::Player1),
+                _ => None,
+            })
+            .unwrap_or(Player::Player0);
+
+        Self {
+            set,
+            hands,
+            chain: 0,
+            ends: None,
+            next_player: leader,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+        }
+    }
+
+    /// Play a tile from the hand of the next player at an end of the chain
+    ///
+    /// The end is ignored for the first tile, which starts the chain
+    pub fn play(&mut self, tile: Tile, end: End) -> Result<(), DominoesError> {
+        if self.status != Status::Ongoing {
+            return Err(DominoesError::GameEnded);
+        }
+
+        let player = self.next_player;
+
+        if !self.set.contains(tile) || self.hands[player as usize] & tile.bit() == 0 {
+            return Err(DominoesError::TileNotInHand);
+        }
+
+        self.ends = Some(match self.ends {
+            None => [tile.low, tile.high],
+            Some(mut ends) => {
+                ends[end as usize] = tile
+                    .other_end(ends[end as usize])
+                    .ok_or(DominoesError::TileMismatch)?;
+                ends
+            }
+        });
+
+        self.hands[player as usize] &= !tile.bit();
+        self.chain |= tile.bit();
+        self.next_player = player.other();
+        self.draw_offer = None;
+
+        if self.hands[player as usize] == 0 {
+            self.status = Status::Win(player);
+        } else if self.is_blocked() {
+            self.status = self.blocked_status();
+        }
+
+        Ok(())
+    }
+
+    /// Pass the turn of the next player, who has no tile matching either end
+    pub fn pass(&mut self) -> Result<(), DominoesError> {
+        if self.status != Status::Ongoing {
+            return Err(DominoesError::GameEnded);
+        }
+
+        if self.can_play_any(self.next_player) {
+            return Err(DominoesError::CannotPass);
+        }
+
+        // a blocked game ends on the last tile played, so the other player can play
+        self.next_player = self.next_player.other();
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Check if the next player can play a tile at an end, same as [`play`](Self::play) succeeding but without changing the game
+    pub fn can_play(&self, tile: Tile, end: End) -> bool {
+        self.status == Status::Ongoing
+            && self.set.contains(tile)
+            && self.hands[self.next_player as usize] & tile.bit() != 0
+            && self
+                .ends
+                .is_none_or(|ends| tile.matches(ends[end as usize]))
+    }
+
+    /// Check if the next player has to pass, same as [`pass`](Self::pass) succeeding but without changing the game
+    pub fn can_pass(&self) -> bool {
+        self.status == Status::Ongoing && !self.can_play_any(self.next_player)
+    }
+
+    /// Apply a move of the next player
+    pub fn apply(&mut self, mv: Move) -> Result<(), DominoesError> {
+        match mv {
+            Move::Play { tile, end } => self.play(tile, end),
+            Move::Pass => self.pass(),
+        }
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    pub fn preview(&self, mv: Move) -> Result<Self, DominoesError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Get the set the game is played with
+    pub const fn set(&self) -> TileSet {
+        self.set
+    }
+
+    /// Get the tiles in the hand of a player
+    pub fn hand(&self, player: Player) -> impl Iterator<Item = Tile> + '_ {
+        self.tiles_in(self.hands[player as usize])
+    }
+
+    /// Get the number of tiles in the hand of a player
+    pub const fn hand_len(&self, player: Player) -> usize {
+        self.hands[player as usize].count_ones() as usize
+    }
+
+    /// Get the total number of pips in the hand of a player
+    pub fn pip_count(&self, player: Player) -> usize {
+        self.hand(player).map(Tile::pip_count).sum()
+    }
+
+    /// Get the tiles in the chain, in the order of the set rather than of the chain
+    pub fn chain(&self) -> impl Iterator<Item = Tile> + '_ {
+        self.tiles_in(self.chain)
+    }
+
+    /// Get the tiles left in the boneyard, dealt to neither player
+    pub fn boneyard(&self) -> impl Iterator<Item = Tile> + '_ {
+        self.tiles_in(!(self.hands[0] | self.hands[1] | self.chain))
+    }
+
+    /// Get the number of pips at the left and right ends of the chain, `None` before the first tile
+    pub const fn ends(&self) -> Option<[u8; 2]> {
+        self.ends
+    }
+
+    /// Get the winner and the points scored, which are the pips left in the hand of the other player
+    ///
+    /// Return `None` if the game is ongoing, drawn, or ended other than by the rules
+    pub fn score(&self) -> Option<(Player, usize)> {
+        match (&self.status, self.end_reason) {
+            (Status::Win(player), EndReason::Normal) => {
+                Some((*player, self.pip_count(player.other())))
+            }
+            _ => None,
+        }
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// Every tile must be in the set and in at most one place, the ends must be reachable by chaining the played tiles, and a status ended by the rules must match the hands
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let [hand0, hand1] = self.hands;
+        let in_set = !0 >> (u64::BITS as usize - self.set.tile_count());
+
+        if (hand0 | hand1 | self.chain) & !in_set != 0
+            || hand0 & hand1 != 0
+            || (hand0 | hand1) & self.chain != 0
+        {
+            return Err(InvariantError::Board);
+        }
+
+        // inside the chain every number of pips is matched by the next tile, so only the ends can be unpaired
+        let is_chain_valid = match self.ends {
+            None => self.chain == 0,
+            Some(ends) => {
+                self.chain != 0
+                    && (0..=self.set.max_pips()).all(|pips| {
+                        let count = self
+                            .chain()
+                            .map(|tile| (tile.low == pips) as usize + (tile.high == pips) as usize)
+                            .sum::<usize>();
+                        let open = ends.iter().filter(|end| **end == pips).count();
+
+                        count >= open && (count - open).is_multiple_of(2)
+                    })
+                    && ends.iter().all(|end| *end <= self.set.max_pips())
+            }
+        };
+
+        if !is_chain_valid {
+            return Err(InvariantError::Board);
+        }
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => hand0 != 0 && hand1 != 0 && !self.is_blocked(),
+            (Status::Ongoing, _) => false,
+            (Status::Win(player), EndReason::Normal) => {
+                let player = *player as usize;
+
+                // the game ends on the first empty hand
+                (self.hands[player] == 0 && self.hands[1 - player] != 0)
+                    || (hand0 != 0
+                        && hand1 != 0
+                        && self.is_blocked()
+                        && self.blocked_status() == self.status)
+            }
+            (Status::Draw, EndReason::Normal) => {
+                hand0 != 0
+                    && hand1 != 0
+                    && self.is_blocked()
+                    && self.blocked_status() == Status::Draw
+            }
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), DominoesError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), DominoesError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), DominoesError> {
+        if self.status != Status::Ongoing {
+            return Err(DominoesError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), DominoesError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(DominoesError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), DominoesError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn tiles_in(&self, tiles: u64) -> impl Iterator<Item = Tile> {
+        self.set.tiles().filter(move |tile| tiles & tile.bit() != 0)
+    }
+
+    fn can_play_any(&self, player: Player) -> bool {
+        match self.ends {
+            None => self.hands[player as usize] != 0,
+            Some(ends) => self
+                .hand(player)
+                .any(|tile| tile.matches(ends[0]) || tile.matches(ends[1])),
+        }
+    }
+
+    fn is_blocked(&self) -> bool {
+        !self.can_play_any(Player::Player0) && !self.can_play_any(Player::Player1)
+    }
+
+    fn blocked_status(&self) -> Status {
+        let pips = [Player::Player0, Player::Player1].map(|player| self.pip_count(player));
+
+        match pips[0].cmp(&pips[1]) {
+            core::cmp::Ordering::Less => Status::Win(Player::Player0),
+            core::cmp::Ordering::Equal => Status::Draw,
+            core::cmp::Ordering::Greater => Status::Win(Player::Player1),
+        }
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), DominoesError> {
+        if self.status != Status::Ongoing {
+            return Err(DominoesError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+}
+
+impl TileSet {
+    /// Get the number of pips on the highest double
+    pub const fn max_pips(self) -> u8 {
+        match self {
+            TileSet::DoubleSix => 6,
+            TileSet::DoubleNine => 9,
+        }
+    }
+
+    /// Get the number of tiles in the set
+    pub const fn tile_count(self) -> usize {
+        let values = self.max_pips() as usize + 1;
+        values * (values + 1) / 2
+    }
+
+    /// Check if a tile is in the set
+    pub const fn contains(self, tile: Tile) -> bool {
+        tile.high <= self.max_pips()
+    }
+
+    /// Get the tiles of the set, in order of their higher and then lower end
+    pub fn tiles(self) -> impl Iterator<Item = Tile> {
+        (0..=self.max_pips()).flat_map(|high| (0..=high).map(move |low| Tile { low, high }))
+    }
+}
+
+impl Tile {
+    /// Create a tile with the pips of its two ends, in any order
+    pub const fn new(a: u8, b: u8) -> Self {
+        if a <= b {
+            Self { low: a, high: b }
+        } else {
+            Self { low: b, high: a }
+        }
+    }
+
+    /// Get the pips of the lower and the higher end
+    pub const fn pips(self) -> [u8; 2] {
+        [self.low, self.high]
+    }
+
+    /// Get the total number of pips
+    pub const fn pip_count(self) -> usize {
+        self.low as usize + self.high as usize
+    }
+
+    /// Check if both ends have the same number of pips
+    pub const fn is_double(self) -> bool {
+        self.low == self.high
+    }
+
+    /// Check if an end of the tile has a number of pips
+    pub const fn matches(self, pips: u8) -> bool {
+        self.low == pips || self.high == pips
+    }
+
+    /// Get the number of pips of the other end, after matching one end to `pips`
+    pub const fn other_end(self, pips: u8) -> Option<u8> {
+        if self.low == pips {
+            Some(self.high)
+        } else if self.high == pips {
+            Some(self.low)
+        } else {
+            None
+        }
+    }
+
+    // the index in every set containing the tile, as the sets share their order
+    const fn bit(self) -> u64 {
+        1 << (self.high as usize * (self.high as usize + 1) / 2 + self.low as usize)
+    }
+}
+
+impl From<[u8; 2]> for Tile {
+    fn from([a, b]: [u8; 2]) -> Self {
+        Self::new(a, b)
+    }
+}
+
+impl From<Tile> for [u8; 2] {
+    fn from(tile: Tile) -> Self {
+        tile.pips()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Tile {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(u.int_in_range(0..=9)?, u.int_in_range(0..=9)?))
+    }
+}
+
+impl Player {
+    /// Get the other player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for Dominoes {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        if self.can_pass() {
+            return Some(Move::Pass);
+        }
+
+        let moves = self
+            .hand(self.next_player)
+            .flat_map(|tile| [End::Left, End::Right].map(|end| Move::Play { tile, end }))
+            .filter(|mv| matches!(mv, Move::Play { tile, end } if self.can_play(*tile, *end)));
+
+        testing::choose(rng, moves)
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dominoes::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let game = Dominoes::with_set(TileSet::DoubleNine, &mut rng).unwrap();
+        assert_eq!(TileSet::DoubleSix.tile_count(), 28);
+        assert_eq!(game.set().tiles().count(), 55);
+        assert_eq!(game.hand_len(Player::Player0), 7);
+        assert_eq!(game.hand_len(Player::Player1), 7);
+        assert_eq!(game.boneyard().count(), 41);
+        assert_eq!(game.validate(), Ok(()));
+
+        assert_eq!(
+            Dominoes::from_deal(TileSet::DoubleSix, [&[Tile::new(1, 2)], &[Tile::new(2, 1)]])
+                .unwrap_err(),
+            DominoesError::InvalidDeal
+        );
+        assert_eq!(
+            Dominoes::from_deal(TileSet::DoubleSix, [&[Tile::new(1, 7)], &[Tile::new(2, 1)]])
+                .unwrap_err(),
+            DominoesError::InvalidDeal
+        );
+
+        let mut game = Dominoes::from_deal(
+            TileSet::DoubleSix,
+            [
+                &[Tile::new(0, 1), Tile::new(5, 5), Tile::new(4, 6)],
+                &[Tile::new(1, 1), Tile::new(3, 3), Tile::new(0, 2)],
+            ],
+        )
+        .unwrap();
+
+        // the double five leads, as nobody holds the double six
+        assert_eq!(game.next_player(), Player::Player0);
+        assert_eq!(
+            game.play(Tile::new(1, 1), End::Left),
+            Err(DominoesError::TileNotInHand)
+        );
+        game.play(Tile::new(0, 1), End::Right).unwrap();
+        assert_eq!(game.ends(), Some([0, 1]));
+
+        assert_eq!(
+            game.play(Tile::new(1, 1), End::Left),
+            Err(DominoesError::TileMismatch)
+        );
+        assert_eq!(game.pass(), Err(DominoesError::CannotPass));
+        game.apply(Move::Play {
+            tile: Tile::new(1, 1),
+            end: End::Right,
+        })
+        .unwrap();
+
+        assert!(game.can_pass());
+        game.pass().unwrap();
+        assert_eq!(game.validate(), Ok(()));
+        assert!(game.preview(Move::Pass).is_err());
+
+        // no tile matches the ends anymore, so the game is blocked with 20 pips against 6
+        game.play(Tile::new(2, 0), End::Left).unwrap();
+        assert_eq!(game.ends(), Some([2, 1]));
+        assert_eq!(game.status(), &Status::Win(Player::Player1));
+        assert_eq!(game.score(), Some((Player::Player1, 20)));
+        assert_eq!(game.validate(), Ok(()));
+
+        let mut game = Dominoes::from_deal(
+            TileSet::DoubleSix,
+            [&[Tile::new(0, 1), Tile::new(6, 6)], &[Tile::new(3, 4)]],
+        )
+        .unwrap();
+        game.play(Tile::new(6, 6), End::Left).unwrap();
+
+        // blocked right away, with 1 pip against 7
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+        assert_eq!(game.score(), Some((Player::Player0, 7)));
+        assert_eq!(game.end_reason(), Some(EndReason::Normal));
+        assert_eq!(game.validate(), Ok(()));
+        assert_eq!(game.pass(), Err(DominoesError::GameEnded));
+
+        let mut game = Dominoes::new(&mut rng).unwrap();
+        game.ends = Some([3, 3]);
+        assert_eq!(game.validate(), Err(InvariantError::Board));
+    }
+}
@@ -0,0 +1,703 @@
+//! Drop Three, Tic-Tac-Toe on a larger board with pieces falling under gravity
+//!
+//! Check struct [`DropThree`] for more information
+
+use crate::{
+    invariant::{self, InvariantError},
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+    util::{self, KInARow},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use snafu::Snafu;
+
+/// The largest size of a board
+pub const MAX_SIZE: usize = 8;
+
+/// Drop Three, a family of games between Tic-Tac-Toe and Connect Four
+///
+/// Two players take turns putting pieces on a square board, and the first to get [`Rules::line_length`] pieces in a row horizontally, vertically or diagonally wins. With [`Rules::has_gravity`], a piece falls down its column, so it rests on the bottom row or on another piece. Without it, any empty position can be taken, as in Tic-Tac-Toe
+///
+/// Row `0` is the top of the board
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::drop_three::{DropThree, Player, Rules, Status};
+/// let mut game = DropThree::new(Rules::default()).unwrap();
+///
+/// // a piece lands on the bottom row, or on top of another one
+/// assert_eq!(game.drop(1).unwrap(), 3);
+/// assert_eq!(game.drop(1).unwrap(), 2);
+/// assert!(game.put(0, 0).is_err());
+///
+/// game.drop(2).unwrap();
+/// game.drop(2).unwrap();
+/// game.drop(3).unwrap();
+///
+/// assert_eq!(game.status(), &Status::Win(Player::Player0));
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DropThree {
+    board: [[Option<Player>; MAX_SIZE]; MAX_SIZE],
+    rules: Rules,
+    move_count: usize,
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(DropThree);
+
+/// The rules of a Drop Three game
+///
+/// The default rules are a 4 by 4 board, three in a row and gravity
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Rules {
+    /// The number of rows and columns of the board, from `1` to [`MAX_SIZE`]
+    pub size: usize,
+    /// The number of pieces in a row a player wins with, from `1` to the size of the board
+    pub line_length: usize,
+    /// Whether pieces fall down their column
+    pub has_gravity: bool,
+}
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::encode_struct!(Rules {
+    size,
+    line_length,
+    has_gravity
+});
+
+crate::canonical::canonical!(DropThree, "drop_three", 1 {
+    board,
+    rules,
+    move_count,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+});
+
+/// A move, which places a piece at a position
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Errors that can occur when creating a game or placing a piece onto the board
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum DropThreeError {
+    #[snafu(display("invalid rules"))]
+    InvalidRules,
+    #[snafu(display("position occupied"))]
+    PositionOccupied,
+    #[snafu(display("position not supported"))]
+    PositionNotSupported,
+    #[snafu(display("column filled"))]
+    ColumnFilled,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("position out of bounds"))]
+    OutOfBounds,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+}
+
+crate::code::codes!(DropThreeError {
+    InvalidRules => "invalid_rules",
+    PositionOccupied => "position_occupied",
+    PositionNotSupported => "position_not_supported",
+    ColumnFilled => "column_filled",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+    NoDrawOffer => "no_draw_offer",
+});
+
+impl DropThree {
+    /// Create a new Drop Three game
+    ///
+    /// Return `Err(DropThreeError::InvalidRules)` if the size is not from `1` to [`MAX_SIZE`], or the line length is not from `1` to the size
+    pub const fn new(rules: Rules) -> Result<Self, DropThreeError> {
+        if rules.size == 0 || rules.size > MAX_SIZE {
+            return Err(DropThreeError::InvalidRules);
+        }
+
+        if rules.line_length == 0 || rules.line_length > rules.size {
+            return Err(DropThreeError::InvalidRules);
+        }
+
+        Ok(Self {
+            board: [[None; MAX_SIZE]; MAX_SIZE],
+            rules,
+            move_count: 0,
+            next_player: Player::Player0,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+        })
+    }
+
+    /// Create a Drop Three game by replaying a list of moves from an empty board
+    ///
+    /// Return the index of the first invalid move and the error it caused on failure, or `usize::MAX` as the index if the rules are invalid
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::drop_three::{DropThree, DropThreeError, Rules};
+    /// let rules = Rules {
+    ///     has_gravity: false,
+    ///     ..Rules::default()
+    /// };
+    /// assert!(DropThree::from_moves(rules, &[(0, 0), (1, 1)]).is_ok());
+    ///
+    /// assert_eq!(
+    ///     DropThree::from_moves(Rules::default(), &[(3, 0), (1, 1)]).unwrap_err(),
+    ///     (1, DropThreeError::PositionNotSupported)
+    /// );
+    /// ```
+    pub const fn from_moves(
+        rules: Rules,
+        moves: &[(usize, usize)],
+    ) -> Result<Self, (usize, DropThreeError)> {
+        let mut game = match Self::new(rules) {
+            Ok(game) => game,
+            Err(err) => return Err((usize::MAX, err)),
+        };
+
+        let mut idx = 0;
+
+        while idx < moves.len() {
+            let (row, col) = moves[idx];
+
+            if let Err(err) = game.put(row, col) {
+                return Err((idx, err));
+            }
+
+            idx += 1;
+        }
+
+        Ok(game)
+    }
+
+    /// Get the rules of the game
+    pub const fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Get a piece at a position
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Option<Player> {
+        assert!(row < self.rules.size && col < self.rules.size);
+        self.board[row][col]
+    }
+
+    /// Put a piece
+    ///
+    /// With gravity, the position must be on the bottom row or right above another piece, see [`drop`](Self::drop)
+    pub const fn put(&mut self, row: usize, col: usize) -> Result<(), DropThreeError> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(DropThreeError::GameEnded);
+        }
+
+        if row >= self.rules.size || col >= self.rules.size {
+            return Err(DropThreeError::OutOfBounds);
+        }
+
+        if self.board[row][col].is_some() {
+            return Err(DropThreeError::PositionOccupied);
+        }
+
+        if !self.is_supported(row, col) {
+            return Err(DropThreeError::PositionNotSupported);
+        }
+
+        let player = self.next_player;
+        self.board[row][col] = Some(player);
+
+        self.move_count += 1;
+        self.draw_offer = None;
+        self.next_player = player.other();
+
+        self.update_status(player, row, col);
+
+        Ok(())
+    }
+
+    /// Drop a piece into a column, where it lands on the lowest empty position
+    ///
+    /// Return the row it landed on. This works without gravity too, taking the lowest empty position of the column
+    pub const fn drop(&mut self, col: usize) -> Result<usize, DropThreeError> {
+        if col >= self.rules.size {
+            return Err(DropThreeError::OutOfBounds);
+        }
+
+        let Some(row) = self.landing_row(col) else {
+            return Err(if matches!(self.status, Status::Ongoing) {
+                DropThreeError::ColumnFilled
+            } else {
+                DropThreeError::GameEnded
+            });
+        };
+
+        match self.put(row, col) {
+            Ok(()) => Ok(row),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Check if a piece can be put at a position, same as [`put`](Self::put) succeeding but without changing the game
+    pub const fn can_put(&self, row: usize, col: usize) -> bool {
+        matches!(self.status, Status::Ongoing)
+            && row < self.rules.size
+            && col < self.rules.size
+            && self.board[row][col].is_none()
+            && self.is_supported(row, col)
+    }
+
+    /// Get the lowest empty row of a column, where a dropped piece lands, `None` if the column is filled
+    ///
+    /// Panic if the column is out of bounds
+    pub const fn landing_row(&self, col: usize) -> Option<usize> {
+        assert!(col < self.rules.size);
+
+        let mut row = self.rules.size;
+
+        while row > 0 {
+            row -= 1;
+
+            if self.board[row][col].is_none() {
+                return Some(row);
+            }
+        }
+
+        None
+    }
+
+    /// Apply a move, same as [`put`](Self::put)
+    pub const fn apply(&mut self, mv: Move) -> Result<(), DropThreeError> {
+        self.put(mv.row, mv.col)
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    pub fn preview(&self, mv: Move) -> Result<Self, DropThreeError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, a position as described in [`parse`](crate::parse), or a column after `drop`, e.g. `drop 2`, for the position a piece dropped into it lands on
+    pub fn parse_move(&self, text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+        let size = self.rules.size;
+
+        let (row, col) = if words.take("drop") {
+            let col = words.number(size)?;
+            (self.landing_row(col).ok_or(ParseError::InvalidMove)?, col)
+        } else {
+            words.position(size, size)?
+        };
+
+        words.finish()?;
+        Ok(Move { row, col })
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The rules must be valid, no piece may lie outside the board or float under gravity, the piece counts must match the move count and the next player, and a status ended by the rules must match the lines on the board
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        if Self::new(self.rules).is_err() {
+            return Err(InvariantError::Board);
+        }
+
+        let size = self.rules.size;
+
+        for (row, cells) in self.board.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                let is_in_bounds = row < size && col < size;
+
+                if cell.is_some() && (!is_in_bounds || !self.is_supported(row, col)) {
+                    return Err(InvariantError::Board);
+                }
+            }
+        }
+
+        let count = |player| {
+            self.board
+                .iter()
+                .flatten()
+                .filter(|cell| **cell == Some(player))
+                .count()
+        };
+
+        let (player0_count, player1_count) = (count(Player::Player0), count(Player::Player1));
+
+        if player0_count + player1_count != self.move_count {
+            return Err(InvariantError::Counter);
+        }
+
+        invariant::check_turns(
+            player0_count,
+            player1_count,
+            self.next_player == Player::Player0,
+        )?;
+
+        let has_line = |player| {
+            self.positions().any(|(row, col)| {
+                self.get(row, col) == Some(player)
+                    && util::k_in_a_row::<MAX_SIZE, MAX_SIZE>(
+                        row,
+                        col,
+                        self.rules.line_length,
+                        |row, col| self.board[row][col] == Some(player),
+                    )
+            })
+        };
+        let has_no_line = !has_line(Player::Player0) && !has_line(Player::Player1);
+        let is_full = self.move_count == size * size;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => has_no_line && !is_full,
+            (Status::Ongoing, _) => false,
+            (Status::Win(player), EndReason::Normal) => {
+                has_line(*player) && !has_line(player.other())
+            }
+            (Status::Draw, EndReason::Normal) => has_no_line && is_full,
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), DropThreeError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), DropThreeError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), DropThreeError> {
+        if self.status != Status::Ongoing {
+            return Err(DropThreeError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), DropThreeError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(DropThreeError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), DropThreeError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    /// Iterate over the positions of the board as `(row, col)`, in row-major order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let size = self.rules.size;
+        (0..size).flat_map(move |row| (0..size).map(move |col| (row, col)))
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), DropThreeError> {
+        if self.status != Status::Ongoing {
+            return Err(DropThreeError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Check if a piece at a position would rest on the bottom row or another piece, always true without gravity
+    const fn is_supported(&self, row: usize, col: usize) -> bool {
+        !self.rules.has_gravity || row + 1 >= self.rules.size || self.board[row + 1][col].is_some()
+    }
+
+    /// Check if a position holds a piece of a player, as `==` is not available in const contexts
+    const fn is_piece_of(&self, row: usize, col: usize, player: Player) -> bool {
+        matches!(
+            (self.board[row][col], player),
+            (Some(Player::Player0), Player::Player0) | (Some(Player::Player1), Player::Player1)
+        )
+    }
+
+    const fn update_status(&mut self, player: Player, row: usize, col: usize) {
+        // positions off the board are always empty, so the scan never connects through them
+        let mut scan = KInARow::<MAX_SIZE, MAX_SIZE>::new(row, col, self.rules.line_length);
+
+        while let Some((row, col)) = scan.next() {
+            scan.record(self.is_piece_of(row, col, player));
+        }
+
+        if scan.is_found() {
+            self.status = Status::Win(player);
+            return;
+        }
+
+        // check draw
+        if self.move_count == self.rules.size * self.rules.size {
+            self.status = Status::Draw;
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            size: 4,
+            line_length: 3,
+            has_gravity: true,
+        }
+    }
+}
+
+impl Player {
+    /// Get the opposite player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} {}", self.row, self.col)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl DropThree {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    ///
+    /// Return `Err(DropThreeError::InvalidRules)` if the rules are invalid, see [`new`](Self::new)
+    pub fn random_position<R: GameRng + ?Sized>(
+        rules: Rules,
+        rng: &mut R,
+        plies: usize,
+    ) -> Result<Self, DropThreeError> {
+        let mut game = Self::new(rules)?;
+        game.play_random_position(rng, plies);
+        Ok(game)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for DropThree {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let moves = testing::positions(self.rules.size, self.rules.size)
+            .filter(|&(row, col)| self.can_put(row, col));
+        testing::choose(rng, moves).map(|(row, col)| Move { row, col })
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::drop_three::*;
+
+    #[test]
+    fn test() {
+        for rules in [
+            Rules {
+                size: 0,
+                ..Rules::default()
+            },
+            Rules {
+                size: MAX_SIZE + 1,
+                ..Rules::default()
+            },
+            Rules {
+                line_length: 5,
+                ..Rules::default()
+            },
+        ] {
+            assert_eq!(
+                DropThree::new(rules).unwrap_err(),
+                DropThreeError::InvalidRules
+            );
+        }
+
+        let mut game = DropThree::new(Rules::default()).unwrap();
+
+        assert_eq!(game.put(2, 0), Err(DropThreeError::PositionNotSupported));
+        assert_eq!(game.put(4, 0), Err(DropThreeError::OutOfBounds));
+        assert!(game.can_put(3, 0));
+        assert!(!game.can_put(2, 0));
+
+        for _ in 0..4 {
+            game.drop(0).unwrap();
+        }
+
+        assert_eq!(game.landing_row(0), None);
+        assert_eq!(game.drop(0), Err(DropThreeError::ColumnFilled));
+        assert_eq!(game.next_player(), Player::Player0);
+        assert_eq!(game.validate(), Ok(()));
+
+        // a diagonal from the bottom left
+        let mut game = DropThree::from_moves(
+            Rules::default(),
+            &[(3, 0), (3, 1), (2, 1), (3, 2), (3, 3), (2, 2)],
+        )
+        .unwrap();
+        assert_eq!(game.status(), &Status::Ongoing);
+
+        assert_eq!(game.drop(2), Ok(1));
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+        assert_eq!(game.validate(), Ok(()));
+        assert_eq!(game.drop(1), Err(DropThreeError::GameEnded));
+
+        // without gravity, it is Tic-Tac-Toe
+        let rules = Rules {
+            size: 3,
+            line_length: 3,
+            has_gravity: false,
+        };
+
+        let game = DropThree::from_moves(rules, &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]).unwrap();
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+
+        let game = DropThree::from_moves(
+            rules,
+            &[
+                (0, 0),
+                (1, 1),
+                (2, 2),
+                (0, 1),
+                (2, 1),
+                (2, 0),
+                (0, 2),
+                (1, 2),
+                (1, 0),
+            ],
+        )
+        .unwrap();
+        assert_eq!(game.status(), &Status::Draw);
+        assert_eq!(game.validate(), Ok(()));
+
+        let mut game = DropThree::from_moves(rules, &[(0, 0), (1, 1)]).unwrap();
+        game.rules.has_gravity = true;
+        assert_eq!(game.validate(), Err(InvariantError::Board));
+
+        game.rules.has_gravity = false;
+        game.next_player = Player::Player1;
+        assert_eq!(game.validate(), Err(InvariantError::NextPlayer));
+    }
+}
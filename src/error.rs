@@ -0,0 +1,254 @@
+//! Crate-wide error type
+//!
+//! Check enum [`Error`] for more information
+
+use crate::code::Code;
+use snafu::Snafu;
+
+/// Any error that can occur in this crate
+///
+/// Each module error converts into it with `From`, so a single error type can be used when hosting multiple games
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "tictactoe")]
+/// # fn tictactoe() -> Result<(), gamie::Error> {
+/// use gamie::tictactoe::TicTacToe;
+///
+/// let mut game = TicTacToe::new().unwrap();
+/// game.put(1, 1)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+#[non_exhaustive]
+pub enum Error {
+    #[cfg(feature = "binairo")]
+    #[snafu(context(false), display("{source}"))]
+    Binairo {
+        source: crate::logic_grid::binairo::BinairoError,
+    },
+    #[cfg(feature = "connect_four")]
+    #[snafu(context(false), display("{source}"))]
+    ConnectFour {
+        source: crate::connect_four::ConnectFourError,
+    },
+    #[cfg(feature = "connect6")]
+    #[snafu(context(false), display("{source}"))]
+    Connect6 {
+        source: crate::connect6::Connect6Error,
+    },
+    #[cfg(feature = "dice")]
+    #[snafu(context(false), display("{source}"))]
+    Dice { source: crate::dice::DiceError },
+    #[cfg(feature = "dominoes")]
+    #[snafu(context(false), display("{source}"))]
+    Dominoes {
+        source: crate::dominoes::DominoesError,
+    },
+    #[cfg(feature = "drop_three")]
+    #[snafu(context(false), display("{source}"))]
+    DropThree {
+        source: crate::drop_three::DropThreeError,
+    },
+    #[cfg(feature = "fairness")]
+    #[snafu(context(false), display("{source}"))]
+    Fairness {
+        source: crate::fairness::FairnessError,
+    },
+    #[cfg(feature = "fox_and_geese")]
+    #[snafu(context(false), display("{source}"))]
+    FoxAndGeese {
+        source: crate::fox_and_geese::FoxAndGeeseError,
+    },
+    #[cfg(feature = "gomoku")]
+    #[snafu(context(false), display("{source}"))]
+    Gomoku { source: crate::gomoku::GomokuError },
+    #[cfg(feature = "hasami_shogi")]
+    #[snafu(context(false), display("{source}"))]
+    HasamiShogi {
+        source: crate::hasami_shogi::HasamiShogiError,
+    },
+    #[snafu(context(false), display("{source}"))]
+    Invariant {
+        source: crate::invariant::InvariantError,
+    },
+    #[cfg(feature = "liars_dice")]
+    #[snafu(context(false), display("{source}"))]
+    LiarsDice {
+        source: crate::liars_dice::LiarsDiceError,
+    },
+    #[cfg(any(feature = "minesweeper", feature = "minesweeper_fixed"))]
+    #[snafu(context(false), display("{source}"))]
+    Minesweeper {
+        source: crate::minesweeper::MinesweeperError,
+    },
+    #[cfg(feature = "minesweeper")]
+    #[snafu(context(false), display("{source}"))]
+    MinesweeperFlags {
+        source: crate::minesweeper::flags::MinesweeperFlagsError,
+    },
+    #[cfg(feature = "minesweeper")]
+    #[snafu(context(false), display("{source}"))]
+    MinesweeperReplay {
+        source: crate::minesweeper::replay::ReplayError,
+    },
+    #[cfg(feature = "orchestration")]
+    #[snafu(context(false), display("{source}"))]
+    Orchestration {
+        source: crate::orchestration::OrchestrationError,
+    },
+    #[cfg(feature = "order_and_chaos")]
+    #[snafu(context(false), display("{source}"))]
+    OrderAndChaos {
+        source: crate::order_and_chaos::OrderAndChaosError,
+    },
+    #[snafu(context(false), display("{source}"))]
+    Parse { source: crate::parse::ParseError },
+    #[cfg(feature = "pente")]
+    #[snafu(context(false), display("{source}"))]
+    Pente { source: crate::pente::PenteError },
+    #[cfg(feature = "pig")]
+    #[snafu(context(false), display("{source}"))]
+    Pig { source: crate::pig::PigError },
+    #[cfg(feature = "reversi")]
+    #[snafu(context(false), display("{source}"))]
+    Reversi {
+        source: crate::reversi::ReversiError,
+    },
+    #[cfg(feature = "rock_paper_scissors")]
+    #[snafu(context(false), display("{source}"))]
+    RockPaperScissors {
+        source: crate::rock_paper_scissors::RockPaperScissorsError,
+    },
+    #[cfg(feature = "score_four")]
+    #[snafu(context(false), display("{source}"))]
+    ScoreFour {
+        source: crate::score_four::ScoreFourError,
+    },
+    #[cfg(feature = "sgf")]
+    #[snafu(context(false), display("{source}"))]
+    Sgf { source: crate::sgf::SgfError },
+    #[cfg(feature = "shut_the_box")]
+    #[snafu(context(false), display("{source}"))]
+    ShutTheBox {
+        source: crate::shut_the_box::ShutTheBoxError,
+    },
+    #[cfg(feature = "sos")]
+    #[snafu(context(false), display("{source}"))]
+    Sos { source: crate::sos::SosError },
+    #[cfg(feature = "tictactoe")]
+    #[snafu(context(false), display("{source}"))]
+    TicTacToe {
+        source: crate::tictactoe::TicTacToeError,
+    },
+}
+
+impl Code for Error {
+    /// Get the code of the module error
+    fn code(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "binairo")]
+            Self::Binairo { source } => source.code(),
+            #[cfg(feature = "connect_four")]
+            Self::ConnectFour { source } => source.code(),
+            #[cfg(feature = "connect6")]
+            Self::Connect6 { source } => source.code(),
+            #[cfg(feature = "dice")]
+            Self::Dice { source } => source.code(),
+            #[cfg(feature = "dominoes")]
+            Self::Dominoes { source } => source.code(),
+            #[cfg(feature = "drop_three")]
+            Self::DropThree { source } => source.code(),
+            #[cfg(feature = "fairness")]
+            Self::Fairness { source } => source.code(),
+            #[cfg(feature = "fox_and_geese")]
+            Self::FoxAndGeese { source } => source.code(),
+            #[cfg(feature = "gomoku")]
+            Self::Gomoku { source } => source.code(),
+            #[cfg(feature = "hasami_shogi")]
+            Self::HasamiShogi { source } => source.code(),
+            Self::Invariant { source } => source.code(),
+            #[cfg(feature = "liars_dice")]
+            Self::LiarsDice { source } => source.code(),
+            #[cfg(any(feature = "minesweeper", feature = "minesweeper_fixed"))]
+            Self::Minesweeper { source } => source.code(),
+            #[cfg(feature = "minesweeper")]
+            Self::MinesweeperFlags { source } => source.code(),
+            #[cfg(feature = "minesweeper")]
+            Self::MinesweeperReplay { source } => source.code(),
+            #[cfg(feature = "orchestration")]
+            Self::Orchestration { source } => source.code(),
+            #[cfg(feature = "order_and_chaos")]
+            Self::OrderAndChaos { source } => source.code(),
+            Self::Parse { source } => source.code(),
+            #[cfg(feature = "pente")]
+            Self::Pente { source } => source.code(),
+            #[cfg(feature = "pig")]
+            Self::Pig { source } => source.code(),
+            #[cfg(feature = "reversi")]
+            Self::Reversi { source } => source.code(),
+            #[cfg(feature = "rock_paper_scissors")]
+            Self::RockPaperScissors { source } => source.code(),
+            #[cfg(feature = "score_four")]
+            Self::ScoreFour { source } => source.code(),
+            #[cfg(feature = "sgf")]
+            Self::Sgf { source } => source.code(),
+            #[cfg(feature = "shut_the_box")]
+            Self::ShutTheBox { source } => source.code(),
+            #[cfg(feature = "sos")]
+            Self::Sos { source } => source.code(),
+            #[cfg(feature = "tictactoe")]
+            Self::TicTacToe { source } => source.code(),
+        }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for Error {
+    /// Write the code of the module error
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.write_str(self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{code::Code, tictactoe::TicTacToe, *};
+
+    #[test]
+    fn test() {
+        let put = || -> Result<(), Error> {
+            let mut game = TicTacToe::new().unwrap();
+            game.put(1, 1)?;
+            game.put(1, 1)?;
+            Ok(())
+        };
+
+        assert_eq!(
+            put(),
+            Err(Error::TicTacToe {
+                source: tictactoe::TicTacToeError::PositionOccupied
+            })
+        );
+        assert_eq!(put().unwrap_err().code(), "position_occupied");
+        assert_eq!(
+            Error::from(tictactoe::TicTacToeError::GameEnded).code(),
+            "game_ended"
+        );
+
+        let mut written = std::string::String::new();
+        let err = Error::from(tictactoe::TicTacToeError::PositionOccupied);
+        ufmt::uwrite!(written, "{} {:?}", err, err).unwrap();
+        assert_eq!(
+            written,
+            "position_occupied TicTacToe { source: PositionOccupied }"
+        );
+    }
+}
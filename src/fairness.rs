@@ -0,0 +1,208 @@
+//! Provably fair boards and shuffles by commit-reveal
+//!
+//! 1. The server picks a secret seed, and publishes its [`Commitment`]
+//! 2. The client contributes entropy of its own
+//! 3. The server reveals the seed, and both sides derive the same [`FairRng`] from the seed and the entropy
+//!
+//! The server cannot pick a seed in favor of a board after seeing the entropy, as the seed is bound by the commitment, and the client cannot predict the board from the entropy alone
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "minesweeper")]
+//! # fn minesweeper() {
+//! use gamie::{
+//!     fairness::{Commitment, FairRng},
+//!     minesweeper::Minesweeper,
+//! };
+//!
+//! // server
+//! let server_seed = [7; 32];
+//! let commitment = Commitment::new(&server_seed);
+//!
+//! // client
+//! let client_entropy = b"entropy of the client";
+//!
+//! let rng = FairRng::new(&server_seed, client_entropy);
+//! let mut game = Minesweeper::new(8, 8, 10, rng).unwrap();
+//! game.click(4, 4, false).unwrap();
+//!
+//! // the client verifies the board after the seed is revealed
+//! let rng = FairRng::reveal(&commitment, &server_seed, client_entropy).unwrap();
+//! let mut verified = Minesweeper::new(8, 8, 10, rng).unwrap();
+//! verified.click(4, 4, false).unwrap();
+//!
+//! assert!(game.diff(&verified).cells.is_empty());
+//! # }
+//! # #[cfg(feature = "minesweeper")]
+//! # minesweeper();
+//! ```
+
+use crate::rng::GameRng;
+use sha2::{Digest, Sha256};
+use snafu::Snafu;
+
+/// The secret seed of a server
+pub type Seed = [u8; 32];
+
+// separates the hashes of this module from the hashes of the same bytes elsewhere
+const COMMITMENT_DOMAIN: &[u8] = b"gamie fairness commitment";
+const KEY_DOMAIN: &[u8] = b"gamie fairness key";
+
+/// The hash of a server seed, published before the client contributes entropy
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Commitment(pub [u8; 32]);
+
+/// A random number generator derived from a server seed and client entropy
+///
+/// The numbers are SHA-256 blocks of the derived key and a counter. [`GameRng::gen_below`] rejects the samples that would bias the result, so every number below the bound is equally likely
+#[derive(Clone, Debug)]
+pub struct FairRng {
+    key: [u8; 32],
+    counter: u64,
+    block: [u8; 32],
+    offset: usize,
+}
+
+/// Errors that can occur when revealing a server seed
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum FairnessError {
+    #[snafu(display("seed does not match the commitment"))]
+    CommitmentMismatch,
+}
+
+crate::code::codes!(FairnessError {
+    CommitmentMismatch => "commitment_mismatch",
+});
+
+impl Commitment {
+    /// Commit to a server seed
+    pub fn new(server_seed: &Seed) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(COMMITMENT_DOMAIN);
+        hasher.update(server_seed);
+
+        Self(hasher.finalize().into())
+    }
+
+    /// Check if a revealed server seed is the one committed to
+    pub fn verify(&self, server_seed: &Seed) -> bool {
+        Self::new(server_seed) == *self
+    }
+}
+
+impl FairRng {
+    /// Derive the generator from a server seed and the entropy of the client
+    pub fn new(server_seed: &Seed, client_entropy: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(KEY_DOMAIN);
+        hasher.update(server_seed);
+        hasher.update(client_entropy);
+
+        let mut rng = Self {
+            key: hasher.finalize().into(),
+            counter: 0,
+            block: [0; 32],
+            offset: 0,
+        };
+
+        rng.refill();
+        rng
+    }
+
+    /// Derive the generator from a revealed server seed, after verifying it against the commitment
+    ///
+    /// Return `Err(FairnessError::CommitmentMismatch)` if the seed is not the one committed to
+    pub fn reveal(
+        commitment: &Commitment,
+        server_seed: &Seed,
+        client_entropy: &[u8],
+    ) -> Result<Self, FairnessError> {
+        if !commitment.verify(server_seed) {
+            return Err(FairnessError::CommitmentMismatch);
+        }
+
+        Ok(Self::new(server_seed, client_entropy))
+    }
+
+    fn refill(&mut self) {
+        let mut hasher = Sha256::new();
+        hasher.update(self.key);
+        hasher.update(self.counter.to_le_bytes());
+
+        self.block = hasher.finalize().into();
+        self.counter += 1;
+        self.offset = 0;
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        if self.offset == self.block.len() {
+            self.refill();
+        }
+
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&self.block[self.offset..self.offset + 8]);
+        self.offset += 8;
+
+        u64::from_le_bytes(bytes)
+    }
+}
+
+impl GameRng for FairRng {
+    fn gen_below(&mut self, bound: usize) -> usize {
+        let bound = bound as u64;
+
+        // the largest multiple of `bound` that fits, samples above it would favor the small numbers
+        let zone = u64::MAX - u64::MAX % bound;
+
+        loop {
+            let sample = self.next_u64();
+
+            if sample < zone {
+                return (sample % bound) as usize;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fairness::*, rng::GameRng};
+
+    #[test]
+    fn test() {
+        let commitment = Commitment::new(&[1; 32]);
+
+        assert!(commitment.verify(&[1; 32]));
+        assert!(!commitment.verify(&[2; 32]));
+        assert_eq!(
+            FairRng::reveal(&commitment, &[2; 32], b"entropy").unwrap_err(),
+            FairnessError::CommitmentMismatch
+        );
+
+        let mut rng = FairRng::reveal(&commitment, &[1; 32], b"entropy").unwrap();
+        let mut other = FairRng::new(&[1; 32], b"other entropy");
+
+        let mut cards: [usize; 52] = core::array::from_fn(|idx| idx);
+        let mut other_cards = cards;
+
+        rng.shuffle(&mut cards);
+        other.shuffle(&mut other_cards);
+        assert_ne!(cards, other_cards);
+
+        // the same seed and entropy always give the same numbers, across refills
+        let mut rng = FairRng::new(&[1; 32], b"entropy");
+        let mut replayed = FairRng::new(&[1; 32], b"entropy");
+
+        for bound in 1..100 {
+            let number = rng.gen_below(bound);
+
+            assert!(number < bound);
+            assert_eq!(replayed.gen_below(bound), number);
+        }
+    }
+}
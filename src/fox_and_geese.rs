@@ -0,0 +1,647 @@
+//! Fox and Geese
+//!
+//! Check struct [`FoxAndGeese`] for more information
+
+use crate::{
+    invariant::InvariantError,
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use core::{
+    convert::Infallible,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+use snafu::Snafu;
+
+pub(crate) const BOARD_SIZE: usize = 7;
+
+// the geese can no longer trap the fox with fewer pieces
+const INITIAL_GEESE: usize = 13;
+const MIN_GEESE: usize = 6;
+
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+/// Fox and Geese
+///
+/// Played on the 33 points of a cross-shaped board, laid out on a 7x7 grid without the 2x2 corners. 13 geese start on rows `4` to `6` and the fox at the center. Pieces move one step along a row or column onto an empty point, and geese cannot move backwards, towards row `6`. The fox captures a goose by jumping over it onto the empty point right behind, one capture per turn. The geese move first
+///
+/// The geese win by leaving the fox no legal move. The fox wins when fewer than 6 geese remain, or when the geese cannot move
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::fox_and_geese::{FoxAndGeese, Piece, Player};
+/// let mut game = FoxAndGeese::new().unwrap();
+///
+/// game.move_piece((4, 3), (3, 3)).unwrap_err();
+/// game.move_piece((4, 2), (3, 2)).unwrap();
+/// game.move_piece((3, 3), (2, 3)).unwrap();
+///
+/// assert_eq!(game.get(2, 3), Some(Piece::Fox));
+/// assert_eq!(game.next_player(), Player::Geese);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FoxAndGeese {
+    board: [[Option<Piece>; BOARD_SIZE]; BOARD_SIZE],
+    fox: (usize, usize),
+    geese: usize,
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(FoxAndGeese);
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Fox,
+    Geese,
+}
+
+/// A piece on the board
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Piece {
+    Fox,
+    Goose,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    /// Only reached by agreement, see [`FoxAndGeese::accept_draw`]
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Fox,
+    1 => Geese,
+});
+
+crate::canonical::encode_enum!(Piece {
+    0 => Fox,
+    1 => Goose,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::canonical!(FoxAndGeese, "fox_and_geese", 1 {
+    board,
+    fox,
+    geese,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+});
+
+/// A move, which moves a piece from a point to another, each point being `(row, col)`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Move {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    /// Positions are always in bounds
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            from: (u.choose_index(BOARD_SIZE)?, u.choose_index(BOARD_SIZE)?),
+            to: (u.choose_index(BOARD_SIZE)?, u.choose_index(BOARD_SIZE)?),
+        })
+    }
+}
+
+/// Errors that can occur when moving a piece
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum FoxAndGeeseError {
+    #[snafu(display("not a piece of the next player"))]
+    NotOwnPiece,
+    #[snafu(display("invalid move"))]
+    InvalidMove,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("position out of bounds"))]
+    OutOfBounds,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+}
+
+crate::code::codes!(FoxAndGeeseError {
+    NotOwnPiece => "not_own_piece",
+    InvalidMove => "invalid_move",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+    NoDrawOffer => "no_draw_offer",
+});
+
+impl FoxAndGeese {
+    /// Create a new Fox and Geese game
+    pub const fn new() -> Result<Self, Infallible> {
+        let mut board = [[None; BOARD_SIZE]; BOARD_SIZE];
+
+        let mut col = 0;
+        while col < BOARD_SIZE {
+            board[4][col] = Some(Piece::Goose);
+            col += 1;
+        }
+
+        let mut col = 2;
+        while col < 5 {
+            board[5][col] = Some(Piece::Goose);
+            board[6][col] = Some(Piece::Goose);
+            col += 1;
+        }
+
+        board[3][3] = Some(Piece::Fox);
+
+        Ok(Self {
+            board,
+            fox: (3, 3),
+            geese: INITIAL_GEESE,
+            next_player: Player::Geese,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+        })
+    }
+
+    /// Create a Fox and Geese game by replaying a list of moves from the starting position
+    ///
+    /// Return the index of the first invalid move and the error it caused on failure
+    pub fn from_moves(moves: &[Move]) -> Result<Self, (usize, FoxAndGeeseError)> {
+        let mut game = Self::new().unwrap();
+
+        for (idx, mv) in moves.iter().enumerate() {
+            let is_out_of_bounds = [mv.from, mv.to]
+                .into_iter()
+                .any(|(row, col)| row >= BOARD_SIZE || col >= BOARD_SIZE);
+
+            if is_out_of_bounds {
+                return Err((idx, FoxAndGeeseError::OutOfBounds));
+            }
+
+            game.apply(*mv).map_err(|err| (idx, err))?;
+        }
+
+        Ok(game)
+    }
+
+    /// Check if a position of the 7x7 grid is a point on the cross-shaped board
+    pub const fn is_on_board(row: usize, col: usize) -> bool {
+        row < BOARD_SIZE && col < BOARD_SIZE && ((row >= 2 && row < 5) || (col >= 2 && col < 5))
+    }
+
+    /// Get a piece at a position, always `None` outside the cross-shaped board
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Option<Piece> {
+        self.board[row][col]
+    }
+
+    /// Get a piece at a position without bounds checking, for the hot loops of a search that keep the positions in bounds already
+    ///
+    /// The bounds are still checked in debug builds
+    ///
+    /// # Safety
+    ///
+    /// The target position must be in bounds, see [`get`](Self::get)
+    pub const unsafe fn get_unchecked(&self, row: usize, col: usize) -> Option<Piece> {
+        debug_assert!(row < self.board.len() && col < self.board[0].len());
+
+        // SAFETY: the caller keeps the position in bounds
+        unsafe { *(*self.board.as_ptr().add(row)).as_ptr().add(col) }
+    }
+
+    /// Move a piece of the next player
+    ///
+    /// Panic if a target position is out of bounds
+    pub fn move_piece(
+        &mut self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Result<(), FoxAndGeeseError> {
+        if self.status != Status::Ongoing {
+            return Err(FoxAndGeeseError::GameEnded);
+        }
+
+        let piece = match self.next_player {
+            Player::Fox => Piece::Fox,
+            Player::Geese => Piece::Goose,
+        };
+
+        if self.board[from.0][from.1] != Some(piece) {
+            return Err(FoxAndGeeseError::NotOwnPiece);
+        }
+
+        match piece {
+            Piece::Goose if self.is_goose_step(from, to) => {}
+            Piece::Fox if self.is_step(from, to) => self.fox = to,
+            Piece::Fox if self.is_jump(from, to) => {
+                self.board[(from.0 + to.0) / 2][(from.1 + to.1) / 2] = None;
+                self.geese -= 1;
+                self.fox = to;
+            }
+            _ => return Err(FoxAndGeeseError::InvalidMove),
+        }
+
+        self.board[from.0][from.1] = None;
+        self.board[to.0][to.1] = Some(piece);
+
+        self.draw_offer = None;
+        self.next_player = self.next_player.other();
+
+        let is_next_player_stuck = match self.next_player {
+            Player::Fox => !self.is_fox_movable(),
+            Player::Geese => !self.are_geese_movable(),
+        };
+
+        if self.geese < MIN_GEESE {
+            self.status = Status::Win(Player::Fox);
+        } else if is_next_player_stuck {
+            self.status = Status::Win(self.next_player.other());
+        }
+
+        Ok(())
+    }
+
+    /// Check if a piece of the next player can be moved, same as [`move_piece`](Self::move_piece) succeeding but without changing the game
+    ///
+    /// Panic if a target position is out of bounds
+    pub fn can_move(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        if self.status != Status::Ongoing {
+            return false;
+        }
+
+        match (self.next_player, self.board[from.0][from.1]) {
+            (Player::Fox, Some(Piece::Fox)) => self.is_step(from, to) || self.is_jump(from, to),
+            (Player::Geese, Some(Piece::Goose)) => self.is_goose_step(from, to),
+            _ => false,
+        }
+    }
+
+    /// Apply a move, same as [`move_piece`](Self::move_piece)
+    ///
+    /// Panic if a target position is out of bounds
+    pub fn apply(&mut self, mv: Move) -> Result<(), FoxAndGeeseError> {
+        self.move_piece(mv.from, mv.to)
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    ///
+    /// The game holds no allocation, so this is a single copy of the game followed by [`apply`](Self::apply)
+    ///
+    /// Panic if a target position is out of bounds
+    pub fn preview(&self, mv: Move) -> Result<Self, FoxAndGeeseError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, the position of a piece and the position it moves to as described in [`parse`](crate::parse), optionally joined by `to`, e.g. `8 0 to 5 0`
+    pub fn parse_move(text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+        let from = words.position(BOARD_SIZE, BOARD_SIZE)?;
+        words.take("to");
+        let to = words.position(BOARD_SIZE, BOARD_SIZE)?;
+
+        words.finish()?;
+        Ok(Move { from, to })
+    }
+
+    /// Get the position of the fox
+    pub const fn fox(&self) -> (usize, usize) {
+        self.fox
+    }
+
+    /// Get the number of geese left
+    pub const fn geese(&self) -> usize {
+        self.geese
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// Pieces must stand on the points of the board, with the fox where it is tracked and no more geese than at the start, and a status ended by the rules must match the geese and the moves left
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let mut foxes = 0;
+        let mut geese = 0;
+
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                match self.board[row][col] {
+                    Some(_) if !Self::is_on_board(row, col) => return Err(InvariantError::Board),
+                    Some(Piece::Fox) if (row, col) != self.fox => {
+                        return Err(InvariantError::Board)
+                    }
+                    Some(Piece::Fox) => foxes += 1,
+                    Some(Piece::Goose) => geese += 1,
+                    None => {}
+                }
+            }
+        }
+
+        if foxes != 1 {
+            return Err(InvariantError::Board);
+        }
+
+        if geese != self.geese || geese > INITIAL_GEESE {
+            return Err(InvariantError::Counter);
+        }
+
+        let is_next_player_stuck = match self.next_player {
+            Player::Fox => !self.is_fox_movable(),
+            Player::Geese => !self.are_geese_movable(),
+        };
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => {
+                self.geese >= MIN_GEESE && !is_next_player_stuck
+            }
+            (Status::Ongoing, _) => false,
+            (Status::Win(Player::Fox), EndReason::Normal) => {
+                self.geese < MIN_GEESE || self.next_player == Player::Geese && is_next_player_stuck
+            }
+            (Status::Win(Player::Geese), EndReason::Normal) => {
+                self.next_player == Player::Fox && is_next_player_stuck
+            }
+            // a draw is only reached by agreement
+            (Status::Draw, EndReason::Normal) => false,
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), FoxAndGeeseError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), FoxAndGeeseError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), FoxAndGeeseError> {
+        if self.status != Status::Ongoing {
+            return Err(FoxAndGeeseError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), FoxAndGeeseError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(FoxAndGeeseError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), FoxAndGeeseError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), FoxAndGeeseError> {
+        if self.status != Status::Ongoing {
+            return Err(FoxAndGeeseError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Check if `to` is an empty point next to `from`
+    fn is_step(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        from.0.abs_diff(to.0) + from.1.abs_diff(to.1) == 1
+            && Self::is_on_board(to.0, to.1)
+            && self.board[to.0][to.1].is_none()
+    }
+
+    fn is_goose_step(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        to.0 <= from.0 && self.is_step(from, to)
+    }
+
+    /// Check if `to` is an empty point two points away from `from` in a line, with a goose in between
+    fn is_jump(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        let is_in_line = (from.0 == to.0 && from.1.abs_diff(to.1) == 2)
+            || (from.1 == to.1 && from.0.abs_diff(to.0) == 2);
+
+        is_in_line
+            && Self::is_on_board(to.0, to.1)
+            && self.board[to.0][to.1].is_none()
+            && self.board[(from.0 + to.0) / 2][(from.1 + to.1) / 2] == Some(Piece::Goose)
+    }
+
+    fn is_fox_movable(&self) -> bool {
+        let (row, col) = self.fox;
+
+        DIRECTIONS.into_iter().any(|(row_step, col_step)| {
+            [1, 2].into_iter().any(|distance| {
+                let to = (
+                    row as isize + row_step * distance,
+                    col as isize + col_step * distance,
+                );
+
+                to.0 >= 0 && to.1 >= 0 && {
+                    let to = (to.0 as usize, to.1 as usize);
+                    self.is_step(self.fox, to) || self.is_jump(self.fox, to)
+                }
+            })
+        })
+    }
+
+    fn are_geese_movable(&self) -> bool {
+        (0..BOARD_SIZE)
+            .flat_map(|row| (0..BOARD_SIZE).map(move |col| (row, col)))
+            .filter(|(row, col)| self.board[*row][*col] == Some(Piece::Goose))
+            .any(|(row, col)| {
+                [
+                    (row.wrapping_sub(1), col),
+                    (row, col.wrapping_sub(1)),
+                    (row, col + 1),
+                ]
+                .into_iter()
+                .any(|to| self.is_goose_step((row, col), to))
+            })
+    }
+}
+
+impl Player {
+    /// Get the other player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Fox => Player::Geese,
+            Player::Geese => Player::Fox,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let ((from_row, from_col), (to_row, to_col)) = (self.from, self.to);
+        write!(f, "{from_row} {from_col} to {to_row} {to_col}")
+    }
+}
+
+#[cfg(feature = "testing")]
+impl FoxAndGeese {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    pub fn random_position<R: GameRng + ?Sized>(rng: &mut R, plies: usize) -> Self {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        game.play_random_position(rng, plies);
+        game
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for FoxAndGeese {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let moves = testing::positions(BOARD_SIZE, BOARD_SIZE)
+            .flat_map(|from| {
+                testing::positions(BOARD_SIZE, BOARD_SIZE).map(move |to| Move { from, to })
+            })
+            .filter(|mv| self.can_move(mv.from, mv.to));
+
+        testing::choose(rng, moves)
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fox_and_geese::*;
+
+    #[test]
+    fn test() {
+        let mut game = FoxAndGeese::new().unwrap();
+
+        assert!(!FoxAndGeese::is_on_board(0, 0));
+        assert!(FoxAndGeese::is_on_board(0, 2));
+        assert_eq!(
+            game.move_piece((3, 3), (2, 3)),
+            Err(FoxAndGeeseError::NotOwnPiece)
+        );
+        assert_eq!(
+            game.move_piece((5, 2), (5, 1)),
+            Err(FoxAndGeeseError::InvalidMove)
+        );
+        assert!(!game.can_move((3, 3), (2, 3)));
+        assert!(!game.can_move((5, 2), (5, 1)));
+        assert!(game.can_move((4, 2), (3, 2)));
+
+        game.move_piece((4, 2), (3, 2)).unwrap();
+        game.move_piece((3, 3), (2, 3)).unwrap();
+        game.move_piece((4, 3), (3, 3)).unwrap();
+
+        // the fox jumps over the goose at (3, 3) onto (4, 3)
+        game.apply(Move {
+            from: (2, 3),
+            to: (4, 3),
+        })
+        .unwrap();
+
+        assert_eq!(game.geese(), 12);
+        assert_eq!(game.get(3, 3), None);
+        assert_eq!(game.fox(), (4, 3));
+
+        // geese cannot move backwards
+        assert!(!game.can_move((3, 2), (4, 2)));
+        assert_eq!(
+            game.move_piece((3, 2), (4, 2)),
+            Err(FoxAndGeeseError::InvalidMove)
+        );
+    }
+}
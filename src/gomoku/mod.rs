@@ -0,0 +1,1108 @@
+//! Gomoku
+//!
+//! Check struct [`Gomoku`] for more information
+
+pub mod notation;
+
+mod threat;
+
+pub use self::threat::Puzzle;
+use crate::{
+    invariant::{self, InvariantError},
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+    util::{KInARow, LINES},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use core::{
+    convert::Infallible,
+    fmt::{Display, Formatter, Result as FmtResult},
+    hash::{Hash, Hasher},
+};
+use snafu::Snafu;
+
+pub(crate) const BOARD_WIDTH: usize = 15;
+pub(crate) const BOARD_HEIGHT: usize = 15;
+
+// the number of pieces in a row a player wins with
+const LINE_LENGTH: usize = 5;
+
+// the distance from a piece within which the empty positions are candidate moves
+const CANDIDATE_DISTANCE: usize = 2;
+
+/// Gomoku
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::gomoku::Gomoku;
+/// let mut game = Gomoku::new().unwrap();
+/// game.put(7, 8).unwrap();
+/// game.put(8, 7).unwrap();
+/// // ...
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Gomoku {
+    board: Pieces,
+    move_count: usize,
+    // the move number of each position, starting from 1, or 0 if empty or a piece of the starting position of a composed game
+    order: [[u8; BOARD_WIDTH]; BOARD_HEIGHT],
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+    is_swapped: bool,
+    // the candidate moves, one bit per column in each row
+    candidates: [u16; BOARD_HEIGHT],
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(Gomoku);
+
+// games are compared by position, leaving out the order of the moves and the candidate moves that follow from it
+impl PartialEq for Gomoku {
+    fn eq(&self, other: &Self) -> bool {
+        self.position_key() == other.position_key()
+    }
+}
+
+impl Eq for Gomoku {}
+
+impl Hash for Gomoku {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.position_key().hash(state);
+    }
+}
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    /// The board is full, or the game was drawn otherwise, see [`Gomoku::end_reason`]
+    ///
+    /// Every empty position is a legal move, so a full board is the only way the next player runs out of moves
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::encode_struct!(Pieces { 0 });
+
+crate::canonical::canonical!(Gomoku, "gomoku", 1 {
+    board,
+    move_count,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+    is_swapped,
+});
+
+/// A move, which places a piece at a position
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    /// Positions are always in bounds
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            row: u.choose_index(BOARD_HEIGHT)?,
+            col: u.choose_index(BOARD_WIDTH)?,
+        })
+    }
+}
+
+/// The change of a position between two game states
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CellDiff {
+    pub before: Option<Player>,
+    pub after: Option<Player>,
+}
+
+/// Differences between two game states, see [`Gomoku::diff`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BoardDiff {
+    /// The change of each position, `None` if unchanged
+    pub cells: [[Option<CellDiff>; BOARD_WIDTH]; BOARD_HEIGHT],
+    /// The next player before and after, `None` if unchanged
+    pub next_player: Option<(Player, Player)>,
+    /// The game status before and after, `None` if unchanged
+    pub status: Option<(Status, Status)>,
+}
+
+/// Errors that can occur when placing a piece onto the board
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum GomokuError {
+    #[snafu(display("position occupied"))]
+    PositionOccupied,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("position out of bounds"))]
+    OutOfBounds,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+    #[snafu(display("swap not allowed"))]
+    SwapNotAllowed,
+}
+
+crate::code::codes!(GomokuError {
+    PositionOccupied => "position_occupied",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+    NoDrawOffer => "no_draw_offer",
+    SwapNotAllowed => "swap_not_allowed",
+});
+
+/// A board of positions, each holding the piece of a player or nothing
+type Board = [[Option<Player>; BOARD_WIDTH]; BOARD_HEIGHT];
+
+/// The pieces of both players, one bit per column in each row for each player
+///
+/// A clone of the game is a plain copy of a few bytes, which keeps searching cheap. It is serialized as the board of positions
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "Board", into = "Board")
+)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct Pieces([[u16; BOARD_HEIGHT]; 2]);
+
+struct LastMove {
+    player: Player,
+    row: usize,
+    col: usize,
+}
+
+impl Gomoku {
+    /// Create a new Gomoku game
+    pub const fn new() -> Result<Self, Infallible> {
+        Ok(Self::initial())
+    }
+
+    /// Create a new Gomoku game, the same as [`new`](Self::new) without the `Result`
+    pub const fn initial() -> Self {
+        Self {
+            board: Pieces::EMPTY,
+            move_count: 0,
+            order: [[0; BOARD_WIDTH]; BOARD_HEIGHT],
+            next_player: Player::Player0,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+            is_swapped: false,
+            candidates: [0; BOARD_HEIGHT],
+        }
+    }
+
+    /// Create a Gomoku game by replaying a list of moves from an empty board
+    ///
+    /// Return the index of the first invalid move and the error it caused on failure
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::{Gomoku, Player};
+    /// let game = Gomoku::from_moves(&[(7, 7), (7, 8)]).unwrap();
+    ///
+    /// assert_eq!(game.next_player(), Player::Player0);
+    /// assert!(Gomoku::from_moves(&[(7, 7), (15, 0)]).is_err());
+    /// ```
+    pub const fn from_moves(moves: &[(usize, usize)]) -> Result<Self, (usize, GomokuError)> {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        let mut idx = 0;
+
+        while idx < moves.len() {
+            let (row, col) = moves[idx];
+
+            if row >= BOARD_HEIGHT || col >= BOARD_WIDTH {
+                return Err((idx, GomokuError::OutOfBounds));
+            }
+
+            if let Err(err) = game.put(row, col) {
+                return Err((idx, err));
+            }
+
+            idx += 1;
+        }
+
+        Ok(game)
+    }
+
+    /// Compose a Gomoku game from a board and the next player, e.g. for a hand-authored puzzle
+    ///
+    /// The status is read from the board. The move order of a composed game is unknown, so the board is its starting position: [`moves`](Self::moves) only lists the moves played afterwards, and [`write_sgf`](Self::write_sgf) writes the board as setup properties
+    ///
+    /// Return the error of [`validate`](Self::validate) if the position could not have been reached by playing
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::{Gomoku, Player, Status};
+    /// let mut board = [[None; 15]; 15];
+    ///
+    /// for col in 3..7 {
+    ///     board[7][col] = Some(Player::Player0);
+    ///     board[8][col] = Some(Player::Player1);
+    /// }
+    ///
+    /// let mut game = Gomoku::from_board(board, Player::Player0).unwrap();
+    /// game.put(7, 7).unwrap();
+    ///
+    /// assert_eq!(game.status(), &Status::Win(Player::Player0));
+    /// ```
+    pub fn from_board(
+        board: [[Option<Player>; BOARD_WIDTH]; BOARD_HEIGHT],
+        next_player: Player,
+    ) -> Result<Self, InvariantError> {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        let count = |player| {
+            board
+                .iter()
+                .flatten()
+                .filter(|cell| **cell == Some(player))
+                .count()
+        };
+
+        // the players must have taken turns to reach the board
+        invariant::check_turns(
+            count(Player::Player0),
+            count(Player::Player1),
+            next_player == Player::Player0,
+        )?;
+
+        // the pieces of the starting position are not numbered, the moves played afterwards are numbered from the piece count on
+        game.move_count = count(Player::Player0) + count(Player::Player1);
+
+        game.board = Pieces::from(board);
+        game.next_player = next_player;
+        game.candidates = game.candidates_of_board();
+
+        let has_line = |player| {
+            Self::lines().any(|line| {
+                line.iter()
+                    .all(|&(row, col)| board[row][col] == Some(player))
+            })
+        };
+
+        game.status = if has_line(Player::Player0) {
+            Status::Win(Player::Player0)
+        } else if has_line(Player::Player1) {
+            Status::Win(Player::Player1)
+        } else if game.move_count == BOARD_HEIGHT * BOARD_WIDTH {
+            Status::Draw
+        } else {
+            Status::Ongoing
+        };
+
+        game.validate()?;
+        Ok(game)
+    }
+
+    /// Get a piece at a position
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Option<Player> {
+        assert!(col < BOARD_WIDTH);
+
+        // SAFETY: the row is checked by indexing, and the column above
+        unsafe { self.get_unchecked(row, col) }
+    }
+
+    /// Get a piece at a position without bounds checking, for the hot loops of a search that keep the positions in bounds already
+    ///
+    /// The bounds are still checked in debug builds
+    ///
+    /// # Safety
+    ///
+    /// The target position must be in bounds, see [`get`](Self::get)
+    pub const unsafe fn get_unchecked(&self, row: usize, col: usize) -> Option<Player> {
+        debug_assert!(row < BOARD_HEIGHT && col < BOARD_WIDTH);
+
+        // SAFETY: the caller keeps the position in bounds
+        let (player0, player1) = unsafe {
+            (
+                *self.board.0[0].as_ptr().add(row),
+                *self.board.0[1].as_ptr().add(row),
+            )
+        };
+
+        if player0 & 1 << col != 0 {
+            Some(Player::Player0)
+        } else if player1 & 1 << col != 0 {
+            Some(Player::Player1)
+        } else {
+            None
+        }
+    }
+
+    /// Put a piece
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn put(&mut self, row: usize, col: usize) -> Result<(), GomokuError> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(GomokuError::GameEnded);
+        }
+
+        if self.get(row, col).is_some() {
+            return Err(GomokuError::PositionOccupied);
+        }
+
+        self.board.0[self.next_player as usize][row] |= 1 << col;
+
+        let last_move = LastMove {
+            player: self.next_player,
+            row,
+            col,
+        };
+
+        self.move_count += 1;
+        self.order[row][col] = self.move_count as u8;
+        self.draw_offer = None;
+        self.next_player = self.next_player.other();
+
+        self.add_candidates_around(row, col);
+        self.update_status(last_move);
+
+        Ok(())
+    }
+
+    /// Check if a piece can be put at a position, same as [`put`](Self::put) succeeding but without changing the game
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn can_put(&self, row: usize, col: usize) -> bool {
+        !matches!(self.status, Status::Win(_) | Status::Draw) && self.get(row, col).is_none()
+    }
+
+    /// Apply a move, same as [`put`](Self::put)
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn apply(&mut self, mv: Move) -> Result<(), GomokuError> {
+        self.put(mv.row, mv.col)
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    ///
+    /// The game holds no allocation, so this is a single copy of the game followed by [`apply`](Self::apply)
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn preview(&self, mv: Move) -> Result<Self, GomokuError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, a position as described in [`parse`](crate::parse)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::Gomoku;
+    /// // the center, also `h8` in Gomoku notation
+    /// assert_eq!(Gomoku::parse_move("7,7"), Gomoku::parse_move("h8"));
+    /// ```
+    pub fn parse_move(text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+        let (row, col) = words.position(BOARD_HEIGHT, BOARD_WIDTH)?;
+
+        words.finish()?;
+        Ok(Move { row, col })
+    }
+
+    /// Swap sides by the pie rule, only allowed right after the first move
+    ///
+    /// [`Player::Player1`] takes over the first stone, and [`Player::Player0`] moves next. Move lists written by [`moves`](Self::moves) do not record the swap, check [`is_swapped`](Self::is_swapped) instead
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::{Gomoku, Player};
+    /// let mut game = Gomoku::new().unwrap();
+    /// game.put(7, 7).unwrap();
+    ///
+    /// game.swap().unwrap();
+    ///
+    /// assert_eq!(game.get(7, 7), Some(Player::Player1));
+    /// assert_eq!(game.next_player(), Player::Player0);
+    /// ```
+    pub fn swap(&mut self) -> Result<(), GomokuError> {
+        if self.status != Status::Ongoing {
+            return Err(GomokuError::GameEnded);
+        }
+
+        if self.move_count != 1 || self.is_swapped {
+            return Err(GomokuError::SwapNotAllowed);
+        }
+
+        self.board.0.swap(0, 1);
+
+        self.next_player = self.next_player.other();
+        self.draw_offer = None;
+        self.is_swapped = true;
+
+        Ok(())
+    }
+
+    /// Check if the sides were swapped by the pie rule, see [`swap`](Self::swap)
+    pub const fn is_swapped(&self) -> bool {
+        self.is_swapped
+    }
+
+    /// Iterate over the candidate moves as `(row, col)`, in row-major order
+    ///
+    /// The candidates are the empty positions within 2 positions of a piece, orthogonally or diagonally, which covers the moves worth searching on all but an empty board. The set is kept up to date as pieces are put, so it is cheap to get at every node of a search. On an empty board there are no candidates, where the center is the usual first move
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::Gomoku;
+    /// let mut game = Gomoku::new().unwrap();
+    /// assert_eq!(game.candidates().count(), 0);
+    ///
+    /// game.put(0, 0).unwrap();
+    /// assert!(game.candidates().eq([(0, 1), (0, 2), (1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)]));
+    /// ```
+    pub fn candidates(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let is_ongoing = matches!(self.status, Status::Ongoing);
+
+        (0..BOARD_HEIGHT)
+            .flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+            .filter(move |(row, col)| is_ongoing && self.is_candidate(*row, *col))
+    }
+
+    /// Check if a position is a candidate move, see [`candidates`](Self::candidates)
+    ///
+    /// The game status is not checked
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn is_candidate(&self, row: usize, col: usize) -> bool {
+        assert!(col < BOARD_WIDTH);
+        self.candidates[row] & 1 << col != 0
+    }
+
+    /// Get the number of candidate moves, see [`candidates`](Self::candidates)
+    ///
+    /// The game status is not checked
+    pub const fn candidate_count(&self) -> usize {
+        let mut count = 0;
+        let mut row = 0;
+
+        while row < BOARD_HEIGHT {
+            count += self.candidates[row].count_ones() as usize;
+            row += 1;
+        }
+
+        count
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// Besides the piece counts and the status, the move order must number each piece on the board played since the starting position exactly once, and the candidate moves must match the board. The pieces of the starting position of a composed game are not numbered
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let mut is_numbered = [false; BOARD_HEIGHT * BOARD_WIDTH + 1];
+        let (mut player0_count, mut player1_count) = (0, 0);
+        let starting_count = self.starting_count();
+
+        for row in 0..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                let number = self.order[row][col] as usize;
+
+                match self.get(row, col) {
+                    Some(Player::Player0) => player0_count += 1,
+                    Some(Player::Player1) => player1_count += 1,
+                    None if number == 0 => continue,
+                    None => return Err(InvariantError::Counter),
+                }
+
+                if number == 0 {
+                    continue;
+                }
+
+                if number <= starting_count || number > self.move_count || is_numbered[number] {
+                    return Err(InvariantError::Counter);
+                }
+
+                is_numbered[number] = true;
+            }
+        }
+
+        if player0_count + player1_count != self.move_count
+            || self.is_swapped && self.move_count == 0
+            || self.candidates != self.candidates_of_board()
+        {
+            return Err(InvariantError::Counter);
+        }
+
+        // after a swap, Player1 owns the first stone
+        if self.is_swapped {
+            invariant::check_turns(
+                player1_count,
+                player0_count,
+                self.next_player == Player::Player1,
+            )?;
+        } else {
+            invariant::check_turns(
+                player0_count,
+                player1_count,
+                self.next_player == Player::Player0,
+            )?;
+        }
+
+        let has_line = |player| {
+            Self::lines().any(|line| {
+                line.iter()
+                    .all(|&(row, col)| self.get(row, col) == Some(player))
+            })
+        };
+        let has_no_line = !has_line(Player::Player0) && !has_line(Player::Player1);
+        let is_full = self.move_count == BOARD_HEIGHT * BOARD_WIDTH;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => has_no_line && !is_full,
+            (Status::Ongoing, _) => false,
+            (Status::Win(player), EndReason::Normal) => {
+                has_line(*player) && !has_line(player.other())
+            }
+            (Status::Draw, EndReason::Normal) => has_no_line && is_full,
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), GomokuError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), GomokuError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), GomokuError> {
+        if self.status != Status::Ongoing {
+            return Err(GomokuError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), GomokuError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(GomokuError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), GomokuError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), GomokuError> {
+        if self.status != Status::Ongoing {
+            return Err(GomokuError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Get the game with the board rotated 90 degrees clockwise, the rest of the state kept
+    pub fn rotate90(&self) -> Self {
+        self.transform(|row, col| (col, BOARD_WIDTH - 1 - row))
+    }
+
+    /// Get the game with the board rotated 180 degrees, the rest of the state kept
+    pub fn rotate180(&self) -> Self {
+        self.transform(|row, col| (BOARD_WIDTH - 1 - row, BOARD_WIDTH - 1 - col))
+    }
+
+    /// Get the game with the board rotated 270 degrees clockwise, the rest of the state kept
+    pub fn rotate270(&self) -> Self {
+        self.transform(|row, col| (BOARD_WIDTH - 1 - col, row))
+    }
+
+    /// Get the game with the board mirrored left to right, the rest of the state kept
+    pub fn mirror_horizontal(&self) -> Self {
+        self.transform(|row, col| (row, BOARD_WIDTH - 1 - col))
+    }
+
+    /// Get the game with the board mirrored top to bottom, the rest of the state kept
+    pub fn mirror_vertical(&self) -> Self {
+        self.transform(|row, col| (BOARD_WIDTH - 1 - row, col))
+    }
+
+    /// Compare the game with a later state of it
+    ///
+    /// The returned diff describes how to get from `self` to `other`
+    pub fn diff(&self, other: &Self) -> BoardDiff {
+        let mut cells = [[None; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        for (row, cells) in cells.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                let (before, after) = (self.get(row, col), other.get(row, col));
+
+                if before != after {
+                    *cell = Some(CellDiff { before, after });
+                }
+            }
+        }
+
+        BoardDiff {
+            cells,
+            next_player: (self.next_player != other.next_player)
+                .then_some((self.next_player, other.next_player)),
+            status: (self.status != other.status)
+                .then(|| (self.status.clone(), other.status.clone())),
+        }
+    }
+
+    /// Iterate over all lines of 5 positions a player can win with, each position being `(row, col)`
+    pub fn lines() -> impl Iterator<Item = [(usize, usize); LINE_LENGTH]> {
+        (0..BOARD_HEIGHT as isize)
+            .flat_map(|row| (0..BOARD_WIDTH as isize).map(move |col| (row, col)))
+            .flat_map(|(row, col)| {
+                LINES.into_iter().filter_map(move |(row_step, col_step)| {
+                    let end_row = row + row_step * (LINE_LENGTH as isize - 1);
+                    let end_col = col + col_step * (LINE_LENGTH as isize - 1);
+
+                    let is_in_bounds = (0..BOARD_HEIGHT as isize).contains(&end_row)
+                        && (0..BOARD_WIDTH as isize).contains(&end_col);
+
+                    is_in_bounds.then(|| {
+                        core::array::from_fn(|distance| {
+                            (
+                                (row + row_step * distance as isize) as usize,
+                                (col + col_step * distance as isize) as usize,
+                            )
+                        })
+                    })
+                })
+            })
+    }
+
+    /// Get the number of lines through each empty position that a player can still win with, which are the lines without a piece of the other player
+    ///
+    /// Occupied positions are `0`. See [`lines`](Self::lines)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::{Gomoku, Player};
+    /// let game = Gomoku::new().unwrap();
+    /// let heatmap = game.heatmap(Player::Player0);
+    ///
+    /// // 5 positions in each of the 4 directions
+    /// assert_eq!(heatmap[7][7], 20);
+    /// assert_eq!(heatmap[0][0], 3);
+    /// ```
+    pub fn heatmap(&self, player: Player) -> [[usize; BOARD_WIDTH]; BOARD_HEIGHT] {
+        let mut heatmap = [[0; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        let open_lines = Self::lines().filter(|line| {
+            line.iter()
+                .all(|(row, col)| self.get(*row, *col) != Some(player.other()))
+        });
+
+        for line in open_lines {
+            for (row, col) in line {
+                if self.get(row, col).is_none() {
+                    heatmap[row][col] += 1;
+                }
+            }
+        }
+
+        heatmap
+    }
+
+    /// Move each piece and its move number to the position mapped by `to`
+    fn transform(&self, to: impl Fn(usize, usize) -> (usize, usize)) -> Self {
+        let mut game = self.clone();
+
+        for row in 0..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                let (to_row, to_col) = to(row, col);
+                game.board.set(to_row, to_col, self.get(row, col));
+                game.order[to_row][to_col] = self.order[row][col];
+            }
+        }
+
+        game.candidates = game.candidates_of_board();
+        game
+    }
+
+    /// The number of pieces of the starting position, which are only on the board of a composed game
+    fn starting_count(&self) -> usize {
+        (0..BOARD_HEIGHT)
+            .flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+            .filter(|(row, col)| self.get(*row, *col).is_some() && self.order[*row][*col] == 0)
+            .count()
+    }
+
+    /// The fields telling positions apart, see the `PartialEq` implementation
+    const fn position_key(
+        &self,
+    ) -> (
+        &Pieces,
+        usize,
+        Player,
+        &Status,
+        EndReason,
+        Option<Player>,
+        bool,
+    ) {
+        (
+            &self.board,
+            self.move_count,
+            self.next_player,
+            &self.status,
+            self.end_reason,
+            self.draw_offer,
+            self.is_swapped,
+        )
+    }
+
+    /// Get the candidate moves of the board from scratch, see [`candidates`](Self::candidates)
+    fn candidates_of_board(&self) -> [u16; BOARD_HEIGHT] {
+        let mut game = self.clone();
+        game.candidates = [0; BOARD_HEIGHT];
+
+        for row in 0..BOARD_HEIGHT {
+            for col in 0..BOARD_WIDTH {
+                if self.get(row, col).is_some() {
+                    game.add_candidates_around(row, col);
+                }
+            }
+        }
+
+        game.candidates
+    }
+
+    /// Mark the empty positions around a piece just put as candidate moves, and the position of the piece as no longer one
+    const fn add_candidates_around(&mut self, row: usize, col: usize) {
+        let row_end = if row + CANDIDATE_DISTANCE < BOARD_HEIGHT {
+            row + CANDIDATE_DISTANCE
+        } else {
+            BOARD_HEIGHT - 1
+        };
+        let col_end = if col + CANDIDATE_DISTANCE < BOARD_WIDTH {
+            col + CANDIDATE_DISTANCE
+        } else {
+            BOARD_WIDTH - 1
+        };
+
+        let mut near_row = row.saturating_sub(CANDIDATE_DISTANCE);
+
+        while near_row <= row_end {
+            let mut near_col = col.saturating_sub(CANDIDATE_DISTANCE);
+
+            while near_col <= col_end {
+                if self.get(near_row, near_col).is_none() {
+                    self.candidates[near_row] |= 1 << near_col;
+                }
+
+                near_col += 1;
+            }
+
+            near_row += 1;
+        }
+
+        self.candidates[row] &= !(1 << col);
+    }
+
+    /// Check if a position holds a piece of a player, as `==` is not available in const contexts
+    const fn is_piece_of(&self, row: usize, col: usize, player: Player) -> bool {
+        matches!(
+            (self.get(row, col), player),
+            (Some(Player::Player0), Player::Player0) | (Some(Player::Player1), Player::Player1)
+        )
+    }
+
+    const fn update_status(&mut self, last_move: LastMove) {
+        let player = last_move.player;
+        let mut scan =
+            KInARow::<BOARD_HEIGHT, BOARD_WIDTH>::new(last_move.row, last_move.col, LINE_LENGTH);
+
+        while let Some((row, col)) = scan.next() {
+            scan.record(self.is_piece_of(row, col, player));
+        }
+
+        if scan.is_found() {
+            self.status = Status::Win(player);
+            return;
+        }
+
+        // check draw
+        if self.move_count == BOARD_HEIGHT * BOARD_WIDTH {
+            self.status = Status::Draw;
+        }
+    }
+}
+
+impl Default for Gomoku {
+    fn default() -> Self {
+        Self::initial()
+    }
+}
+
+impl Pieces {
+    const EMPTY: Self = Self([[0; BOARD_HEIGHT]; 2]);
+
+    /// Put the piece of a player at a position, or empty it with `None`
+    fn set(&mut self, row: usize, col: usize, piece: Option<Player>) {
+        for (player, rows) in self.0.iter_mut().enumerate() {
+            if piece.is_some_and(|piece| piece as usize == player) {
+                rows[row] |= 1 << col;
+            } else {
+                rows[row] &= !(1 << col);
+            }
+        }
+    }
+}
+
+impl From<Board> for Pieces {
+    fn from(board: Board) -> Self {
+        let mut pieces = Self::EMPTY;
+
+        for (row, cells) in board.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                pieces.set(row, col, *cell);
+            }
+        }
+
+        pieces
+    }
+}
+
+impl From<Pieces> for Board {
+    fn from(pieces: Pieces) -> Self {
+        core::array::from_fn(|row| {
+            core::array::from_fn(|col| {
+                let is_piece_of = |player: Player| pieces.0[player as usize][row] & 1 << col != 0;
+
+                if is_piece_of(Player::Player0) {
+                    Some(Player::Player0)
+                } else if is_piece_of(Player::Player1) {
+                    Some(Player::Player1)
+                } else {
+                    None
+                }
+            })
+        })
+    }
+}
+
+impl BoardDiff {
+    /// Iterate over the changed positions as `(row, col, diff)`, in row-major order
+    pub fn changes(&self) -> impl Iterator<Item = (usize, usize, CellDiff)> + '_ {
+        self.cells.iter().enumerate().flat_map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .filter_map(move |(col, cell)| cell.map(|cell| (row, col, cell)))
+        })
+    }
+
+    /// Check if the two game states are identical
+    pub fn is_empty(&self) -> bool {
+        self.changes().next().is_none() && self.next_player.is_none() && self.status.is_none()
+    }
+}
+
+impl Player {
+    /// Get the other player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} {}", self.row, self.col)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Gomoku {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    pub fn random_position<R: GameRng + ?Sized>(rng: &mut R, plies: usize) -> Self {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        game.play_random_position(rng, plies);
+        game
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for Gomoku {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let moves = testing::positions(BOARD_HEIGHT, BOARD_WIDTH)
+            .filter(|&(row, col)| self.can_put(row, col));
+        testing::choose(rng, moves).map(|(row, col)| Move { row, col })
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::gomoku::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test() {
+        // Player0 won with a five in the first row and has another piece below it
+        let mut board = [[None; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        board[0][..5].fill(Some(Player::Player0));
+        board[14][..4].fill(Some(Player::Player1));
+        board[14][14] = Some(Player::Player0);
+        board[12][0] = Some(Player::Player1);
+
+        let composed = Gomoku::from_board(board, Player::Player1).unwrap();
+        assert_eq!(composed.status(), &Status::Win(Player::Player0));
+        assert_eq!(composed.moves().count(), 0);
+        assert_eq!(composed.validate(), Ok(()));
+
+        let mut board = [[None; BOARD_WIDTH]; BOARD_HEIGHT];
+        board[7][7] = Some(Player::Player0);
+        board[7][8] = Some(Player::Player0);
+        board[8][8] = Some(Player::Player1);
+
+        let mut composed = Gomoku::from_board(board, Player::Player1).unwrap();
+        composed.put(6, 6).unwrap();
+        composed.put(9, 9).unwrap();
+        assert_eq!(composed.to_notation().to_string(), "g9 j6");
+        assert_eq!(composed.validate(), Ok(()));
+        assert_eq!(composed.rotate90().validate(), Ok(()));
+
+        #[cfg(feature = "sgf")]
+        {
+            let mut sgf = std::string::String::new();
+            composed.write_sgf(&mut sgf).unwrap();
+            assert_eq!(sgf, "(;FF[4]GM[4]SZ[15]AB[hh][ih]AW[ii]PL[W];W[gg];B[jj])");
+
+            let read = Gomoku::from_sgf(&sgf).unwrap();
+            assert_eq!(read, composed);
+            assert!(read.moves().eq(composed.moves()));
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Gomoku notation
+//!
+//! Positions are written as a column letter from `a` to `o` followed by a row number from `1` to `15`, counted from the bottom, e.g. `h8` for the center. Move lists are positions joined with optional whitespace or commas, e.g. `h8 h9 i7` or `h8h9i7`
+//!
+//! Records in the PSQ format of Piskvork can be read and written with the `psq` feature, and SGF games with the `sgf` feature
+
+use super::{Gomoku, GomokuError, BOARD_HEIGHT, BOARD_WIDTH};
+#[cfg(feature = "sgf")]
+use super::{Player, Status};
+#[cfg(feature = "sgf")]
+use crate::sgf::{self, SgfError};
+use core::{
+    fmt::{Display, Formatter, Result as FmtResult, Write},
+    str::FromStr,
+};
+use snafu::Snafu;
+
+/// A position in Gomoku notation
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::gomoku::notation::Coordinate;
+/// let center: Coordinate = "h8".parse().unwrap();
+///
+/// assert_eq!(center, Coordinate { row: 7, col: 7 });
+/// assert_eq!(Coordinate { row: 14, col: 0 }.to_string(), "a1");
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Coordinate {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Errors that can occur when reading Gomoku notation
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum NotationError {
+    #[snafu(display("invalid coordinate"))]
+    InvalidCoordinate,
+    #[snafu(display("invalid coordinate at move {index}"))]
+    InvalidMoveCoordinate { index: usize },
+    #[snafu(display("invalid move {index}: {source}"))]
+    InvalidMove { index: usize, source: GomokuError },
+    #[snafu(display("invalid record at line {line}"))]
+    InvalidRecord { line: usize },
+}
+
+crate::code::codes!(NotationError {
+    InvalidCoordinate => "invalid_coordinate",
+    InvalidMoveCoordinate => "invalid_move_coordinate",
+    InvalidMove => "invalid_move",
+    InvalidRecord => "invalid_record",
+});
+
+impl Display for Coordinate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let col = (b'a' + self.col as u8) as char;
+        write!(f, "{col}{}", BOARD_HEIGHT - self.row)
+    }
+}
+
+impl FromStr for Coordinate {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_coordinate(s) {
+            Some((coordinate, "")) => Ok(coordinate),
+            _ => Err(NotationError::InvalidCoordinate),
+        }
+    }
+}
+
+impl Gomoku {
+    /// Create a Gomoku game from a move list in Gomoku notation
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::{Gomoku, Player};
+    /// let game = Gomoku::from_notation("h8 h9 i7").unwrap();
+    ///
+    /// assert_eq!(game.get(7, 7), Some(Player::Player0));
+    /// assert_eq!(game.to_notation().to_string(), "h8 h9 i7");
+    /// ```
+    pub fn from_notation(s: &str) -> Result<Self, NotationError> {
+        let mut game = Self::new().unwrap();
+        let mut rest = s;
+        let mut index = 0;
+
+        loop {
+            rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+
+            if rest.is_empty() {
+                return Ok(game);
+            }
+
+            let Some((coordinate, remaining)) = parse_coordinate(rest) else {
+                return Err(NotationError::InvalidMoveCoordinate { index });
+            };
+
+            game.put(coordinate.row, coordinate.col)
+                .map_err(|source| NotationError::InvalidMove { index, source })?;
+
+            rest = remaining;
+            index += 1;
+        }
+    }
+
+    /// Get the moves played so far, in order, after the starting position of a composed game
+    pub fn moves(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        (1..=self.move_count).filter_map(|number| {
+            positions()
+                .find(|(row, col)| self.order[*row][*col] as usize == number)
+                .map(|(row, col)| Coordinate { row, col })
+        })
+    }
+
+    /// Get the moves played so far as a move list in Gomoku notation, separated by spaces, after the starting position of a composed game
+    pub fn to_notation(&self) -> impl Display + '_ {
+        Notation(self)
+    }
+
+    /// Create a Gomoku game from a record in the PSQ format of Piskvork
+    ///
+    /// Only the moves are read, other information in the record is ignored
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::Gomoku;
+    /// let game = Gomoku::from_psq("Piskvork 15x15, 11:11, 0\n8,8,0\n9,9,0\n-1\n").unwrap();
+    ///
+    /// assert_eq!(game.to_notation().to_string(), "h8 i7");
+    /// ```
+    #[cfg(feature = "psq")]
+    pub fn from_psq(s: &str) -> Result<Self, NotationError> {
+        let mut lines = s.lines().enumerate();
+
+        // e.g. `Piskvork 15x15, 11:11, 0`
+        let is_header_valid = lines.next().is_some_and(|(_, header)| {
+            header
+                .strip_prefix("Piskvork ")
+                .and_then(|rest| rest.split(',').next())
+                .and_then(|size| size.trim().split_once('x'))
+                .is_some_and(|(width, height)| {
+                    width.parse() == Ok(BOARD_WIDTH) && height.parse() == Ok(BOARD_HEIGHT)
+                })
+        });
+
+        if !is_header_valid {
+            return Err(NotationError::InvalidRecord { line: 0 });
+        }
+
+        let mut game = Self::new().unwrap();
+
+        for (index, (line, content)) in lines.enumerate() {
+            let mut fields = content
+                .trim()
+                .split(',')
+                .map(|field| field.trim().parse::<usize>());
+
+            // the move list ends at the first line which is not a move
+            let (Some(Ok(x)), Some(Ok(y)), Some(Ok(_)), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                break;
+            };
+
+            if !(1..=BOARD_WIDTH).contains(&x) || !(1..=BOARD_HEIGHT).contains(&y) {
+                return Err(NotationError::InvalidRecord { line });
+            }
+
+            game.put(y - 1, x - 1)
+                .map_err(|source| NotationError::InvalidMove { index, source })?;
+        }
+
+        Ok(game)
+    }
+
+    /// Write the moves played so far as a record in the PSQ format of Piskvork
+    ///
+    /// Move times are not tracked and written as `0`
+    #[cfg(feature = "psq")]
+    pub fn write_psq(&self, w: &mut impl Write) -> FmtResult {
+        writeln!(w, "Piskvork {BOARD_WIDTH}x{BOARD_HEIGHT}, 11:11, 0")?;
+
+        for Coordinate { row, col } in self.moves() {
+            writeln!(w, "{},{},0", col + 1, row + 1)?;
+        }
+
+        writeln!(w, "-1")
+    }
+
+    /// Create a Gomoku game from the main line of an SGF game with `GM[4]`
+    ///
+    /// Black is [`Player::Player0`]. SGF rows are counted from the top, so `hh` is the center `h8`. Setup properties compose the game with [`from_board`](Self::from_board), with black to move unless `PL` says otherwise
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::Gomoku;
+    /// let game = Gomoku::from_sgf("(;FF[4]GM[4]SZ[15];B[hh];W[hg](;B[ii])(;B[gg]))").unwrap();
+    ///
+    /// assert_eq!(game.to_notation().to_string(), "h8 h9 i7");
+    /// ```
+    #[cfg(feature = "sgf")]
+    pub fn from_sgf(s: &str) -> Result<Self, SgfError> {
+        let mut board = [[None; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        let is_black_next = sgf::read_setup(s, BOARD_WIDTH, BOARD_HEIGHT, |is_black, row, col| {
+            board[row][col] = Some(match is_black {
+                true => Player::Player0,
+                false => Player::Player1,
+            });
+        })?;
+
+        let next_player = match is_black_next {
+            Some(false) => Player::Player1,
+            _ => Player::Player0,
+        };
+
+        let mut game = Self::from_board(board, next_player).map_err(|_| SgfError::InvalidSetup)?;
+
+        for (index, mv) in sgf::read_moves(s, 4, BOARD_WIDTH, BOARD_HEIGHT).enumerate() {
+            let sgf::Move { is_black, position } = mv?;
+
+            let Some((row, col)) = position else {
+                return Err(SgfError::IllegalMove { index });
+            };
+
+            if is_black != (game.next_player == Player::Player0) {
+                return Err(SgfError::WrongPlayer { index });
+            }
+
+            game.put(row, col)
+                .map_err(|_| SgfError::IllegalMove { index })?;
+        }
+
+        Ok(game)
+    }
+
+    /// Write the moves played so far as an SGF game with `GM[4]`, see [`Gomoku::from_sgf`]
+    ///
+    /// The result is written if the game has ended. The board of a composed game is written as setup properties, with the player to move in `PL`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::Gomoku;
+    /// let game = Gomoku::from_notation("h8 h9").unwrap();
+    ///
+    /// let mut sgf = String::new();
+    /// game.write_sgf(&mut sgf).unwrap();
+    ///
+    /// assert_eq!(sgf, "(;FF[4]GM[4]SZ[15];B[hh];W[hg])");
+    /// ```
+    #[cfg(feature = "sgf")]
+    pub fn write_sgf(&self, w: &mut impl Write) -> FmtResult {
+        write!(w, "(;FF[4]GM[4]SZ[{BOARD_WIDTH}]")?;
+
+        match self.status {
+            Status::Ongoing => {}
+            Status::Draw => write!(w, "RE[0]")?,
+            Status::Win(Player::Player0) => write!(w, "RE[B+]")?,
+            Status::Win(Player::Player1) => write!(w, "RE[W+]")?,
+        }
+
+        let starting_count = self.starting_count();
+
+        if starting_count > 0 {
+            for (player, ident) in [(Player::Player0, "AB"), (Player::Player1, "AW")] {
+                let mut pieces = positions().filter(|(row, col)| {
+                    self.get(*row, *col) == Some(player) && self.order[*row][*col] == 0
+                });
+
+                if let Some((row, col)) = pieces.next() {
+                    write!(
+                        w,
+                        "{ident}[{}{}]",
+                        sgf::coordinate(col),
+                        sgf::coordinate(row)
+                    )?;
+                }
+
+                for (row, col) in pieces {
+                    write!(w, "[{}{}]", sgf::coordinate(col), sgf::coordinate(row))?;
+                }
+            }
+
+            // the players took turns to reach the starting position
+            let color = if starting_count.is_multiple_of(2) {
+                'B'
+            } else {
+                'W'
+            };
+            write!(w, "PL[{color}]")?;
+        }
+
+        for (idx, Coordinate { row, col }) in self.moves().enumerate() {
+            let color = if (starting_count + idx).is_multiple_of(2) {
+                'B'
+            } else {
+                'W'
+            };
+            write!(
+                w,
+                ";{color}[{}{}]",
+                sgf::coordinate(col),
+                sgf::coordinate(row)
+            )?;
+        }
+
+        write!(w, ")")
+    }
+}
+
+struct Notation<'a>(&'a Gomoku);
+
+impl Display for Notation<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        for (idx, coordinate) in self.0.moves().enumerate() {
+            if idx > 0 {
+                f.write_char(' ')?;
+            }
+
+            write!(f, "{coordinate}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a coordinate at the start of a string, returning the rest of it
+fn parse_coordinate(s: &str) -> Option<(Coordinate, &str)> {
+    let mut chars = s.chars();
+
+    let col = match chars.next()?.to_ascii_lowercase() {
+        letter @ 'a'..='z' => letter as usize - 'a' as usize,
+        _ => return None,
+    };
+
+    let rest = chars.as_str();
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let number = rest[..digits].parse::<usize>().ok()?;
+
+    if col >= BOARD_WIDTH || !(1..=BOARD_HEIGHT).contains(&number) {
+        return None;
+    }
+
+    let coordinate = Coordinate {
+        row: BOARD_HEIGHT - number,
+        col,
+    };
+
+    Some((coordinate, &rest[digits..]))
+}
+
+fn positions() -> impl Iterator<Item = (usize, usize)> {
+    (0..BOARD_HEIGHT).flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+}
@@ -0,0 +1,450 @@
+use super::{Board, Gomoku, Player, Status, BOARD_HEIGHT, BOARD_WIDTH, LINE_LENGTH};
+use crate::{
+    outcome::EndReason,
+    util::{self, LINES},
+};
+
+// each of the four lines through a position holds at most two five points next to it
+const MAX_FIVE_POINTS: usize = 8;
+
+impl Gomoku {
+    /// Search for a victory by continuous fours (VCF) for the next player
+    ///
+    /// Every move of the winning sequence makes a four, so the other player is forced to block each time. `depth` limits the number of moves the next player makes before connecting five
+    ///
+    /// Return the first move of the winning sequence, or `None` if no forced win is found within the depth limit
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::Gomoku;
+    /// let game = Gomoku::from_moves(&[(7, 4), (0, 0), (7, 5), (0, 2), (7, 6), (0, 4)]).unwrap();
+    ///
+    /// // the open three becomes an open four
+    /// assert!(matches!(game.vcf(1), Some((7, 3) | (7, 7))));
+    /// ```
+    pub fn vcf(&self, depth: usize) -> Option<(usize, usize)> {
+        if self.status != Status::Ongoing {
+            return None;
+        }
+
+        let mut board = Board::from(self.board);
+        search(&mut board, self.next_player, depth, false)
+    }
+
+    /// Search for a victory by continuous threats (VCT) for the next player
+    ///
+    /// Besides fours, the winning sequence may contain threes, which threaten to make an unstoppable four. After a three, every defense of the other player is considered, including counter fours. `depth` limits the number of moves the next player makes before connecting five
+    ///
+    /// Return the first move of the winning sequence, or `None` if no forced win is found within the depth limit
+    ///
+    /// The search time grows quickly with `depth`, so keep it small
+    pub fn vct(&self, depth: usize) -> Option<(usize, usize)> {
+        if self.status != Status::Ongoing {
+            return None;
+        }
+
+        let mut board = Board::from(self.board);
+        search(&mut board, self.next_player, depth, true)
+    }
+
+    /// Mine the positions of the game for VCF puzzles, e.g. from recorded games
+    ///
+    /// Each position the game went through, from the first stone or the starting position of a composed game on, is searched for a victory by continuous fours of its next player within `max_depth`, see [`vcf`](Self::vcf). A puzzle is made of each position with one, at the smallest depth it is found
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::gomoku::{Gomoku, Player};
+    /// let game =
+    ///     Gomoku::from_moves(&[(7, 4), (0, 0), (7, 5), (0, 2), (7, 6), (0, 4), (7, 7)]).unwrap();
+    ///
+    /// // the open three of Player0, before it became an open four
+    /// let puzzle = game.vcf_puzzles(2).next().unwrap();
+    ///
+    /// assert_eq!(puzzle.game.get(7, 7), None);
+    /// assert_eq!(puzzle.game.next_player(), Player::Player0);
+    /// assert_eq!(puzzle.depth, 1);
+    ///
+    /// // a four, the block and the five
+    /// assert_eq!(puzzle.solution().count(), 3);
+    /// ```
+    pub fn vcf_puzzles(&self, max_depth: usize) -> impl Iterator<Item = Puzzle> + '_ {
+        (self.starting_count().max(1)..=self.move_count).filter_map(move |move_count| {
+            let game = self.position_at(move_count);
+
+            (0..=max_depth)
+                .find(|depth| game.vcf(*depth).is_some())
+                .map(|depth| Puzzle { game, depth })
+        })
+    }
+
+    /// The position after a number of moves of the game, ongoing
+    fn position_at(&self, move_count: usize) -> Self {
+        let mut game = self.clone();
+
+        if move_count == self.move_count {
+            return game;
+        }
+
+        for (row, col) in positions() {
+            let number = self.order[row][col] as usize;
+
+            // the player who made the next move
+            if number == move_count + 1 {
+                game.next_player = self.get(row, col).unwrap_or(game.next_player);
+            }
+
+            if number > move_count {
+                game.board.set(row, col, None);
+                game.order[row][col] = 0;
+            }
+        }
+
+        game.move_count = move_count;
+        game.candidates = game.candidates_of_board();
+        game.status = Status::Ongoing;
+        game.end_reason = EndReason::Normal;
+        game.draw_offer = None;
+        game
+    }
+}
+
+/// A position with a victory by continuous fours for its next player, see [`Gomoku::vcf_puzzles`]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Puzzle {
+    /// The position, where the next player is to win
+    pub game: Gomoku,
+    /// The fewest fours the next player makes before connecting five
+    pub depth: usize,
+}
+
+impl Puzzle {
+    /// Get the winning sequence, with the forced blocks of the other player in between
+    ///
+    /// The last move connects five
+    pub fn solution(&self) -> impl Iterator<Item = (usize, usize)> {
+        let attacker = self.game.next_player;
+        let mut game = self.game.clone();
+        let mut depth = self.depth;
+
+        core::iter::from_fn(move || {
+            let point = if game.next_player == attacker {
+                let point = game.vcf(depth)?;
+                depth = depth.saturating_sub(1);
+                point
+            } else if game.status == Status::Ongoing {
+                five_points(&Board::from(game.board), attacker).first()?
+            } else {
+                return None;
+            };
+
+            game.put(point.0, point.1).ok()?;
+            Some(point)
+        })
+    }
+}
+
+/// Empty positions where a player connects five or more
+struct FivePoints {
+    len: usize,
+    points: [(usize, usize); MAX_FIVE_POINTS],
+}
+
+impl FivePoints {
+    const fn new() -> Self {
+        Self {
+            len: 0,
+            points: [(0, 0); MAX_FIVE_POINTS],
+        }
+    }
+
+    fn push(&mut self, point: (usize, usize)) {
+        if self.len < MAX_FIVE_POINTS {
+            self.points[self.len] = point;
+        }
+
+        self.len += 1;
+    }
+
+    fn first(&self) -> Option<(usize, usize)> {
+        (self.len > 0).then_some(self.points[0])
+    }
+}
+
+/// Search for a forced win of the attacker, who is the next to move
+fn search(
+    board: &mut Board,
+    attacker: Player,
+    depth: usize,
+    with_threes: bool,
+) -> Option<(usize, usize)> {
+    let defender = attacker.other();
+
+    if let Some(point) = five_points(board, attacker).first() {
+        return Some(point);
+    }
+
+    if depth == 0 {
+        return None;
+    }
+
+    // a four of the defender must be blocked first, and two of them cannot be
+    let defender_fives = five_points(board, defender);
+
+    if defender_fives.len >= 2 {
+        return None;
+    }
+
+    let forced = defender_fives.first();
+
+    // fours are tried before threes, as they are cheaper to verify
+    for is_three_pass in [false, true] {
+        if is_three_pass && !with_threes {
+            break;
+        }
+
+        for (row, col) in positions() {
+            if board[row][col].is_some()
+                || forced.is_some_and(|point| point != (row, col))
+                || !is_near(board, row, col, attacker)
+            {
+                continue;
+            }
+
+            board[row][col] = Some(attacker);
+
+            let points = five_points_through(board, row, col, attacker);
+
+            let is_won = match (points.len, is_three_pass) {
+                (0, true) => is_three_won(board, attacker, depth, with_threes),
+                (0, false) | (_, true) => false,
+                (1, false) => {
+                    let (block_row, block_col) = points.points[0];
+
+                    board[block_row][block_col] = Some(defender);
+                    let is_won = search(board, attacker, depth - 1, with_threes).is_some();
+                    board[block_row][block_col] = None;
+
+                    is_won
+                }
+                // an open four or a double four
+                (_, false) => true,
+            };
+
+            board[row][col] = None;
+
+            if is_won {
+                return Some((row, col));
+            }
+        }
+    }
+
+    None
+}
+
+/// Check if the attacker wins after a three, whatever the defender replies
+fn is_three_won(board: &mut Board, attacker: Player, depth: usize, with_threes: bool) -> bool {
+    let defender = attacker.other();
+
+    // the three itself, then the winning four
+    if depth < 2 {
+        return false;
+    }
+
+    let Some(defenses) = defenses(board, attacker) else {
+        return false;
+    };
+
+    for (row, col) in positions() {
+        if board[row][col].is_some() {
+            continue;
+        }
+
+        let is_near = is_near(board, row, col, defender);
+        board[row][col] = Some(defender);
+
+        let is_candidate = defenses[row][col]
+            || (is_near && five_points_through(board, row, col, defender).len > 0);
+
+        let is_refuted = is_candidate && search(board, attacker, depth - 1, with_threes).is_none();
+
+        board[row][col] = None;
+
+        if is_refuted {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Get the positions that stop every winning four of the attacker, or `None` if the attacker has no winning four
+///
+/// A winning four gives two or more five points at once. It is stopped by taking its position, or one of its five points if there are exactly two
+fn defenses(board: &mut Board, attacker: Player) -> Option<[[bool; BOARD_WIDTH]; BOARD_HEIGHT]> {
+    let mut defenses: Option<[[bool; BOARD_WIDTH]; BOARD_HEIGHT]> = None;
+
+    for (row, col) in positions() {
+        if board[row][col].is_some() || !is_near(board, row, col, attacker) {
+            continue;
+        }
+
+        board[row][col] = Some(attacker);
+        let points = five_points_through(board, row, col, attacker);
+        board[row][col] = None;
+
+        if points.len < 2 {
+            continue;
+        }
+
+        let mut stops = [[false; BOARD_WIDTH]; BOARD_HEIGHT];
+        stops[row][col] = true;
+
+        if points.len == 2 {
+            for (row, col) in points.points[..2].iter() {
+                stops[*row][*col] = true;
+            }
+        }
+
+        match &mut defenses {
+            None => defenses = Some(stops),
+            Some(defenses) => {
+                for (defenses, stops) in defenses.iter_mut().zip(stops) {
+                    for (defense, stop) in defenses.iter_mut().zip(stops) {
+                        *defense &= stop;
+                    }
+                }
+            }
+        }
+    }
+
+    defenses
+}
+
+fn five_points(board: &Board, player: Player) -> FivePoints {
+    let mut points = FivePoints::new();
+
+    for (row, col) in positions() {
+        if board[row][col].is_none() && is_five(board, row, col, player) {
+            points.push((row, col));
+        }
+    }
+
+    points
+}
+
+/// Get the five points on the lines through a position, within reach of it
+fn five_points_through(board: &Board, row: usize, col: usize, player: Player) -> FivePoints {
+    let mut points = FivePoints::new();
+
+    for (row_step, col_step) in LINES {
+        for distance in (-4..=4).filter(|distance| *distance != 0) {
+            let (row, col) = (
+                row as isize + row_step * distance,
+                col as isize + col_step * distance,
+            );
+
+            if get(board, row, col) == Some(None)
+                && is_five(board, row as usize, col as usize, player)
+            {
+                points.push((row as usize, col as usize));
+            }
+        }
+    }
+
+    points
+}
+
+/// Check if placing a piece of the player at an empty position connects five or more
+fn is_five(board: &Board, row: usize, col: usize, player: Player) -> bool {
+    util::k_in_a_row::<BOARD_HEIGHT, BOARD_WIDTH>(row, col, LINE_LENGTH, |row, col| {
+        board[row][col] == Some(player)
+    })
+}
+
+/// Check if a piece of the player is within reach of a position along any line
+fn is_near(board: &Board, row: usize, col: usize, player: Player) -> bool {
+    LINES.iter().any(|(row_step, col_step)| {
+        (-4..=4).any(|distance| {
+            get(
+                board,
+                row as isize + row_step * distance,
+                col as isize + col_step * distance,
+            ) == Some(Some(player))
+        })
+    })
+}
+
+fn get(board: &Board, row: isize, col: isize) -> Option<Option<Player>> {
+    let is_in_bounds =
+        (0..BOARD_HEIGHT as isize).contains(&row) && (0..BOARD_WIDTH as isize).contains(&col);
+
+    is_in_bounds.then(|| board[row as usize][col as usize])
+}
+
+fn positions() -> impl Iterator<Item = (usize, usize)> {
+    (0..BOARD_HEIGHT).flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gomoku::*;
+
+    /// A board with a piece of Player1 in each corner, keeping the piece counts even
+    fn cornered() -> [[Option<Player>; BOARD_WIDTH]; BOARD_HEIGHT] {
+        let mut board = [[None; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        for (row, col) in [(0, 0), (0, 14), (14, 0), (14, 14)] {
+            board[row][col] = Some(Player::Player1);
+        }
+
+        board
+    }
+
+    #[test]
+    fn test() {
+        // a closed three of Player0 in row 7 and two pieces on the diagonal through its end
+        let mut board = cornered();
+        board[7][3] = Some(Player::Player1);
+        board[7][4..7].fill(Some(Player::Player0));
+        board[8][8] = Some(Player::Player0);
+        board[9][9] = Some(Player::Player0);
+
+        let mut game = Gomoku::from_board(board, Player::Player0).unwrap();
+
+        // no single four wins, while the four at the end of the three leads to an open four on the diagonal
+        assert_eq!(game.vcf(0), None);
+        assert_eq!(game.vcf(1), None);
+        assert_eq!(game.vcf(2), Some((7, 7)));
+
+        game.put(7, 7).unwrap();
+        game.put(7, 8).unwrap();
+        assert_eq!(game.vcf(1), Some((6, 6)));
+
+        // two open twos crossing at an empty position, where a double three wins without any four
+        let mut board = cornered();
+        board[7][5] = Some(Player::Player0);
+        board[7][6] = Some(Player::Player0);
+        board[5][7] = Some(Player::Player0);
+        board[6][7] = Some(Player::Player0);
+
+        let game = Gomoku::from_board(board, Player::Player0).unwrap();
+
+        assert_eq!(game.vcf(4), None);
+        assert_eq!(game.vct(1), None);
+        assert_eq!(game.vct(2), Some((7, 7)));
+
+        // an open three of Player1 answers the double three with a counter four
+        board[0][0] = None;
+        board[0][14] = None;
+        board[14][0] = None;
+        board[10][2..5].fill(Some(Player::Player1));
+
+        let game = Gomoku::from_board(board, Player::Player0).unwrap();
+
+        assert_eq!(game.vct(2), None);
+    }
+}
@@ -0,0 +1,720 @@
+//! Hasami Shogi
+//!
+//! Check struct [`HasamiShogi`] for more information
+
+use crate::{
+    affect::{self, Affected, Affects, Effect},
+    invariant::InvariantError,
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use snafu::Snafu;
+
+pub(crate) const BOARD_SIZE: usize = 9;
+
+// the number of captured pieces a player wins at by default
+const DEFAULT_CAPTURES_TO_WIN: usize = 5;
+
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+/// Hasami Shogi
+///
+/// Each player starts with 9 pieces on their back row, [`Player::Player0`] on row `8` and [`Player::Player1`] on row `0`. A piece moves any number of empty positions along its row or column. Pieces of the other player enclosed on both ends of a row or column by the moved piece and another piece of the mover are captured. A player wins by capturing a threshold of pieces, 5 by default
+///
+/// If the next player cannot move any piece, the game ends in a draw
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::hasami_shogi::{HasamiShogi, Player};
+/// let mut game = HasamiShogi::new().unwrap();
+///
+/// game.move_piece((8, 0), (1, 0)).unwrap();
+/// game.move_piece((0, 1), (1, 1)).unwrap();
+/// game.move_piece((8, 2), (1, 2)).unwrap();
+///
+/// assert_eq!(game.get(1, 1), None);
+/// assert_eq!(game.captures(Player::Player0), 1);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HasamiShogi {
+    board: [[Option<Player>; BOARD_SIZE]; BOARD_SIZE],
+    captures: [usize; 2],
+    captures_to_win: usize,
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(HasamiShogi);
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::canonical!(HasamiShogi, "hasami_shogi", 1 {
+    board,
+    captures,
+    captures_to_win,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+});
+
+/// A rules event between two game states, see [`HasamiShogi::events`]
+#[cfg(feature = "events")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// A player captured pieces of the other player
+    Captured { player: Player, count: usize },
+    /// A player won by the rules
+    Won(Player),
+}
+
+/// A move, which moves a piece from a position to another, each position being `(row, col)`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Move {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    /// Positions are always in bounds
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            from: (u.choose_index(BOARD_SIZE)?, u.choose_index(BOARD_SIZE)?),
+            to: (u.choose_index(BOARD_SIZE)?, u.choose_index(BOARD_SIZE)?),
+        })
+    }
+}
+
+/// Errors that can occur when moving a piece
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum HasamiShogiError {
+    #[snafu(display("not a piece of the next player"))]
+    NotOwnPiece,
+    #[snafu(display("invalid move"))]
+    InvalidMove,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("position out of bounds"))]
+    OutOfBounds,
+    #[snafu(display("invalid capture threshold"))]
+    InvalidThreshold,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+}
+
+crate::code::codes!(HasamiShogiError {
+    NotOwnPiece => "not_own_piece",
+    InvalidMove => "invalid_move",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+    InvalidThreshold => "invalid_threshold",
+    NoDrawOffer => "no_draw_offer",
+});
+
+impl HasamiShogi {
+    /// Create a new Hasami Shogi game, won by capturing 5 pieces
+    pub const fn new() -> Result<Self, HasamiShogiError> {
+        Self::with_captures_to_win(DEFAULT_CAPTURES_TO_WIN)
+    }
+
+    /// Create a new Hasami Shogi game, won by capturing the given number of pieces
+    ///
+    /// The threshold must be from 1 to 9
+    pub const fn with_captures_to_win(captures_to_win: usize) -> Result<Self, HasamiShogiError> {
+        if captures_to_win == 0 || captures_to_win > BOARD_SIZE {
+            return Err(HasamiShogiError::InvalidThreshold);
+        }
+
+        let mut board = [[None; BOARD_SIZE]; BOARD_SIZE];
+        board[0] = [Some(Player::Player1); BOARD_SIZE];
+        board[BOARD_SIZE - 1] = [Some(Player::Player0); BOARD_SIZE];
+
+        Ok(Self {
+            board,
+            captures: [0; 2],
+            captures_to_win,
+            next_player: Player::Player0,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+        })
+    }
+
+    /// Create a Hasami Shogi game by replaying a list of moves from the starting position
+    ///
+    /// Return the index of the first invalid move and the error it caused on failure
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::hasami_shogi::{HasamiShogi, Move};
+    /// let moves = [Move {
+    ///     from: (8, 4),
+    ///     to: (4, 4),
+    /// }];
+    /// let game = HasamiShogi::from_moves(&moves).unwrap();
+    ///
+    /// assert!(game.get(4, 4).is_some());
+    /// ```
+    pub fn from_moves(moves: &[Move]) -> Result<Self, (usize, HasamiShogiError)> {
+        let mut game = Self::new().unwrap();
+
+        for (idx, mv) in moves.iter().enumerate() {
+            let is_out_of_bounds = [mv.from, mv.to]
+                .into_iter()
+                .any(|(row, col)| row >= BOARD_SIZE || col >= BOARD_SIZE);
+
+            if is_out_of_bounds {
+                return Err((idx, HasamiShogiError::OutOfBounds));
+            }
+
+            game.apply(*mv).map_err(|err| (idx, err))?;
+        }
+
+        Ok(game)
+    }
+
+    /// Get a piece at a position
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Option<Player> {
+        self.board[row][col]
+    }
+
+    /// Get a piece at a position without bounds checking, for the hot loops of a search that keep the positions in bounds already
+    ///
+    /// The bounds are still checked in debug builds
+    ///
+    /// # Safety
+    ///
+    /// The target position must be in bounds, see [`get`](Self::get)
+    pub const unsafe fn get_unchecked(&self, row: usize, col: usize) -> Option<Player> {
+        debug_assert!(row < self.board.len() && col < self.board[0].len());
+
+        // SAFETY: the caller keeps the position in bounds
+        unsafe { *(*self.board.as_ptr().add(row)).as_ptr().add(col) }
+    }
+
+    /// Move a piece of the next player, capturing the enclosed pieces of the other player
+    ///
+    /// Panic if a target position is out of bounds
+    pub fn move_piece(
+        &mut self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Result<(), HasamiShogiError> {
+        self.move_capturing(from, to).map(|_| ())
+    }
+
+    /// Move a piece, same as [`move_piece`](Self::move_piece), and get the pieces it captured, each being [`Effect::Captured`]
+    ///
+    /// The pieces are collected while capturing them, in row-major order, rather than by comparing states with [`Affects`]
+    ///
+    /// Panic if a target position is out of bounds
+    pub fn move_piece_with_effects(
+        &mut self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Result<impl Iterator<Item = Affected>, HasamiShogiError> {
+        let captured = self.move_capturing(from, to)?;
+        Ok(affect::bitboard_cells(
+            captured,
+            BOARD_SIZE,
+            Effect::Captured,
+        ))
+    }
+
+    /// Move a piece, return the pieces captured as a row-major bitboard
+    fn move_capturing(
+        &mut self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Result<u128, HasamiShogiError> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(HasamiShogiError::GameEnded);
+        }
+
+        let player = self.next_player;
+
+        if self.board[from.0][from.1] != Some(player) {
+            return Err(HasamiShogiError::NotOwnPiece);
+        }
+
+        if !self.is_path_clear(from, to) {
+            return Err(HasamiShogiError::InvalidMove);
+        }
+
+        self.board[from.0][from.1] = None;
+        self.board[to.0][to.1] = Some(player);
+
+        let mut captured = 0;
+
+        for (row_step, col_step) in DIRECTIONS {
+            let at = |distance: isize| {
+                (
+                    to.0 as isize + row_step * distance,
+                    to.1 as isize + col_step * distance,
+                )
+            };
+
+            let enclosed = (1..)
+                .take_while(|distance| self.get_signed(at(*distance)) == Some(Some(player.other())))
+                .count() as isize;
+
+            if enclosed > 0 && self.get_signed(at(enclosed + 1)) == Some(Some(player)) {
+                for distance in 1..=enclosed {
+                    let (row, col) = at(distance);
+                    self.board[row as usize][col as usize] = None;
+                    captured |= 1 << (row as usize * BOARD_SIZE + col as usize);
+                }
+
+                self.captures[player as usize] += enclosed as usize;
+            }
+        }
+
+        self.draw_offer = None;
+        self.next_player = player.other();
+
+        if self.captures[player as usize] >= self.captures_to_win {
+            self.status = Status::Win(player);
+        } else if !self.is_next_player_movable() {
+            self.status = Status::Draw;
+        }
+
+        Ok(captured)
+    }
+
+    /// Check if a piece of the next player can be moved, same as [`move_piece`](Self::move_piece) succeeding but without changing the game
+    ///
+    /// Panic if a target position is out of bounds
+    pub fn can_move(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        !matches!(self.status, Status::Win(_) | Status::Draw)
+            && self.board[from.0][from.1] == Some(self.next_player)
+            && self.is_path_clear(from, to)
+    }
+
+    /// Apply a move, same as [`move_piece`](Self::move_piece)
+    ///
+    /// Panic if a target position is out of bounds
+    pub fn apply(&mut self, mv: Move) -> Result<(), HasamiShogiError> {
+        self.move_piece(mv.from, mv.to)
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    ///
+    /// The game holds no allocation, so this is a single copy of the game followed by [`apply`](Self::apply)
+    ///
+    /// Panic if a target position is out of bounds
+    pub fn preview(&self, mv: Move) -> Result<Self, HasamiShogiError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, the position of a piece and the position it moves to as described in [`parse`](crate::parse), optionally joined by `to`, e.g. `8 0 to 5 0`
+    pub fn parse_move(text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+        let from = words.position(BOARD_SIZE, BOARD_SIZE)?;
+        words.take("to");
+        let to = words.position(BOARD_SIZE, BOARD_SIZE)?;
+
+        words.finish()?;
+        Ok(Move { from, to })
+    }
+
+    /// Get the number of pieces captured by a player
+    pub const fn captures(&self, player: Player) -> usize {
+        self.captures[player as usize]
+    }
+
+    /// Get the number of captured pieces a player wins at
+    pub const fn captures_to_win(&self) -> usize {
+        self.captures_to_win
+    }
+
+    /// Get the rules events from this state to a later one, e.g. right before and after a move, for downstream apps to map to achievements
+    ///
+    /// The captures come first, then the win. A win is reported only when the game ended by the rules
+    #[cfg(feature = "events")]
+    pub fn events(&self, after: &Self) -> impl Iterator<Item = Event> {
+        let captured = [Player::Player0, Player::Player1].map(|player| {
+            let count = after.captures(player).saturating_sub(self.captures(player));
+            (count > 0).then_some(Event::Captured { player, count })
+        });
+
+        let won = match (&self.status, &after.status, after.end_reason) {
+            (Status::Ongoing, Status::Win(player), EndReason::Normal) => Some(Event::Won(*player)),
+            _ => None,
+        };
+
+        captured.into_iter().chain([won]).flatten()
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// Each player must have the pieces not captured by the other player left on the board, and a status ended by the rules must match the captures and the moves left. Any number of moves may have been played, so the next player is not checked
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        if self.captures_to_win == 0 || self.captures_to_win > BOARD_SIZE {
+            return Err(InvariantError::Counter);
+        }
+
+        for player in [Player::Player0, Player::Player1] {
+            let count = self
+                .board
+                .iter()
+                .flatten()
+                .filter(|cell| **cell == Some(player))
+                .count();
+
+            if self.captures[player.other() as usize] > BOARD_SIZE
+                || count != BOARD_SIZE - self.captures[player.other() as usize]
+            {
+                return Err(InvariantError::Counter);
+            }
+        }
+
+        let is_winning = |player: Player| self.captures[player as usize] >= self.captures_to_win;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => {
+                !is_winning(Player::Player0)
+                    && !is_winning(Player::Player1)
+                    && self.is_next_player_movable()
+            }
+            (Status::Ongoing, _) => false,
+            (Status::Win(player), EndReason::Normal) => is_winning(*player),
+            (Status::Draw, EndReason::Normal) => !self.is_next_player_movable(),
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), HasamiShogiError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), HasamiShogiError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), HasamiShogiError> {
+        if self.status != Status::Ongoing {
+            return Err(HasamiShogiError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), HasamiShogiError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(HasamiShogiError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), HasamiShogiError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), HasamiShogiError> {
+        if self.status != Status::Ongoing {
+            return Err(HasamiShogiError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Check if the positions are different and on the same row or column, with nothing in between or at the destination
+    fn is_path_clear(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        if from == to || (from.0 != to.0 && from.1 != to.1) {
+            return false;
+        }
+
+        let row_step = (to.0 as isize - from.0 as isize).signum();
+        let col_step = (to.1 as isize - from.1 as isize).signum();
+        let distance = from.0.abs_diff(to.0).max(from.1.abs_diff(to.1)) as isize;
+
+        (1..=distance).all(|distance| {
+            let row = from.0 as isize + row_step * distance;
+            let col = from.1 as isize + col_step * distance;
+
+            self.board[row as usize][col as usize].is_none()
+        })
+    }
+
+    fn is_next_player_movable(&self) -> bool {
+        (0..BOARD_SIZE)
+            .flat_map(|row| (0..BOARD_SIZE).map(move |col| (row, col)))
+            .filter(|(row, col)| self.board[*row][*col] == Some(self.next_player))
+            .any(|(row, col)| {
+                DIRECTIONS.into_iter().any(|(row_step, col_step)| {
+                    self.get_signed((row as isize + row_step, col as isize + col_step))
+                        == Some(None)
+                })
+            })
+    }
+
+    /// Get a piece at a signed position, `None` if out of bounds
+    fn get_signed(&self, (row, col): (isize, isize)) -> Option<Option<Player>> {
+        let is_in_bounds =
+            (0..BOARD_SIZE as isize).contains(&row) && (0..BOARD_SIZE as isize).contains(&col);
+
+        is_in_bounds.then(|| self.get(row as usize, col as usize))
+    }
+}
+
+impl Affects for HasamiShogi {
+    /// The pieces of the other player captured, each being [`Effect::Captured`]
+    fn affected<'a>(&'a self, after: &'a Self) -> impl Iterator<Item = Affected> + 'a {
+        (0..BOARD_SIZE)
+            .flat_map(|row| (0..BOARD_SIZE).map(move |col| (row, col)))
+            .filter(|(row, col)| {
+                self.board[*row][*col] == Some(self.next_player.other())
+                    && after.board[*row][*col].is_none()
+            })
+            .map(|(row, col)| Affected {
+                row,
+                col,
+                effect: Effect::Captured,
+            })
+    }
+}
+
+impl Player {
+    /// Get the other player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let ((from_row, from_col), (to_row, to_col)) = (self.from, self.to);
+        write!(f, "{from_row} {from_col} to {to_row} {to_col}")
+    }
+}
+
+#[cfg(feature = "testing")]
+impl HasamiShogi {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    pub fn random_position<R: GameRng + ?Sized>(rng: &mut R, plies: usize) -> Self {
+        // the default threshold is in range
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(_) => unreachable!(),
+        };
+
+        game.play_random_position(rng, plies);
+        game
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for HasamiShogi {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let moves = testing::positions(BOARD_SIZE, BOARD_SIZE)
+            .flat_map(|from| {
+                testing::positions(BOARD_SIZE, BOARD_SIZE).map(move |to| Move { from, to })
+            })
+            .filter(|mv| self.can_move(mv.from, mv.to));
+
+        testing::choose(rng, moves)
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{affect::Affects, hasami_shogi::*};
+
+    #[test]
+    fn test() {
+        let mut game = HasamiShogi::with_captures_to_win(2).unwrap();
+
+        assert_eq!(
+            game.move_piece((0, 0), (1, 0)),
+            Err(HasamiShogiError::NotOwnPiece)
+        );
+        assert_eq!(
+            game.move_piece((8, 0), (7, 1)),
+            Err(HasamiShogiError::InvalidMove)
+        );
+        assert_eq!(
+            game.move_piece((8, 0), (8, 1)),
+            Err(HasamiShogiError::InvalidMove)
+        );
+
+        // Player1 moves two pieces next to a piece of Player0 on row 4, which are then enclosed
+        game.apply(Move {
+            from: (8, 0),
+            to: (4, 0),
+        })
+        .unwrap();
+        game.apply(Move {
+            from: (0, 1),
+            to: (4, 1),
+        })
+        .unwrap();
+        game.apply(Move {
+            from: (8, 8),
+            to: (7, 8),
+        })
+        .unwrap();
+        game.apply(Move {
+            from: (0, 2),
+            to: (4, 2),
+        })
+        .unwrap();
+
+        assert_eq!(game.status(), &Status::Ongoing);
+        let before = game.clone();
+
+        game.apply(Move {
+            from: (8, 3),
+            to: (4, 3),
+        })
+        .unwrap();
+
+        assert_eq!(game.get(4, 1), None);
+        assert_eq!(game.get(4, 2), None);
+        assert_eq!(game.captures(Player::Player0), 2);
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+        assert!(before.events(&game).eq([
+            Event::Captured {
+                player: Player::Player0,
+                count: 2,
+            },
+            Event::Won(Player::Player0),
+        ]));
+
+        // the captures collected by the move match the ones found by comparing states
+        let mut with_effects = before.clone();
+        assert!(with_effects
+            .move_piece_with_effects((8, 3), (4, 3))
+            .unwrap()
+            .eq(before.affected(&game)));
+        assert_eq!(before.affected(&game).count(), 2);
+
+        assert_eq!(
+            HasamiShogi::with_captures_to_win(10).unwrap_err(),
+            HasamiShogiError::InvalidThreshold
+        );
+    }
+}
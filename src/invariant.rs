@@ -0,0 +1,206 @@
+//! Consistency checks of game states
+//!
+//! Each game has a `validate()` method checking that its state could have been reached by playing it, e.g. after deserializing a state from an untrusted source. Check enum [`InvariantError`] for the kinds of inconsistency
+//!
+//! With the `serde-validate` feature, deserializing a game runs the check, and an inconsistent game is rejected with a deserialization error
+
+// the turn check is only used by the games alternating single moves
+#![cfg_attr(
+    not(any(
+        feature = "connect_four",
+        feature = "gomoku",
+        feature = "pente",
+        feature = "score_four",
+        feature = "tictactoe"
+    )),
+    allow(dead_code)
+)]
+
+use snafu::Snafu;
+
+/// Errors that can occur when validating a game state
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum InvariantError {
+    #[snafu(display("impossible board"))]
+    Board,
+    #[snafu(display("counter does not match the board"))]
+    Counter,
+    #[snafu(display("next player does not match the board"))]
+    NextPlayer,
+    #[snafu(display("status does not match the board"))]
+    Status,
+}
+
+crate::code::codes!(InvariantError {
+    Board => "board",
+    Counter => "counter",
+    NextPlayer => "next_player",
+    Status => "status",
+});
+
+/// Check that the piece counts of the player moving first and the other player are reachable by taking turns, and match the next player
+///
+/// `is_first_next` tells if the player moving first is the next player
+pub(crate) const fn check_turns(
+    first_count: usize,
+    second_count: usize,
+    is_first_next: bool,
+) -> Result<(), InvariantError> {
+    if first_count != second_count && first_count != second_count + 1 {
+        return Err(InvariantError::Board);
+    }
+
+    if is_first_next != (first_count == second_count) {
+        return Err(InvariantError::NextPlayer);
+    }
+
+    Ok(())
+}
+
+/// Implement the serde traits of a game through the ones derived with `#[serde(remote = "Self")]`, rejecting deserialized states that fail `validate()`
+// unused without any game
+#[cfg(feature = "serde-validate")]
+#[allow(unused_macros)]
+macro_rules! validated_serde {
+    ($game:ident) => {
+        impl serde::Serialize for $game {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                Self::serialize(self, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $game {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let game = Self::deserialize(deserializer)?;
+                game.validate().map_err(serde::de::Error::custom)?;
+                Ok(game)
+            }
+        }
+    };
+    ($game:ident<R>) => {
+        impl<R: serde::Serialize> serde::Serialize for $game<R> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                Self::serialize(self, serializer)
+            }
+        }
+
+        impl<'de, R: serde::Deserialize<'de> + $crate::rng::GameRng> serde::Deserialize<'de>
+            for $game<R>
+        {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let game = Self::deserialize(deserializer)?;
+                game.validate().map_err(serde::de::Error::custom)?;
+                Ok(game)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "serde-validate")]
+#[allow(unused_imports)]
+pub(crate) use validated_serde;
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        connect6::Connect6,
+        dominoes::{Dominoes, TileSet},
+        fox_and_geese::FoxAndGeese,
+        gomoku::Gomoku,
+        hasami_shogi::HasamiShogi,
+        invariant::*,
+        liars_dice::LiarsDice,
+        minesweeper::Minesweeper,
+        pente::Pente,
+        pig::{Pig, Variant},
+        reversi::Reversi,
+        rock_paper_scissors::RockPaperScissors,
+        score_four::ScoreFour,
+        shut_the_box::ShutTheBox,
+        testing::RandomPlay,
+        tictactoe::TicTacToe,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn play<G: RandomPlay + core::fmt::Debug>(
+        mut game: G,
+        validate: fn(&G) -> Result<(), InvariantError>,
+        rng: &mut StdRng,
+        max_moves: usize,
+    ) {
+        assert_eq!(validate(&game), Ok(()));
+
+        for _ in 0..max_moves {
+            let Some(mv) = game.random_move(rng) else {
+                break;
+            };
+
+            game.apply_move(mv);
+            assert_eq!(validate(&game), Ok(()), "{:?}", game);
+        }
+    }
+
+    #[test]
+    fn test() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..4 {
+            play(TicTacToe::new().unwrap(), TicTacToe::validate, &mut rng, 9);
+            play(Connect6::new().unwrap(), Connect6::validate, &mut rng, 100);
+            play(Gomoku::new().unwrap(), Gomoku::validate, &mut rng, 100);
+            play(
+                Reversi::with_free_setup().unwrap(),
+                Reversi::validate,
+                &mut rng,
+                64,
+            );
+            play(Reversi::new().unwrap(), Reversi::validate, &mut rng, 64);
+            play(ScoreFour::new().unwrap(), ScoreFour::validate, &mut rng, 64);
+            play(Pente::new().unwrap(), Pente::validate, &mut rng, 40);
+            play(
+                HasamiShogi::new().unwrap(),
+                HasamiShogi::validate,
+                &mut rng,
+                100,
+            );
+            play(
+                FoxAndGeese::new().unwrap(),
+                FoxAndGeese::validate,
+                &mut rng,
+                100,
+            );
+
+            play(
+                RockPaperScissors::with_best_of(5).unwrap(),
+                RockPaperScissors::validate,
+                &mut rng,
+                100,
+            );
+
+            let game = Pig::with_rules(3, Variant::BigPig, 50).unwrap();
+            play(game, Pig::validate, &mut rng, 1000);
+
+            play(
+                ShutTheBox::new().unwrap(),
+                ShutTheBox::validate,
+                &mut rng,
+                100,
+            );
+
+            let game = LiarsDice::with_rules(4, 3, true).unwrap();
+            play(game, LiarsDice::validate, &mut rng, 1000);
+
+            let game = Dominoes::with_set(TileSet::DoubleNine, &mut rng).unwrap();
+            play(game, Dominoes::validate, &mut rng, 100);
+
+            let game = Minesweeper::new(8, 8, 10, StdRng::seed_from_u64(0)).unwrap();
+            play(game, Minesweeper::validate, &mut rng, 64);
+        }
+
+        assert_eq!(check_turns(2, 2, true), Ok(()));
+        assert_eq!(check_turns(3, 1, false), Err(InvariantError::Board));
+        assert_eq!(check_turns(2, 1, true), Err(InvariantError::NextPlayer));
+    }
+}
@@ -0,0 +1,642 @@
+//! Liar's Dice
+//!
+//! Check struct [`LiarsDice`] for more information
+
+#[cfg(feature = "testing")]
+use crate::testing::{self, RandomPlay};
+use crate::{dice::Die, invariant::InvariantError, rng::GameRng};
+use snafu::Snafu;
+
+/// The most players a game can have
+pub const MAX_PLAYERS: usize = 6;
+/// The most dice a player can start with
+pub const MAX_DICE: usize = 5;
+
+/// The faces of the dice of every player, `0` for the dice a player does not have
+pub type Faces = [[u8; MAX_DICE]; MAX_PLAYERS];
+
+/// Liar's Dice for 2 to 6 players, each with a cup of up to 5 dice hidden from the others
+///
+/// Each round, every player rolls their dice. The players then take turns, each either raising the bid, a claim that at least a quantity of all dice show a face, or challenging the last bid. On a challenge, all dice are revealed, and the challenger loses a die if the bid holds, or the bidder does otherwise. The loser starts the next round, and the last player with dice left wins
+///
+/// The dice are rolled with [`roll`](LiarsDice::roll) from a [`GameRng`], or set with [`roll_dice`](LiarsDice::roll_dice) when they are rolled elsewhere. The dice of all players are part of the game state, so a server should not send the whole game to a player
+///
+/// Players are numbered from `0`
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::liars_dice::{Bid, LiarsDice};
+/// let mut game = LiarsDice::new(2).unwrap();
+///
+/// let mut faces = [[0; 5]; 6];
+/// faces[0] = [2, 2, 3, 4, 6];
+/// faces[1] = [2, 5, 5, 5, 1];
+/// game.roll_dice(faces).unwrap();
+///
+/// game.bid(Bid {
+///     quantity: 2,
+///     face: 5,
+/// })
+/// .unwrap();
+/// game.bid(Bid {
+///     quantity: 4,
+///     face: 5,
+/// })
+/// .unwrap();
+///
+/// // only three fives, so the bidder loses a die
+/// let reveal = game.challenge().unwrap();
+/// assert_eq!((reveal.count, reveal.loser), (3, 1));
+/// assert_eq!(game.dice_count(1), 4);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LiarsDice {
+    players: usize,
+    dice_per_player: usize,
+    is_one_wild: bool,
+    faces: Faces,
+    dice_counts: [usize; MAX_PLAYERS],
+    is_rolled: bool,
+    bid: Option<(usize, Bid)>,
+    last_reveal: Option<Reveal>,
+    next_player: usize,
+    status: Status,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(LiarsDice);
+
+/// A claim that at least `quantity` of all dice show `face`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Bid {
+    pub quantity: usize,
+    pub face: u8,
+}
+
+/// A resolved challenge
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Reveal {
+    /// The bid challenged
+    pub bid: Bid,
+    pub bidder: usize,
+    pub challenger: usize,
+    /// The number of dice showing the face of the bid, wild ones included
+    pub count: usize,
+    /// The player losing a die
+    pub loser: usize,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Win(usize),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Win(player),
+});
+
+crate::canonical::encode_struct!(Bid { quantity, face });
+
+crate::canonical::encode_struct!(Reveal {
+    bid,
+    bidder,
+    challenger,
+    count,
+    loser
+});
+
+crate::canonical::canonical!(LiarsDice, "liars_dice", 1 {
+    players,
+    dice_per_player,
+    is_one_wild,
+    faces,
+    dice_counts,
+    is_rolled,
+    bid,
+    last_reveal,
+    next_player,
+    status,
+});
+
+/// A move of the next player, or the roll starting a round
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Move {
+    /// A roll with the faces of the dice of every player
+    Roll(Faces),
+    Bid(Bid),
+    Challenge,
+}
+
+/// Errors that can occur when creating a game or making a move
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum LiarsDiceError {
+    #[snafu(display("number of players out of range"))]
+    InvalidPlayers,
+    #[snafu(display("number of dice out of range"))]
+    InvalidDiceCount,
+    #[snafu(display("face out of range"))]
+    InvalidFace,
+    #[snafu(display("already rolled"))]
+    AlreadyRolled,
+    #[snafu(display("not rolled yet"))]
+    NotRolled,
+    #[snafu(display("bid out of range"))]
+    InvalidBid,
+    #[snafu(display("bid does not raise the last bid"))]
+    BidTooLow,
+    #[snafu(display("no bid to challenge"))]
+    NoBid,
+    #[snafu(display("game ended"))]
+    GameEnded,
+}
+
+crate::code::codes!(LiarsDiceError {
+    InvalidPlayers => "invalid_players",
+    InvalidDiceCount => "invalid_dice_count",
+    InvalidFace => "invalid_face",
+    AlreadyRolled => "already_rolled",
+    NotRolled => "not_rolled",
+    InvalidBid => "invalid_bid",
+    BidTooLow => "bid_too_low",
+    NoBid => "no_bid",
+    GameEnded => "game_ended",
+});
+
+impl LiarsDice {
+    /// Create a new game with 5 dice per player and no wild face
+    ///
+    /// Return `Err(LiarsDiceError::InvalidPlayers)` if `players` is not from `2` to [`MAX_PLAYERS`]
+    pub const fn new(players: usize) -> Result<Self, LiarsDiceError> {
+        Self::with_rules(players, MAX_DICE, false)
+    }
+
+    /// Create a new game with a number of dice per player, and ones counting as every face if `is_one_wild` is `true`
+    ///
+    /// Return `Err(LiarsDiceError::InvalidPlayers)` if `players` is not from `2` to [`MAX_PLAYERS`], or `Err(LiarsDiceError::InvalidDiceCount)` if `dice_per_player` is not from `1` to [`MAX_DICE`]
+    pub const fn with_rules(
+        players: usize,
+        dice_per_player: usize,
+        is_one_wild: bool,
+    ) -> Result<Self, LiarsDiceError> {
+        if players < 2 || players > MAX_PLAYERS {
+            return Err(LiarsDiceError::InvalidPlayers);
+        }
+
+        if dice_per_player == 0 || dice_per_player > MAX_DICE {
+            return Err(LiarsDiceError::InvalidDiceCount);
+        }
+
+        let mut dice_counts = [0; MAX_PLAYERS];
+        let mut player = 0;
+
+        while player < players {
+            dice_counts[player] = dice_per_player;
+            player += 1;
+        }
+
+        Ok(Self {
+            players,
+            dice_per_player,
+            is_one_wild,
+            faces: [[0; MAX_DICE]; MAX_PLAYERS],
+            dice_counts,
+            is_rolled: false,
+            bid: None,
+            last_reveal: None,
+            next_player: 0,
+            status: Status::Ongoing,
+        })
+    }
+
+    /// Roll the dice of every player with six-sided dice from `rng`, starting a round
+    pub fn roll<R: GameRng + ?Sized>(&mut self, rng: &mut R) -> Result<(), LiarsDiceError> {
+        let faces = self.dice_counts.map(|count| {
+            core::array::from_fn(|idx| match idx < count {
+                true => Die::D6.roll(rng) as u8,
+                false => 0,
+            })
+        });
+
+        self.roll_dice(faces)
+    }
+
+    /// Start a round with the faces of the dice of every player, rolled elsewhere
+    ///
+    /// The faces of the dice a player does not have are ignored. Return `Err(LiarsDiceError::InvalidFace)` if a face of a die in play is not from `1` to `6`
+    pub fn roll_dice(&mut self, faces: Faces) -> Result<(), LiarsDiceError> {
+        if self.status != Status::Ongoing {
+            return Err(LiarsDiceError::GameEnded);
+        }
+
+        if self.is_rolled {
+            return Err(LiarsDiceError::AlreadyRolled);
+        }
+
+        for (player, count) in self.dice_counts.iter().enumerate() {
+            if !faces[player][..*count].iter().all(|face| is_face(*face)) {
+                return Err(LiarsDiceError::InvalidFace);
+            }
+        }
+
+        for (player, count) in self.dice_counts.iter().enumerate() {
+            self.faces[player] = core::array::from_fn(|idx| match idx < *count {
+                true => faces[player][idx],
+                false => 0,
+            });
+        }
+
+        self.is_rolled = true;
+        Ok(())
+    }
+
+    /// Make a bid on behalf of the next player, raising the last bid
+    ///
+    /// A bid raises another with a higher quantity, or the same quantity and a higher face. Return `Err(LiarsDiceError::InvalidBid)` if the face is not from `1` to `6`, or the quantity is `0` or more than the dice in play
+    pub fn bid(&mut self, bid: Bid) -> Result<(), LiarsDiceError> {
+        self.check_rolled()?;
+
+        if !is_face(bid.face) || bid.quantity == 0 || bid.quantity > self.total_dice() {
+            return Err(LiarsDiceError::InvalidBid);
+        }
+
+        if self.bid.is_some_and(|(_, last)| !bid.raises(last)) {
+            return Err(LiarsDiceError::BidTooLow);
+        }
+
+        self.bid = Some((self.next_player, bid));
+        self.next_player = self.next_active(self.next_player);
+        Ok(())
+    }
+
+    /// Challenge the last bid on behalf of the next player, revealing all dice and ending the round
+    pub fn challenge(&mut self) -> Result<Reveal, LiarsDiceError> {
+        self.check_rolled()?;
+
+        let Some((bidder, bid)) = self.bid else {
+            return Err(LiarsDiceError::NoBid);
+        };
+
+        let challenger = self.next_player;
+        let count = self.count(bid.face);
+        let loser = match count >= bid.quantity {
+            true => challenger,
+            false => bidder,
+        };
+
+        let reveal = Reveal {
+            bid,
+            bidder,
+            challenger,
+            count,
+            loser,
+        };
+
+        self.dice_counts[loser] -= 1;
+        self.faces = [[0; MAX_DICE]; MAX_PLAYERS];
+        self.is_rolled = false;
+        self.bid = None;
+        self.last_reveal = Some(reveal);
+
+        // the loser starts the next round, or the player after if eliminated
+        self.next_player = match self.dice_counts[loser] {
+            0 => self.next_active(loser),
+            _ => loser,
+        };
+
+        if self.active_players() == 1 {
+            self.status = Status::Win(self.next_player);
+        }
+
+        Ok(reveal)
+    }
+
+    /// Check if the next player can make a bid, same as [`bid`](Self::bid) succeeding but without changing the game
+    pub fn can_bid(&self, bid: Bid) -> bool {
+        self.status == Status::Ongoing
+            && self.is_rolled
+            && is_face(bid.face)
+            && bid.quantity != 0
+            && bid.quantity <= self.total_dice()
+            && self.bid.is_none_or(|(_, last)| bid.raises(last))
+    }
+
+    /// Check if the next player can challenge, same as [`challenge`](Self::challenge) succeeding but without changing the game
+    pub fn can_challenge(&self) -> bool {
+        self.status == Status::Ongoing && self.bid.is_some()
+    }
+
+    /// Apply a move
+    pub fn apply(&mut self, mv: Move) -> Result<(), LiarsDiceError> {
+        match mv {
+            Move::Roll(faces) => self.roll_dice(faces),
+            Move::Bid(bid) => self.bid(bid),
+            Move::Challenge => self.challenge().map(|_| ()),
+        }
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    pub fn preview(&self, mv: Move) -> Result<Self, LiarsDiceError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Get the number of players
+    pub const fn players(&self) -> usize {
+        self.players
+    }
+
+    /// Check if ones count as every face
+    pub const fn is_one_wild(&self) -> bool {
+        self.is_one_wild
+    }
+
+    /// Get the faces of the dice of a player, hidden from the other players, all `0` before rolling
+    ///
+    /// Panic if the player is out of range
+    pub fn dice(&self, player: usize) -> &[u8] {
+        assert!(player < self.players);
+        &self.faces[player][..self.dice_counts[player]]
+    }
+
+    /// Get the number of dice left to a player, `0` if eliminated
+    ///
+    /// Panic if the player is out of range
+    pub const fn dice_count(&self, player: usize) -> usize {
+        assert!(player < self.players);
+        self.dice_counts[player]
+    }
+
+    /// Get the number of dice in play
+    pub fn total_dice(&self) -> usize {
+        self.dice_counts.iter().sum()
+    }
+
+    /// Check if the dice of the round are rolled, so the bidding is open
+    pub const fn is_rolled(&self) -> bool {
+        self.is_rolled
+    }
+
+    /// Get the last bid of the round and the player who made it
+    pub const fn current_bid(&self) -> Option<(usize, Bid)> {
+        self.bid
+    }
+
+    /// Get the last resolved challenge
+    pub const fn last_reveal(&self) -> Option<Reveal> {
+        self.last_reveal
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The players and their dice must be in range, the faces must match the round, and the status must match the players left
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        if !(2..=MAX_PLAYERS).contains(&self.players)
+            || !(1..=MAX_DICE).contains(&self.dice_per_player)
+            || self.next_player >= self.players
+            || self.dice_counts[self.players..]
+                .iter()
+                .any(|count| *count != 0)
+            || self
+                .dice_counts
+                .iter()
+                .any(|count| *count > self.dice_per_player)
+        {
+            return Err(InvariantError::Counter);
+        }
+
+        let is_faces_valid =
+            self.faces
+                .iter()
+                .zip(self.dice_counts)
+                .all(|(faces, count)| {
+                    faces.iter().enumerate().all(|(idx, face)| {
+                        match self.is_rolled && idx < count {
+                            true => is_face(*face),
+                            false => *face == 0,
+                        }
+                    })
+                });
+
+        let is_bid_valid = self.bid.is_none_or(|(bidder, bid)| {
+            self.is_rolled
+                && bidder < self.players
+                && self.dice_counts[bidder] != 0
+                && bidder != self.next_player
+                && is_face(bid.face)
+                && (1..=self.total_dice()).contains(&bid.quantity)
+        });
+
+        if !is_faces_valid || !is_bid_valid {
+            return Err(InvariantError::Board);
+        }
+
+        let is_status_valid = match self.status {
+            Status::Ongoing => self.active_players() > 1 && self.dice_counts[self.next_player] != 0,
+            Status::Win(player) => {
+                self.active_players() == 1
+                    && player == self.next_player
+                    && self.dice_counts[player] != 0
+                    && !self.is_rolled
+            }
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> usize {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn check_rolled(&self) -> Result<(), LiarsDiceError> {
+        if self.status != Status::Ongoing {
+            return Err(LiarsDiceError::GameEnded);
+        }
+
+        if !self.is_rolled {
+            return Err(LiarsDiceError::NotRolled);
+        }
+
+        Ok(())
+    }
+
+    fn count(&self, face: u8) -> usize {
+        self.faces
+            .iter()
+            .flatten()
+            .filter(|die| **die == face || (self.is_one_wild && **die == 1))
+            .count()
+    }
+
+    fn active_players(&self) -> usize {
+        self.dice_counts.iter().filter(|count| **count != 0).count()
+    }
+
+    fn next_active(&self, player: usize) -> usize {
+        (1..self.players)
+            .map(|offset| (player + offset) % self.players)
+            .find(|player| self.dice_counts[*player] != 0)
+            .unwrap_or(player)
+    }
+}
+
+impl Bid {
+    /// Check if the bid raises another, with a higher quantity, or the same quantity and a higher face
+    pub const fn raises(self, other: Self) -> bool {
+        self.quantity > other.quantity
+            || (self.quantity == other.quantity && self.face > other.face)
+    }
+}
+
+const fn is_face(face: u8) -> bool {
+    face >= 1 && face as usize <= Die::D6.faces()
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for LiarsDice {
+    type Move = Move;
+
+    /// Roll with faces drawn from `rng` when a round starts, or pick uniformly from the challenge and the possible bids
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        if self.status != Status::Ongoing {
+            return None;
+        }
+
+        if !self.is_rolled {
+            let faces = self.dice_counts.map(|count| {
+                core::array::from_fn(|idx| match idx < count {
+                    true => Die::D6.roll(rng) as u8,
+                    false => 0,
+                })
+            });
+
+            return Some(Move::Roll(faces));
+        }
+
+        let bids = (1..=self.total_dice())
+            .flat_map(|quantity| (1..=6).map(move |face| Bid { quantity, face }))
+            .filter(|bid| self.can_bid(*bid))
+            .map(Move::Bid);
+        let challenge = self.can_challenge().then_some(Move::Challenge);
+
+        testing::choose(rng, bids.chain(challenge))
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::liars_dice::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test() {
+        assert_eq!(
+            LiarsDice::new(7).unwrap_err(),
+            LiarsDiceError::InvalidPlayers
+        );
+        assert_eq!(
+            LiarsDice::with_rules(2, 6, false).unwrap_err(),
+            LiarsDiceError::InvalidDiceCount
+        );
+
+        let mut game = LiarsDice::with_rules(3, 2, true).unwrap();
+        let bid = |quantity, face| Bid { quantity, face };
+
+        assert_eq!(game.bid(bid(1, 2)), Err(LiarsDiceError::NotRolled));
+
+        let mut faces = [[0; MAX_DICE]; MAX_PLAYERS];
+        faces[0] = [1, 4, 6, 6, 6];
+        faces[1] = [4, 2, 0, 0, 0];
+        faces[2] = [3, 7, 0, 0, 0];
+        assert_eq!(game.roll_dice(faces), Err(LiarsDiceError::InvalidFace));
+
+        faces[2] = [3, 3, 0, 0, 0];
+        game.roll_dice(faces).unwrap();
+        assert_eq!(game.dice(0), [1, 4]);
+        assert_eq!(game.challenge(), Err(LiarsDiceError::NoBid));
+
+        game.bid(bid(2, 3)).unwrap();
+        assert_eq!(game.bid(bid(2, 2)), Err(LiarsDiceError::BidTooLow));
+        assert_eq!(game.bid(bid(7, 2)), Err(LiarsDiceError::InvalidBid));
+        game.apply(Move::Bid(bid(3, 4))).unwrap();
+        assert_eq!(game.current_bid(), Some((1, bid(3, 4))));
+        assert_eq!(game.validate(), Ok(()));
+
+        // the one is wild, so there are three fours
+        let reveal = game.challenge().unwrap();
+        assert_eq!((reveal.count, reveal.challenger, reveal.loser), (3, 2, 2));
+        assert_eq!(game.next_player(), 2);
+        assert!(!game.is_rolled());
+        assert_eq!(game.validate(), Ok(()));
+
+        faces = [[1; MAX_DICE]; MAX_PLAYERS];
+        game.roll_dice(faces).unwrap();
+        game.bid(bid(1, 6)).unwrap();
+        game.bid(bid(2, 6)).unwrap();
+        game.bid(bid(5, 5)).unwrap();
+
+        // the player losing the last die is eliminated, and the next one starts
+        game.challenge().unwrap();
+        assert_eq!(game.dice_count(2), 0);
+        assert_eq!(game.next_player(), 0);
+        assert_eq!(game.validate(), Ok(()));
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut game = LiarsDice::new(4).unwrap();
+
+        while game.status() == &Status::Ongoing {
+            if !game.is_rolled() {
+                game.roll(&mut rng).unwrap();
+            }
+
+            match game.current_bid() {
+                Some((_, last)) if last.quantity * 3 > game.total_dice() => {
+                    game.challenge().unwrap();
+                }
+                Some((_, last)) => game.bid(bid(last.quantity + 1, last.face)).unwrap(),
+                None => game.bid(bid(1, 6)).unwrap(),
+            }
+
+            assert_eq!(game.validate(), Ok(()));
+        }
+    }
+}
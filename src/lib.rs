@@ -1,17 +1,98 @@
 #![doc = include_str!("../README.md")]
 #![no_std]
 
+#[cfg(any(feature = "minesweeper", feature = "serde"))]
+extern crate alloc;
+
+// the derive macro of `arbitrary` refers to `std`
+#[cfg(feature = "arbitrary")]
+extern crate std;
+
+pub mod affect;
+pub mod canonical;
+pub mod code;
+mod error;
+pub mod invariant;
+pub mod outcome;
+pub mod parse;
+pub mod rng;
+
+pub use error::Error;
+
 #[cfg(feature = "connect_four")]
 pub mod connect_four;
 
+#[cfg(feature = "connect6")]
+pub mod connect6;
+
+#[cfg(feature = "dice")]
+pub mod dice;
+
+#[cfg(feature = "dominoes")]
+pub mod dominoes;
+
+#[cfg(feature = "drop_three")]
+pub mod drop_three;
+
+#[cfg(feature = "fairness")]
+pub mod fairness;
+
+#[cfg(feature = "fox_and_geese")]
+pub mod fox_and_geese;
+
 #[cfg(feature = "gomoku")]
 pub mod gomoku;
 
-#[cfg(feature = "minesweeper")]
+#[cfg(feature = "hasami_shogi")]
+pub mod hasami_shogi;
+
+#[cfg(feature = "liars_dice")]
+pub mod liars_dice;
+
+#[cfg(feature = "logic_grid")]
+pub mod logic_grid;
+
+#[cfg(any(feature = "minesweeper", feature = "minesweeper_fixed"))]
 pub mod minesweeper;
 
+#[cfg(feature = "orchestration")]
+pub mod orchestration;
+
+#[cfg(feature = "order_and_chaos")]
+pub mod order_and_chaos;
+
+#[cfg(feature = "pente")]
+pub mod pente;
+
+#[cfg(feature = "pig")]
+pub mod pig;
+
 #[cfg(feature = "reversi")]
 pub mod reversi;
 
+#[cfg(feature = "rock_paper_scissors")]
+pub mod rock_paper_scissors;
+
+#[cfg(feature = "score_four")]
+pub mod score_four;
+
+#[cfg(feature = "serde")]
+pub mod session;
+
+#[cfg(feature = "sgf")]
+pub mod sgf;
+
+#[cfg(feature = "shut_the_box")]
+pub mod shut_the_box;
+
+#[cfg(feature = "sos")]
+pub mod sos;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
 #[cfg(feature = "tictactoe")]
 pub mod tictactoe;
+
+#[cfg(feature = "util")]
+pub mod util;
@@ -0,0 +1,387 @@
+//! Binairo, also known as Takuzu
+//!
+//! Check struct [`Binairo`] for more information
+
+use super::{Constraint, Contradiction, Domain, Grid};
+use crate::rng::GameRng;
+use snafu::Snafu;
+
+// the most cells of the same value in a row along a line
+const MAX_RUN: usize = 2;
+
+/// Binairo, a puzzle of filling an `N` by `N` grid with `0` and `1`
+///
+/// No three cells in a row or a column hold the same value, each row and column holds as many `0` as `1`, and no two rows or two columns are the same. A puzzle starts with some cells given as clues, enough to leave a single solution
+///
+/// `N` must be even and at most `16`
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::logic_grid::binairo::Binairo;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut game = Binairo::<6>::generate(&mut StdRng::seed_from_u64(0)).unwrap();
+/// assert!(game.has_unique_solution());
+///
+/// let solution = game.solution().unwrap();
+///
+/// for (row, col) in Binairo::<6>::positions() {
+///     if game.clue(row, col).is_none() {
+///         game.put(row, col, Some(solution[row][col])).unwrap();
+///     }
+/// }
+///
+/// assert!(game.is_solved());
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Binairo<const N: usize> {
+    clues: [[Option<u8>; N]; N],
+    cells: [[Option<u8>; N]; N],
+}
+
+crate::canonical::canonical!([const N: usize] Binairo<N>, "binairo", 1 {
+    clues,
+    cells,
+});
+
+/// Errors that can occur when creating a Binairo puzzle or filling a cell
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum BinairoError {
+    #[snafu(display("size not even or larger than 16"))]
+    InvalidSize,
+    #[snafu(display("value not 0 or 1"))]
+    InvalidValue,
+    #[snafu(display("cell given as a clue"))]
+    CellGiven,
+    #[snafu(display("no solution"))]
+    NoSolution,
+}
+
+crate::code::codes!(BinairoError {
+    InvalidSize => "invalid_size",
+    InvalidValue => "invalid_value",
+    CellGiven => "cell_given",
+    NoSolution => "no_solution",
+});
+
+/// The rule of no three cells in a row of the same value
+struct NoThree;
+
+/// The rule of as many `0` as `1` in each line
+struct Balance;
+
+/// The rule of no two rows or two columns the same
+struct Distinct;
+
+impl<const N: usize> Binairo<N> {
+    /// Create a puzzle from its clues
+    ///
+    /// Return `Err(BinairoError::InvalidSize)` if `N` is odd or more than `16`, `Err(BinairoError::InvalidValue)` if a clue is not `0` or `1`, and `Err(BinairoError::NoSolution)` if the clues break the rules or leave no solution. The clues may leave more than one solution, see [`has_unique_solution`](Self::has_unique_solution)
+    pub fn new(clues: [[Option<u8>; N]; N]) -> Result<Self, BinairoError> {
+        if N % 2 == 1 || N > 16 {
+            return Err(BinairoError::InvalidSize);
+        }
+
+        if clues.iter().flatten().flatten().any(|value| *value > 1) {
+            return Err(BinairoError::InvalidValue);
+        }
+
+        let game = Self {
+            clues,
+            cells: clues,
+        };
+
+        if game.solution().is_none() {
+            return Err(BinairoError::NoSolution);
+        }
+
+        Ok(game)
+    }
+
+    /// Generate a puzzle with a unique solution
+    ///
+    /// A random solution is drawn first, then its cells are taken out in random order as long as the solution stays unique, so every clue left is needed
+    ///
+    /// Return `Err(BinairoError::InvalidSize)` if `N` is odd or more than `16`
+    pub fn generate<R: GameRng + ?Sized>(rng: &mut R) -> Result<Self, BinairoError> {
+        if N % 2 == 1 || N > 16 {
+            return Err(BinairoError::InvalidSize);
+        }
+
+        let solved = super::random_solution(&Grid::new(Domain::below(2)), &Self::CONSTRAINTS, rng)
+            .and_then(|grid| grid.values())
+            .ok_or(BinairoError::NoSolution)?;
+
+        let mut clues = solved.map(|row| row.map(Some));
+        let mut positions = [(0, 0); 256];
+
+        for (idx, position) in Self::positions().enumerate() {
+            positions[idx] = position;
+        }
+
+        let positions = &mut positions[..N * N];
+        rng.shuffle(positions);
+
+        for (row, col) in positions.iter() {
+            let clue = clues[*row][*col].take();
+
+            if super::count_solutions(&Self::grid_of(&clues), &Self::CONSTRAINTS, 2) > 1 {
+                clues[*row][*col] = clue;
+            }
+        }
+
+        Ok(Self {
+            clues,
+            cells: clues,
+        })
+    }
+
+    /// Get the clue of a cell, `None` if it is not given
+    ///
+    /// Panic if the position is out of bounds
+    pub const fn clue(&self, row: usize, col: usize) -> Option<u8> {
+        self.clues[row][col]
+    }
+
+    /// Get the value of a cell, given or filled, `None` if empty
+    ///
+    /// Panic if the position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Option<u8> {
+        self.cells[row][col]
+    }
+
+    /// Fill a cell with a value, or empty it with `None`
+    ///
+    /// The value is not checked against the rules, see [`is_solved`](Self::is_solved)
+    ///
+    /// Return `Err(BinairoError::CellGiven)` if the cell is a clue, and `Err(BinairoError::InvalidValue)` if the value is not `0` or `1`
+    ///
+    /// Panic if the position is out of bounds
+    pub fn put(&mut self, row: usize, col: usize, value: Option<u8>) -> Result<(), BinairoError> {
+        if self.clues[row][col].is_some() {
+            return Err(BinairoError::CellGiven);
+        }
+
+        if value.is_some_and(|value| value > 1) {
+            return Err(BinairoError::InvalidValue);
+        }
+
+        self.cells[row][col] = value;
+        Ok(())
+    }
+
+    /// Check if every cell is filled and the rules are kept
+    pub fn is_solved(&self) -> bool {
+        let grid = Self::grid_of(&self.cells);
+        grid.is_decided() && super::propagate(&mut grid.clone(), &Self::CONSTRAINTS).is_ok()
+    }
+
+    /// Get a solution of the puzzle from its clues, `None` if there is none
+    pub fn solution(&self) -> Option<[[u8; N]; N]> {
+        super::solve(&Self::grid_of(&self.clues), &Self::CONSTRAINTS).and_then(|grid| grid.values())
+    }
+
+    /// Check if the clues leave a single solution
+    pub fn has_unique_solution(&self) -> bool {
+        super::count_solutions(&Self::grid_of(&self.clues), &Self::CONSTRAINTS, 2) == 1
+    }
+
+    /// Iterate over the positions as `(row, col)`, in row-major order
+    pub fn positions() -> impl Iterator<Item = (usize, usize)> {
+        (0..N).flat_map(|row| (0..N).map(move |col| (row, col)))
+    }
+
+    const CONSTRAINTS: [&'static dyn Constraint<N, N>; 3] = [&NoThree, &Balance, &Distinct];
+
+    /// Get the grid of the cells, with the empty ones undecided
+    fn grid_of(cells: &[[Option<u8>; N]; N]) -> Grid<N, N> {
+        let mut grid = Grid::new(Domain::below(2));
+
+        for (row, col) in Self::positions() {
+            if let Some(value) = cells[row][col] {
+                grid.domains[row][col] = Domain::single(value);
+            }
+        }
+
+        grid
+    }
+}
+
+/// Iterate over the rows then the columns, each as the positions along it
+fn lines<const N: usize>() -> impl Iterator<Item = [(usize, usize); N]> {
+    let rows = (0..N).map(|row| core::array::from_fn(|col| (row, col)));
+    let cols = (0..N).map(|col| core::array::from_fn(|row| (row, col)));
+
+    rows.chain(cols)
+}
+
+impl<const N: usize> Constraint<N, N> for NoThree {
+    fn propagate(&self, grid: &mut Grid<N, N>) -> Result<bool, Contradiction> {
+        let mut is_changed = false;
+
+        for line in lines::<N>() {
+            for window in line.windows(MAX_RUN + 1) {
+                // two cells of a value in a window of three rule the value out of the third
+                for (idx, &(row, col)) in window.iter().enumerate() {
+                    let mut others = window
+                        .iter()
+                        .enumerate()
+                        .filter(|(other, _)| *other != idx)
+                        .map(|(_, (row, col))| grid.get(*row, *col).value());
+
+                    if let (Some(Some(a)), Some(Some(b))) = (others.next(), others.next()) {
+                        if a == b {
+                            is_changed |= grid.remove(row, col, a)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(is_changed)
+    }
+}
+
+impl<const N: usize> Constraint<N, N> for Balance {
+    fn propagate(&self, grid: &mut Grid<N, N>) -> Result<bool, Contradiction> {
+        let mut is_changed = false;
+
+        for line in lines::<N>() {
+            for value in 0..2 {
+                let count = line
+                    .iter()
+                    .filter(|(row, col)| grid.get(*row, *col).value() == Some(value))
+                    .count();
+
+                if count > N / 2 {
+                    return Err(Contradiction);
+                }
+
+                // a line with half of its cells of a value has the value nowhere else
+                if count == N / 2 {
+                    for (row, col) in line {
+                        if grid.get(row, col).value() != Some(value) {
+                            is_changed |= grid.remove(row, col, value)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(is_changed)
+    }
+}
+
+impl<const N: usize> Constraint<N, N> for Distinct {
+    fn propagate(&self, grid: &mut Grid<N, N>) -> Result<bool, Contradiction> {
+        let mut is_changed = false;
+        let numbered = || lines::<N>().enumerate();
+
+        for (idx, full) in numbered() {
+            let Some(full_values) = line_values(grid, &full) else {
+                continue;
+            };
+
+            // only lines of the same direction are compared, the rows come first
+            for (_, other) in numbered().filter(|(other, _)| *other != idx && *other / N == idx / N)
+            {
+                let undecided = other
+                    .iter()
+                    .filter(|(row, col)| grid.get(*row, *col).len() > 1);
+
+                let is_matching = other.iter().zip(full_values).all(|((row, col), value)| {
+                    grid.get(*row, *col)
+                        .value()
+                        .is_none_or(|other| other == value)
+                });
+
+                if !is_matching {
+                    continue;
+                }
+
+                match undecided.count() {
+                    0 => return Err(Contradiction),
+                    // with the counts balanced, the two cells left must differ from the full line
+                    2 => {
+                        for ((row, col), value) in other.iter().zip(full_values) {
+                            if grid.get(*row, *col).len() > 1 {
+                                is_changed |= grid.remove(*row, *col, value)?;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(is_changed)
+    }
+}
+
+/// Get the values along a line, `None` unless every cell is decided
+fn line_values<const N: usize>(grid: &Grid<N, N>, line: &[(usize, usize); N]) -> Option<[u8; N]> {
+    let mut values = [0; N];
+
+    for (idx, (row, col)) in line.iter().enumerate() {
+        values[idx] = grid.get(*row, *col).value()?;
+    }
+
+    Some(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic_grid::binairo::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..4 {
+            let game = Binairo::<6>::generate(&mut rng).unwrap();
+            assert!(game.has_unique_solution());
+
+            // every clue left is needed
+            for (row, col) in Binairo::<6>::positions() {
+                if game.clue(row, col).is_some() {
+                    let mut clues = game.clues;
+                    clues[row][col] = None;
+                    assert!(!Binairo::new(clues).unwrap().has_unique_solution());
+                }
+            }
+        }
+
+        let game = Binairo::<8>::generate(&mut rng).unwrap();
+        let solution = game.solution().unwrap();
+        let mut filled = Binairo::new(solution.map(|row| row.map(Some))).unwrap();
+        assert!(filled.is_solved());
+        assert_eq!(filled.put(0, 0, None), Err(BinairoError::CellGiven));
+
+        // two rows the same
+        let mut clues = [[None; 4]; 4];
+        clues[0] = [Some(0), Some(1), Some(0), Some(1)];
+        clues[1] = [Some(0), Some(1), Some(0), Some(1)];
+        assert_eq!(Binairo::new(clues), Err(BinairoError::NoSolution));
+
+        // three in a row
+        let mut clues = [[None; 4]; 4];
+        clues[0] = [Some(1), Some(1), Some(1), None];
+        assert_eq!(Binairo::new(clues), Err(BinairoError::NoSolution));
+
+        let mut game = Binairo::new([[None; 4]; 4]).unwrap();
+        assert!(!game.has_unique_solution());
+        assert_eq!(game.put(0, 0, Some(2)), Err(BinairoError::InvalidValue));
+        game.put(0, 0, Some(1)).unwrap();
+        assert!(!game.is_solved());
+
+        assert_eq!(
+            Binairo::<5>::new([[None; 5]; 5]),
+            Err(BinairoError::InvalidSize)
+        );
+    }
+}
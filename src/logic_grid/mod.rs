@@ -0,0 +1,333 @@
+//! Logic grid puzzles, solved by constraint propagation and search, without allocation
+//!
+//! A puzzle is a [`Grid`] of cells, each holding the [`Domain`] of values it may still take, and a set of [`Constraint`]s, each narrowing down the domains the rules allow. [`solve`] propagates the constraints until nothing changes, then tries each value of the cell with the fewest left, which also tells if a puzzle has a unique solution with [`count_solutions`]
+//!
+//! Check module [`binairo`] for a puzzle built on it
+//!
+//! # Examples
+//!
+//! ```rust
+//! use gamie::logic_grid::{self, Constraint, Contradiction, Domain, Grid};
+//!
+//! // a Latin square, where each row and column holds each value once
+//! struct Latin;
+//!
+//! impl Constraint<3, 3> for Latin {
+//!     fn propagate(&self, grid: &mut Grid<3, 3>) -> Result<bool, Contradiction> {
+//!         let mut is_changed = false;
+//!
+//!         for (row, col) in grid.positions() {
+//!             let Some(value) = grid.get(row, col).value() else {
+//!                 continue;
+//!             };
+//!
+//!             for other in 0..3 {
+//!                 if other != col {
+//!                     is_changed |= grid.remove(row, other, value)?;
+//!                 }
+//!                 if other != row {
+//!                     is_changed |= grid.remove(other, col, value)?;
+//!                 }
+//!             }
+//!         }
+//!
+//!         Ok(is_changed)
+//!     }
+//! }
+//!
+//! let mut grid = Grid::<3, 3>::new(Domain::below(3));
+//! grid.assign(0, 0, 0).unwrap();
+//! grid.assign(1, 1, 1).unwrap();
+//!
+//! assert_eq!(logic_grid::count_solutions(&grid, &[&Latin], 2), 1);
+//! assert_eq!(
+//!     logic_grid::solve(&grid, &[&Latin]).unwrap().values(),
+//!     Some([[0, 2, 1], [2, 1, 0], [1, 0, 2]])
+//! );
+//! ```
+
+#[cfg(feature = "binairo")]
+pub mod binairo;
+
+use crate::rng::GameRng;
+
+// the values a domain can hold, `0` to `15`
+const MAX_VALUES: usize = u16::BITS as usize;
+
+/// The values a cell may still take, a set of numbers from `0` to `15`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Domain(u16);
+
+/// The state of a puzzle, a grid of `H` rows and `W` columns of domains
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Grid<const H: usize, const W: usize> {
+    domains: [[Domain; W]; H],
+}
+
+/// A grid left a cell without any value, so it has no solution
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Contradiction;
+
+/// A rule of a puzzle, over some or all cells of a grid of `H` rows and `W` columns
+pub trait Constraint<const H: usize, const W: usize> {
+    /// Remove the values the rule rules out from the domains of the grid
+    ///
+    /// Return if any domain changed, or `Err(Contradiction)` if the grid cannot satisfy the rule. A grid with every cell decided must be checked in full, as the solver takes it as a solution once no constraint finds a contradiction
+    fn propagate(&self, grid: &mut Grid<H, W>) -> Result<bool, Contradiction>;
+}
+
+impl Domain {
+    /// The domain without any value
+    pub const EMPTY: Self = Self(0);
+
+    /// Create the domain of the values from `0` to `count - 1`
+    ///
+    /// Panic if `count` is more than `16`
+    pub const fn below(count: usize) -> Self {
+        assert!(count <= MAX_VALUES);
+        Self((((1u32 << count) - 1) & u16::MAX as u32) as u16)
+    }
+
+    /// Create the domain of a single value
+    ///
+    /// Panic if the value is more than `15`
+    pub const fn single(value: u8) -> Self {
+        assert!((value as usize) < MAX_VALUES);
+        Self(1 << value)
+    }
+
+    /// Check if the domain holds a value
+    pub const fn contains(self, value: u8) -> bool {
+        (value as usize) < MAX_VALUES && self.0 & 1 << value != 0
+    }
+
+    /// Get the number of values in the domain
+    pub const fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Check if the domain has no value left
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Get the value of a domain decided to a single one, `None` otherwise
+    pub const fn value(self) -> Option<u8> {
+        if self.len() == 1 {
+            Some(self.0.trailing_zeros() as u8)
+        } else {
+            None
+        }
+    }
+
+    /// Get the domain without a value
+    pub const fn without(self, value: u8) -> Self {
+        if self.contains(value) {
+            Self(self.0 & !(1 << value))
+        } else {
+            self
+        }
+    }
+
+    /// Iterate over the values in the domain, in ascending order
+    pub fn values(self) -> impl Iterator<Item = u8> {
+        (0..MAX_VALUES as u8).filter(move |value| self.contains(*value))
+    }
+}
+
+impl<const H: usize, const W: usize> Grid<H, W> {
+    /// Create a grid with every cell holding the same domain
+    pub const fn new(domain: Domain) -> Self {
+        Self {
+            domains: [[domain; W]; H],
+        }
+    }
+
+    /// Get the domain of a cell
+    ///
+    /// Panic if the position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Domain {
+        self.domains[row][col]
+    }
+
+    /// Narrow the domain of a cell down to the values also in another domain
+    ///
+    /// Return if the domain changed, or `Err(Contradiction)` if no value is left
+    ///
+    /// Panic if the position is out of bounds
+    pub fn restrict(
+        &mut self,
+        row: usize,
+        col: usize,
+        domain: Domain,
+    ) -> Result<bool, Contradiction> {
+        let before = self.domains[row][col];
+        let after = Domain(before.0 & domain.0);
+
+        if after.is_empty() {
+            return Err(Contradiction);
+        }
+
+        self.domains[row][col] = after;
+        Ok(after != before)
+    }
+
+    /// Remove a value from the domain of a cell, see [`restrict`](Self::restrict)
+    ///
+    /// Panic if the position is out of bounds
+    pub fn remove(&mut self, row: usize, col: usize, value: u8) -> Result<bool, Contradiction> {
+        self.restrict(row, col, Domain::below(MAX_VALUES).without(value))
+    }
+
+    /// Decide a cell to a value, see [`restrict`](Self::restrict)
+    ///
+    /// Panic if the position is out of bounds, or the value is more than `15`
+    pub fn assign(&mut self, row: usize, col: usize, value: u8) -> Result<bool, Contradiction> {
+        self.restrict(row, col, Domain::single(value))
+    }
+
+    /// Check if every cell is decided to a single value
+    pub fn is_decided(&self) -> bool {
+        self.domains
+            .iter()
+            .flatten()
+            .all(|domain| domain.len() == 1)
+    }
+
+    /// Get the value of every cell, `None` unless every cell is decided
+    pub fn values(&self) -> Option<[[u8; W]; H]> {
+        let mut values = [[0; W]; H];
+
+        for (row, col) in self.positions() {
+            values[row][col] = self.domains[row][col].value()?;
+        }
+
+        Some(values)
+    }
+
+    /// Iterate over the positions as `(row, col)`, in row-major order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        (0..H).flat_map(|row| (0..W).map(move |col| (row, col)))
+    }
+}
+
+/// Propagate the constraints over a grid until none of them changes it
+///
+/// Return `Err(Contradiction)` if a constraint cannot be satisfied
+pub fn propagate<const H: usize, const W: usize>(
+    grid: &mut Grid<H, W>,
+    constraints: &[&dyn Constraint<H, W>],
+) -> Result<(), Contradiction> {
+    loop {
+        let mut is_changed = false;
+
+        for constraint in constraints {
+            is_changed |= constraint.propagate(grid)?;
+        }
+
+        if !is_changed {
+            return Ok(());
+        }
+    }
+}
+
+/// Get the first solution of a grid under the constraints, with every cell decided, `None` if there is none
+pub fn solve<const H: usize, const W: usize>(
+    grid: &Grid<H, W>,
+    constraints: &[&dyn Constraint<H, W>],
+) -> Option<Grid<H, W>> {
+    let mut solution = None;
+
+    search(grid.clone(), constraints, &mut |_| {}, &mut |solved| {
+        solution = Some(solved.clone());
+        true
+    });
+
+    solution
+}
+
+/// Get a random solution of a grid under the constraints, `None` if there is none
+///
+/// The values of each cell are tried in random order, e.g. to fill an empty grid when generating a puzzle
+pub fn random_solution<const H: usize, const W: usize, R: GameRng + ?Sized>(
+    grid: &Grid<H, W>,
+    constraints: &[&dyn Constraint<H, W>],
+    rng: &mut R,
+) -> Option<Grid<H, W>> {
+    let mut solution = None;
+
+    search(
+        grid.clone(),
+        constraints,
+        &mut |values| rng.shuffle(values),
+        &mut |solved| {
+            solution = Some(solved.clone());
+            true
+        },
+    );
+
+    solution
+}
+
+/// Count the solutions of a grid under the constraints, stopping at `limit`
+///
+/// A limit of `2` is enough to tell if a puzzle has a unique solution
+pub fn count_solutions<const H: usize, const W: usize>(
+    grid: &Grid<H, W>,
+    constraints: &[&dyn Constraint<H, W>],
+    limit: usize,
+) -> usize {
+    let mut count = 0;
+
+    if limit > 0 {
+        search(grid.clone(), constraints, &mut |_| {}, &mut |_| {
+            count += 1;
+            count >= limit
+        });
+    }
+
+    count
+}
+
+/// Search the solutions depth first, trying the values of the undecided cell with the fewest left in the order given by `order`
+///
+/// `visit` is called with each solution, and stops the search by returning `true`. Return if the search was stopped
+fn search<const H: usize, const W: usize>(
+    mut grid: Grid<H, W>,
+    constraints: &[&dyn Constraint<H, W>],
+    order: &mut impl FnMut(&mut [u8]),
+    visit: &mut impl FnMut(&Grid<H, W>) -> bool,
+) -> bool {
+    if propagate(&mut grid, constraints).is_err() {
+        return false;
+    }
+
+    let branch = grid
+        .positions()
+        .filter(|(row, col)| grid.get(*row, *col).len() > 1)
+        .min_by_key(|(row, col)| grid.get(*row, *col).len());
+
+    let Some((row, col)) = branch else {
+        return visit(&grid);
+    };
+
+    let domain = grid.get(row, col);
+    let mut values = [0; MAX_VALUES];
+
+    for (idx, value) in domain.values().enumerate() {
+        values[idx] = value;
+    }
+
+    let values = &mut values[..domain.len()];
+    order(values);
+
+    values.iter().any(|value| {
+        let mut branch = grid.clone();
+        branch.domains[row][col] = Domain::single(*value);
+        search(branch, constraints, order, visit)
+    })
+}
@@ -0,0 +1,163 @@
+//! Types shared by [`Minesweeper`](super::Minesweeper) and [`FixedGame`](super::FixedGame)
+
+use snafu::Snafu;
+
+/// The most boards generated for the first click to land on a cell without adjacent mines, which for [`Minesweeper`](super::Minesweeper) also opens [`Rules::min_opening`](super::Rules::min_opening) cells
+pub const MAX_OPENING_ATTEMPTS: usize = 1 << 12;
+
+/// The cell in the board.
+///
+/// A cell takes 4 bytes, a byte for each field. With up to [`MAX_MINES_PER_CELL`](super::MAX_MINES_PER_CELL) mines and as many flags in a cell, and up to 8 times as many mines around it, a cell needs 19 bits, so it does not fit into a single byte
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Cell {
+    /// The number of mines in the cell, up to [`Rules::max_mines_per_cell`](super::Rules::max_mines_per_cell)
+    pub mines: u8,
+    /// The total number of mines in the adjacent cells
+    pub mine_adjacent: u8,
+    pub is_revealed: bool,
+    /// The number of flags placed on the cell, up to [`Rules::max_mines_per_cell`](super::Rules::max_mines_per_cell)
+    pub flags: u8,
+}
+
+impl Cell {
+    pub(super) const fn new() -> Self {
+        Self {
+            mines: 0,
+            mine_adjacent: 0,
+            is_revealed: false,
+            flags: 0,
+        }
+    }
+
+    /// Check if the cell contains any mine
+    pub const fn is_mine(&self) -> bool {
+        self.mines > 0
+    }
+
+    /// Check if the cell carries any flag
+    pub const fn is_flagged(&self) -> bool {
+        self.flags > 0
+    }
+}
+
+crate::canonical::encode_struct!(Cell {
+    mines,
+    mine_adjacent,
+    is_revealed,
+    flags
+});
+
+#[derive(Clone)]
+pub(super) struct AdjacentCells {
+    around: [(isize, isize); 8],
+    board_height: isize,
+    board_width: isize,
+    offset: usize,
+}
+
+impl Iterator for AdjacentCells {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.around[self.offset..]
+            .iter()
+            .enumerate()
+            .find(|(_, (row, col))| {
+                *row >= 0 && *col >= 0 && *row < self.board_height && *col < self.board_width
+            })
+            .map(|(idx, (row, col))| {
+                self.offset += idx + 1;
+                (row * self.board_width + col) as usize
+            })
+    }
+}
+
+impl AdjacentCells {
+    pub(super) fn new(
+        row: usize,
+        col: usize,
+        board_height: usize,
+        board_width: usize,
+        is_toroidal: bool,
+    ) -> Self {
+        let (row, col, board_height, board_width) = (
+            row as isize,
+            col as isize,
+            board_height as isize,
+            board_width as isize,
+        );
+
+        let mut around = [
+            (row - 1, col - 1),
+            (row - 1, col),
+            (row - 1, col + 1),
+            (row, col - 1),
+            (row, col + 1),
+            (row + 1, col - 1),
+            (row + 1, col),
+            (row + 1, col + 1),
+        ];
+
+        if is_toroidal {
+            for (row, col) in &mut around {
+                *row = row.rem_euclid(board_height);
+                *col = col.rem_euclid(board_width);
+            }
+        }
+
+        AdjacentCells {
+            around,
+            board_height,
+            board_width,
+            offset: 0,
+        }
+    }
+}
+
+/// Errors that can occur.
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum MinesweeperError {
+    #[snafu(display("Too many mines"))]
+    TooManyMines,
+    #[snafu(display("Board too small"))]
+    BoardTooSmall,
+    #[snafu(display("Too many flags"))]
+    TooManyFlags,
+    #[snafu(display("Clicked an already flagged cell"))]
+    AlreadyFlagged,
+    #[snafu(display("Clicked an already revealed cell"))]
+    AlreadyRevealed,
+    #[snafu(display("The game was already end"))]
+    GameEnded,
+    #[snafu(display("No explosion to undo"))]
+    NothingToUndo,
+    #[snafu(display("Chorded an unrevealed cell"))]
+    NotRevealed,
+    #[snafu(display("Invalid mine layout"))]
+    InvalidLayout,
+    #[snafu(display("Opening too large for the mines"))]
+    OpeningTooLarge,
+    #[snafu(display("Invalid rules"))]
+    InvalidRules,
+    #[snafu(display("No mines to find"))]
+    NoMines,
+}
+
+crate::code::codes!(MinesweeperError {
+    TooManyMines => "too_many_mines",
+    BoardTooSmall => "board_too_small",
+    TooManyFlags => "too_many_flags",
+    AlreadyFlagged => "already_flagged",
+    AlreadyRevealed => "already_revealed",
+    GameEnded => "game_ended",
+    NothingToUndo => "nothing_to_undo",
+    NotRevealed => "not_revealed",
+    InvalidLayout => "invalid_layout",
+    OpeningTooLarge => "opening_too_large",
+    InvalidRules => "invalid_rules",
+    NoMines => "no_mines",
+});
@@ -0,0 +1,681 @@
+//! Minesweeper on a board of fixed size, without memory allocation
+//!
+//! Check struct [`FixedGame`] for more information
+
+use super::common::{AdjacentCells, Cell, MinesweeperError, MAX_OPENING_ATTEMPTS};
+use crate::{
+    affect::{Affected, Affects, Effect},
+    invariant::InvariantError,
+    rng::GameRng,
+};
+use core::hash::{Hash, Hasher};
+
+/// Minesweeper on a board of fixed size, with the classic rules
+///
+/// The board of `H` rows and `W` columns is stored in arrays, and revealing cells uses a queue of fixed capacity, so the game never allocates memory. It is available with the `minesweeper_fixed` feature alone, for targets without an allocator
+///
+/// Passing an invalid position to a method will cause panic. Check the target position validity first when dealing with user input
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::minesweeper::{fixed::Status, FixedGame};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut game = FixedGame::<8, 8, _>::new(10, StdRng::seed_from_u64(0)).unwrap();
+///
+/// game.click(4, 4, false).unwrap();
+/// assert!(game.get(4, 4).is_revealed);
+/// assert_eq!(game.get_game_status(), &Status::InProgress);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FixedGame<const W: usize, const H: usize, R> {
+    board: [[Cell; W]; H],
+    mine: usize,
+    rng: R,
+    step_count: usize,
+    flag_count: usize,
+    revealed_safe_cells: usize,
+    flagged_mine_cells: usize,
+    status: Status,
+}
+
+/// Game status
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Win,
+    /// The mines hit are revealed
+    Exploded,
+    InProgress,
+}
+
+crate::code::codes!(Status {
+    Win => "win",
+    Exploded => "exploded",
+    InProgress => "in_progress",
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Win,
+    1 => Exploded,
+    2 => InProgress,
+});
+
+crate::canonical::canonical!(
+    [const W: usize, const H: usize, R] FixedGame<W, H, R>, "fixed_minesweeper", 1 {
+        board,
+        mine,
+        step_count,
+        flag_count,
+        status,
+    }
+);
+
+// games are compared by the board as played, leaving out the random number generator and the cell counts that follow from the board
+impl<const W: usize, const H: usize, R> PartialEq for FixedGame<W, H, R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.position_key() == other.position_key()
+    }
+}
+
+impl<const W: usize, const H: usize, R> Eq for FixedGame<W, H, R> {}
+
+impl<const W: usize, const H: usize, R> Hash for FixedGame<W, H, R> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.position_key().hash(state);
+    }
+}
+
+impl<const W: usize, const H: usize, R> Affects for FixedGame<W, H, R> {
+    /// The cells revealed, each being [`Effect::Revealed`], including the cell clicked
+    fn affected<'a>(&'a self, after: &'a Self) -> impl Iterator<Item = Affected> + 'a {
+        (0..H)
+            .flat_map(|row| (0..W).map(move |col| (row, col)))
+            .filter(|(row, col)| {
+                !self.board[*row][*col].is_revealed && after.board[*row][*col].is_revealed
+            })
+            .map(|(row, col)| Affected {
+                row,
+                col,
+                effect: Effect::Revealed,
+            })
+    }
+}
+
+impl<const W: usize, const H: usize, R> FixedGame<W, H, R> {
+    /// The fields telling games apart, see the `PartialEq` implementation
+    const fn position_key(&self) -> (&[[Cell; W]; H], usize, usize, usize, Status) {
+        (
+            &self.board,
+            self.mine,
+            self.step_count,
+            self.flag_count,
+            self.status,
+        )
+    }
+}
+
+impl<const W: usize, const H: usize, R: GameRng> FixedGame<W, H, R> {
+    /// Create a new Minesweeper game
+    ///
+    /// A random number generator is required for randomizing mine positions
+    ///
+    /// Return `Err(MinesweeperError::BoardTooSmall)` if `H` or `W` is `0`
+    ///
+    /// Return `Err(MinesweeperError::TooManyMines)` if `(H - 1) * (W - 1) < mines`
+    pub fn new(mines: usize, rng: R) -> Result<Self, MinesweeperError> {
+        if H == 0 || W == 0 {
+            return Err(MinesweeperError::BoardTooSmall);
+        }
+
+        // the first click always opens a cell and its neighbors
+        if (H - 1) * (W - 1) < mines {
+            return Err(MinesweeperError::TooManyMines);
+        }
+
+        let mut game = Self {
+            board: [[Cell::new(); W]; H],
+            mine: mines,
+            rng,
+            step_count: 0,
+            flag_count: 0,
+            revealed_safe_cells: 0,
+            flagged_mine_cells: 0,
+            status: Status::InProgress,
+        };
+
+        game.randomize();
+        // a board without mines has every mine flagged already
+        game.check_game_status();
+
+        Ok(game)
+    }
+
+    /// Get a cell reference from the game board
+    /// Panic when target position out of bounds
+    pub fn get(&self, row: usize, col: usize) -> &Cell {
+        &self.board[row][col]
+    }
+
+    /// Click a cell on the game board
+    ///
+    /// Behaves the same as [`Minesweeper::click`](super::Minesweeper::click), including chording already revealed cells and the safe first click
+    ///
+    /// The return value indicates if the game board is changed from the click
+    ///
+    /// Return `Err(MinesweeperError::OpeningTooLarge)` if none of the boards generated for the first click has a cell without adjacent mines at the target position, see [`MAX_OPENING_ATTEMPTS`]
+    ///
+    /// Panic when target position out of bounds
+    pub fn click(
+        &mut self,
+        row: usize,
+        col: usize,
+        auto_flag: bool,
+    ) -> Result<bool, MinesweeperError> {
+        self.click_revealing(row, col, auto_flag, &mut RevealQueue::new())
+    }
+
+    /// Click a cell, same as [`click`](Self::click), and get the cells it revealed, each being [`Effect::Revealed`]
+    ///
+    /// The cells are collected from the flood fills of the click, in row-major order, rather than by comparing states with [`Affects`]
+    ///
+    /// Panic when target position out of bounds
+    pub fn click_with_effects(
+        &mut self,
+        row: usize,
+        col: usize,
+        auto_flag: bool,
+    ) -> Result<impl Iterator<Item = Affected>, MinesweeperError> {
+        let mut revealed = RevealQueue::new();
+        self.click_revealing(row, col, auto_flag, &mut revealed)?;
+
+        let RevealQueue { mut cells, len } = revealed;
+        cells.as_flattened_mut()[..len].sort_unstable();
+
+        Ok(cells.into_iter().flatten().take(len).map(|idx| Affected {
+            row: idx / W,
+            col: idx % W,
+            effect: Effect::Revealed,
+        }))
+    }
+
+    /// Click a cell, queueing the cells revealed
+    fn click_revealing(
+        &mut self,
+        row: usize,
+        col: usize,
+        auto_flag: bool,
+        revealed: &mut RevealQueue<W, H>,
+    ) -> Result<bool, MinesweeperError> {
+        assert!(row < H);
+        assert!(col < W);
+
+        if self.is_ended() {
+            return Err(MinesweeperError::GameEnded);
+        }
+
+        let is_changed = if !self.board[row][col].is_revealed {
+            self.ensure_first_click_safe(row, col)?;
+            self.click_unrevealed(row, col, revealed)?;
+            true
+        } else {
+            self.click_revealed(row, col, auto_flag, revealed)
+        };
+
+        if is_changed {
+            self.step_count += 1;
+        }
+
+        Ok(is_changed)
+    }
+
+    /// Flag or unflag a cell on the board
+    ///
+    /// Return Err(MinesweeperError::AlreadyRevealed) if the target cell is already revealed
+    ///
+    /// Panic when target position out of bounds
+    pub fn toggle_flag(&mut self, row: usize, col: usize) -> Result<(), MinesweeperError> {
+        if self.is_ended() {
+            return Err(MinesweeperError::GameEnded);
+        }
+
+        if self.board[row][col].is_revealed {
+            return Err(MinesweeperError::AlreadyRevealed);
+        }
+
+        if self.board[row][col].is_flagged() {
+            self.set_flag(row, col, false);
+        } else {
+            if self.flag_count == self.mine {
+                return Err(MinesweeperError::TooManyFlags);
+            }
+
+            self.set_flag(row, col, true);
+        }
+
+        self.check_game_status();
+
+        Ok(())
+    }
+
+    /// Check if a cell can be clicked, same as [`click`](Self::click) succeeding but without changing the game
+    ///
+    /// Clicking a revealed cell is allowed even if it changes nothing
+    ///
+    /// Panic when target position out of bounds
+    pub fn can_click(&self, row: usize, col: usize) -> bool {
+        let cell = self.get(row, col);
+        !self.is_ended() && (cell.is_revealed || !cell.is_flagged())
+    }
+
+    /// Check if the flags of a cell can be toggled, same as [`toggle_flag`](Self::toggle_flag) succeeding but without changing the game
+    ///
+    /// Panic when target position out of bounds
+    pub fn can_flag(&self, row: usize, col: usize) -> bool {
+        let cell = self.get(row, col);
+        !self.is_ended() && !cell.is_revealed && (cell.is_flagged() || self.flag_count < self.mine)
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// Same as [`Minesweeper::validate`](super::Minesweeper::validate), with at most one mine and one flag a cell
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let mut mines = 0;
+        let mut flags = 0;
+        let mut revealed_safe_cells = 0;
+        let mut flagged_mine_cells = 0;
+        let mut revealed_mine_cells = 0;
+
+        for row in 0..H {
+            for col in 0..W {
+                let cell = &self.board[row][col];
+
+                let mine_adjacent = self
+                    .get_adjacent_cells(row, col)
+                    .map(|idx| self.board[idx / W][idx % W].mines)
+                    .sum::<u8>();
+
+                if cell.mines > 1
+                    || cell.flags > 1
+                    || cell.is_revealed && cell.is_flagged()
+                    || cell.mine_adjacent != mine_adjacent
+                {
+                    return Err(InvariantError::Board);
+                }
+
+                mines += usize::from(cell.mines);
+                flags += usize::from(cell.flags);
+
+                match (cell.is_mine(), cell.is_revealed) {
+                    (true, true) => revealed_mine_cells += 1,
+                    (true, false) => flagged_mine_cells += usize::from(cell.flags),
+                    (false, true) => revealed_safe_cells += 1,
+                    (false, false) => {}
+                }
+            }
+        }
+
+        if mines != self.mine
+            || flags != self.flag_count
+            || revealed_safe_cells != self.revealed_safe_cells
+            || flagged_mine_cells != self.flagged_mine_cells
+        {
+            return Err(InvariantError::Counter);
+        }
+
+        let is_won = revealed_safe_cells == H * W - mines || flagged_mine_cells == mines;
+
+        let is_status_valid = match self.status {
+            Status::Win => is_won && revealed_mine_cells == 0,
+            Status::Exploded => revealed_mine_cells > 0,
+            Status::InProgress => !is_won && revealed_mine_cells == 0,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Check if the game was end
+    pub fn is_ended(&self) -> bool {
+        self.status != Status::InProgress
+    }
+
+    /// Get the game status
+    pub fn get_game_status(&self) -> &Status {
+        &self.status
+    }
+
+    /// Get the height of the game board
+    pub const fn get_height(&self) -> usize {
+        H
+    }
+
+    /// Get the width of the game board
+    pub const fn get_width(&self) -> usize {
+        W
+    }
+
+    /// Get the number of mines in the game board
+    pub fn get_mine_count(&self) -> usize {
+        self.mine
+    }
+
+    /// Get the number of flags used
+    pub fn get_flag_count(&self) -> usize {
+        self.flag_count
+    }
+
+    /// Get the number of steps taken
+    pub fn get_step_count(&self) -> usize {
+        self.step_count
+    }
+
+    fn randomize(&mut self) {
+        self.board
+            .iter_mut()
+            .flatten()
+            .for_each(|cell| cell.mines = 0);
+
+        let mut placed = 0;
+
+        while placed < self.mine {
+            let idx = self.rng.gen_below(H * W);
+            let cell = &mut self.board[idx / W][idx % W];
+
+            if !cell.is_mine() {
+                cell.mines = 1;
+                placed += 1;
+            }
+        }
+
+        for idx in 0..H * W {
+            let (row, col) = (idx / W, idx % W);
+
+            self.board[row][col].mine_adjacent = self
+                .get_adjacent_cells(row, col)
+                .map(|idx| self.board[idx / W][idx % W].mines)
+                .sum();
+        }
+    }
+
+    fn ensure_first_click_safe(&mut self, row: usize, col: usize) -> Result<(), MinesweeperError> {
+        if self.step_count != 0 {
+            return Ok(());
+        }
+
+        let mut attempts = 1;
+
+        while self.board[row][col].is_mine() || self.board[row][col].mine_adjacent > 0 {
+            if attempts == MAX_OPENING_ATTEMPTS {
+                return Err(MinesweeperError::OpeningTooLarge);
+            }
+
+            self.randomize();
+            attempts += 1;
+        }
+
+        Ok(())
+    }
+
+    fn click_unrevealed(
+        &mut self,
+        row: usize,
+        col: usize,
+        revealed: &mut RevealQueue<W, H>,
+    ) -> Result<(), MinesweeperError> {
+        if self.board[row][col].is_flagged() {
+            return Err(MinesweeperError::AlreadyFlagged);
+        }
+
+        if self.board[row][col].is_mine() {
+            self.board[row][col].is_revealed = true;
+            revealed.push(row * W + col);
+            self.status = Status::Exploded;
+            return Ok(());
+        }
+
+        self.reveal_from(row, col, revealed);
+        self.check_game_status();
+
+        Ok(())
+    }
+
+    fn click_revealed(
+        &mut self,
+        row: usize,
+        col: usize,
+        auto_flag: bool,
+        revealed: &mut RevealQueue<W, H>,
+    ) -> bool {
+        let mine_adjacent = self.board[row][col].mine_adjacent;
+
+        if mine_adjacent == 0 {
+            return false;
+        }
+
+        let mut is_changed = false;
+
+        let (adjacent_unrevealed, adjacent_flags) = self
+            .get_adjacent_cells(row, col)
+            .map(|idx| self.board[idx / W][idx % W])
+            .filter(|cell| !cell.is_revealed)
+            .fold((0, 0), |(unrevealed, flags), cell| {
+                if cell.is_flagged() {
+                    (unrevealed, flags + 1)
+                } else {
+                    (unrevealed + 1, flags)
+                }
+            });
+
+        if adjacent_unrevealed > 0 {
+            if adjacent_flags == mine_adjacent {
+                for idx in self.get_adjacent_cells(row, col) {
+                    let (row, col) = (idx / W, idx % W);
+                    let cell = self.board[row][col];
+
+                    if !cell.is_flagged() && !cell.is_revealed {
+                        if cell.is_mine() {
+                            self.board[row][col].is_revealed = true;
+                            revealed.push(idx);
+                            self.status = Status::Exploded;
+                        } else {
+                            self.reveal_from(row, col, revealed);
+                        }
+
+                        is_changed = true;
+                    }
+                }
+
+                if self.status == Status::Exploded {
+                    return true;
+                }
+            }
+
+            if auto_flag && adjacent_unrevealed + adjacent_flags == mine_adjacent {
+                for idx in self.get_adjacent_cells(row, col) {
+                    let (row, col) = (idx / W, idx % W);
+                    let cell = self.board[row][col];
+
+                    if !cell.is_flagged() && !cell.is_revealed {
+                        self.set_flag(row, col, true);
+                        is_changed = true;
+                    }
+                }
+            }
+        }
+
+        self.check_game_status();
+
+        is_changed
+    }
+
+    /// Reveal a safe cell, and all cells connected to it through cells without adjacent mines
+    ///
+    /// The cells revealed are queued after the ones revealed earlier by the same click
+    fn reveal_from(&mut self, row: usize, col: usize, revealed: &mut RevealQueue<W, H>) {
+        let mut head = revealed.len;
+
+        revealed.push(row * W + col);
+        self.reveal_cell(row, col);
+
+        while let Some(idx) = revealed.get(head) {
+            head += 1;
+
+            if self.board[idx / W][idx % W].mine_adjacent != 0 {
+                continue;
+            }
+
+            for neighbor_idx in self.get_adjacent_cells(idx / W, idx % W) {
+                let (row, col) = (neighbor_idx / W, neighbor_idx % W);
+                let neighbor = &self.board[row][col];
+
+                if !neighbor.is_flagged() && !neighbor.is_revealed {
+                    self.reveal_cell(row, col);
+                    revealed.push(neighbor_idx);
+                }
+            }
+        }
+    }
+
+    fn reveal_cell(&mut self, row: usize, col: usize) {
+        let cell = &mut self.board[row][col];
+        cell.is_revealed = true;
+
+        if !cell.is_mine() {
+            self.revealed_safe_cells += 1;
+        }
+    }
+
+    fn set_flag(&mut self, row: usize, col: usize, is_flagged: bool) {
+        let cell = &mut self.board[row][col];
+        cell.flags = is_flagged as u8;
+
+        if is_flagged {
+            self.flag_count += 1;
+        } else {
+            self.flag_count -= 1;
+        }
+
+        if cell.is_mine() {
+            if is_flagged {
+                self.flagged_mine_cells += 1;
+            } else {
+                self.flagged_mine_cells -= 1;
+            }
+        }
+    }
+
+    fn check_game_status(&mut self) {
+        let all_revealed = self.revealed_safe_cells == H * W - self.mine;
+        let all_flagged = self.flagged_mine_cells == self.mine;
+
+        if all_revealed || all_flagged {
+            self.status = Status::Win;
+        }
+    }
+
+    fn get_adjacent_cells(&self, row: usize, col: usize) -> AdjacentCells {
+        AdjacentCells::new(row, col, H, W, false)
+    }
+}
+
+/// The cells revealed by a click in the order revealed, which is also the queue of its flood fills
+///
+/// Cells are marked as revealed when queued, so each cell is queued at most once and a queue with a slot for every cell never overflows
+struct RevealQueue<const W: usize, const H: usize> {
+    cells: [[usize; W]; H],
+    len: usize,
+}
+
+impl<const W: usize, const H: usize> RevealQueue<W, H> {
+    const fn new() -> Self {
+        Self {
+            cells: [[0; W]; H],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, idx: usize) {
+        self.cells[self.len / W][self.len % W] = idx;
+        self.len += 1;
+    }
+
+    fn get(&self, pos: usize) -> Option<usize> {
+        (pos < self.len).then(|| self.cells[pos / W][pos % W])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        affect::Affects,
+        minesweeper::{fixed::*, MinesweeperError},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test() {
+        assert_eq!(
+            FixedGame::<4, 4, _>::new(10, StdRng::seed_from_u64(0)).unwrap_err(),
+            MinesweeperError::TooManyMines
+        );
+
+        let mut game = FixedGame::<9, 6, _>::new(10, StdRng::seed_from_u64(0)).unwrap();
+
+        let before = game.clone();
+        assert!(game.click(3, 4, false).unwrap());
+        assert_eq!(game.get(3, 4).mine_adjacent, 0);
+        assert!(before
+            .clone()
+            .click_with_effects(3, 4, false)
+            .unwrap()
+            .eq(before.affected(&game)));
+
+        let cells = || (0..6).flat_map(|row| (0..9).map(move |col| (row, col)));
+
+        for (row, col) in cells() {
+            if game.get(row, col).is_mine() {
+                game.toggle_flag(row, col).unwrap();
+            }
+        }
+
+        assert_eq!(game.get_flag_count(), 10);
+        assert_eq!(game.get_game_status(), &Status::Win);
+        assert_eq!(game.click(0, 0, false), Err(MinesweeperError::GameEnded));
+
+        let mut game = FixedGame::<9, 6, _>::new(10, StdRng::seed_from_u64(1)).unwrap();
+        game.click(0, 0, false).unwrap();
+
+        let (row, col) = cells()
+            .find(|(row, col)| game.get(*row, *col).is_mine())
+            .unwrap();
+
+        game.click(row, col, false).unwrap();
+        assert_eq!(game.get_game_status(), &Status::Exploded);
+        assert!(game.get(row, col).is_revealed);
+
+        // a board without mines is won from the start
+        let game = FixedGame::<4, 4, _>::new(0, StdRng::seed_from_u64(0)).unwrap();
+        assert_eq!(game.get_game_status(), &Status::Win);
+        assert_eq!(game.validate(), Ok(()));
+
+        // every cell of a 3 by 3 board is next to the mine, so the first click cannot be made safe
+        let mut game = FixedGame::<3, 3, _>::new(1, StdRng::seed_from_u64(0)).unwrap();
+        assert_eq!(
+            game.click(1, 1, false),
+            Err(MinesweeperError::OpeningTooLarge)
+        );
+        assert_eq!(game.get_step_count(), 0);
+
+        assert_eq!(
+            FixedGame::<0, 4, _>::new(0, StdRng::seed_from_u64(0)).unwrap_err(),
+            MinesweeperError::BoardTooSmall
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use super::{Action, Minesweeper};
+use crate::rng::GameRng;
+use alloc::vec::Vec;
+
+impl<R: GameRng> Minesweeper<R> {
+    /// Let the computer play until the game ends or no further progress can be made
+    ///
+    /// Each round, every cell known to be safe is clicked and every cell known to be a mine is flagged, based on [`mine_probabilities`](Minesweeper::mine_probabilities). Flags on cells known to be safe are removed first. Only exact probabilities are trusted, estimates from sampling are never taken as certain
+    ///
+    /// When no cell is known to be safe, the cell with the lowest mine probability is clicked if `guess` is `true`, otherwise the bot stops
+    ///
+    /// Return the actions taken, in order
+    ///
+    /// # Examples
+    /// ```rust
+    /// # fn minesweeper() {
+    /// use gamie::minesweeper::Minesweeper;
+    /// use rand::rngs::ThreadRng;
+    ///
+    /// let mut game = Minesweeper::new(16, 16, 40, ThreadRng::default()).unwrap();
+    /// let actions = game.auto_play(true);
+    ///
+    /// assert!(!actions.is_empty());
+    /// assert!(game.is_ended());
+    /// # }
+    /// ```
+    pub fn auto_play(&mut self, guess: bool) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        if !self.is_ended() && self.step_count == 0 {
+            // the first click is always safe
+            let (row, col) = (self.height / 2, self.width / 2);
+
+            if self.board[row * self.width + col].is_flagged() {
+                self.apply_auto_play_action(Action::Flag { row, col }, &mut actions);
+            }
+
+            self.apply_auto_play_action(
+                Action::Click {
+                    row,
+                    col,
+                    auto_flag: false,
+                },
+                &mut actions,
+            );
+        }
+
+        while !self.is_ended() {
+            let analysis = self.analyze();
+            let mut is_progressed = false;
+
+            for idx in 0..self.height * self.width {
+                if self.is_ended() {
+                    break;
+                }
+
+                let cell = self.board[idx];
+
+                if cell.is_revealed || !analysis.is_exact[idx] {
+                    continue;
+                }
+
+                let (row, col) = (idx / self.width, idx % self.width);
+                let probability = analysis.probabilities[idx];
+
+                if probability <= 0.0 {
+                    if cell.is_flagged() {
+                        self.apply_auto_play_action(Action::Flag { row, col }, &mut actions);
+                    }
+
+                    is_progressed |= self.apply_auto_play_action(
+                        Action::Click {
+                            row,
+                            col,
+                            auto_flag: false,
+                        },
+                        &mut actions,
+                    );
+                } else if probability >= 1.0 && !cell.is_flagged() {
+                    is_progressed |=
+                        self.apply_auto_play_action(Action::Flag { row, col }, &mut actions);
+                }
+            }
+
+            if is_progressed || self.is_ended() {
+                continue;
+            }
+
+            if !guess {
+                break;
+            }
+
+            let guessed = (0..self.height * self.width)
+                .filter(|idx| !self.board[*idx].is_revealed && !self.board[*idx].is_flagged())
+                .min_by(|a, b| analysis.probabilities[*a].total_cmp(&analysis.probabilities[*b]));
+
+            let Some(idx) = guessed else {
+                break;
+            };
+
+            let (row, col) = (idx / self.width, idx % self.width);
+
+            if !self.apply_auto_play_action(
+                Action::Click {
+                    row,
+                    col,
+                    auto_flag: false,
+                },
+                &mut actions,
+            ) {
+                break;
+            }
+        }
+
+        actions
+    }
+
+    fn apply_auto_play_action(&mut self, action: Action, actions: &mut Vec<Action>) -> bool {
+        let is_applied = self.apply(action.clone()).is_ok();
+
+        if is_applied {
+            actions.push(action);
+        }
+
+        is_applied
+    }
+}
@@ -0,0 +1,424 @@
+//! Minesweeper Flags
+//!
+//! Check struct [`MinesweeperFlags`] for more information
+
+use super::{Cell, Minesweeper, MinesweeperError};
+use crate::{
+    invariant::InvariantError,
+    outcome::{AdjudicationReason, EndReason},
+    rng::GameRng,
+};
+use core::hash::{Hash, Hasher};
+use snafu::Snafu;
+
+/// Minesweeper Flags, the competitive two-player variant of Minesweeper
+///
+/// Players take turns clicking cells. Clicking a mine does not explode, instead the player claims it, scores a point for each mine in the cell, and takes another turn. Clicking a safe cell reveals it as usual and passes the turn
+///
+/// The first player to find more than half of the mines wins
+///
+/// # Examples
+///
+/// ```rust
+/// # fn minesweeper_flags() {
+/// use gamie::minesweeper::flags::{MinesweeperFlags, Player};
+/// use rand::rngs::ThreadRng;
+///
+/// let mut game = MinesweeperFlags::new(16, 16, 51, ThreadRng::default()).unwrap();
+///
+/// game.click(8, 8).unwrap();
+/// // ...
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+// the board is only deserialized with a generator it can be validated with
+#[cfg_attr(
+    feature = "serde-validate",
+    serde(
+        remote = "Self",
+        bound(deserialize = "R: serde::Deserialize<'de> + GameRng")
+    )
+)]
+pub struct MinesweeperFlags<R> {
+    game: Minesweeper<R>,
+    scores: [usize; 2],
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(MinesweeperFlags<R>);
+
+// the random number generator of the board is left out, see the `PartialEq` implementation of `Minesweeper`
+impl<R> PartialEq for MinesweeperFlags<R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.game == other.game
+            && self.scores == other.scores
+            && self.next_player == other.next_player
+            && self.status == other.status
+            && self.end_reason == other.end_reason
+            && self.draw_offer == other.draw_offer
+    }
+}
+
+impl<R> Eq for MinesweeperFlags<R> {}
+
+impl<R> Hash for MinesweeperFlags<R> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.game.hash(state);
+        self.scores.hash(state);
+        self.next_player.hash(state);
+        self.status.hash(state);
+        self.end_reason.hash(state);
+        self.draw_offer.hash(state);
+    }
+}
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::canonical!([R] MinesweeperFlags<R>, "minesweeper_flags", 1 {
+    game,
+    scores,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+});
+
+/// Errors that can occur when clicking a cell
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum MinesweeperFlagsError {
+    #[snafu(display("Clicked an already revealed cell"))]
+    AlreadyRevealed,
+    #[snafu(display("The game was already end"))]
+    GameEnded,
+    #[snafu(display("No draw offer to accept"))]
+    NoDrawOffer,
+}
+
+crate::code::codes!(MinesweeperFlagsError {
+    AlreadyRevealed => "already_revealed",
+    GameEnded => "game_ended",
+    NoDrawOffer => "no_draw_offer",
+});
+
+impl<R: GameRng> MinesweeperFlags<R> {
+    /// Create a new Minesweeper Flags game
+    ///
+    /// An odd number of mines makes sure that the game cannot end in a draw
+    ///
+    /// Return `Err(MinesweeperError::NoMines)` if `mines` is `0`, as there is nothing to claim
+    ///
+    /// Return `Err(MinesweeperError::TooManyMines)` if `(height - 1) * (width - 1) < mines`
+    pub fn new(
+        height: usize,
+        width: usize,
+        mines: usize,
+        rng: R,
+    ) -> Result<Self, MinesweeperError> {
+        if mines == 0 {
+            return Err(MinesweeperError::NoMines);
+        }
+
+        Ok(Self {
+            game: Minesweeper::new(height, width, mines, rng)?,
+            scores: [0; 2],
+            next_player: Player::Player0,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+        })
+    }
+
+    /// Get a cell reference from the game board
+    ///
+    /// Panic when target position out of bounds
+    pub fn get(&self, row: usize, col: usize) -> &Cell {
+        self.game.get(row, col)
+    }
+
+    /// Click a cell
+    ///
+    /// Return the number of mines claimed by the click. The same player moves again if it is not `0`
+    ///
+    /// Panic when target position out of bounds
+    pub fn click(&mut self, row: usize, col: usize) -> Result<usize, MinesweeperFlagsError> {
+        assert!(row < self.game.height);
+        assert!(col < self.game.width);
+
+        if self.status != Status::Ongoing {
+            return Err(MinesweeperFlagsError::GameEnded);
+        }
+
+        let idx = row * self.game.width + col;
+        let cell = &mut self.game.board[idx];
+
+        if cell.is_revealed {
+            return Err(MinesweeperFlagsError::AlreadyRevealed);
+        }
+
+        self.game.step_count += 1;
+        self.draw_offer = None;
+
+        if !cell.is_mine() {
+            self.game.reveal_from(idx);
+            self.next_player = self.next_player.other();
+            return Ok(0);
+        }
+
+        cell.is_revealed = true;
+
+        let mines = usize::from(cell.mines);
+        let player = self.next_player;
+        self.scores[player as usize] += mines;
+
+        let total = self.game.mine;
+        let claimed = self.scores[0] + self.scores[1];
+
+        if self.scores[player as usize] * 2 > total {
+            self.status = Status::Win(player);
+        } else if claimed == total {
+            self.status = Status::Draw;
+        }
+
+        Ok(mines)
+    }
+
+    /// Check if a cell can be clicked, same as [`click`](Self::click) succeeding but without changing the game
+    ///
+    /// Panic when target position out of bounds
+    pub fn can_click(&self, row: usize, col: usize) -> bool {
+        self.status == Status::Ongoing && !self.get(row, col).is_revealed
+    }
+
+    /// Get the score of a player, which is the number of mines they have found
+    pub const fn score(&self, player: Player) -> usize {
+        self.scores[player as usize]
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The board is checked as in [`Minesweeper::validate`], except that revealed mines are the ones claimed, which must add up to the scores. A status ended by the rules must match the scores
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        self.game.validate_board()?;
+
+        if self.game.status != super::Status::InProgress {
+            return Err(InvariantError::Status);
+        }
+
+        let claimed = self.scores[0] + self.scores[1];
+        let revealed_mines = self
+            .game
+            .board
+            .iter()
+            .filter(|cell| cell.is_revealed)
+            .map(|cell| usize::from(cell.mines))
+            .sum::<usize>();
+
+        if claimed != revealed_mines {
+            return Err(InvariantError::Counter);
+        }
+
+        let is_winning = |player: Player| self.scores[player as usize] * 2 > self.game.mine;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => {
+                !is_winning(Player::Player0)
+                    && !is_winning(Player::Player1)
+                    && claimed < self.game.mine
+            }
+            (Status::Ongoing, _) => false,
+            (Status::Win(player), EndReason::Normal) => is_winning(*player),
+            (Status::Draw, EndReason::Normal) => {
+                !is_winning(Player::Player0)
+                    && !is_winning(Player::Player1)
+                    && claimed == self.game.mine
+            }
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), MinesweeperFlagsError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), MinesweeperFlagsError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), MinesweeperFlagsError> {
+        if self.status != Status::Ongoing {
+            return Err(MinesweeperFlagsError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), MinesweeperFlagsError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(MinesweeperFlagsError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), MinesweeperFlagsError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), MinesweeperFlagsError> {
+        if self.status != Status::Ongoing {
+            return Err(MinesweeperFlagsError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Get the underlying board, for its size and mine count
+    pub const fn game(&self) -> &Minesweeper<R> {
+        &self.game
+    }
+}
+
+impl Player {
+    /// Get the other player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::minesweeper::flags::*;
+    use alloc::vec::Vec;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test() {
+        let mut game = MinesweeperFlags::new(8, 8, 9, StdRng::seed_from_u64(0)).unwrap();
+
+        let mut mines = (0..8)
+            .flat_map(|row| (0..8).map(move |col| (row, col)))
+            .filter(|(row, col)| game.get(*row, *col).is_mine())
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let (row, col) = mines.next().unwrap();
+        assert_eq!(game.click(row, col), Ok(1));
+        assert_eq!(game.next_player(), Player::Player0);
+        assert_eq!(game.score(Player::Player0), 1);
+        assert_eq!(
+            game.click(row, col),
+            Err(MinesweeperFlagsError::AlreadyRevealed)
+        );
+
+        for (row, col) in mines.take(4) {
+            game.click(row, col).unwrap();
+        }
+
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+        assert_eq!(game.click(0, 0), Err(MinesweeperFlagsError::GameEnded));
+
+        assert!(matches!(
+            MinesweeperFlags::new(8, 8, 0, StdRng::seed_from_u64(0)),
+            Err(MinesweeperError::NoMines)
+        ));
+    }
+}
@@ -0,0 +1,597 @@
+//! Infinite Minesweeper
+//!
+//! Check struct [`InfiniteMinesweeper`] for more information
+
+use super::{Cell, MinesweeperError};
+use crate::canonical::{self, Canonical, Encode};
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use core::hash::{Hash, Hasher};
+
+/// The side length of a chunk
+pub const CHUNK_SIZE: usize = 16;
+
+/// The maximum number of cells revealed by a single opening
+///
+/// Clicking a revealed cell without adjacent mines continues the opening from it
+pub const REVEAL_LIMIT: usize = 1 << 16;
+
+const CHUNK_AREA: usize = CHUNK_SIZE * CHUNK_SIZE;
+const CHUNK_WORDS: usize = CHUNK_AREA / 64;
+
+/// Minesweeper on an infinite board
+///
+/// The board is split into square chunks of [`CHUNK_SIZE`], each of which contains the same number of mines. Chunks are generated lazily when explored, and the mine layout of a chunk only depends on the seed of the game and the position of the chunk, so the same seed always produces the same board
+///
+/// The first click clears the mines in the clicked cell and its adjacent cells
+///
+/// Positions are signed, and the board extends in all directions up to the bounds of `i64`, where it ends like the edge of a finite board
+///
+/// # Examples
+///
+/// ```rust
+/// use gamie::minesweeper::infinite::InfiniteMinesweeper;
+///
+/// let mut game = InfiniteMinesweeper::new(42, 40).unwrap();
+///
+/// game.click(0, 0, false).unwrap();
+/// assert!(game.get(0, 0).is_revealed);
+///
+/// game.click(-1000, 1000, false).unwrap();
+/// // ...
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InfiniteMinesweeper {
+    seed: u64,
+    mines_per_chunk: usize,
+    chunks: BTreeMap<(i64, i64), Chunk>,
+    safe_zone: Option<(i64, i64)>,
+    step_count: usize,
+    flag_count: usize,
+    status: Status,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Status {
+    Exploded(Vec<(i64, i64)>),
+    InProgress,
+}
+
+crate::code::codes!(Status {
+    Exploded => "exploded",
+    InProgress => "in_progress",
+});
+
+// `ufmt` implements `uDebug` for `Vec` only with its `std` feature, so the cells are written as a slice
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for Status {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        match self {
+            Self::Exploded(cells) => f
+                .debug_tuple("Exploded")?
+                .field(&cells.as_slice())?
+                .finish(),
+            Self::InProgress => f.write_str("InProgress"),
+        }
+    }
+}
+
+crate::canonical::encode_enum!(Status {
+    0 => Exploded(cells),
+    1 => InProgress,
+});
+
+// games are compared by the board as played, leaving out the chunks stored without being played on, which follow from the seed
+impl PartialEq for InfiniteMinesweeper {
+    fn eq(&self, other: &Self) -> bool {
+        self.seed == other.seed
+            && self.mines_per_chunk == other.mines_per_chunk
+            && self.safe_zone == other.safe_zone
+            && self.step_count == other.step_count
+            && self.flag_count == other.flag_count
+            && self.status == other.status
+            && self.played_chunks().eq(other.played_chunks())
+    }
+}
+
+impl Eq for InfiniteMinesweeper {}
+
+impl Hash for InfiniteMinesweeper {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.seed.hash(state);
+        self.mines_per_chunk.hash(state);
+        self.safe_zone.hash(state);
+        self.step_count.hash(state);
+        self.flag_count.hash(state);
+        self.status.hash(state);
+        self.played_chunks().for_each(|chunk| chunk.hash(state));
+    }
+}
+
+impl Canonical for InfiniteMinesweeper {
+    /// The chunks are encoded as in the `PartialEq` implementation, as their number followed by the position, the cells revealed and the cells flagged of each
+    fn write_canonical<E: Extend<u8>>(&self, out: &mut E) {
+        canonical::header(out, "infinite_minesweeper", 1);
+        self.seed.encode(out);
+        self.mines_per_chunk.encode(out);
+        self.safe_zone.encode(out);
+        self.step_count.encode(out);
+        self.flag_count.encode(out);
+        self.status.encode(out);
+        self.played_chunks().count().encode(out);
+        self.played_chunks().for_each(|(key, revealed, flagged)| {
+            key.encode(out);
+            revealed.encode(out);
+            flagged.encode(out);
+        });
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Chunk {
+    mines: [u64; CHUNK_WORDS],
+    revealed: [u64; CHUNK_WORDS],
+    flagged: [u64; CHUNK_WORDS],
+}
+
+impl InfiniteMinesweeper {
+    /// Create a new infinite Minesweeper game
+    ///
+    /// Return `Err(MinesweeperError::TooManyMines)` if `mines_per_chunk` is greater than the number of cells in a chunk
+    pub fn new(seed: u64, mines_per_chunk: usize) -> Result<Self, MinesweeperError> {
+        if mines_per_chunk > CHUNK_AREA {
+            return Err(MinesweeperError::TooManyMines);
+        }
+
+        Ok(Self {
+            seed,
+            mines_per_chunk,
+            chunks: BTreeMap::new(),
+            safe_zone: None,
+            step_count: 0,
+            flag_count: 0,
+            status: Status::InProgress,
+        })
+    }
+
+    /// Get a cell from the game board
+    ///
+    /// Unexplored chunks are generated on the fly without being stored
+    pub fn get(&self, row: i64, col: i64) -> Cell {
+        let (key, offset) = locate(row, col);
+        let chunk = self.chunk(key);
+        let is_mine = self.is_mine(row, col);
+
+        Cell {
+            mines: is_mine as u8,
+            mine_adjacent: self.mine_adjacent(row, col),
+            is_revealed: get_bit(&chunk.revealed, offset),
+            flags: get_bit(&chunk.flagged, offset) as u8,
+        }
+    }
+
+    /// Click a cell on the game board
+    ///
+    /// The first click is always a safe click
+    ///
+    /// Clicking an already revealed cell will unreveal its adjacent cells if the flagged cell count around it equals to its adjacent mine count
+    /// When `auto_flag` is `true`, clicking an already revealed cell will flag its adjacent unflagged-unrevealed cells if the unflagged-revealed cell count around it equals to its adjacent mine count
+    ///
+    /// An opening reveals at most [`REVEAL_LIMIT`] cells
+    ///
+    /// The return value indicates if the game board is changed from the click
+    pub fn click(&mut self, row: i64, col: i64, auto_flag: bool) -> Result<bool, MinesweeperError> {
+        if self.is_ended() {
+            return Err(MinesweeperError::GameEnded);
+        }
+
+        let cell = self.get(row, col);
+
+        let is_changed = if !cell.is_revealed {
+            if cell.is_flagged() {
+                return Err(MinesweeperError::AlreadyFlagged);
+            }
+
+            if self.safe_zone.is_none() {
+                self.safe_zone = Some((row, col));
+            }
+
+            self.cache_around(row, col);
+
+            if self.is_mine(row, col) {
+                self.status = Status::Exploded(vec![(row, col)]);
+            } else {
+                self.reveal_from(row, col);
+            }
+
+            true
+        } else if cell.mine_adjacent == 0 {
+            self.reveal_from(row, col)
+        } else {
+            self.click_revealed(row, col, cell.mine_adjacent, auto_flag)
+        };
+
+        if is_changed {
+            self.step_count += 1;
+        }
+
+        Ok(is_changed)
+    }
+
+    /// Flag or unflag a cell on the board
+    ///
+    /// Return Err(MinesweeperError::AlreadyRevealed) if the target cell is already revealed
+    pub fn toggle_flag(&mut self, row: i64, col: i64) -> Result<(), MinesweeperError> {
+        if self.is_ended() {
+            return Err(MinesweeperError::GameEnded);
+        }
+
+        let (key, offset) = locate(row, col);
+        let chunk = self.chunk_mut(key);
+
+        if get_bit(&chunk.revealed, offset) {
+            return Err(MinesweeperError::AlreadyRevealed);
+        }
+
+        let is_flagged = !get_bit(&chunk.flagged, offset);
+        set_bit(&mut chunk.flagged, offset, is_flagged);
+
+        if is_flagged {
+            self.flag_count += 1;
+        } else {
+            self.flag_count -= 1;
+        }
+
+        Ok(())
+    }
+
+    /// Check if a cell can be clicked, same as [`click`](Self::click) succeeding but without changing the game
+    ///
+    /// Clicking a revealed cell is allowed even if it changes nothing
+    pub fn can_click(&self, row: i64, col: i64) -> bool {
+        let cell = self.get(row, col);
+        !self.is_ended() && (cell.is_revealed || !cell.is_flagged())
+    }
+
+    /// Check if the flags of a cell can be toggled, same as [`toggle_flag`](Self::toggle_flag) succeeding but without changing the game
+    pub fn can_flag(&self, row: i64, col: i64) -> bool {
+        !self.is_ended() && !self.get(row, col).is_revealed
+    }
+
+    /// Check if the game was end
+    pub fn is_ended(&self) -> bool {
+        self.status != Status::InProgress
+    }
+
+    /// Get the game status
+    pub fn get_game_status(&self) -> &Status {
+        &self.status
+    }
+
+    /// Get the seed of the game
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Get the number of mines in each chunk
+    pub fn get_mines_per_chunk(&self) -> usize {
+        self.mines_per_chunk
+    }
+
+    /// Get the positions of the explored chunks, in chunk coordinates
+    ///
+    /// The chunk at (`row`, `col`) covers cells from (`row * CHUNK_SIZE`, `col * CHUNK_SIZE`) to (`(row + 1) * CHUNK_SIZE - 1`, `(col + 1) * CHUNK_SIZE - 1`)
+    pub fn explored_chunks(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// Get the number of flags used
+    pub fn get_flag_count(&self) -> usize {
+        self.flag_count
+    }
+
+    /// Get the number of steps taken
+    pub fn get_step_count(&self) -> usize {
+        self.step_count
+    }
+
+    fn click_revealed(&mut self, row: i64, col: i64, mine_adjacent: u8, auto_flag: bool) -> bool {
+        let mut is_changed = false;
+        let mut adjacent_unrevealed = 0;
+        let mut adjacent_flags = 0;
+
+        for (row, col) in adjacent_cells(row, col) {
+            let cell = self.get(row, col);
+
+            if !cell.is_revealed {
+                if cell.is_flagged() {
+                    adjacent_flags += 1;
+                } else {
+                    adjacent_unrevealed += 1;
+                }
+            }
+        }
+
+        if adjacent_unrevealed == 0 {
+            return false;
+        }
+
+        if adjacent_flags == mine_adjacent {
+            let mut exploded = Vec::new();
+
+            for (row, col) in adjacent_cells(row, col) {
+                let cell = self.get(row, col);
+
+                if cell.is_revealed || cell.is_flagged() {
+                    continue;
+                }
+
+                if cell.is_mine() {
+                    let (key, offset) = locate(row, col);
+                    set_bit(&mut self.chunk_mut(key).revealed, offset, true);
+                    exploded.push((row, col));
+                } else {
+                    self.reveal_from(row, col);
+                    is_changed = true;
+                }
+            }
+
+            if !exploded.is_empty() {
+                self.status = Status::Exploded(exploded);
+                return true;
+            }
+        }
+
+        if auto_flag && adjacent_unrevealed + adjacent_flags == mine_adjacent {
+            for (row, col) in adjacent_cells(row, col) {
+                let (key, offset) = locate(row, col);
+                let chunk = self.chunk_mut(key);
+
+                if !get_bit(&chunk.revealed, offset) && !get_bit(&chunk.flagged, offset) {
+                    set_bit(&mut chunk.flagged, offset, true);
+                    self.flag_count += 1;
+                    is_changed = true;
+                }
+            }
+        }
+
+        is_changed
+    }
+
+    /// Reveal a safe cell and flood fill from it, up to [`REVEAL_LIMIT`] cells
+    ///
+    /// Return if any cell is revealed
+    fn reveal_from(&mut self, row: i64, col: i64) -> bool {
+        let mut queue = vec![(row, col)];
+        let mut cursor = 0;
+        let mut revealed = 0;
+
+        {
+            let (key, offset) = locate(row, col);
+            let chunk = self.chunk_mut(key);
+
+            if !get_bit(&chunk.revealed, offset) {
+                set_bit(&mut chunk.revealed, offset, true);
+                revealed += 1;
+            }
+        }
+
+        while let Some(&(row, col)) = queue.get(cursor) {
+            cursor += 1;
+
+            self.cache_around(row, col);
+
+            if self.mine_adjacent(row, col) != 0 {
+                continue;
+            }
+
+            for (row, col) in adjacent_cells(row, col) {
+                if revealed >= REVEAL_LIMIT {
+                    return true;
+                }
+
+                let (key, offset) = locate(row, col);
+                let chunk = self.chunk_mut(key);
+
+                // cells are marked as revealed when queued, so each cell is visited once
+                if !get_bit(&chunk.revealed, offset) && !get_bit(&chunk.flagged, offset) {
+                    set_bit(&mut chunk.revealed, offset, true);
+                    queue.push((row, col));
+                    revealed += 1;
+                }
+            }
+        }
+
+        revealed > 0
+    }
+
+    fn is_mine(&self, row: i64, col: i64) -> bool {
+        if let Some((safe_row, safe_col)) = self.safe_zone {
+            if row.abs_diff(safe_row) <= 1 && col.abs_diff(safe_col) <= 1 {
+                return false;
+            }
+        }
+
+        let (key, offset) = locate(row, col);
+        get_bit(&self.chunk(key).mines, offset)
+    }
+
+    fn mine_adjacent(&self, row: i64, col: i64) -> u8 {
+        adjacent_cells(row, col)
+            .filter(|(row, col)| self.is_mine(*row, *col))
+            .count() as u8
+    }
+
+    /// Make sure the chunks of the cell and its adjacent cells are stored
+    fn cache_around(&mut self, row: i64, col: i64) {
+        for (row, col) in [
+            (row.saturating_sub(1), col.saturating_sub(1)),
+            (row.saturating_sub(1), col.saturating_add(1)),
+            (row.saturating_add(1), col.saturating_sub(1)),
+            (row.saturating_add(1), col.saturating_add(1)),
+        ] {
+            self.chunk_mut(locate(row, col).0);
+        }
+    }
+
+    fn chunk(&self, key: (i64, i64)) -> Chunk {
+        self.chunks
+            .get(&key)
+            .copied()
+            .unwrap_or_else(|| Chunk::generate(self.seed, key, self.mines_per_chunk))
+    }
+
+    /// Get the stored chunks with any cell revealed or flagged, by position, with the cells revealed and flagged
+    fn played_chunks(
+        &self,
+    ) -> impl Iterator<Item = (&(i64, i64), &[u64; CHUNK_WORDS], &[u64; CHUNK_WORDS])> {
+        self.chunks
+            .iter()
+            .filter(|(_, chunk)| {
+                chunk.revealed != [0; CHUNK_WORDS] || chunk.flagged != [0; CHUNK_WORDS]
+            })
+            .map(|(key, chunk)| (key, &chunk.revealed, &chunk.flagged))
+    }
+
+    fn chunk_mut(&mut self, key: (i64, i64)) -> &mut Chunk {
+        let (seed, mines) = (self.seed, self.mines_per_chunk);
+
+        self.chunks
+            .entry(key)
+            .or_insert_with(|| Chunk::generate(seed, key, mines))
+    }
+}
+
+impl Chunk {
+    fn generate(seed: u64, (row, col): (i64, i64), mines: usize) -> Self {
+        let mut rng = SplitMix64(
+            seed ^ (row as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+                ^ (col as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f),
+        );
+
+        // a partial Fisher-Yates shuffle picks `mines` distinct cells
+        let mut cells = [0; CHUNK_AREA];
+        cells
+            .iter_mut()
+            .enumerate()
+            .for_each(|(idx, cell)| *cell = idx);
+
+        let mut chunk = Self {
+            mines: [0; CHUNK_WORDS],
+            revealed: [0; CHUNK_WORDS],
+            flagged: [0; CHUNK_WORDS],
+        };
+
+        for idx in 0..mines {
+            let picked = idx + rng.below(CHUNK_AREA - idx);
+            cells.swap(idx, picked);
+            set_bit(&mut chunk.mines, cells[idx], true);
+        }
+
+        chunk
+    }
+}
+
+/// A small, portable random number generator, so that the board of a seed never changes
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        ((self.next() as u128 * bound as u128) >> 64) as usize
+    }
+}
+
+fn locate(row: i64, col: i64) -> ((i64, i64), usize) {
+    let size = CHUNK_SIZE as i64;
+    let key = (row.div_euclid(size), col.div_euclid(size));
+    let offset = (row.rem_euclid(size) * size + col.rem_euclid(size)) as usize;
+
+    (key, offset)
+}
+
+fn adjacent_cells(row: i64, col: i64) -> impl Iterator<Item = (i64, i64)> {
+    (-1..=1)
+        .flat_map(move |row_offset| (-1..=1).map(move |col_offset| (row_offset, col_offset)))
+        .filter(|offset| *offset != (0, 0))
+        // the cells beyond the bounds of `i64` do not exist
+        .filter_map(move |(row_offset, col_offset)| {
+            Some((row.checked_add(row_offset)?, col.checked_add(col_offset)?))
+        })
+}
+
+fn get_bit(set: &[u64; CHUNK_WORDS], idx: usize) -> bool {
+    set[idx / 64] & (1 << (idx % 64)) != 0
+}
+
+fn set_bit(set: &mut [u64; CHUNK_WORDS], idx: usize, value: bool) {
+    if value {
+        set[idx / 64] |= 1 << (idx % 64);
+    } else {
+        set[idx / 64] &= !(1 << (idx % 64));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::minesweeper::infinite::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test() {
+        let mut game = InfiniteMinesweeper::new(0, 40).unwrap();
+        let other = InfiniteMinesweeper::new(0, 40).unwrap();
+
+        assert!(game.click(-5, 7, false).unwrap());
+        assert!(game.get(-5, 7).is_revealed);
+        assert_eq!(game.get(-5, 7).mine_adjacent, 0);
+
+        // chunks are independent of the exploring order
+        let mines = |game: &InfiniteMinesweeper| {
+            (32..48)
+                .flat_map(|row| (112..128).map(move |col| (row, col)))
+                .filter(|(row, col)| game.get(*row, *col).is_mine())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(mines(&game), mines(&other));
+        assert_eq!(mines(&game).len(), 40);
+
+        let mine = mines(&game)[0];
+        game.toggle_flag(mine.0, mine.1).unwrap();
+        assert_eq!(
+            game.click(mine.0, mine.1, false),
+            Err(MinesweeperError::AlreadyFlagged)
+        );
+
+        game.toggle_flag(mine.0, mine.1).unwrap();
+        game.click(mine.0, mine.1, false).unwrap();
+        assert_eq!(game.get_game_status(), &Status::Exploded(vec![mine]));
+
+        // the board ends at the bounds of the coordinates
+        let mut game = InfiniteMinesweeper::new(0, 40).unwrap();
+        assert!(game.click(i64::MAX, 0, false).unwrap());
+        assert!(game.get(i64::MAX, 0).is_revealed);
+
+        let mut game = InfiniteMinesweeper::new(0, 40).unwrap();
+        game.click(i64::MIN / 2, 0, false).unwrap();
+        assert_eq!(game.get(i64::MIN / 2, 0).mine_adjacent, 0);
+        game.get(i64::MAX / 2 + 10, 0);
+        game.get(i64::MIN, i64::MAX);
+    }
+}
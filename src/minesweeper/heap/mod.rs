@@ -0,0 +1,2086 @@
+//! Minesweeper on a board stored in a `Vec`, available with the `minesweeper` feature
+
+pub mod flags;
+pub mod infinite;
+pub mod replay;
+pub mod view;
+
+mod auto_play;
+mod probability;
+mod puzzle;
+
+pub use self::puzzle::Puzzle;
+use super::common::{AdjacentCells, Cell, MinesweeperError, MAX_OPENING_ATTEMPTS};
+#[cfg(feature = "testing")]
+use crate::testing::{self, RandomPlay};
+use crate::{
+    affect::{Affected, Affects, Effect},
+    invariant::InvariantError,
+    parse::{ParseError, Words},
+    rng::GameRng,
+};
+use alloc::{vec, vec::Vec};
+use core::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    hash::{Hash, Hasher},
+};
+
+/// Minesweeper
+///
+/// To avoid unessecary memory allocation, the game board is stored in a single `Vec` rather than a nested one.
+///
+/// Passing an invalid position to a method will cause panic. Check the target position validity first when dealing with user input
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+pub struct Minesweeper<R> {
+    board: Vec<Cell>,
+    height: usize,
+    width: usize,
+    mine: usize,
+    rng: R,
+    step_count: usize,
+    flag_count: usize,
+    counts: Counts,
+    status: Status,
+    rules: Rules,
+    forgiveness_used: usize,
+    before_explosion: Option<Snapshot>,
+    history: Vec<Action>,
+    is_layout_fixed: bool,
+    stats: Stats,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(Minesweeper<R>);
+
+// games are compared by the board as played, leaving out the random number generator, the cell counts that follow from the board, and the history and statistics of how it was played
+impl<R> PartialEq for Minesweeper<R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.position_key() == other.position_key()
+    }
+}
+
+impl<R> Eq for Minesweeper<R> {}
+
+impl<R> Hash for Minesweeper<R> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.position_key().hash(state);
+    }
+}
+
+impl<R> Affects for Minesweeper<R> {
+    /// The cells revealed, each being [`Effect::Revealed`], including the cell clicked
+    ///
+    /// Panic if the boards are of different sizes
+    fn affected<'a>(&'a self, after: &'a Self) -> impl Iterator<Item = Affected> + 'a {
+        assert_eq!(self.height, after.height);
+        assert_eq!(self.width, after.width);
+
+        self.board
+            .iter()
+            .zip(&after.board)
+            .enumerate()
+            .filter(|(_, (before, after))| !before.is_revealed && after.is_revealed)
+            .map(|(idx, _)| Affected {
+                row: idx / self.width,
+                col: idx % self.width,
+                effect: Effect::Revealed,
+            })
+    }
+}
+
+impl<R> Minesweeper<R> {
+    /// The fields telling games apart, see the `PartialEq` implementation
+    #[allow(clippy::type_complexity)]
+    fn position_key(
+        &self,
+    ) -> (
+        &[Cell],
+        usize,
+        usize,
+        usize,
+        usize,
+        usize,
+        &Status,
+        &Rules,
+        usize,
+        &Option<Snapshot>,
+        bool,
+    ) {
+        (
+            &self.board,
+            self.height,
+            self.width,
+            self.mine,
+            self.step_count,
+            self.flag_count,
+            &self.status,
+            &self.rules,
+            self.forgiveness_used,
+            &self.before_explosion,
+            self.is_layout_fixed,
+        )
+    }
+}
+
+/// The most mines a single cell can contain, see [`Rules::max_mines_per_cell`]
+///
+/// A cell stores its adjacent mine count in a byte, which holds 8 adjacent cells of up to this many mines each
+pub const MAX_MINES_PER_CELL: usize = 31;
+
+/// Optional rules of a Minesweeper game
+///
+/// The default rules are the classic ones
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Rules {
+    /// The number of explosions that can be undone with [`Minesweeper::undo_explosion`]
+    pub forgiveness: usize,
+    /// Whether the board wraps around at the edges, making cells on opposite edges adjacent
+    ///
+    /// Both the height and the width of a toroidal board must be at least 3
+    pub is_toroidal: bool,
+    /// The maximum number of mines a single cell can contain, from 1 up to [`MAX_MINES_PER_CELL`]
+    ///
+    /// Adjacent mine counts sum up all mines around a cell, and a cell can carry as many flags as it may contain mines
+    pub max_mines_per_cell: usize,
+    /// The condition for winning the game
+    pub win_condition: WinCondition,
+    /// The minimum number of cells the first click opens, the clicked cell included
+    ///
+    /// The first click always lands on a cell without adjacent mines, which opens it and its neighbors at least. A larger opening spares the player from guessing right after the start, but the board is generated again until the opening is large enough, up to [`MAX_OPENING_ATTEMPTS`] times, so a large opening on a dense board can make the first click fail
+    pub min_opening: usize,
+}
+
+/// The condition for winning a Minesweeper game
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum WinCondition {
+    /// All safe cells are revealed
+    Reveal,
+    /// All mines are flagged
+    Flag,
+    /// Either all safe cells are revealed or all mines are flagged
+    #[default]
+    Either,
+}
+
+/// The classic difficulty presets, see [`Minesweeper::with_difficulty`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Difficulty {
+    /// 9 by 9 cells with 10 mines
+    Beginner,
+    /// 16 by 16 cells with 40 mines
+    Intermediate,
+    /// 16 rows by 30 columns with 99 mines
+    Expert,
+}
+
+impl Difficulty {
+    /// Get the height of the board
+    pub const fn height(self) -> usize {
+        match self {
+            Difficulty::Beginner => 9,
+            Difficulty::Intermediate | Difficulty::Expert => 16,
+        }
+    }
+
+    /// Get the width of the board
+    pub const fn width(self) -> usize {
+        match self {
+            Difficulty::Beginner => 9,
+            Difficulty::Intermediate => 16,
+            Difficulty::Expert => 30,
+        }
+    }
+
+    /// Get the number of mines
+    pub const fn mines(self) -> usize {
+        match self {
+            Difficulty::Beginner => 10,
+            Difficulty::Intermediate => 40,
+            Difficulty::Expert => 99,
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Rules {
+    /// At most 8 mines a cell, which keeps the mine capacity of a board far from overflowing, and no minimum opening, which a dense board may never reach
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            forgiveness: u.int_in_range(0..=u8::MAX as usize)?,
+            is_toroidal: u.arbitrary()?,
+            max_mines_per_cell: u.int_in_range(1..=8)?,
+            win_condition: u.arbitrary()?,
+            min_opening: 0,
+        })
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            forgiveness: 0,
+            is_toroidal: false,
+            max_mines_per_cell: 1,
+            win_condition: WinCondition::Either,
+            min_opening: 0,
+        }
+    }
+}
+
+/// Statistics of a game, counted as it is played, see [`Minesweeper::get_stats`]
+///
+/// Undoing an explosion keeps the statistics, as they count what the player did
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stats {
+    /// The clicks on revealed cells that changed the board
+    pub chords: usize,
+    /// The flags placed with [`Minesweeper::toggle_flag`]
+    pub flags_placed: usize,
+    /// The most safe cells revealed by a single step, which is the longest chain of a flood fill
+    pub max_revealed: usize,
+}
+
+#[cfg(feature = "events")]
+/// A rules event between two game states, see [`Minesweeper::events`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// Safe cells were revealed, more than one for a flood fill
+    Opened { revealed: usize },
+    /// A click on a revealed cell changed the board
+    Chorded,
+    /// A flag was placed
+    Flagged,
+    /// Mines were hit
+    Exploded { mines: usize },
+    /// An explosion was undone with a forgiveness token
+    ExplosionUndone,
+    /// The game was won, see [`WinCondition`]
+    Won,
+}
+
+/// The board state right before an explosion, kept while forgiveness is available
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct Snapshot {
+    board: Vec<Cell>,
+    step_count: usize,
+    flag_count: usize,
+    counts: Counts,
+}
+
+/// Cell counts maintained along with the board, so that checking the game status does not scan the whole board
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct Counts {
+    /// Cells containing any mine
+    mine_cells: usize,
+    /// Safe cells revealed
+    revealed_safe_cells: usize,
+    /// Cells containing any mine, with as many flags as mines
+    flagged_mine_cells: usize,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Status {
+    Win,
+    Exploded(Vec<(usize, usize)>),
+    InProgress,
+}
+
+crate::code::codes!(Status {
+    Win => "win",
+    Exploded => "exploded",
+    InProgress => "in_progress",
+});
+
+// `ufmt` implements `uDebug` for `Vec` only with its `std` feature, so the cells are written as a slice
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for Status {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        match self {
+            Self::Win => f.write_str("Win"),
+            Self::Exploded(cells) => f
+                .debug_tuple("Exploded")?
+                .field(&cells.as_slice())?
+                .finish(),
+            Self::InProgress => f.write_str("InProgress"),
+        }
+    }
+}
+
+crate::canonical::encode_enum!(Status {
+    0 => Win,
+    1 => Exploded(cells),
+    2 => InProgress,
+});
+
+crate::canonical::encode_enum!(WinCondition {
+    0 => Reveal,
+    1 => Flag,
+    2 => Either,
+});
+
+crate::canonical::encode_struct!(Rules {
+    forgiveness,
+    is_toroidal,
+    max_mines_per_cell,
+    win_condition,
+    min_opening
+});
+
+crate::canonical::encode_struct!(Snapshot {
+    board,
+    step_count,
+    flag_count,
+    counts
+});
+
+crate::canonical::encode_struct!(Counts {
+    mine_cells,
+    revealed_safe_cells,
+    flagged_mine_cells
+});
+
+crate::canonical::canonical!([R] Minesweeper<R>, "minesweeper", 1 {
+    board,
+    height,
+    width,
+    mine,
+    step_count,
+    flag_count,
+    status,
+    rules,
+    forgiveness_used,
+    before_explosion,
+    is_layout_fixed,
+});
+
+/// The change of a cell between two game states
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CellDiff {
+    pub row: usize,
+    pub col: usize,
+    pub before: Cell,
+    pub after: Cell,
+}
+
+/// Differences between two game states, see [`Minesweeper::diff`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BoardDiff {
+    /// The changed cells, in row-major order
+    pub cells: Vec<CellDiff>,
+    /// The game status before and after, `None` if unchanged
+    pub status: Option<(Status, Status)>,
+}
+
+/// The result of [`Minesweeper::reveal`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Revealed {
+    /// The cells newly revealed, in row-major order
+    pub revealed: Vec<(usize, usize)>,
+    /// The mines hit, in the order they were given
+    pub exploded: Vec<(usize, usize)>,
+}
+
+/// An action taken on the game board
+///
+/// The size of the board is not known to an arbitrary action, so its positions may be out of bounds
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Action {
+    /// Click a cell with [`Minesweeper::click`]
+    Click {
+        row: usize,
+        col: usize,
+        auto_flag: bool,
+    },
+    /// Click an already revealed cell with [`Minesweeper::click`], revealing or flagging its adjacent cells
+    Chord {
+        row: usize,
+        col: usize,
+        auto_flag: bool,
+    },
+    /// Flag or unflag a cell with [`Minesweeper::toggle_flag`]
+    Flag { row: usize, col: usize },
+    /// Reveal a list of cells with [`Minesweeper::reveal`]
+    Reveal { cells: Vec<(usize, usize)> },
+    /// Undo an explosion with [`Minesweeper::undo_explosion`]
+    UndoExplosion,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Click {
+                row,
+                col,
+                auto_flag,
+            } => write!(
+                f,
+                "click {row} {col}{}",
+                if *auto_flag { " auto" } else { "" }
+            ),
+            Self::Chord {
+                row,
+                col,
+                auto_flag,
+            } => write!(
+                f,
+                "chord {row} {col}{}",
+                if *auto_flag { " auto" } else { "" }
+            ),
+            Self::Flag { row, col } => write!(f, "flag {row} {col}"),
+            Self::Reveal { cells } => {
+                write!(f, "reveal")?;
+
+                for (idx, (row, col)) in cells.iter().enumerate() {
+                    let separator = if idx == 0 { " " } else { ", " };
+                    write!(f, "{separator}{row} {col}")?;
+                }
+
+                Ok(())
+            }
+            Self::UndoExplosion => write!(f, "undo"),
+        }
+    }
+}
+
+impl<R: GameRng> Minesweeper<R> {
+    /// Create a new Minesweeper game
+    ///
+    /// A random number generator is required for randomizing mine positions
+    ///
+    /// Return `Err(MinesweeperError::TooManyMines)` if `(height - 1) * (width - 1) < mines`
+    ///
+    /// # Examples
+    /// ```rust
+    /// # fn minesweeper() {
+    /// use gamie::minesweeper::Minesweeper;
+    /// use rand::rngs::ThreadRng;
+    ///
+    /// let mut game = Minesweeper::new(8, 8, 9, ThreadRng::default()).unwrap();
+    /// # }
+    /// ```
+    pub fn new(
+        height: usize,
+        width: usize,
+        mines: usize,
+        rng: R,
+    ) -> Result<Self, MinesweeperError> {
+        Self::with_rules(height, width, mines, Rules::default(), rng)
+    }
+
+    /// Create a new Minesweeper game of a difficulty preset
+    ///
+    /// The mines of each preset fit its board, so this never fails
+    ///
+    /// # Examples
+    /// ```rust
+    /// # fn minesweeper() {
+    /// use gamie::minesweeper::{Difficulty, Minesweeper};
+    /// use rand::rngs::ThreadRng;
+    ///
+    /// let game = Minesweeper::with_difficulty(Difficulty::Expert, ThreadRng::default()).unwrap();
+    ///
+    /// assert_eq!(game.get_width(), 30);
+    /// # }
+    /// ```
+    pub fn with_difficulty(difficulty: Difficulty, rng: R) -> Result<Self, MinesweeperError> {
+        Self::new(
+            difficulty.height(),
+            difficulty.width(),
+            difficulty.mines(),
+            rng,
+        )
+    }
+
+    /// Create a new Minesweeper game with optional rules
+    ///
+    /// A board without mines is won from the start, unless the win condition is [`WinCondition::Reveal`]
+    ///
+    /// Return `Err(MinesweeperError::TooManyMines)` if `(height - 1) * (width - 1) * max_mines_per_cell < mines`, or `(height * width - 9) * max_mines_per_cell < mines` for a toroidal board
+    ///
+    /// Return `Err(MinesweeperError::BoardTooSmall)` if the board is toroidal and its height or width is less than 3
+    ///
+    /// Return `Err(MinesweeperError::OpeningTooLarge)` if `min_opening` is more than the cells left safe by the mines
+    ///
+    /// Return `Err(MinesweeperError::InvalidRules)` if `max_mines_per_cell` is not from 1 up to [`MAX_MINES_PER_CELL`]
+    ///
+    /// # Examples
+    /// ```rust
+    /// # fn minesweeper() {
+    /// use gamie::minesweeper::{Minesweeper, Rules};
+    /// use rand::rngs::ThreadRng;
+    ///
+    /// let rules = Rules {
+    ///     forgiveness: 1,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let mut game = Minesweeper::with_rules(8, 8, 9, rules, ThreadRng::default()).unwrap();
+    /// # }
+    /// ```
+    pub fn with_rules(
+        height: usize,
+        width: usize,
+        mines: usize,
+        rules: Rules,
+        rng: R,
+    ) -> Result<Self, MinesweeperError> {
+        if !(1..=MAX_MINES_PER_CELL).contains(&rules.max_mines_per_cell) {
+            return Err(MinesweeperError::InvalidRules);
+        }
+
+        if rules.is_toroidal && (height < 3 || width < 3) {
+            return Err(MinesweeperError::BoardTooSmall);
+        }
+
+        // the first click always opens a cell and its neighbors
+        let capacity = if rules.is_toroidal {
+            height * width - 9
+        } else {
+            (height - 1) * (width - 1)
+        };
+
+        if capacity * rules.max_mines_per_cell < mines {
+            return Err(MinesweeperError::TooManyMines);
+        }
+
+        if height * width - mines.div_ceil(rules.max_mines_per_cell) < rules.min_opening {
+            return Err(MinesweeperError::OpeningTooLarge);
+        }
+
+        let board = vec![Cell::new(); height * width];
+
+        let mut minesweeper = Self {
+            board,
+            height,
+            width,
+            mine: mines,
+            rng,
+            step_count: 0,
+            flag_count: 0,
+            counts: Counts::default(),
+            status: Status::InProgress,
+            rules,
+            forgiveness_used: 0,
+            before_explosion: None,
+            history: Vec::new(),
+            is_layout_fixed: false,
+            stats: Stats::default(),
+        };
+
+        minesweeper.randomize();
+        // a board without mines has every mine flagged already
+        minesweeper.check_game_status();
+
+        Ok(minesweeper)
+    }
+
+    /// Create a new Minesweeper game with a fixed mine layout
+    ///
+    /// `mines` holds the number of mines in each cell, in row-major order. The layout is kept as is, so the first click is not guaranteed to be safe. The random number generator is never used
+    ///
+    /// Return `Err(MinesweeperError::InvalidLayout)` if the length of `mines` is not `height * width`, or a cell contains more than `max_mines_per_cell` mines
+    ///
+    /// Return `Err(MinesweeperError::BoardTooSmall)` if the board is toroidal and its height or width is less than 3
+    ///
+    /// Return `Err(MinesweeperError::InvalidRules)` if `max_mines_per_cell` is not from 1 up to [`MAX_MINES_PER_CELL`]
+    pub fn from_layout(
+        height: usize,
+        width: usize,
+        mines: &[usize],
+        rules: Rules,
+        rng: R,
+    ) -> Result<Self, MinesweeperError> {
+        if !(1..=MAX_MINES_PER_CELL).contains(&rules.max_mines_per_cell) {
+            return Err(MinesweeperError::InvalidRules);
+        }
+
+        if rules.is_toroidal && (height < 3 || width < 3) {
+            return Err(MinesweeperError::BoardTooSmall);
+        }
+
+        if mines.len() != height * width
+            || mines.iter().any(|mines| *mines > rules.max_mines_per_cell)
+        {
+            return Err(MinesweeperError::InvalidLayout);
+        }
+
+        let board = mines
+            .iter()
+            .map(|mines| Cell {
+                mines: *mines as u8,
+                ..Cell::new()
+            })
+            .collect();
+
+        let mut minesweeper = Self {
+            board,
+            height,
+            width,
+            mine: mines.iter().sum(),
+            rng,
+            step_count: 0,
+            flag_count: 0,
+            counts: Counts::default(),
+            status: Status::InProgress,
+            rules,
+            forgiveness_used: 0,
+            before_explosion: None,
+            history: Vec::new(),
+            is_layout_fixed: true,
+            stats: Stats::default(),
+        };
+
+        minesweeper.update_adjacent_mine_count();
+        minesweeper.update_counts();
+        minesweeper.check_game_status();
+
+        Ok(minesweeper)
+    }
+
+    /// Get a cell reference from the game board
+    /// Panic when target position out of bounds
+    pub fn get(&self, row: usize, col: usize) -> &Cell {
+        assert!(row < self.height);
+        assert!(col < self.width);
+
+        &self.board[row * self.width + col]
+    }
+
+    /// Click a cell on the game board
+    ///
+    /// The first click is always a safe click
+    ///
+    /// Clicking an already revealed cell will unreveal its adjacent cells if the flagged cell count around it equals to its adjacent mine count
+    /// When `auto_flag` is `true`, clicking an already revealed cell will flag its adjacent unflagged-unrevealed cells if the unflagged-revealed cell count around it equals to its adjacent mine count
+    ///
+    /// The return value indicates if the game board is changed from the click
+    ///
+    /// Return `Err(MinesweeperError::OpeningTooLarge)` if none of the boards generated for the first click opens [`Rules::min_opening`] cells, see [`MAX_OPENING_ATTEMPTS`]
+    ///
+    /// Panic when target position out of bounds
+    pub fn click(
+        &mut self,
+        row: usize,
+        col: usize,
+        auto_flag: bool,
+    ) -> Result<bool, MinesweeperError> {
+        self.click_revealing(row, col, auto_flag, &mut Vec::new())
+    }
+
+    /// Click a cell, same as [`click`](Self::click), and get the cells it revealed, each being [`Effect::Revealed`]
+    ///
+    /// The cells are collected from the flood fills of the click, in row-major order, rather than by comparing states with [`Affects`]
+    ///
+    /// Panic when target position out of bounds
+    pub fn click_with_effects(
+        &mut self,
+        row: usize,
+        col: usize,
+        auto_flag: bool,
+    ) -> Result<impl Iterator<Item = Affected>, MinesweeperError> {
+        let mut revealed = Vec::new();
+        self.click_revealing(row, col, auto_flag, &mut revealed)?;
+        revealed.sort_unstable();
+
+        let width = self.width;
+        Ok(revealed.into_iter().map(move |idx| Affected {
+            row: idx / width,
+            col: idx % width,
+            effect: Effect::Revealed,
+        }))
+    }
+
+    /// Click a cell, appending the indices of the cells revealed
+    fn click_revealing(
+        &mut self,
+        row: usize,
+        col: usize,
+        auto_flag: bool,
+        revealed: &mut Vec<usize>,
+    ) -> Result<bool, MinesweeperError> {
+        assert!(row < self.height);
+        assert!(col < self.width);
+
+        if self.is_ended() {
+            return Err(MinesweeperError::GameEnded);
+        }
+
+        let snapshot = self.take_snapshot();
+        let revealed_before = self.counts.revealed_safe_cells;
+
+        let is_changed = if !self.board[row * self.width + col].is_revealed {
+            self.ensure_first_click_safe(row * self.width + col)?;
+            self.click_unrevealed(row, col, revealed)?;
+            true
+        } else {
+            let is_changed = self.click_revealed(row, col, auto_flag, revealed)?;
+            self.stats.chords += is_changed as usize;
+            is_changed
+        };
+
+        if is_changed {
+            self.step_count += 1;
+        }
+
+        self.record_revealed(revealed_before);
+
+        if matches!(self.status, Status::Exploded(_)) {
+            self.before_explosion = snapshot;
+        }
+
+        self.history.push(Action::Click {
+            row,
+            col,
+            auto_flag,
+        });
+
+        Ok(is_changed)
+    }
+
+    /// Reveal a list of cells at once
+    ///
+    /// Already revealed and flagged cells are skipped. All safe cells are revealed even if some of the cells are mines, in which case the game explodes with every mine hit. Either all cells are processed or, on error, none are
+    ///
+    /// If this is the first step, the first cell to reveal is always safe. Return `Err(MinesweeperError::OpeningTooLarge)` if it cannot be made so, same as [`click`](Self::click)
+    ///
+    /// Panic when any target position out of bounds
+    ///
+    /// # Examples
+    /// ```rust
+    /// # fn minesweeper() {
+    /// use gamie::minesweeper::Minesweeper;
+    /// use rand::rngs::ThreadRng;
+    ///
+    /// let mut game = Minesweeper::new(8, 8, 9, ThreadRng::default()).unwrap();
+    /// let revealed = game.reveal(&[(0, 0), (7, 7)]).unwrap();
+    ///
+    /// assert!(revealed.revealed.contains(&(0, 0)));
+    /// # }
+    /// ```
+    pub fn reveal(&mut self, cells: &[(usize, usize)]) -> Result<Revealed, MinesweeperError> {
+        for (row, col) in cells {
+            assert!(*row < self.height);
+            assert!(*col < self.width);
+        }
+
+        if self.is_ended() {
+            return Err(MinesweeperError::GameEnded);
+        }
+
+        let snapshot = self.take_snapshot();
+        let revealed_before = self.counts.revealed_safe_cells;
+
+        let targets = cells
+            .iter()
+            .map(|(row, col)| row * self.width + col)
+            .filter(|idx| !self.board[*idx].is_revealed && !self.board[*idx].is_flagged())
+            .collect::<Vec<_>>();
+
+        if let Some(idx) = targets.first() {
+            self.ensure_first_click_safe(*idx)?;
+        }
+
+        let mut revealed = Vec::new();
+        let mut exploded = Vec::new();
+
+        for idx in targets {
+            if self.board[idx].is_mine() {
+                let position = (idx / self.width, idx % self.width);
+
+                if !exploded.contains(&position) {
+                    exploded.push(position);
+                }
+            } else if !self.board[idx].is_revealed {
+                revealed.append(&mut self.reveal_from(idx));
+            }
+        }
+
+        revealed.sort_unstable();
+
+        let revealed = revealed
+            .into_iter()
+            .map(|idx| (idx / self.width, idx % self.width))
+            .collect::<Vec<_>>();
+
+        if !revealed.is_empty() || !exploded.is_empty() {
+            self.step_count += 1;
+        }
+
+        self.record_revealed(revealed_before);
+
+        if exploded.is_empty() {
+            self.check_game_status();
+        } else {
+            self.status = Status::Exploded(exploded.clone());
+            self.before_explosion = snapshot;
+        }
+
+        self.history.push(Action::Reveal {
+            cells: cells.to_vec(),
+        });
+
+        Ok(Revealed { revealed, exploded })
+    }
+
+    /// Click an unrevealed cell, with the flood fill of cells without adjacent mines queued in a scratch buffer supplied by the caller
+    ///
+    /// The result is the same as [`Minesweeper::click`], and the click is recorded as such, but no memory is allocated for the flood fill. When a huge opening overflows the buffer, the cells left out are marked and the range holding them is rescanned, so a small buffer trades speed for memory. Any buffer length works, including `0`
+    ///
+    /// Return the number of cells revealed
+    ///
+    /// Return `Err(MinesweeperError::AlreadyRevealed)` if the target cell is already revealed
+    ///
+    /// Return `Err(MinesweeperError::OpeningTooLarge)` if the first click cannot open [`Rules::min_opening`] cells, same as [`click`](Self::click)
+    ///
+    /// Panic when target position out of bounds
+    ///
+    /// # Examples
+    /// ```rust
+    /// # fn minesweeper() {
+    /// use gamie::minesweeper::Minesweeper;
+    /// use rand::rngs::ThreadRng;
+    ///
+    /// let mut game = Minesweeper::new(64, 64, 99, ThreadRng::default()).unwrap();
+    /// let mut buffer = [0; 32];
+    ///
+    /// let revealed = game.reveal_with_buffer(32, 32, &mut buffer).unwrap();
+    /// assert!(revealed >= 9);
+    /// # }
+    /// ```
+    pub fn reveal_with_buffer(
+        &mut self,
+        row: usize,
+        col: usize,
+        buffer: &mut [usize],
+    ) -> Result<usize, MinesweeperError> {
+        assert!(row < self.height);
+        assert!(col < self.width);
+
+        if self.is_ended() {
+            return Err(MinesweeperError::GameEnded);
+        }
+
+        let idx = row * self.width + col;
+
+        if self.board[idx].is_revealed {
+            return Err(MinesweeperError::AlreadyRevealed);
+        }
+
+        if self.board[idx].is_flagged() {
+            return Err(MinesweeperError::AlreadyFlagged);
+        }
+
+        let snapshot = self.take_snapshot();
+        self.ensure_first_click_safe(idx)?;
+
+        let revealed = if self.board[idx].is_mine() {
+            self.status = Status::Exploded(vec![(row, col)]);
+            self.before_explosion = snapshot;
+            0
+        } else {
+            let revealed = self.reveal_from_with_buffer(idx, buffer);
+            self.check_game_status();
+            revealed
+        };
+
+        self.step_count += 1;
+        self.stats.max_revealed = self.stats.max_revealed.max(revealed);
+
+        self.history.push(Action::Click {
+            row,
+            col,
+            auto_flag: false,
+        });
+
+        Ok(revealed)
+    }
+
+    /// Reveal all cells in a rectangular region at once, see [`Minesweeper::reveal`]
+    ///
+    /// The region starts from (`row`, `col`) and spans `height` rows and `width` columns
+    ///
+    /// Panic when the region out of bounds
+    pub fn reveal_area(
+        &mut self,
+        row: usize,
+        col: usize,
+        height: usize,
+        width: usize,
+    ) -> Result<Revealed, MinesweeperError> {
+        let cells = (row..row + height)
+            .flat_map(|row| (col..col + width).map(move |col| (row, col)))
+            .collect::<Vec<_>>();
+
+        self.reveal(&cells)
+    }
+
+    /// Undo the click that caused an explosion, restoring the board to the state right before it
+    ///
+    /// Each undo consumes one forgiveness token, see [`Rules::forgiveness`]
+    ///
+    /// Return `Err(MinesweeperError::NothingToUndo)` if the game has not exploded, or no forgiveness token was left when it did
+    pub fn undo_explosion(&mut self) -> Result<(), MinesweeperError> {
+        let Some(snapshot) = self.before_explosion.take() else {
+            return Err(MinesweeperError::NothingToUndo);
+        };
+
+        self.board = snapshot.board;
+        self.step_count = snapshot.step_count;
+        self.flag_count = snapshot.flag_count;
+        self.counts = snapshot.counts;
+        self.status = Status::InProgress;
+        self.forgiveness_used += 1;
+
+        self.history.push(Action::UndoExplosion);
+
+        Ok(())
+    }
+
+    /// Flag or unflag a cell on the board
+    ///
+    /// When a cell can contain multiple mines, each toggle adds one more flag until the maximum is reached, then the next toggle removes all flags from the cell
+    ///
+    /// Return Err(MinesweeperError::AlreadyRevealed) if the target cell is already revealed
+    ///
+    /// Panic when target position out of bounds
+    pub fn toggle_flag(&mut self, row: usize, col: usize) -> Result<(), MinesweeperError> {
+        assert!(row < self.height);
+        assert!(col < self.width);
+
+        if self.is_ended() {
+            return Err(MinesweeperError::GameEnded);
+        }
+
+        if self.board[row * self.width + col].is_revealed {
+            return Err(MinesweeperError::AlreadyRevealed);
+        }
+
+        let idx = row * self.width + col;
+        let flags = self.board[idx].flags;
+
+        if usize::from(flags) < self.rules.max_mines_per_cell {
+            if self.flag_count == self.mine {
+                return Err(MinesweeperError::TooManyFlags);
+            }
+
+            self.set_flags(idx, flags + 1);
+            self.stats.flags_placed += 1;
+        } else {
+            self.set_flags(idx, 0);
+        }
+
+        self.check_game_status();
+
+        self.history.push(Action::Flag { row, col });
+
+        Ok(())
+    }
+
+    /// Apply an action to the game board
+    ///
+    /// Return the same errors as the method the action stands for, or `Err(MinesweeperError::NotRevealed)` if a chord targets an unrevealed cell
+    ///
+    /// Panic when target position out of bounds
+    pub fn apply(&mut self, action: Action) -> Result<(), MinesweeperError> {
+        match action {
+            Action::Click {
+                row,
+                col,
+                auto_flag,
+            } => self.click(row, col, auto_flag).map(|_| ()),
+            Action::Chord {
+                row,
+                col,
+                auto_flag,
+            } => {
+                if !self.get(row, col).is_revealed {
+                    return Err(MinesweeperError::NotRevealed);
+                }
+
+                self.click(row, col, auto_flag).map(|_| ())
+            }
+            Action::Flag { row, col } => self.toggle_flag(row, col),
+            Action::Reveal { cells } => self.reveal(&cells).map(|_| ()),
+            Action::UndoExplosion => self.undo_explosion(),
+        }
+    }
+
+    /// Read an action from text, a position as described in [`parse`](crate::parse) after `click`, `chord` or `flag`, or any number of them after `reveal`, e.g. `flag 3 2`
+    ///
+    /// A position alone is a click, a click or a chord followed by `auto` flags cells automatically, and `undo` undoes an explosion
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn minesweeper() {
+    /// use gamie::minesweeper::{Action, Minesweeper};
+    /// use rand::rngs::ThreadRng;
+    ///
+    /// let game = Minesweeper::new(8, 8, 9, ThreadRng::default()).unwrap();
+    ///
+    /// assert_eq!(game.parse_move("flag 3 2"), Ok(Action::Flag { row: 3, col: 2 }));
+    /// assert_eq!(
+    ///     game.parse_move("7,7 auto"),
+    ///     Ok(Action::Click {
+    ///         row: 7,
+    ///         col: 7,
+    ///         auto_flag: true
+    ///     })
+    /// );
+    ///
+    /// assert_eq!(Action::Flag { row: 3, col: 2 }.to_string(), "flag 3 2");
+    /// # }
+    /// ```
+    pub fn parse_move(&self, text: &str) -> Result<Action, ParseError> {
+        let mut words = Words::new(text);
+
+        let action = if words.take("undo") {
+            Action::UndoExplosion
+        } else if words.take("flag") {
+            let (row, col) = words.position(self.height, self.width)?;
+            Action::Flag { row, col }
+        } else if words.take("reveal") {
+            let mut cells = Vec::new();
+
+            while !words.is_empty() {
+                cells.push(words.position(self.height, self.width)?);
+            }
+
+            Action::Reveal { cells }
+        } else {
+            let is_chord = words.take("chord");
+
+            if !is_chord {
+                words.take("click");
+            }
+
+            let (row, col) = words.position(self.height, self.width)?;
+            let auto_flag = words.take("auto");
+
+            if is_chord {
+                Action::Chord {
+                    row,
+                    col,
+                    auto_flag,
+                }
+            } else {
+                Action::Click {
+                    row,
+                    col,
+                    auto_flag,
+                }
+            }
+        };
+
+        words.finish()?;
+        Ok(action)
+    }
+
+    /// Check if a cell can be clicked, same as [`click`](Self::click) succeeding but without changing the game
+    ///
+    /// Clicking a revealed cell is allowed even if it changes nothing
+    ///
+    /// Panic when target position out of bounds
+    pub fn can_click(&self, row: usize, col: usize) -> bool {
+        let cell = self.get(row, col);
+        !self.is_ended() && (cell.is_revealed || !cell.is_flagged())
+    }
+
+    /// Check if the flags of a cell can be toggled, same as [`toggle_flag`](Self::toggle_flag) succeeding but without changing the game
+    ///
+    /// Panic when target position out of bounds
+    pub fn can_flag(&self, row: usize, col: usize) -> bool {
+        let cell = self.get(row, col);
+
+        // removing all flags from a cell is always allowed
+        !self.is_ended()
+            && !cell.is_revealed
+            && (usize::from(cell.flags) == self.rules.max_mines_per_cell
+                || self.flag_count < self.mine)
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The cells must be within the rules, with adjacent mine counts matching the mines around them and no flags on revealed cells, and the counters and the status must match the board. Only the mines hit may be revealed
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        self.validate_board()?;
+
+        let exploded: &[(usize, usize)] = match &self.status {
+            Status::Win if !self.is_won() => return Err(InvariantError::Status),
+            Status::InProgress if self.is_won() => return Err(InvariantError::Status),
+            Status::Exploded(exploded) if exploded.is_empty() => {
+                return Err(InvariantError::Status)
+            }
+            Status::Exploded(exploded) => exploded,
+            _ => &[],
+        };
+
+        for (row, col) in exploded {
+            if *row >= self.height || *col >= self.width || !self.get(*row, *col).is_mine() {
+                return Err(InvariantError::Status);
+            }
+        }
+
+        let is_revealed_mine_hit = |idx: usize| {
+            let cell = &self.board[idx];
+            !(cell.is_mine() && cell.is_revealed)
+                || exploded.contains(&(idx / self.width, idx % self.width))
+        };
+
+        if !(0..self.board.len()).all(is_revealed_mine_hit) {
+            return Err(InvariantError::Board);
+        }
+
+        Ok(())
+    }
+
+    /// Check if the game was end
+    pub fn is_ended(&self) -> bool {
+        self.status != Status::InProgress
+    }
+
+    /// Get the game status
+    pub fn get_game_status(&self) -> &Status {
+        &self.status
+    }
+
+    /// Get the height of the game board
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the width of the game board
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the number of mines in the game board
+    pub fn get_mine_count(&self) -> usize {
+        self.mine
+    }
+
+    /// Get the number of flags used
+    pub fn get_flag_count(&self) -> usize {
+        self.flag_count
+    }
+
+    /// Get the number of steps taken
+    pub fn get_step_count(&self) -> usize {
+        self.step_count
+    }
+
+    /// Get the optional rules of the game
+    pub fn get_rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Get the number of forgiveness tokens used to undo explosions
+    pub fn get_forgiveness_used(&self) -> usize {
+        self.forgiveness_used
+    }
+
+    /// Get the statistics of the game, e.g. for achievements
+    ///
+    /// The statistics are part of the game state, so they are kept when the game is serialized
+    pub fn get_stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Get the rules events from this state to a later one, e.g. right before and after an action, for downstream apps to map to achievements
+    ///
+    /// The events are in the order of the board changes, then the status. Undoing an explosion reports no opening, as the cells revealed by the undone step were covered again
+    #[cfg(feature = "events")]
+    pub fn events(&self, after: &Self) -> impl Iterator<Item = Event> {
+        let revealed = after
+            .counts
+            .revealed_safe_cells
+            .saturating_sub(self.counts.revealed_safe_cells);
+
+        let changes = [
+            (revealed > 0).then_some(Event::Opened { revealed }),
+            (after.stats.chords > self.stats.chords).then_some(Event::Chorded),
+            (after.stats.flags_placed > self.stats.flags_placed).then_some(Event::Flagged),
+            (after.forgiveness_used > self.forgiveness_used).then_some(Event::ExplosionUndone),
+        ];
+
+        let status = match (&self.status, &after.status) {
+            (Status::InProgress, Status::Exploded(mines)) => {
+                Some(Event::Exploded { mines: mines.len() })
+            }
+            (Status::InProgress, Status::Win) => Some(Event::Won),
+            _ => None,
+        };
+
+        changes.into_iter().chain([status]).flatten()
+    }
+
+    /// Compare the game with a later state of it
+    ///
+    /// The returned diff describes how to get from `self` to `other`
+    ///
+    /// Panic if the boards are of different sizes
+    pub fn diff(&self, other: &Self) -> BoardDiff {
+        assert_eq!(self.height, other.height);
+        assert_eq!(self.width, other.width);
+
+        let cells = self
+            .board
+            .iter()
+            .zip(&other.board)
+            .enumerate()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(idx, (before, after))| CellDiff {
+                row: idx / self.width,
+                col: idx % self.width,
+                before: *before,
+                after: *after,
+            })
+            .collect();
+
+        BoardDiff {
+            cells,
+            status: (self.status != other.status)
+                .then(|| (self.status.clone(), other.status.clone())),
+        }
+    }
+
+    /// Get all actions successfully applied to the game board, in order
+    pub fn get_history(&self) -> &[Action] {
+        &self.history
+    }
+
+    fn randomize(&mut self) {
+        // each cell has `max_mines_per_cell` slots, and mines are placed into distinct slots picked uniformly
+        self.board.iter_mut().for_each(|cell| cell.mines = 0);
+
+        let mut placed = 0;
+
+        while placed < self.mine {
+            let idx = self.rng.gen_below(self.height * self.width);
+            let slot = self.rng.gen_below(self.rules.max_mines_per_cell);
+
+            if slot >= usize::from(self.board[idx].mines) {
+                self.board[idx].mines += 1;
+                placed += 1;
+            }
+        }
+
+        self.update_adjacent_mine_count();
+        self.update_counts();
+    }
+
+    fn take_snapshot(&self) -> Option<Snapshot> {
+        (self.forgiveness_used < self.rules.forgiveness).then(|| Snapshot {
+            board: self.board.clone(),
+            step_count: self.step_count,
+            flag_count: self.flag_count,
+            counts: self.counts,
+        })
+    }
+
+    fn ensure_first_click_safe(&mut self, idx: usize) -> Result<(), MinesweeperError> {
+        if self.step_count != 0 || self.is_layout_fixed {
+            return Ok(());
+        }
+
+        let mut attempts = 1;
+
+        while self.board[idx].is_mine()
+            || self.board[idx].mine_adjacent > 0
+            || self.opening_size(idx) < self.rules.min_opening
+        {
+            if attempts == MAX_OPENING_ATTEMPTS {
+                return Err(MinesweeperError::OpeningTooLarge);
+            }
+
+            self.randomize();
+            attempts += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Count the cells clicking a cell without adjacent mines would open, without revealing them
+    fn opening_size(&self, idx: usize) -> usize {
+        let mut is_opened = vec![false; self.board.len()];
+        let mut opened = vec![idx];
+        is_opened[idx] = true;
+
+        let mut cursor = 0;
+
+        while let Some(&cell_idx) = opened.get(cursor) {
+            cursor += 1;
+
+            if self.board[cell_idx].mine_adjacent != 0 {
+                continue;
+            }
+
+            for neighbor_idx in
+                self.get_adjacent_cells(cell_idx / self.width, cell_idx % self.width)
+            {
+                if !is_opened[neighbor_idx] {
+                    is_opened[neighbor_idx] = true;
+                    opened.push(neighbor_idx);
+                }
+            }
+        }
+
+        opened.len()
+    }
+
+    fn click_unrevealed(
+        &mut self,
+        row: usize,
+        col: usize,
+        revealed: &mut Vec<usize>,
+    ) -> Result<(), MinesweeperError> {
+        if self.board[row * self.width + col].is_flagged() {
+            return Err(MinesweeperError::AlreadyFlagged);
+        }
+
+        if self.board[row * self.width + col].is_mine() {
+            self.status = Status::Exploded(vec![(row, col)]);
+            return Ok(());
+        }
+
+        revealed.append(&mut self.reveal_from(row * self.width + col));
+        self.check_game_status();
+
+        Ok(())
+    }
+
+    fn click_revealed(
+        &mut self,
+        row: usize,
+        col: usize,
+        auto_flag: bool,
+        revealed: &mut Vec<usize>,
+    ) -> Result<bool, MinesweeperError> {
+        let mut is_changed = false;
+
+        if self.board[row * self.width + col].mine_adjacent > 0 {
+            // unrevealed cells without flags, and the flags on the unrevealed cells
+            let mut adjacent_unrevealed = 0;
+            let mut adjacent_flags = 0;
+
+            self.get_adjacent_cells(row, col)
+                .map(|idx| self.board[idx])
+                .for_each(|cell| {
+                    if !cell.is_revealed {
+                        if cell.is_flagged() {
+                            adjacent_flags += cell.flags;
+                        } else {
+                            adjacent_unrevealed += 1;
+                        }
+                    }
+                });
+
+            if adjacent_unrevealed > 0 {
+                if adjacent_flags == self.board[row * self.width + col].mine_adjacent {
+                    let mut exploded = None;
+
+                    self.get_adjacent_cells(row, col).for_each(|idx| {
+                        if !self.board[idx].is_flagged() && !self.board[idx].is_revealed {
+                            if self.board[idx].is_mine() {
+                                self.board[idx].is_revealed = true;
+                                revealed.push(idx);
+
+                                let position = (idx / self.width, idx % self.width);
+
+                                match exploded {
+                                    None => exploded = Some(vec![position]),
+                                    Some(ref mut exploded) => {
+                                        exploded.push(position);
+                                    }
+                                }
+                            } else {
+                                revealed.append(&mut self.reveal_from(idx));
+                                is_changed = true;
+                            }
+                        }
+                    });
+
+                    if let Some(exploded) = exploded {
+                        self.status = Status::Exploded(exploded);
+                        return Ok(true);
+                    }
+                }
+
+                let max_mines = self.rules.max_mines_per_cell;
+
+                if auto_flag
+                    && adjacent_unrevealed * max_mines + usize::from(adjacent_flags)
+                        == usize::from(self.board[row * self.width + col].mine_adjacent)
+                {
+                    self.get_adjacent_cells(row, col).for_each(|idx| {
+                        if !self.board[idx].is_flagged() && !self.board[idx].is_revealed {
+                            self.set_flags(idx, max_mines as u8);
+                            is_changed = true;
+                        }
+                    });
+                }
+            }
+
+            self.check_game_status();
+        }
+
+        Ok(is_changed)
+    }
+
+    /// Reveal a safe cell, and all cells connected to it through cells without adjacent mines
+    ///
+    /// Return the indices of the cells revealed. The returned `Vec` is also used as the flood fill queue
+    fn reveal_from(&mut self, idx: usize) -> Vec<usize> {
+        let mut revealed = vec![idx];
+        self.reveal_cell(idx);
+
+        let mut cursor = 0;
+
+        while let Some(&cell_idx) = revealed.get(cursor) {
+            cursor += 1;
+
+            if self.board[cell_idx].mine_adjacent != 0 {
+                continue;
+            }
+
+            for neighbor_idx in
+                self.get_adjacent_cells(cell_idx / self.width, cell_idx % self.width)
+            {
+                let neighbor = &self.board[neighbor_idx];
+
+                // cells are marked as revealed when queued, so each cell is visited once
+                if !neighbor.is_flagged() && !neighbor.is_revealed {
+                    self.reveal_cell(neighbor_idx);
+                    revealed.push(neighbor_idx);
+                }
+            }
+        }
+
+        revealed
+    }
+
+    /// Same as [`reveal_from`](Self::reveal_from), with the queue kept in a scratch buffer
+    ///
+    /// Cells without adjacent mines that do not fit into the full queue are marked with [`DROPPED_FLAGS`] and picked up again later, so only the frontier dropped by this call is expanded. A marked cell that still does not fit is expanded right away
+    ///
+    /// Return the number of cells revealed
+    fn reveal_from_with_buffer(&mut self, idx: usize, buffer: &mut [usize]) -> usize {
+        let mut queue = ScratchQueue::new(buffer);
+        let mut dropped = None;
+
+        self.reveal_cell(idx);
+
+        if !queue.push(idx) {
+            self.drop_cell(idx, &mut dropped);
+        }
+
+        let mut revealed = 1;
+
+        loop {
+            while let Some(cell_idx) = queue.pop() {
+                revealed += self.reveal_adjacent(cell_idx, &mut queue, &mut dropped);
+            }
+
+            // only the range of the dropped cells is rescanned, and each dropped cell is either queued again or expanded right away, so every rescan makes progress even with an empty buffer
+            let Some((first, last)) = dropped.take() else {
+                return revealed;
+            };
+
+            for cell_idx in first..=last {
+                if self.board[cell_idx].flags != DROPPED_FLAGS {
+                    continue;
+                }
+
+                self.board[cell_idx].flags = 0;
+
+                if !queue.push(cell_idx) {
+                    revealed += self.reveal_adjacent(cell_idx, &mut queue, &mut dropped);
+                }
+            }
+        }
+    }
+
+    /// Reveal the unrevealed neighbors of a cell without adjacent mines, and queue the ones without adjacent mines either
+    ///
+    /// Return the number of cells revealed
+    fn reveal_adjacent(
+        &mut self,
+        idx: usize,
+        queue: &mut ScratchQueue,
+        dropped: &mut Option<(usize, usize)>,
+    ) -> usize {
+        let mut revealed = 0;
+
+        if self.board[idx].mine_adjacent != 0 {
+            return revealed;
+        }
+
+        for neighbor_idx in self.get_adjacent_cells(idx / self.width, idx % self.width) {
+            let neighbor = &self.board[neighbor_idx];
+
+            if !neighbor.is_flagged() && !neighbor.is_revealed {
+                self.reveal_cell(neighbor_idx);
+                revealed += 1;
+
+                if self.board[neighbor_idx].mine_adjacent == 0 && !queue.push(neighbor_idx) {
+                    self.drop_cell(neighbor_idx, dropped);
+                }
+            }
+        }
+
+        revealed
+    }
+
+    /// Mark a revealed cell left out of the full queue, see [`DROPPED_FLAGS`]
+    fn drop_cell(&mut self, idx: usize, dropped: &mut Option<(usize, usize)>) {
+        self.board[idx].flags = DROPPED_FLAGS;
+        Self::extend_dropped(idx, dropped);
+    }
+
+    fn extend_dropped(idx: usize, dropped: &mut Option<(usize, usize)>) {
+        *dropped = Some(match *dropped {
+            Some((first, last)) => (first.min(idx), last.max(idx)),
+            None => (idx, idx),
+        });
+    }
+
+    /// Record the safe cells revealed by a step, from the count before it
+    fn record_revealed(&mut self, revealed_before: usize) {
+        let revealed = self.counts.revealed_safe_cells - revealed_before;
+        self.stats.max_revealed = self.stats.max_revealed.max(revealed);
+    }
+
+    fn reveal_cell(&mut self, idx: usize) {
+        let cell = &mut self.board[idx];
+        cell.is_revealed = true;
+
+        if !cell.is_mine() {
+            self.counts.revealed_safe_cells += 1;
+        }
+    }
+
+    fn set_flags(&mut self, idx: usize, flags: u8) {
+        let cell = &mut self.board[idx];
+        let was_flagged_mine = cell.is_mine() && cell.flags == cell.mines;
+
+        self.flag_count = self.flag_count - usize::from(cell.flags) + usize::from(flags);
+        cell.flags = flags;
+
+        let is_flagged_mine = cell.is_mine() && cell.flags == cell.mines;
+
+        if is_flagged_mine && !was_flagged_mine {
+            self.counts.flagged_mine_cells += 1;
+        } else if was_flagged_mine && !is_flagged_mine {
+            self.counts.flagged_mine_cells -= 1;
+        }
+    }
+
+    fn check_game_status(&mut self) {
+        self.status = if self.is_won() {
+            Status::Win
+        } else {
+            Status::InProgress
+        };
+    }
+
+    fn is_won(&self) -> bool {
+        let counts = self.counts;
+        let all_revealed = counts.revealed_safe_cells == self.board.len() - counts.mine_cells;
+        let all_flagged = counts.flagged_mine_cells == counts.mine_cells;
+
+        match self.rules.win_condition {
+            WinCondition::Reveal => all_revealed,
+            WinCondition::Flag => all_flagged,
+            WinCondition::Either => all_revealed || all_flagged,
+        }
+    }
+
+    fn update_counts(&mut self) {
+        self.counts = self.compute_counts();
+    }
+
+    fn compute_counts(&self) -> Counts {
+        let mut counts = Counts::default();
+
+        for cell in &self.board {
+            if cell.is_mine() {
+                counts.mine_cells += 1;
+
+                if cell.flags == cell.mines {
+                    counts.flagged_mine_cells += 1;
+                }
+            } else if cell.is_revealed {
+                counts.revealed_safe_cells += 1;
+            }
+        }
+
+        counts
+    }
+
+    fn update_adjacent_mine_count(&mut self) {
+        for idx in 0..self.height * self.width {
+            self.board[idx].mine_adjacent = self.count_adjacent_mines(idx);
+        }
+    }
+
+    // at most 8 cells of `MAX_MINES_PER_CELL` mines, which fits in a `u8`
+    fn count_adjacent_mines(&self, idx: usize) -> u8 {
+        self.get_adjacent_cells(idx / self.width, idx % self.width)
+            .map(|idx| self.board[idx].mines)
+            .sum()
+    }
+
+    /// The checks of [`validate`](Self::validate) shared with [`MinesweeperFlags`](flags::MinesweeperFlags), where revealed mines are claimed rather than hit
+    fn validate_board(&self) -> Result<(), InvariantError> {
+        if self.board.len() != self.height * self.width
+            || self.rules.is_toroidal && (self.height < 3 || self.width < 3)
+            || !(1..=MAX_MINES_PER_CELL).contains(&self.rules.max_mines_per_cell)
+        {
+            return Err(InvariantError::Board);
+        }
+
+        let max_mines = self.rules.max_mines_per_cell as u8;
+
+        for (idx, cell) in self.board.iter().enumerate() {
+            if cell.mines > max_mines
+                || cell.flags > max_mines
+                || cell.is_revealed && cell.is_flagged()
+                || cell.mine_adjacent != self.count_adjacent_mines(idx)
+            {
+                return Err(InvariantError::Board);
+            }
+        }
+
+        if self
+            .board
+            .iter()
+            .map(|cell| usize::from(cell.mines))
+            .sum::<usize>()
+            != self.mine
+            || self
+                .board
+                .iter()
+                .map(|cell| usize::from(cell.flags))
+                .sum::<usize>()
+                != self.flag_count
+            || self.counts != self.compute_counts()
+            || self.forgiveness_used > self.rules.forgiveness
+            || self.stats.max_revealed > self.board.len()
+        {
+            return Err(InvariantError::Counter);
+        }
+
+        Ok(())
+    }
+
+    fn get_adjacent_cells(&self, row: usize, col: usize) -> AdjacentCells {
+        AdjacentCells::new(row, col, self.height, self.width, self.rules.is_toroidal)
+    }
+}
+
+/// The flags a revealed cell carries while it waits for the queue of [`Minesweeper::reveal_with_buffer`], as revealed cells are never flagged otherwise
+const DROPPED_FLAGS: u8 = u8::MAX;
+
+/// A first-in-first-out queue of cell indices stored in a buffer of fixed length
+struct ScratchQueue<'a> {
+    buffer: &'a mut [usize],
+    head: usize,
+    len: usize,
+}
+
+impl<'a> ScratchQueue<'a> {
+    fn new(buffer: &'a mut [usize]) -> Self {
+        Self {
+            buffer,
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Return `false` if the queue is full
+    fn push(&mut self, idx: usize) -> bool {
+        if self.len == self.buffer.len() {
+            return false;
+        }
+
+        self.buffer[(self.head + self.len) % self.buffer.len()] = idx;
+        self.len += 1;
+        true
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let idx = self.buffer[self.head];
+        self.head = (self.head + 1) % self.buffer.len();
+        self.len -= 1;
+        Some(idx)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<R: GameRng> RandomPlay for Minesweeper<R> {
+    type Move = Action;
+
+    /// Pick a click on an unrevealed cell or a flag toggle
+    fn random_move<T: GameRng + ?Sized>(&self, rng: &mut T) -> Option<Action> {
+        let clicks = testing::positions(self.height, self.width)
+            .filter(|&(row, col)| !self.get(row, col).is_revealed && self.can_click(row, col))
+            .map(|(row, col)| Action::Click {
+                row,
+                col,
+                auto_flag: false,
+            });
+        let flags = testing::positions(self.height, self.width)
+            .filter(|&(row, col)| self.can_flag(row, col))
+            .map(|(row, col)| Action::Flag { row, col });
+
+        testing::choose(rng, clicks.chain(flags))
+    }
+
+    fn apply_move(&mut self, action: Action) {
+        self.apply(action).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{affect::Affects, minesweeper::*, rng::GameRng};
+    use alloc::vec::Vec;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // a generator which can be serialized along with the game
+    #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+    struct Lcg(u64);
+
+    impl GameRng for Lcg {
+        fn gen_below(&mut self, bound: usize) -> usize {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ((self.0 >> 33) % bound as u64) as usize
+        }
+    }
+
+    #[test]
+    fn test() {
+        // chords, events and forgiveness
+        {
+            let rules = Rules {
+                forgiveness: 1,
+                ..Default::default()
+            };
+
+            let mut game =
+                Minesweeper::with_rules(8, 8, 10, rules, StdRng::seed_from_u64(0)).unwrap();
+
+            let chord = Action::Chord {
+                row: 4,
+                col: 4,
+                auto_flag: false,
+            };
+            assert_eq!(
+                game.apply(chord.clone()),
+                Err(MinesweeperError::NotRevealed)
+            );
+
+            let before = game.clone();
+            game.click(4, 4, false).unwrap();
+            assert!(matches!(
+                before.events(&game).next(),
+                Some(Event::Opened { revealed }) if revealed >= 9
+            ));
+            assert!(before
+                .affected(&game)
+                .any(|affected| (affected.row, affected.col) == (4, 4)));
+            assert_eq!(
+                before.affected(&game).count(),
+                game.get_stats().max_revealed
+            );
+            assert!(game.can_click(4, 4));
+            assert!(!game.can_flag(4, 4));
+            assert!(game.get_stats().max_revealed >= 9);
+            assert!(before
+                .clone()
+                .click_with_effects(4, 4, false)
+                .unwrap()
+                .eq(before.affected(&game)));
+
+            let before = game.clone();
+            game.apply(chord).unwrap();
+            assert!(before
+                .clone()
+                .click_with_effects(4, 4, false)
+                .unwrap()
+                .eq(before.affected(&game)));
+            assert_eq!(game.undo_explosion(), Err(MinesweeperError::NothingToUndo));
+
+            let mines = (0..8)
+                .flat_map(|row| (0..8).map(move |col| (row, col)))
+                .filter(|(row, col)| game.get(*row, *col).is_mine())
+                .collect::<Vec<_>>();
+
+            let step_count = game.get_step_count();
+
+            game.click(mines[0].0, mines[0].1, false).unwrap();
+            assert!(game.is_ended());
+            assert!(!game.can_click(mines[1].0, mines[1].1));
+
+            let exploded = game.clone();
+            game.undo_explosion().unwrap();
+            assert!(exploded.events(&game).eq([Event::ExplosionUndone]));
+            assert_eq!(game.get_game_status(), &Status::InProgress);
+            assert_eq!(game.get_step_count(), step_count);
+            assert_eq!(game.get_forgiveness_used(), 1);
+
+            game.toggle_flag(mines[1].0, mines[1].1).unwrap();
+            game.toggle_flag(mines[1].0, mines[1].1).unwrap();
+            assert_eq!(game.get_stats().flags_placed, 1);
+
+            game.click(mines[1].0, mines[1].1, false).unwrap();
+            assert_eq!(game.undo_explosion(), Err(MinesweeperError::NothingToUndo));
+        }
+
+        // difficulty presets
+        {
+            for difficulty in [
+                Difficulty::Beginner,
+                Difficulty::Intermediate,
+                Difficulty::Expert,
+            ] {
+                let game =
+                    Minesweeper::with_difficulty(difficulty, StdRng::seed_from_u64(0)).unwrap();
+                assert_eq!(
+                    game.get_height() * game.get_width(),
+                    difficulty.height() * difficulty.width()
+                );
+                assert_eq!(game.get_mine_count(), difficulty.mines());
+            }
+        }
+
+        // cells with many mines
+        {
+            assert_eq!(core::mem::size_of::<Cell>(), 4);
+
+            let rules = Rules {
+                max_mines_per_cell: MAX_MINES_PER_CELL,
+                ..Default::default()
+            };
+            let game = Minesweeper::from_layout(
+                3,
+                3,
+                &[MAX_MINES_PER_CELL; 9],
+                rules,
+                StdRng::seed_from_u64(0),
+            )
+            .unwrap();
+            assert_eq!(
+                usize::from(game.get(1, 1).mine_adjacent),
+                8 * MAX_MINES_PER_CELL
+            );
+
+            let rules = Rules {
+                max_mines_per_cell: MAX_MINES_PER_CELL + 1,
+                ..Default::default()
+            };
+            assert_eq!(
+                Minesweeper::with_rules(8, 8, 10, rules, StdRng::seed_from_u64(0)).unwrap_err(),
+                MinesweeperError::InvalidRules
+            );
+        }
+
+        // logic puzzles
+        {
+            let puzzle = Minesweeper::puzzle(6, 6, 5, 100, StdRng::seed_from_u64(0))
+                .unwrap()
+                .unwrap();
+            let mut solved = puzzle.game.clone();
+
+            for action in puzzle.solution {
+                solved.apply(action).unwrap();
+            }
+
+            assert_eq!(solved.get_game_status(), &Status::Win);
+            assert!(Minesweeper::puzzle(6, 6, 5, 0, StdRng::seed_from_u64(0))
+                .unwrap()
+                .is_none());
+        }
+
+        // reveal with a scratch buffer
+        {
+            // a buffer too small for the opening gives the same board as a plain click
+            let mut game = Minesweeper::new(16, 16, 10, StdRng::seed_from_u64(0)).unwrap();
+            let mut clicked = game.clone();
+
+            let revealed = game.reveal_with_buffer(8, 8, &mut [0; 2]).unwrap();
+            clicked.click(8, 8, false).unwrap();
+
+            assert!(revealed > 2);
+            assert_eq!(
+                (0..256)
+                    .filter(|idx| game.get(idx / 16, idx % 16).is_revealed)
+                    .count(),
+                revealed
+            );
+            assert!(game.diff(&clicked).cells.is_empty());
+            assert_eq!(
+                game.reveal_with_buffer(8, 8, &mut []),
+                Err(MinesweeperError::AlreadyRevealed)
+            );
+
+            // an empty buffer expands every cell right away
+            let mut game = Minesweeper::new(16, 16, 10, StdRng::seed_from_u64(0)).unwrap();
+            let mut clicked = game.clone();
+
+            let revealed = game.reveal_with_buffer(8, 8, &mut []).unwrap();
+            clicked.click(8, 8, false).unwrap();
+
+            assert_eq!(revealed, clicked.get_stats().max_revealed);
+            assert!(game.diff(&clicked).cells.is_empty());
+
+            // a cell unflagged next to an earlier opening is left alone by an overflowing reveal elsewhere, as by a plain click
+            let mines: Vec<usize> = (0..45).map(|idx| usize::from(idx % 9 == 4)).collect();
+            let mut game =
+                Minesweeper::from_layout(5, 9, &mines, Rules::default(), StdRng::seed_from_u64(0))
+                    .unwrap();
+
+            game.toggle_flag(2, 1).unwrap();
+            game.click(0, 0, false).unwrap();
+            game.toggle_flag(2, 1).unwrap();
+
+            let mut clicked = game.clone();
+
+            let revealed = game.reveal_with_buffer(0, 8, &mut [0; 1]).unwrap();
+            clicked.click(0, 8, false).unwrap();
+
+            assert_eq!(revealed, 20);
+            assert!(!game.get(2, 1).is_revealed);
+            assert!(game.diff(&clicked).cells.is_empty());
+        }
+
+        // reveal an area
+        {
+            let mut game = Minesweeper::new(8, 8, 10, StdRng::seed_from_u64(0)).unwrap();
+            let before = game.clone();
+            let revealed = game.reveal_area(0, 0, 8, 8).unwrap();
+
+            let diff = before.diff(&game);
+            assert!(diff.cells.len() >= revealed.revealed.len());
+            assert_eq!(diff.status.unwrap().0, Status::InProgress);
+
+            assert_eq!(revealed.revealed.len(), 54);
+            assert_eq!(revealed.exploded.len(), 10);
+            assert!(game.is_ended());
+            assert_eq!(game.get_step_count(), 1);
+        }
+
+        // win conditions
+        {
+            let rules = Rules {
+                win_condition: WinCondition::Reveal,
+                ..Default::default()
+            };
+
+            let mut game =
+                Minesweeper::with_rules(8, 8, 10, rules, StdRng::seed_from_u64(0)).unwrap();
+            game.click(4, 4, false).unwrap();
+
+            for idx in 0..64 {
+                if game.get(idx / 8, idx % 8).is_mine() {
+                    game.toggle_flag(idx / 8, idx % 8).unwrap();
+                }
+            }
+
+            assert!(!game.is_ended());
+        }
+
+        // minimum opening
+        {
+            let rules = Rules {
+                min_opening: 20,
+                ..Default::default()
+            };
+
+            let mut game =
+                Minesweeper::with_rules(9, 9, 10, rules, StdRng::seed_from_u64(0)).unwrap();
+            game.click(4, 4, false).unwrap();
+            assert!(game.get_stats().max_revealed >= 20);
+
+            let rules = Rules {
+                min_opening: 72,
+                ..Default::default()
+            };
+            assert!(matches!(
+                Minesweeper::with_rules(9, 9, 10, rules, StdRng::seed_from_u64(0)),
+                Err(MinesweeperError::OpeningTooLarge)
+            ));
+
+            // an opening of every safe cell on a dense board is allowed, but never found
+            let rules = Rules {
+                min_opening: 41,
+                ..Default::default()
+            };
+
+            let mut game =
+                Minesweeper::with_rules(9, 9, 40, rules, StdRng::seed_from_u64(0)).unwrap();
+            assert_eq!(
+                game.click(4, 4, false),
+                Err(MinesweeperError::OpeningTooLarge)
+            );
+            assert_eq!(game.get_step_count(), 0);
+        }
+
+        // boards without mines
+        {
+            // a board without mines is won from the start
+            let game = Minesweeper::new(4, 4, 0, StdRng::seed_from_u64(0)).unwrap();
+            assert_eq!(game.get_game_status(), &Status::Win);
+            assert_eq!(game.validate(), Ok(()));
+
+            let layout =
+                Minesweeper::from_layout(2, 2, &[0; 4], Rules::default(), StdRng::seed_from_u64(0))
+                    .unwrap();
+            assert_eq!(layout.get_game_status(), &Status::Win);
+            assert_eq!(layout.validate(), Ok(()));
+
+            // the state is validated on deserializing
+            let game = Minesweeper::new(4, 4, 0, Lcg(0)).unwrap();
+            let saved = serde_json::to_string(&game).unwrap();
+            let loaded: Minesweeper<Lcg> = serde_json::from_str(&saved).unwrap();
+            assert_eq!(loaded, game);
+        }
+    }
+}
@@ -0,0 +1,725 @@
+use super::Minesweeper;
+use crate::rng::GameRng;
+use alloc::{vec, vec::Vec};
+
+/// Maximum number of search nodes spent enumerating a single frontier group before falling back to sampling
+const EXACT_SEARCH_LIMIT: usize = 1 << 18;
+
+/// Number of random solutions drawn for a frontier group that is too large to enumerate
+const SAMPLE_COUNT: usize = 512;
+
+/// Maximum number of search nodes spent drawing a single sample
+const SAMPLE_SEARCH_LIMIT: usize = 1 << 12;
+
+impl<R: GameRng> Minesweeper<R> {
+    /// Compute the probability of each cell being a mine, using only the information visible to the player
+    ///
+    /// The returned `Vec` is indexed by `row * width + col`. Revealed cells have a probability of `0.0` (or `1.0` for revealed mines after an explosion). Flags are not trusted and are treated as unrevealed cells
+    ///
+    /// When cells may contain multiple mines, the probability is the one of containing at least one mine
+    ///
+    /// Unrevealed cells next to revealed numbers are split into independent groups. Each group is solved exactly by enumerating every mine layout consistent with the numbers, and the groups are then weighted against the cells far from any number using the total mine count. Groups with too many layouts to enumerate fall back to sampling random consistent layouts, so their probabilities are estimates
+    ///
+    /// # Examples
+    /// ```rust
+    /// # fn minesweeper() {
+    /// use gamie::minesweeper::Minesweeper;
+    /// use rand::rngs::ThreadRng;
+    ///
+    /// let mut game = Minesweeper::new(8, 8, 9, ThreadRng::default()).unwrap();
+    /// game.click(3, 3, false).unwrap();
+    ///
+    /// let probabilities = game.mine_probabilities();
+    /// assert_eq!(probabilities[3 * 8 + 3], 0.0);
+    /// # }
+    /// ```
+    pub fn mine_probabilities(&self) -> Vec<f64> {
+        self.analyze().probabilities
+    }
+
+    pub(super) fn analyze(&self) -> Analysis {
+        let mut probabilities = vec![0.0; self.height * self.width];
+        let mut is_exact = vec![true; self.height * self.width];
+        let mut remaining_mines = self.mine;
+        let max_mines = self.rules.max_mines_per_cell;
+
+        // index of each unrevealed cell in `vars` if it is on the frontier
+        let mut var_of_cell = vec![None; self.height * self.width];
+        let mut vars = Vec::new();
+        let mut constraints = Vec::new();
+
+        for (idx, cell) in self.board.iter().enumerate() {
+            if cell.is_revealed && cell.is_mine() {
+                probabilities[idx] = 1.0;
+                remaining_mines -= usize::from(cell.mines);
+            }
+        }
+
+        for (idx, cell) in self.board.iter().enumerate() {
+            if !cell.is_revealed || cell.is_mine() {
+                continue;
+            }
+
+            let mut constraint = Constraint {
+                vars: Vec::new(),
+                target: usize::from(cell.mine_adjacent),
+            };
+
+            for neighbor_idx in self.get_adjacent_cells(idx / self.width, idx % self.width) {
+                let neighbor = &self.board[neighbor_idx];
+
+                if !neighbor.is_revealed {
+                    let var = *var_of_cell[neighbor_idx].get_or_insert_with(|| {
+                        vars.push(neighbor_idx);
+                        vars.len() - 1
+                    });
+
+                    constraint.vars.push(var);
+                } else {
+                    constraint.target -= usize::from(neighbor.mines);
+                }
+            }
+
+            if !constraint.vars.is_empty() {
+                constraints.push(constraint);
+            }
+        }
+
+        let interior_count = self
+            .board
+            .iter()
+            .enumerate()
+            .filter(|(idx, cell)| !cell.is_revealed && var_of_cell[*idx].is_none())
+            .count();
+
+        let mut rng = XorShift(0x9e37_79b9_7f4a_7c15 ^ (self.step_count as u64));
+
+        let split = split_groups(vars.len(), &constraints);
+
+        // index of each frontier cell within its group
+        let mut local_of_var = vec![0; vars.len()];
+        for (group_vars, _) in &split {
+            for (local, var) in group_vars.iter().enumerate() {
+                local_of_var[*var] = local;
+            }
+        }
+
+        let mut groups = split
+            .into_iter()
+            .map(|(group_vars, group_constraints)| {
+                let search = Search::new(
+                    group_vars.len(),
+                    max_mines,
+                    &group_constraints,
+                    &constraints,
+                    &local_of_var,
+                );
+
+                Group::solve(group_vars, search, &mut rng)
+            })
+            .collect::<Vec<_>>();
+
+        let center = groups.iter().map(Group::center).sum();
+        let (ratio, weights) = interior_weights(
+            remaining_mines,
+            interior_count * max_mines,
+            vars.len() * max_mines,
+            center,
+        );
+
+        for group in &mut groups {
+            group.tilt(ratio);
+        }
+
+        let mut layout_weights = Vec::with_capacity(groups.len());
+        collect_layout_weights(&groups, weights.clone(), &mut layout_weights);
+
+        let is_all_exact = groups.iter().all(|group| group.is_exact);
+
+        for (group, layout_weights) in groups.iter().zip(&layout_weights) {
+            let total = group
+                .counts
+                .iter()
+                .enumerate()
+                .map(|(k, count)| count * layout_weights.get(k))
+                .sum::<f64>();
+
+            if total > 0.0 {
+                for (local, var) in group.vars.iter().enumerate() {
+                    let mines = group
+                        .mine_counts
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, mine_counts)| !mine_counts.is_empty())
+                        .map(|(k, mine_counts)| mine_counts[local] * layout_weights.get(k))
+                        .sum::<f64>();
+
+                    probabilities[vars[*var]] = mines / total;
+
+                    // a cell that is safe or a mine in every layout stays so regardless of the other groups
+                    let is_settled =
+                        group
+                            .mine_counts
+                            .iter()
+                            .zip(&group.counts)
+                            .all(|(mine_counts, count)| {
+                                mine_counts.is_empty()
+                                    || mine_counts[local] == 0.0
+                                    || mine_counts[local] == *count
+                            });
+
+                    is_exact[vars[*var]] = group.is_exact && (is_all_exact || is_settled);
+                }
+            }
+        }
+
+        if interior_count > 0 {
+            let all = distribution(&groups);
+
+            let slots = interior_count * max_mines;
+
+            let (mines, total) =
+                all.values
+                    .iter()
+                    .enumerate()
+                    .fold((0.0, 0.0), |(mines, total), (idx, count)| {
+                        let t = all.offset + idx;
+                        let weight = count * weights.get(t);
+                        let interior_mines = remaining_mines.saturating_sub(t);
+
+                        // chance that none of the cell's slots hold one of the interior mines
+                        let empty = (0..max_mines)
+                            .map(|slot| {
+                                slots.saturating_sub(interior_mines + slot) as f64
+                                    / (slots - slot) as f64
+                            })
+                            .product::<f64>();
+
+                        (mines + weight * (1.0 - empty), total + weight)
+                    });
+
+            if total > 0.0 {
+                let probability = mines / total;
+
+                for (idx, cell) in self.board.iter().enumerate() {
+                    if !cell.is_revealed && var_of_cell[idx].is_none() {
+                        probabilities[idx] = probability;
+                        is_exact[idx] = is_all_exact;
+                    }
+                }
+            }
+        }
+
+        Analysis {
+            probabilities,
+            is_exact,
+        }
+    }
+}
+
+/// Mine probabilities of the cells, along with whether each of them is exact or estimated from sampling
+pub(super) struct Analysis {
+    pub(super) probabilities: Vec<f64>,
+    pub(super) is_exact: Vec<bool>,
+}
+
+/// A revealed number: `vars` contain exactly `target` mines in total
+struct Constraint {
+    vars: Vec<usize>,
+    target: usize,
+}
+
+/// Frontier cells that share constraints, along with their solved layouts
+struct Group {
+    vars: Vec<usize>,
+    /// whether the layouts were enumerated rather than sampled
+    is_exact: bool,
+    /// `counts[k]` is the (relative) number of layouts with `k` mines
+    ///
+    /// A cell holding `v` mines out of `max_mines` slots counts as `C(max_mines, v)` layouts
+    counts: Vec<f64>,
+    /// `mine_counts[k][i]` is the (relative) number of layouts with `k` mines where `vars[i]` is a mine
+    mine_counts: Vec<Vec<f64>>,
+    max_mines: usize,
+}
+
+impl Group {
+    fn solve(vars: Vec<usize>, mut search: Search, rng: &mut XorShift) -> Self {
+        let max_mines = search.max_value;
+
+        let mut group = Self {
+            counts: vec![0.0; vars.len() * max_mines + 1],
+            mine_counts: vec![Vec::new(); vars.len() * max_mines + 1],
+            vars,
+            is_exact: true,
+            max_mines,
+        };
+
+        let is_exhausted = search.run(
+            EXACT_SEARCH_LIMIT,
+            || 0,
+            |values| {
+                group.record(values);
+                true
+            },
+        );
+
+        if !is_exhausted {
+            group.is_exact = false;
+            group.counts.iter_mut().for_each(|count| *count = 0.0);
+            group.mine_counts.iter_mut().for_each(Vec::clear);
+
+            for _ in 0..SAMPLE_COUNT {
+                search.reset();
+                search.run(
+                    SAMPLE_SEARCH_LIMIT,
+                    || rng.next_below(max_mines + 1),
+                    |values| {
+                        group.record(values);
+                        false
+                    },
+                );
+            }
+        }
+
+        group
+    }
+
+    /// Middle of the range of mine counts this group can have
+    fn center(&self) -> usize {
+        let min = self.counts.iter().position(|count| *count > 0.0);
+        let max = self.counts.iter().rposition(|count| *count > 0.0);
+
+        match (min, max) {
+            (Some(min), Some(max)) => (min + max) / 2,
+            _ => 0,
+        }
+    }
+
+    /// Multiply the layouts with `k` mines by `ratio^k`, normalizing the largest count to `1.0`
+    ///
+    /// This moves the geometric part of the interior weights into the groups, so the remaining weights stay in range of `f64`
+    fn tilt(&mut self, ratio: f64) {
+        let min = self
+            .counts
+            .iter()
+            .position(|count| *count > 0.0)
+            .unwrap_or(0);
+        let mut factor = 1.0;
+
+        for (count, mine_counts) in self.counts[min..]
+            .iter_mut()
+            .zip(&mut self.mine_counts[min..])
+        {
+            *count *= factor;
+            mine_counts.iter_mut().for_each(|value| *value *= factor);
+            factor *= ratio;
+        }
+
+        let max = self.counts.iter().copied().fold(0.0, f64::max);
+        if max > 0.0 {
+            for (count, mine_counts) in self.counts.iter_mut().zip(&mut self.mine_counts) {
+                *count /= max;
+                mine_counts.iter_mut().for_each(|value| *value /= max);
+            }
+        }
+    }
+
+    fn record(&mut self, values: &[usize]) {
+        let k = values.iter().sum::<usize>();
+        let weight = values
+            .iter()
+            .map(|value| binomial(self.max_mines, *value))
+            .product::<f64>();
+
+        self.counts[k] += weight;
+
+        let mine_counts = &mut self.mine_counts[k];
+        if mine_counts.is_empty() {
+            mine_counts.resize(values.len(), 0.0);
+        }
+
+        for (mine_count, value) in mine_counts.iter_mut().zip(values) {
+            if *value > 0 {
+                *mine_count += weight;
+            }
+        }
+    }
+}
+
+/// Backtracking search over the layouts of a group
+struct Search {
+    /// constraints of each variable, indexed into `targets` / `sums` / `unassigned`
+    var_constraints: Vec<Vec<usize>>,
+    targets: Vec<usize>,
+    sums: Vec<usize>,
+    unassigned: Vec<usize>,
+    max_value: usize,
+    values: Vec<usize>,
+    tried: Vec<usize>,
+    /// variables before `depth` are assigned
+    depth: usize,
+}
+
+impl Search {
+    fn new(
+        var_count: usize,
+        max_value: usize,
+        group_constraints: &[usize],
+        constraints: &[Constraint],
+        local_of_var: &[usize],
+    ) -> Self {
+        let mut var_constraints = vec![Vec::new(); var_count];
+
+        for (local_constraint, constraint) in group_constraints.iter().enumerate() {
+            for var in &constraints[*constraint].vars {
+                var_constraints[local_of_var[*var]].push(local_constraint);
+            }
+        }
+
+        Self {
+            var_constraints,
+            targets: group_constraints
+                .iter()
+                .map(|constraint| constraints[*constraint].target)
+                .collect(),
+            sums: vec![0; group_constraints.len()],
+            unassigned: group_constraints
+                .iter()
+                .map(|constraint| constraints[*constraint].vars.len())
+                .collect(),
+            max_value,
+            values: vec![0; var_count],
+            tried: vec![0; var_count],
+            depth: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        while self.depth > 0 {
+            self.depth -= 1;
+            self.unassign(self.depth);
+        }
+
+        self.tried.iter_mut().for_each(|tried| *tried = 0);
+    }
+
+    /// Walk through the layouts, calling `on_solution` for each until it returns `false`
+    ///
+    /// `first_value` picks which value a variable tries first. Return `true` if the search space is exhausted within `limit` nodes
+    fn run(
+        &mut self,
+        limit: usize,
+        mut first_value: impl FnMut() -> usize,
+        mut on_solution: impl FnMut(&[usize]) -> bool,
+    ) -> bool {
+        let var_count = self.values.len();
+        let mut first_values = vec![0; var_count];
+        let mut nodes = 0;
+
+        loop {
+            if self.depth == var_count {
+                if !on_solution(&self.values) {
+                    return false;
+                }
+
+                self.depth -= 1;
+                self.unassign(self.depth);
+                continue;
+            }
+
+            if self.tried[self.depth] > self.max_value {
+                self.tried[self.depth] = 0;
+
+                if self.depth == 0 {
+                    return true;
+                }
+
+                self.depth -= 1;
+                self.unassign(self.depth);
+                continue;
+            }
+
+            if self.tried[self.depth] == 0 {
+                first_values[self.depth] = first_value();
+            }
+
+            nodes += 1;
+            if nodes > limit {
+                return false;
+            }
+
+            let value = (first_values[self.depth] + self.tried[self.depth]) % (self.max_value + 1);
+            self.tried[self.depth] += 1;
+
+            if self.assign(self.depth, value) {
+                self.depth += 1;
+            } else {
+                self.unassign(self.depth);
+            }
+        }
+    }
+
+    fn assign(&mut self, local: usize, value: usize) -> bool {
+        self.values[local] = value;
+
+        let mut is_feasible = true;
+
+        for constraint in &self.var_constraints[local] {
+            self.sums[*constraint] += value;
+            self.unassigned[*constraint] -= 1;
+
+            let sum = self.sums[*constraint];
+            let target = self.targets[*constraint];
+            is_feasible &=
+                sum <= target && sum + self.unassigned[*constraint] * self.max_value >= target;
+        }
+
+        is_feasible
+    }
+
+    fn unassign(&mut self, local: usize) {
+        for constraint in &self.var_constraints[local] {
+            self.sums[*constraint] -= self.values[local];
+            self.unassigned[*constraint] += 1;
+        }
+    }
+}
+
+/// Split the frontier into groups of variables connected through constraints, in breadth-first order
+///
+/// Each group is returned along with the indices of its constraints
+fn split_groups(var_count: usize, constraints: &[Constraint]) -> Vec<(Vec<usize>, Vec<usize>)> {
+    let mut var_constraints = vec![Vec::new(); var_count];
+    for (idx, constraint) in constraints.iter().enumerate() {
+        for var in &constraint.vars {
+            var_constraints[*var].push(idx);
+        }
+    }
+
+    let mut is_visited = vec![false; var_count];
+    let mut is_constraint_visited = vec![false; constraints.len()];
+    let mut groups = Vec::new();
+
+    for start in 0..var_count {
+        if is_visited[start] {
+            continue;
+        }
+
+        is_visited[start] = true;
+        let mut group = vec![start];
+        let mut group_constraints = Vec::new();
+        let mut head = 0;
+
+        while let Some(var) = group.get(head).copied() {
+            head += 1;
+
+            for constraint in &var_constraints[var] {
+                if !is_constraint_visited[*constraint] {
+                    is_constraint_visited[*constraint] = true;
+                    group_constraints.push(*constraint);
+                }
+
+                for neighbor in &constraints[*constraint].vars {
+                    if !is_visited[*neighbor] {
+                        is_visited[*neighbor] = true;
+                        group.push(*neighbor);
+                    }
+                }
+            }
+        }
+
+        groups.push((group, group_constraints));
+    }
+
+    groups
+}
+
+/// Relative weights indexed by mine count, starting from `offset`
+#[derive(Clone)]
+struct Distribution {
+    offset: usize,
+    values: Vec<f64>,
+}
+
+impl Distribution {
+    /// Build a distribution, dropping zero weights on both ends and normalizing the largest weight to `1.0`
+    fn new(mut offset: usize, mut values: Vec<f64>) -> Self {
+        let leading = values.iter().take_while(|value| **value == 0.0).count();
+        let trailing = values[leading..]
+            .iter()
+            .rev()
+            .take_while(|value| **value == 0.0)
+            .count();
+
+        values.truncate(values.len() - trailing);
+        values.drain(..leading);
+        offset += leading;
+
+        let max = values.iter().copied().fold(0.0, f64::max);
+        if max > 0.0 {
+            values.iter_mut().for_each(|value| *value /= max);
+        }
+
+        Self { offset, values }
+    }
+
+    fn get(&self, k: usize) -> f64 {
+        k.checked_sub(self.offset)
+            .and_then(|idx| self.values.get(idx))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    fn convolve(&self, other: &Self) -> Self {
+        let mut values = vec![0.0; (self.values.len() + other.values.len()).saturating_sub(1)];
+
+        for (i, x) in self.values.iter().enumerate() {
+            for (j, y) in other.values.iter().enumerate() {
+                values[i + j] += x * y;
+            }
+        }
+
+        Self::new(self.offset + other.offset, values)
+    }
+}
+
+/// Mine count distribution of the layouts of all `groups` combined
+fn distribution(groups: &[Group]) -> Distribution {
+    groups.iter().fold(
+        Distribution {
+            offset: 0,
+            values: vec![1.0],
+        },
+        |distribution, group| distribution.convolve(&Distribution::new(0, group.counts.clone())),
+    )
+}
+
+/// For each group, compute the weight of its layouts by mine count, summed over the layouts of every other group and the interior
+///
+/// `outside[s]` is the weight of the layouts of `groups` having `s` mines in total. Splitting the groups in halves keeps this quadratic in the frontier size rather than in the number of groups times the frontier size
+fn collect_layout_weights(groups: &[Group], outside: Distribution, result: &mut Vec<Distribution>) {
+    match groups.len() {
+        0 => {}
+        1 => result.push(outside),
+        len => {
+            let (left, right) = groups.split_at(len / 2);
+            let left_distribution = distribution(left);
+            let right_distribution = distribution(right);
+
+            let left_outside = correlate(&outside, &right_distribution, &left_distribution);
+            let right_outside = correlate(&outside, &left_distribution, &right_distribution);
+
+            collect_layout_weights(left, left_outside, result);
+            collect_layout_weights(right, right_outside, result);
+        }
+    }
+}
+
+/// Sum `outside[s + u] * other[u]` over `u`, for every `s` in the range of `target`
+fn correlate(outside: &Distribution, other: &Distribution, target: &Distribution) -> Distribution {
+    let values = (0..target.values.len())
+        .map(|idx| {
+            let s = target.offset + idx;
+
+            other
+                .values
+                .iter()
+                .enumerate()
+                .map(|(u, weight)| weight * outside.get(s + other.offset + u))
+                .sum()
+        })
+        .collect();
+
+    Distribution::new(target.offset, values)
+}
+
+/// Relative number of ways to place the remaining mines in the interior slots, indexed by the number of mines on the frontier
+///
+/// The weights are `C(n, m - t)`, which shrink roughly geometrically in `t` and quickly leave the range of `f64`. They are split into a geometric ratio around `center`, to be applied to the groups with [`Group::tilt`], and the remaining weights, which peak at `center`
+fn interior_weights(
+    mines: usize,
+    interior_slots: usize,
+    frontier_slots: usize,
+    center: usize,
+) -> (f64, Distribution) {
+    let min = mines.saturating_sub(interior_slots);
+    let max = mines.min(frontier_slots);
+
+    if min > max {
+        let empty = Distribution {
+            offset: 0,
+            values: Vec::new(),
+        };
+
+        return (1.0, empty);
+    }
+
+    // C(n, m - t - 1) / C(n, m - t) = (m - t) / (n - m + t + 1)
+    let step = |t: usize| (mines - t) as f64 / (interior_slots + t + 1 - mines) as f64;
+
+    if min == max {
+        return (1.0, Distribution::new(min, vec![1.0]));
+    }
+
+    let center = center.clamp(min, max - 1);
+    let ratio = step(center);
+
+    let mut values = vec![0.0; max - min + 1];
+    values[center - min] = 1.0;
+
+    for t in center..max {
+        values[t + 1 - min] = values[t - min] * step(t) / ratio;
+    }
+
+    for t in (min..center).rev() {
+        values[t - min] = values[t + 1 - min] * ratio / step(t);
+    }
+
+    (ratio, Distribution::new(min, values))
+}
+
+/// Small deterministic generator for sampling, so the analysis does not touch the game's RNG
+struct XorShift(u64);
+
+impl XorShift {
+    fn next_below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |result, i| result * (n - i) as f64 / (i + 1) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::minesweeper::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test() {
+        let mut game = Minesweeper::new(16, 16, 40, StdRng::seed_from_u64(0)).unwrap();
+
+        let probabilities = game.mine_probabilities();
+        assert!(probabilities
+            .iter()
+            .all(|probability| (probability - 40.0 / 256.0).abs() < 1e-9));
+
+        game.click(8, 8, false).unwrap();
+
+        let probabilities = game.mine_probabilities();
+        assert!((probabilities.iter().sum::<f64>() - 40.0).abs() < 1e-6);
+
+        for row in 0..16 {
+            for col in 0..16 {
+                if game.get(row, col).is_revealed {
+                    assert_eq!(probabilities[row * 16 + col], 0.0);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use super::{Action, Minesweeper, MinesweeperError, Status};
+use crate::rng::GameRng;
+use alloc::vec::Vec;
+
+/// A logic puzzle, which is a game with a revealed region the rest of the board can be deduced from, see [`Minesweeper::puzzle`]
+#[derive(Clone, Debug)]
+pub struct Puzzle<R> {
+    /// The game right after the opening click, with the revealed region
+    pub game: Minesweeper<R>,
+    /// The deductions solving the game from the revealed region, in order, as played by [`Minesweeper::auto_play`]
+    pub solution: Vec<Action>,
+}
+
+impl<R: GameRng + Clone> Minesweeper<R> {
+    /// Generate a logic puzzle, a board solvable by deduction alone from the region revealed by clicking its center
+    ///
+    /// Every step of the solution is certain, so the mine layout is the only one consistent with the revealed region and the mine count, and no guess is ever needed. Small boards with few mines are found quickly, as most random layouts of a large board need a guess somewhere
+    ///
+    /// Up to `max_attempts` random layouts are tried. Return `Ok(None)` if none of them makes a puzzle
+    ///
+    /// Return `Err(MinesweeperError::TooManyMines)` if `(height - 1) * (width - 1) < mines`
+    ///
+    /// # Examples
+    /// ```rust
+    /// # fn minesweeper() {
+    /// use gamie::minesweeper::Minesweeper;
+    /// use rand::rngs::ThreadRng;
+    ///
+    /// let puzzle = Minesweeper::puzzle(6, 6, 5, 100, ThreadRng::default())
+    ///     .unwrap()
+    ///     .unwrap();
+    ///
+    /// let mut game = puzzle.game.clone();
+    ///
+    /// for action in puzzle.solution {
+    ///     game.apply(action).unwrap();
+    /// }
+    ///
+    /// assert!(game.is_ended());
+    /// # }
+    /// ```
+    pub fn puzzle(
+        height: usize,
+        width: usize,
+        mines: usize,
+        max_attempts: usize,
+        rng: R,
+    ) -> Result<Option<Puzzle<R>>, MinesweeperError> {
+        let mut game = Self::new(height, width, mines, rng)?;
+        let (row, col) = (height / 2, width / 2);
+
+        for _ in 0..max_attempts {
+            game.randomize();
+
+            // only an opening without adjacent mines reveals a region, and keeps the layout from being redrawn
+            let cell = game.board[row * width + col];
+
+            if cell.is_mine() || cell.mine_adjacent > 0 {
+                continue;
+            }
+
+            let mut opened = game.clone();
+            opened.click(row, col, false)?;
+
+            let mut solved = opened.clone();
+            let solution = solved.auto_play(false);
+
+            if solved.status == Status::Win {
+                return Ok(Some(Puzzle {
+                    game: opened,
+                    solution,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,258 @@
+//! Minesweeper replay
+//!
+//! Check struct [`Replay`] for more information
+
+use super::{Action, Minesweeper, MinesweeperError, Rules, Status};
+use crate::rng::GameRng;
+use alloc::{string::String, vec::Vec};
+use snafu::Snafu;
+
+/// A recorded Minesweeper game, which can be replayed action by action
+///
+/// The mine layout is recorded rather than the random number generator seed, as the generator is provided by the caller and may not be seedable. Replaying is fully deterministic, so a replay can be re-validated against the claimed result with [`Replay::verify`]
+///
+/// # Examples
+///
+/// ```rust
+/// # fn minesweeper() {
+/// use gamie::minesweeper::Minesweeper;
+/// use rand::rngs::ThreadRng;
+///
+/// let mut game = Minesweeper::new(8, 8, 9, ThreadRng::default()).unwrap();
+/// game.auto_play(true);
+///
+/// let replay = game.export_replay();
+/// let replayed = replay.verify(ThreadRng::default()).unwrap();
+///
+/// assert_eq!(replayed.get_game_status(), game.get_game_status());
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Replay {
+    pub height: usize,
+    pub width: usize,
+    pub rules: Rules,
+    /// The number of mines in each cell, in row-major order
+    pub mines: Vec<usize>,
+    /// The actions taken, in order
+    pub actions: Vec<Action>,
+    /// The claimed game status after all actions are applied
+    pub status: Status,
+    /// Analysis attached to the actions as `(index, annotation)`, ordered by the index of the action
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub annotations: Vec<(usize, Annotation)>,
+}
+
+/// Analysis attached to an action of a [`Replay`], e.g. by a player reviewing the game or an engine
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Annotation {
+    /// An evaluation of the board after the action, in a scale chosen by the caller
+    pub score: Option<i32>,
+    pub comment: Option<String>,
+    /// Whether the action is considered a mistake
+    pub is_blunder: bool,
+}
+
+/// Errors that can occur when verifying a replay
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum ReplayError {
+    #[snafu(display("Invalid board: {source}"))]
+    InvalidBoard { source: MinesweeperError },
+    #[snafu(display("Action {index} is out of bounds"))]
+    OutOfBounds { index: usize },
+    #[snafu(display("Action {index} is rejected: {source}"))]
+    InvalidAction {
+        index: usize,
+        source: MinesweeperError,
+    },
+    #[snafu(display("The game status does not match the claimed one"))]
+    StatusMismatch,
+    #[snafu(display("Action {index} does not exist"))]
+    NoSuchAction { index: usize },
+}
+
+crate::code::codes!(ReplayError {
+    InvalidBoard => "invalid_board",
+    OutOfBounds => "out_of_bounds",
+    InvalidAction => "invalid_action",
+    StatusMismatch => "status_mismatch",
+    NoSuchAction => "no_such_action",
+});
+
+impl<R: GameRng> Minesweeper<R> {
+    /// Export the game played so far as a [`Replay`]
+    ///
+    /// The mine layout is fixed by the first click, so a game should be exported after it
+    pub fn export_replay(&self) -> Replay {
+        Replay {
+            height: self.height,
+            width: self.width,
+            rules: self.rules,
+            mines: self
+                .board
+                .iter()
+                .map(|cell| usize::from(cell.mines))
+                .collect(),
+            actions: self.history.clone(),
+            status: self.status.clone(),
+            annotations: Vec::new(),
+        }
+    }
+}
+
+impl Replay {
+    /// Create the game board the replay starts from
+    ///
+    /// Apply [`Replay::actions`] with [`Minesweeper::apply`] to step through the game. The random number generator is never used
+    pub fn start<R: GameRng>(&self, rng: R) -> Result<Minesweeper<R>, ReplayError> {
+        Minesweeper::from_layout(self.height, self.width, &self.mines, self.rules, rng)
+            .map_err(|source| ReplayError::InvalidBoard { source })
+    }
+
+    /// Attach an annotation to an action, replacing the one already attached
+    ///
+    /// Return `Err(ReplayError::NoSuchAction)` if `index` is not the index of an action
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn minesweeper() {
+    /// use gamie::minesweeper::{replay::Annotation, Minesweeper};
+    /// use rand::rngs::ThreadRng;
+    ///
+    /// let mut game = Minesweeper::new(8, 8, 9, ThreadRng::default()).unwrap();
+    /// game.click(4, 4, false).unwrap();
+    ///
+    /// let mut replay = game.export_replay();
+    /// let annotation = Annotation {
+    ///     comment: Some("Opened from the center".to_string()),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// replay.annotate(0, annotation.clone()).unwrap();
+    /// assert_eq!(replay.annotation(0), Some(&annotation));
+    /// # }
+    /// ```
+    pub fn annotate(&mut self, index: usize, annotation: Annotation) -> Result<(), ReplayError> {
+        if index >= self.actions.len() {
+            return Err(ReplayError::NoSuchAction { index });
+        }
+
+        match self
+            .annotations
+            .binary_search_by_key(&index, |(index, _)| *index)
+        {
+            Ok(position) => self.annotations[position].1 = annotation,
+            Err(position) => self.annotations.insert(position, (index, annotation)),
+        }
+
+        Ok(())
+    }
+
+    /// Get the annotation attached to an action
+    pub fn annotation(&self, index: usize) -> Option<&Annotation> {
+        self.annotations
+            .iter()
+            .find(|(annotated, _)| *annotated == index)
+            .map(|(_, annotation)| annotation)
+    }
+
+    /// Replay all actions and check the result against the claimed game status
+    ///
+    /// Annotations must be attached to existing actions, but are otherwise not checked
+    ///
+    /// Return the game board after the last action. The random number generator is never used
+    pub fn verify<R: GameRng>(&self, rng: R) -> Result<Minesweeper<R>, ReplayError> {
+        if let Some((index, _)) = self
+            .annotations
+            .iter()
+            .find(|(index, _)| *index >= self.actions.len())
+        {
+            return Err(ReplayError::NoSuchAction { index: *index });
+        }
+
+        let mut game = self.start(rng)?;
+
+        for (index, action) in self.actions.iter().enumerate() {
+            let is_out_of_bounds = match action {
+                Action::Click { row, col, .. }
+                | Action::Chord { row, col, .. }
+                | Action::Flag { row, col } => *row >= self.height || *col >= self.width,
+                Action::Reveal { cells } => cells
+                    .iter()
+                    .any(|(row, col)| *row >= self.height || *col >= self.width),
+                Action::UndoExplosion => false,
+            };
+
+            if is_out_of_bounds {
+                return Err(ReplayError::OutOfBounds { index });
+            }
+
+            game.apply(action.clone())
+                .map_err(|source| ReplayError::InvalidAction { index, source })?;
+        }
+
+        if game.status != self.status {
+            return Err(ReplayError::StatusMismatch);
+        }
+
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::minesweeper::{replay::*, Minesweeper};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test() {
+        let mut game = Minesweeper::new(16, 16, 40, StdRng::seed_from_u64(0)).unwrap();
+        game.auto_play(true);
+
+        let replay = game.export_replay();
+        assert_eq!(replay.actions, game.get_history());
+
+        let replayed = replay.verify(StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(replayed.get_game_status(), game.get_game_status());
+        assert_eq!(replayed.get_step_count(), game.get_step_count());
+
+        let mut forged = replay.clone();
+        forged.status = match replay.status {
+            Status::Win => Status::InProgress,
+            _ => Status::Win,
+        };
+        assert_eq!(
+            forged.verify(StdRng::seed_from_u64(1)).unwrap_err(),
+            ReplayError::StatusMismatch
+        );
+
+        let mut annotated = replay.clone();
+        let blunder = Annotation {
+            score: Some(-100),
+            is_blunder: true,
+            ..Default::default()
+        };
+
+        annotated.annotate(0, Annotation::default()).unwrap();
+        annotated.annotate(0, blunder.clone()).unwrap();
+        assert_eq!(annotated.annotations, [(0, blunder)]);
+        assert!(annotated.verify(StdRng::seed_from_u64(1)).is_ok());
+
+        let index = replay.actions.len();
+        assert_eq!(
+            annotated.annotate(index, Annotation::default()),
+            Err(ReplayError::NoSuchAction { index })
+        );
+
+        let mut forged = replay;
+        forged.actions.push(Action::Flag { row: 16, col: 0 });
+        assert!(forged.verify(StdRng::seed_from_u64(1)).is_err());
+    }
+}
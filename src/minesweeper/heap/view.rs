@@ -0,0 +1,121 @@
+//! Redacted view of a Minesweeper game
+//!
+//! Check struct [`RedactedView`] for more information
+
+use super::{Minesweeper, Status};
+use crate::rng::GameRng;
+use alloc::vec::Vec;
+
+/// A view of a Minesweeper game with hidden information stripped, safe to be sent to players and spectators
+///
+/// Unrevealed cells only expose their flags, so the mine layout cannot be recovered from the view
+///
+/// # Examples
+///
+/// ```rust
+/// # fn minesweeper() {
+/// use gamie::minesweeper::{view::RedactedCell, Minesweeper};
+/// use rand::rngs::ThreadRng;
+///
+/// let mut game = Minesweeper::new(8, 8, 9, ThreadRng::default()).unwrap();
+/// game.click(4, 4, false).unwrap();
+///
+/// let view = game.redacted_view();
+/// assert!(matches!(view.get(4, 4), RedactedCell::Revealed { .. }));
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RedactedView {
+    pub height: usize,
+    pub width: usize,
+    /// The cells, in row-major order
+    pub cells: Vec<RedactedCell>,
+    pub mine_count: usize,
+    pub flag_count: usize,
+    pub step_count: usize,
+    pub status: Status,
+}
+
+/// A cell as seen from a [`RedactedView`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RedactedCell {
+    /// An unrevealed cell and the flags on it
+    Hidden { flags: u8 },
+    /// A revealed safe cell and the total number of mines in its adjacent cells
+    Revealed { mine_adjacent: u8 },
+    /// A revealed mine, which only appears when the game exploded
+    Mine { mines: u8 },
+}
+
+impl<R: GameRng> Minesweeper<R> {
+    /// Get a view of the game with the mine layout stripped, see [`RedactedView`]
+    pub fn redacted_view(&self) -> RedactedView {
+        let cells = self
+            .board
+            .iter()
+            .map(|cell| {
+                if !cell.is_revealed {
+                    RedactedCell::Hidden { flags: cell.flags }
+                } else if cell.is_mine() {
+                    RedactedCell::Mine { mines: cell.mines }
+                } else {
+                    RedactedCell::Revealed {
+                        mine_adjacent: cell.mine_adjacent,
+                    }
+                }
+            })
+            .collect();
+
+        RedactedView {
+            height: self.height,
+            width: self.width,
+            cells,
+            mine_count: self.mine,
+            flag_count: self.flag_count,
+            step_count: self.step_count,
+            status: self.status.clone(),
+        }
+    }
+}
+
+impl RedactedView {
+    /// Get a cell from the view
+    ///
+    /// Panic when target position out of bounds
+    pub fn get(&self, row: usize, col: usize) -> RedactedCell {
+        assert!(row < self.height);
+        assert!(col < self.width);
+
+        self.cells[row * self.width + col]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::minesweeper::{view::*, Minesweeper};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test() {
+        let mut game = Minesweeper::new(8, 8, 10, StdRng::seed_from_u64(0)).unwrap();
+        game.click(4, 4, false).unwrap();
+
+        let view = game.redacted_view();
+
+        for (idx, cell) in view.cells.iter().enumerate() {
+            let original = game.get(idx / 8, idx % 8);
+
+            match cell {
+                RedactedCell::Hidden { .. } => assert!(!original.is_revealed),
+                RedactedCell::Revealed { mine_adjacent } => {
+                    assert_eq!(*mine_adjacent, original.mine_adjacent)
+                }
+                RedactedCell::Mine { .. } => unreachable!(),
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Minesweeper
+//!
+//! Check struct [`Minesweeper`](https://docs.rs/gamie/*/gamie/minesweeper/struct.Minesweeper.html) for more information
+//!
+//! # Examples
+//!
+//! ```rust
+//! # fn minesweeper() {
+//! use gamie::minesweeper::Minesweeper;
+//! use rand::rngs::ThreadRng;
+//!
+//! let mut game = Minesweeper::new(8, 8, 9, ThreadRng::default()).unwrap();
+//!
+//! game.toggle_flag(3, 2).unwrap();
+//! // ...
+//! game.click(7, 7, true).unwrap();
+//! // ...
+//! # }
+//! ```
+
+#[cfg(feature = "minesweeper")]
+mod heap;
+
+pub mod fixed;
+
+mod common;
+
+#[cfg(feature = "minesweeper")]
+pub use self::heap::*;
+pub use self::{
+    common::{Cell, MinesweeperError, MAX_OPENING_ATTEMPTS},
+    fixed::FixedGame,
+};
@@ -0,0 +1,473 @@
+//! Helpers for driving any game by name and text commands, e.g. from a terminal frontend or a chat bot
+//!
+//! Check enum [`Game`] for more information
+
+use crate::Error;
+use snafu::Snafu;
+
+/// A game of any module with a fixed setup, created by its name and played by text commands
+///
+/// A command is a move read by `parse_move()` of the game, see [`parse`](crate::parse), `drop_three` is played with the default rules, and `sos` on an 8x8 board
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "tictactoe")]
+/// # fn tictactoe() {
+/// use gamie::orchestration::{Game, Status};
+///
+/// let mut game = Game::new("Tic-Tac-Toe").unwrap();
+///
+/// for command in ["0 0", "1 0", "0,1", "1, 1", "0 2"] {
+///     game.play(command).unwrap();
+/// }
+///
+/// assert_eq!(game.name(), "tictactoe");
+/// assert_eq!(game.status(), Status::Win(0));
+/// # }
+/// # #[cfg(feature = "tictactoe")]
+/// # tictactoe();
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Game {
+    #[cfg(feature = "connect_four")]
+    ConnectFour(crate::connect_four::ConnectFour),
+    #[cfg(feature = "connect6")]
+    Connect6(crate::connect6::Connect6),
+    #[cfg(feature = "drop_three")]
+    DropThree(crate::drop_three::DropThree),
+    #[cfg(feature = "fox_and_geese")]
+    FoxAndGeese(crate::fox_and_geese::FoxAndGeese),
+    #[cfg(feature = "gomoku")]
+    Gomoku(crate::gomoku::Gomoku),
+    #[cfg(feature = "hasami_shogi")]
+    HasamiShogi(crate::hasami_shogi::HasamiShogi),
+    #[cfg(feature = "order_and_chaos")]
+    OrderAndChaos(crate::order_and_chaos::OrderAndChaos),
+    #[cfg(feature = "pente")]
+    Pente(crate::pente::Pente),
+    #[cfg(feature = "reversi")]
+    Reversi(crate::reversi::Reversi),
+    #[cfg(feature = "score_four")]
+    ScoreFour(crate::score_four::ScoreFour),
+    #[cfg(feature = "sos")]
+    Sos(crate::sos::Sos),
+    #[cfg(feature = "tictactoe")]
+    TicTacToe(crate::tictactoe::TicTacToe),
+}
+
+/// The status of a game, with players as their index in the order of the player enum of the game
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Draw,
+    Win(usize),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+/// Errors that can occur when creating a game by name
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum OrchestrationError {
+    #[snafu(display("unknown game"))]
+    UnknownGame,
+}
+
+crate::code::codes!(OrchestrationError {
+    UnknownGame => "unknown_game",
+});
+
+macro_rules! games {
+    ($($feature:literal, $variant:ident, $name:literal;)*) => {
+        /// A game driven through [`Game`]
+        #[cfg(any($(feature = $feature),*))]
+        trait Driven: Sized {
+            fn start() -> Result<Self, Error>;
+            fn play_command(&mut self, command: &str) -> Result<(), Error>;
+            fn outcome(&self) -> Status;
+            fn turn(&self) -> usize;
+        }
+
+        impl Game {
+            /// The names of the games enabled by features, in alphabetical order
+            pub const NAMES: &'static [&'static str] = &[$(#[cfg(feature = $feature)] $name,)*];
+
+            /// Create a game by its name, see [`NAMES`](Self::NAMES)
+            ///
+            /// The name is matched ignoring case, and `-`, `_` and spaces, so `"Connect Four"` and `"tic-tac-toe"` are found too
+            pub fn new(name: &str) -> Result<Self, Error> {
+                let name = Self::NAMES
+                    .iter()
+                    .find(|expected| is_same_name(name, expected))
+                    .ok_or(OrchestrationError::UnknownGame)?;
+
+                match *name {
+                    $(
+                        #[cfg(feature = $feature)]
+                        $name => Ok(Self::$variant(Driven::start()?)),
+                    )*
+                    _ => unreachable!("every name is in the list"),
+                }
+            }
+
+            /// Get the name of the game
+            pub const fn name(&self) -> &'static str {
+                match *self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(_) => $name,
+                    )*
+                }
+            }
+
+            /// Read a command and play it on behalf of the next player
+            ///
+            /// Return `Err(Error::Parse { .. })` if the command cannot be read or is out of bounds, or the error of the game if the move is illegal
+            pub fn play(&mut self, command: &str) -> Result<(), Error> {
+                // without any game, there is no game to play the command on
+                #[cfg(not(any($(feature = $feature),*)))]
+                let _ = command;
+
+                match *self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(ref mut game) => game.play_command(command),
+                    )*
+                }
+            }
+
+            /// Get the status of the game
+            pub fn status(&self) -> Status {
+                match *self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(ref game) => game.outcome(),
+                    )*
+                }
+            }
+
+            /// Get the index of the next player, in the order of the player enum of the game
+            pub fn next_player(&self) -> usize {
+                match *self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(ref game) => game.turn(),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+games! {
+    "connect_four", ConnectFour, "connect_four";
+    "connect6", Connect6, "connect6";
+    "drop_three", DropThree, "drop_three";
+    "fox_and_geese", FoxAndGeese, "fox_and_geese";
+    "gomoku", Gomoku, "gomoku";
+    "hasami_shogi", HasamiShogi, "hasami_shogi";
+    "order_and_chaos", OrderAndChaos, "order_and_chaos";
+    "pente", Pente, "pente";
+    "reversi", Reversi, "reversi";
+    "score_four", ScoreFour, "score_four";
+    "sos", Sos, "sos";
+    "tictactoe", TicTacToe, "tictactoe";
+}
+
+/// Implement the status and the next player of a game with the usual `Status` and `Player` enums
+#[cfg(any(
+    feature = "connect_four",
+    feature = "connect6",
+    feature = "drop_three",
+    feature = "fox_and_geese",
+    feature = "gomoku",
+    feature = "hasami_shogi",
+    feature = "pente",
+    feature = "reversi",
+    feature = "score_four",
+    feature = "sos",
+    feature = "tictactoe"
+))]
+macro_rules! usual_outcome {
+    ($module:ident) => {
+        fn outcome(&self) -> Status {
+            match self.status() {
+                crate::$module::Status::Ongoing => Status::Ongoing,
+                crate::$module::Status::Draw => Status::Draw,
+                crate::$module::Status::Win(player) => Status::Win(*player as usize),
+            }
+        }
+
+        fn turn(&self) -> usize {
+            self.next_player() as usize
+        }
+    };
+}
+
+#[cfg(feature = "connect_four")]
+impl Driven for crate::connect_four::ConnectFour {
+    fn start() -> Result<Self, Error> {
+        Self::new().map_err(|err| match err {})
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = Self::parse_move(command)?;
+        Ok(self.apply(mv)?)
+    }
+
+    usual_outcome!(connect_four);
+}
+
+#[cfg(feature = "connect6")]
+impl Driven for crate::connect6::Connect6 {
+    fn start() -> Result<Self, Error> {
+        Self::new().map_err(|err| match err {})
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = Self::parse_move(command)?;
+        Ok(self.apply(mv)?)
+    }
+
+    usual_outcome!(connect6);
+}
+
+#[cfg(feature = "drop_three")]
+impl Driven for crate::drop_three::DropThree {
+    fn start() -> Result<Self, Error> {
+        Ok(Self::new(crate::drop_three::Rules::default())?)
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = self.parse_move(command)?;
+        Ok(self.apply(mv)?)
+    }
+
+    usual_outcome!(drop_three);
+}
+
+#[cfg(feature = "fox_and_geese")]
+impl Driven for crate::fox_and_geese::FoxAndGeese {
+    fn start() -> Result<Self, Error> {
+        Self::new().map_err(|err| match err {})
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = Self::parse_move(command)?;
+        Ok(self.apply(mv)?)
+    }
+
+    usual_outcome!(fox_and_geese);
+}
+
+#[cfg(feature = "gomoku")]
+impl Driven for crate::gomoku::Gomoku {
+    fn start() -> Result<Self, Error> {
+        Self::new().map_err(|err| match err {})
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = Self::parse_move(command)?;
+        Ok(self.apply(mv)?)
+    }
+
+    usual_outcome!(gomoku);
+}
+
+#[cfg(feature = "hasami_shogi")]
+impl Driven for crate::hasami_shogi::HasamiShogi {
+    fn start() -> Result<Self, Error> {
+        Ok(Self::new()?)
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = Self::parse_move(command)?;
+        Ok(self.apply(mv)?)
+    }
+
+    usual_outcome!(hasami_shogi);
+}
+
+#[cfg(feature = "order_and_chaos")]
+impl Driven for crate::order_and_chaos::OrderAndChaos {
+    fn start() -> Result<Self, Error> {
+        Self::new().map_err(|err| match err {})
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = Self::parse_move(command)?;
+        Ok(self.apply(mv)?)
+    }
+
+    fn outcome(&self) -> Status {
+        match self.status() {
+            crate::order_and_chaos::Status::Ongoing => Status::Ongoing,
+            crate::order_and_chaos::Status::Win(player) => Status::Win(*player as usize),
+        }
+    }
+
+    fn turn(&self) -> usize {
+        self.next_player() as usize
+    }
+}
+
+#[cfg(feature = "pente")]
+impl Driven for crate::pente::Pente {
+    fn start() -> Result<Self, Error> {
+        Self::new().map_err(|err| match err {})
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = Self::parse_move(command)?;
+        Ok(self.apply(mv)?)
+    }
+
+    usual_outcome!(pente);
+}
+
+#[cfg(feature = "reversi")]
+impl Driven for crate::reversi::Reversi {
+    fn start() -> Result<Self, Error> {
+        Self::new().map_err(|err| match err {})
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = Self::parse_move(command)?;
+        Ok(self.apply(mv)?)
+    }
+
+    usual_outcome!(reversi);
+}
+
+#[cfg(feature = "score_four")]
+impl Driven for crate::score_four::ScoreFour {
+    fn start() -> Result<Self, Error> {
+        Self::new().map_err(|err| match err {})
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = Self::parse_move(command)?;
+        Ok(self.apply(mv)?)
+    }
+
+    usual_outcome!(score_four);
+}
+
+#[cfg(feature = "sos")]
+impl Driven for crate::sos::Sos {
+    fn start() -> Result<Self, Error> {
+        Ok(Self::new(8, 8)?)
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = self.parse_move(command)?;
+        self.apply(mv)?;
+        Ok(())
+    }
+
+    usual_outcome!(sos);
+}
+
+#[cfg(feature = "tictactoe")]
+impl Driven for crate::tictactoe::TicTacToe {
+    fn start() -> Result<Self, Error> {
+        Self::new().map_err(|err| match err {})
+    }
+
+    fn play_command(&mut self, command: &str) -> Result<(), Error> {
+        let mv = Self::parse_move(command)?;
+        Ok(self.apply(mv)?)
+    }
+
+    usual_outcome!(tictactoe);
+}
+
+/// Compare two names ignoring case, and `-`, `_` and spaces
+fn is_same_name(name: &str, expected: &str) -> bool {
+    fn letters(name: &str) -> impl Iterator<Item = char> + '_ {
+        name.chars()
+            .filter(|char| !matches!(char, '-' | '_' | ' '))
+            .map(|char| char.to_ascii_lowercase())
+    }
+
+    letters(name).eq(letters(expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{connect_four::ConnectFourError, orchestration::*, parse::ParseError};
+
+    #[test]
+    fn test() {
+        assert_eq!(Game::NAMES.len(), 12);
+
+        for name in Game::NAMES {
+            let game = Game::new(name).unwrap();
+            assert_eq!(game.name(), *name);
+            assert_eq!(game.status(), Status::Ongoing);
+        }
+
+        assert_eq!(
+            Game::new("chess").unwrap_err(),
+            OrchestrationError::UnknownGame.into()
+        );
+
+        let mut game = Game::new("Connect Four").unwrap();
+        game.play("3").unwrap();
+        assert_eq!(game.next_player(), 1);
+        assert_eq!(
+            game.play(" FLIP ").unwrap_err(),
+            ConnectFourError::FlipNotAllowed.into()
+        );
+
+        assert_eq!(game.play("7").unwrap_err(), ParseError::OutOfBounds.into());
+
+        for command in ["3 3", "", "x"] {
+            assert_eq!(
+                game.play(command).unwrap_err(),
+                ParseError::InvalidMove.into()
+            );
+        }
+
+        for _ in 0..6 {
+            game.play("0").unwrap();
+        }
+
+        assert_eq!(
+            game.play("0").unwrap_err(),
+            ConnectFourError::ColumnFilled.into()
+        );
+
+        let mut game = Game::new("connect6").unwrap();
+        game.play("9 9").unwrap();
+        game.play("0 0, 0 1").unwrap();
+        assert_eq!(game.next_player(), 0);
+
+        let mut game = Game::new("order and chaos").unwrap();
+        game.play("0 0 X").unwrap();
+        assert!(game.play("0 1").is_err());
+        game.play("0 1 o").unwrap();
+        assert_eq!(game.next_player(), 0);
+
+        let mut game = Game::new("SOS").unwrap();
+        game.play("0 0 s").unwrap();
+        game.play("0 2 s").unwrap();
+        // the scoring player moves again
+        game.play("0 1 o").unwrap();
+        assert_eq!(game.next_player(), 0);
+        assert_eq!(
+            game.play("8 0 s").unwrap_err(),
+            ParseError::OutOfBounds.into()
+        );
+    }
+}
@@ -0,0 +1,541 @@
+//! Order and Chaos
+//!
+//! Check struct [`OrderAndChaos`] for more information
+
+use crate::{
+    invariant::InvariantError,
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+    util::{self, KInARow},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use core::{
+    convert::Infallible,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+use snafu::Snafu;
+
+pub(crate) const BOARD_WIDTH: usize = 6;
+pub(crate) const BOARD_HEIGHT: usize = 6;
+
+// the number of like symbols in a row Order wins with
+const LINE_LENGTH: usize = 5;
+
+/// Order and Chaos
+///
+/// Both players put either an X or an O on the 6 by 6 board. Order wins by getting five like symbols in a row horizontally, vertically or diagonally, and Chaos wins by filling the board without that. Order moves first
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::order_and_chaos::{OrderAndChaos, Player, Status, Symbol};
+/// let mut game = OrderAndChaos::new().unwrap();
+///
+/// for col in 0..4 {
+///     // Order lines up Xs, Chaos plays elsewhere
+///     game.put(0, col, Symbol::X).unwrap();
+///     game.put(5, col, Symbol::O).unwrap();
+/// }
+///
+/// game.put(0, 4, Symbol::X).unwrap();
+/// assert_eq!(game.status(), &Status::Win(Player::Order));
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OrderAndChaos {
+    board: [[Option<Symbol>; BOARD_WIDTH]; BOARD_HEIGHT],
+    move_count: usize,
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(OrderAndChaos);
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    /// The player aiming for five like symbols in a row, who moves first
+    Order,
+    /// The player aiming to fill the board without five like symbols in a row
+    Chaos,
+}
+
+/// A symbol, which either player may put
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Symbol {
+    X,
+    O,
+}
+
+/// Game status
+///
+/// The game never ends in a draw by the rules, as a full board is a win of Chaos
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Order,
+    1 => Chaos,
+});
+
+crate::canonical::encode_enum!(Symbol {
+    0 => X,
+    1 => O,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Win(player),
+});
+
+crate::canonical::canonical!(OrderAndChaos, "order_and_chaos", 1 {
+    board,
+    move_count,
+    next_player,
+    status,
+    end_reason,
+});
+
+/// A move, which places a symbol at a position
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+    pub symbol: Symbol,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    /// Positions are always in bounds
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            row: u.choose_index(BOARD_HEIGHT)?,
+            col: u.choose_index(BOARD_WIDTH)?,
+            symbol: u.arbitrary()?,
+        })
+    }
+}
+
+/// Errors that can occur when placing a symbol onto the board
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum OrderAndChaosError {
+    #[snafu(display("position occupied"))]
+    PositionOccupied,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("position out of bounds"))]
+    OutOfBounds,
+}
+
+crate::code::codes!(OrderAndChaosError {
+    PositionOccupied => "position_occupied",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+});
+
+impl OrderAndChaos {
+    /// Create a new Order and Chaos game
+    pub const fn new() -> Result<Self, Infallible> {
+        Ok(Self {
+            board: [[None; BOARD_WIDTH]; BOARD_HEIGHT],
+            move_count: 0,
+            next_player: Player::Order,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+        })
+    }
+
+    /// Create an Order and Chaos game by replaying a list of moves from an empty board
+    ///
+    /// Return the index of the first invalid move and the error it caused on failure
+    pub const fn from_moves(moves: &[Move]) -> Result<Self, (usize, OrderAndChaosError)> {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        let mut idx = 0;
+
+        while idx < moves.len() {
+            let Move { row, col, symbol } = moves[idx];
+
+            if row >= BOARD_HEIGHT || col >= BOARD_WIDTH {
+                return Err((idx, OrderAndChaosError::OutOfBounds));
+            }
+
+            if let Err(err) = game.put(row, col, symbol) {
+                return Err((idx, err));
+            }
+
+            idx += 1;
+        }
+
+        Ok(game)
+    }
+
+    /// Get a symbol at a position
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Option<Symbol> {
+        self.board[row][col]
+    }
+
+    /// Put a symbol on behalf of the next player
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn put(
+        &mut self,
+        row: usize,
+        col: usize,
+        symbol: Symbol,
+    ) -> Result<(), OrderAndChaosError> {
+        if matches!(self.status, Status::Win(_)) {
+            return Err(OrderAndChaosError::GameEnded);
+        }
+
+        if self.board[row][col].is_some() {
+            return Err(OrderAndChaosError::PositionOccupied);
+        }
+
+        self.board[row][col] = Some(symbol);
+
+        self.move_count += 1;
+        self.next_player = self.next_player.other();
+
+        self.update_status(row, col, symbol);
+
+        Ok(())
+    }
+
+    /// Check if a symbol can be put at a position, same as [`put`](Self::put) succeeding but without changing the game
+    ///
+    /// Either symbol can be put at an empty position
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn can_put(&self, row: usize, col: usize) -> bool {
+        matches!(self.status, Status::Ongoing) && self.board[row][col].is_none()
+    }
+
+    /// Apply a move, same as [`put`](Self::put)
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn apply(&mut self, mv: Move) -> Result<(), OrderAndChaosError> {
+        self.put(mv.row, mv.col, mv.symbol)
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn preview(&self, mv: Move) -> Result<Self, OrderAndChaosError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, a position as described in [`parse`](crate::parse) followed by the symbol, `x` or `o`
+    pub fn parse_move(text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+        let (row, col) = words.position(BOARD_HEIGHT, BOARD_WIDTH)?;
+
+        let symbol = if words.take("x") {
+            Symbol::X
+        } else if words.take("o") {
+            Symbol::O
+        } else {
+            return Err(ParseError::InvalidMove);
+        };
+
+        words.finish()?;
+        Ok(Move { row, col, symbol })
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The symbol count must match the move count, the next player must follow from it, and a status ended by the rules must match the lines on the board
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let count = self.board.iter().flatten().flatten().count();
+
+        if count != self.move_count {
+            return Err(InvariantError::Counter);
+        }
+
+        let next_player = if count.is_multiple_of(2) {
+            Player::Order
+        } else {
+            Player::Chaos
+        };
+
+        if self.next_player != next_player {
+            return Err(InvariantError::NextPlayer);
+        }
+
+        let has_line = self.has_line();
+        let is_full = self.move_count == BOARD_HEIGHT * BOARD_WIDTH;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => !has_line && !is_full,
+            (Status::Ongoing, _) => false,
+            (Status::Win(Player::Order), EndReason::Normal) => has_line,
+            (Status::Win(Player::Chaos), EndReason::Normal) => !has_line && is_full,
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), OrderAndChaosError> {
+        self.end_by(player.other(), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), OrderAndChaosError> {
+        self.end_by(player.other(), EndReason::Timeout)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Player,
+        reason: AdjudicationReason,
+    ) -> Result<(), OrderAndChaosError> {
+        self.end_by(winner, EndReason::Adjudication(reason))
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, winner: Player, reason: EndReason) -> Result<(), OrderAndChaosError> {
+        if self.status != Status::Ongoing {
+            return Err(OrderAndChaosError::GameEnded);
+        }
+
+        self.status = Status::Win(winner);
+        self.end_reason = reason;
+        Ok(())
+    }
+
+    /// Check if five like symbols are in a row anywhere on the board
+    fn has_line(&self) -> bool {
+        (0..BOARD_HEIGHT)
+            .flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+            .any(|(row, col)| {
+                self.board[row][col].is_some_and(|symbol| {
+                    util::k_in_a_row::<BOARD_HEIGHT, BOARD_WIDTH>(
+                        row,
+                        col,
+                        LINE_LENGTH,
+                        |row, col| self.board[row][col] == Some(symbol),
+                    )
+                })
+            })
+    }
+
+    /// Check if a position holds a symbol, as `==` is not available in const contexts
+    const fn is_symbol(&self, row: usize, col: usize, symbol: Symbol) -> bool {
+        matches!(
+            (self.board[row][col], symbol),
+            (Some(Symbol::X), Symbol::X) | (Some(Symbol::O), Symbol::O)
+        )
+    }
+
+    const fn update_status(&mut self, row: usize, col: usize, symbol: Symbol) {
+        let mut scan = KInARow::<BOARD_HEIGHT, BOARD_WIDTH>::new(row, col, LINE_LENGTH);
+
+        while let Some((row, col)) = scan.next() {
+            scan.record(self.is_symbol(row, col, symbol));
+        }
+
+        // the line wins for Order, whoever made it
+        if scan.is_found() {
+            self.status = Status::Win(Player::Order);
+            return;
+        }
+
+        if self.move_count == BOARD_HEIGHT * BOARD_WIDTH {
+            self.status = Status::Win(Player::Chaos);
+        }
+    }
+}
+
+impl Player {
+    /// Get the opposite player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Order => Player::Chaos,
+            Player::Chaos => Player::Order,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let symbol = match self.symbol {
+            Symbol::X => 'x',
+            Symbol::O => 'o',
+        };
+
+        write!(f, "{} {} {symbol}", self.row, self.col)
+    }
+}
+
+impl Symbol {
+    /// Get the opposite symbol
+    pub const fn other(self) -> Self {
+        match self {
+            Symbol::X => Symbol::O,
+            Symbol::O => Symbol::X,
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl OrderAndChaos {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    pub fn random_position<R: GameRng + ?Sized>(rng: &mut R, plies: usize) -> Self {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        game.play_random_position(rng, plies);
+        game
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for OrderAndChaos {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let moves = testing::positions(BOARD_HEIGHT, BOARD_WIDTH)
+            .filter(|&(row, col)| self.can_put(row, col))
+            .flat_map(|(row, col)| [Symbol::X, Symbol::O].map(|symbol| Move { row, col, symbol }));
+        testing::choose(rng, moves)
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::order_and_chaos::*;
+
+    #[test]
+    fn test() {
+        let mut game = OrderAndChaos::new().unwrap();
+
+        game.put(2, 2, Symbol::X).unwrap();
+        assert_eq!(game.next_player(), Player::Chaos);
+        assert_eq!(
+            game.put(2, 2, Symbol::O),
+            Err(OrderAndChaosError::PositionOccupied)
+        );
+
+        for idx in 3..6 {
+            game.put(idx, idx, Symbol::X).unwrap();
+            game.put(0, idx, Symbol::O).unwrap();
+            assert_eq!(game.status(), &Status::Ongoing);
+        }
+
+        // a line made by Chaos wins for Order too
+        assert_eq!(game.next_player(), Player::Chaos);
+        assert_eq!(game.put(1, 1, Symbol::X), Ok(()));
+        assert_eq!(game.status(), &Status::Win(Player::Order));
+        assert_eq!(game.validate(), Ok(()));
+        assert_eq!(
+            game.put(0, 5, Symbol::O),
+            Err(OrderAndChaosError::GameEnded)
+        );
+
+        // alternating pairs of symbols never line up five
+        let moves: [Move; BOARD_HEIGHT * BOARD_WIDTH] = core::array::from_fn(|idx| {
+            let (row, col) = (idx / BOARD_WIDTH, idx % BOARD_WIDTH);
+            let symbol = if (col / 2 + row) % 2 == 0 {
+                Symbol::X
+            } else {
+                Symbol::O
+            };
+
+            Move { row, col, symbol }
+        });
+
+        let game = OrderAndChaos::from_moves(&moves).unwrap();
+        assert_eq!(game.status(), &Status::Win(Player::Chaos));
+        assert_eq!(game.validate(), Ok(()));
+
+        assert_eq!(
+            OrderAndChaos::from_moves(&[Move {
+                row: 6,
+                col: 0,
+                symbol: Symbol::X
+            }])
+            .unwrap_err(),
+            (0, OrderAndChaosError::OutOfBounds)
+        );
+
+        let mut game = OrderAndChaos::new().unwrap();
+        game.resign(Player::Chaos).unwrap();
+        assert_eq!(game.status(), &Status::Win(Player::Order));
+        assert_eq!(game.end_reason(), Some(EndReason::Resignation));
+
+        let mut game = OrderAndChaos::new().unwrap();
+        game.put(0, 0, Symbol::O).unwrap();
+        game.next_player = Player::Order;
+        assert_eq!(game.validate(), Err(InvariantError::NextPlayer));
+    }
+}
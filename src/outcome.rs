@@ -0,0 +1,89 @@
+//! Game outcomes shared by the two-player games
+//!
+//! Check enum [`EndReason`] for more information
+
+/// How a two-player game ended, alongside its status
+///
+/// Games ending by their rules end with [`EndReason::Normal`]. The others are set by the `resign()`, `time_out()`, `accept_draw()` and `adjudicate()` methods of the games, and are part of the game state like the status itself
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "gomoku")]
+/// # fn gomoku() {
+/// use gamie::{
+///     gomoku::{Gomoku, Player, Status},
+///     outcome::EndReason,
+/// };
+///
+/// let mut game = Gomoku::new().unwrap();
+/// game.put(7, 7).unwrap();
+/// game.resign(Player::Player1).unwrap();
+///
+/// assert_eq!(game.status(), &Status::Win(Player::Player0));
+/// assert_eq!(game.end_reason(), Some(EndReason::Resignation));
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum EndReason {
+    /// The game ended by its rules
+    Normal,
+    /// A player resigned
+    Resignation,
+    /// A player ran out of time
+    Timeout,
+    /// Both players agreed to a draw
+    Agreement,
+    /// The result was set administratively
+    Adjudication(AdjudicationReason),
+}
+
+crate::code::codes!(EndReason {
+    Normal => "normal",
+    Resignation => "resignation",
+    Timeout => "timeout",
+    Agreement => "agreement",
+    Adjudication => "adjudication",
+});
+
+/// Why a game was adjudicated, see [`EndReason::Adjudication`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum AdjudicationReason {
+    /// A player disconnected
+    Disconnect,
+    /// A player broke a rule outside the game, e.g. of a tournament
+    RuleViolation,
+    /// An arbiter decided the result of an unfinished game
+    Arbiter,
+    /// A reason code defined by the caller
+    Other(u32),
+}
+
+crate::code::codes!(AdjudicationReason {
+    Disconnect => "disconnect",
+    RuleViolation => "rule_violation",
+    Arbiter => "arbiter",
+    Other => "other",
+});
+
+crate::canonical::encode_enum!(EndReason {
+    0 => Normal,
+    1 => Resignation,
+    2 => Timeout,
+    3 => Agreement,
+    4 => Adjudication(reason),
+});
+
+crate::canonical::encode_enum!(AdjudicationReason {
+    0 => Disconnect,
+    1 => RuleViolation,
+    2 => Arbiter,
+    3 => Other(code),
+});
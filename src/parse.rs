@@ -0,0 +1,187 @@
+//! Reading moves from text, e.g. typed by players in a terminal or a chat
+//!
+//! Each game reads its moves with `parse_move()`, and writes them with `Display` in a form it reads back. Words are separated by spaces, commas or parentheses, and matched ignoring case
+//!
+//! A position is read either as a row and a column counted from `0`, e.g. `7 7` or `(7, 7)`, or in algebraic notation, a column letter from `a` followed by a row number from `1` counted from the bottom, e.g. `h8`. Positions are written as a row and a column
+//!
+//! Check struct [`Words`] for more information
+
+use core::str::Split;
+use snafu::Snafu;
+
+// the characters between words
+const SEPARATORS: [char; 5] = [' ', ',', '\t', '(', ')'];
+
+/// The words of a move, read in order
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::parse::{ParseError, Words};
+/// let mut words = Words::new("flag (2, 3)");
+///
+/// assert!(words.take("FLAG"));
+/// assert_eq!(words.position(8, 8), Ok((2, 3)));
+/// assert_eq!(words.finish(), Ok(()));
+///
+/// // the bottom row of a board of 8 rows
+/// assert_eq!(Words::new("a1").position(8, 8), Ok((7, 0)));
+/// assert_eq!(Words::new("8 0").position(8, 8), Err(ParseError::OutOfBounds));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Words<'a> {
+    words: Split<'a, [char; 5]>,
+}
+
+/// Errors that can occur when reading a move
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum ParseError {
+    #[snafu(display("invalid move"))]
+    InvalidMove,
+    #[snafu(display("position out of bounds"))]
+    OutOfBounds,
+}
+
+crate::code::codes!(ParseError {
+    InvalidMove => "invalid_move",
+    OutOfBounds => "out_of_bounds",
+});
+
+impl<'a> Words<'a> {
+    /// Split a text into words
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            words: text.split(SEPARATORS),
+        }
+    }
+
+    /// Take the next word
+    pub fn next_word(&mut self) -> Option<&'a str> {
+        self.words.find(|word| !word.is_empty())
+    }
+
+    /// Take the next word if it is the given one, ignoring case
+    pub fn take(&mut self, expected: &str) -> bool {
+        let mut words = self.clone();
+        let is_taken = words
+            .next_word()
+            .is_some_and(|word| word.eq_ignore_ascii_case(expected));
+
+        if is_taken {
+            *self = words;
+        }
+
+        is_taken
+    }
+
+    /// Take the next word as a number below `bound`
+    pub fn number(&mut self, bound: usize) -> Result<usize, ParseError> {
+        let number = self
+            .next_word()
+            .and_then(|word| word.parse().ok())
+            .ok_or(ParseError::InvalidMove)?;
+
+        if number < bound {
+            Ok(number)
+        } else {
+            Err(ParseError::OutOfBounds)
+        }
+    }
+
+    /// Take a position on a board of `height` rows and `width` columns as `(row, col)`, either a word in algebraic notation or two numbers
+    pub fn position(&mut self, height: usize, width: usize) -> Result<(usize, usize), ParseError> {
+        let mut words = self.clone();
+        let word = words.next_word().ok_or(ParseError::InvalidMove)?;
+
+        let Some(letter) = word.chars().next().filter(char::is_ascii_alphabetic) else {
+            return Ok((self.number(height)?, self.number(width)?));
+        };
+
+        let number: usize = word[1..].parse().map_err(|_| ParseError::InvalidMove)?;
+        let col = (letter.to_ascii_lowercase() as u8 - b'a') as usize;
+
+        if !(1..=height).contains(&number) || col >= width {
+            return Err(ParseError::OutOfBounds);
+        }
+
+        *self = words;
+        Ok((height - number, col))
+    }
+
+    /// Check if no word is left
+    pub fn is_empty(&self) -> bool {
+        self.clone().next_word().is_none()
+    }
+
+    /// Check that no word is left, as a move is read in full
+    pub fn finish(self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(ParseError::InvalidMove)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::parse::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test() {
+        let mut words = Words::new(" (h8),\tCLICK  ");
+        assert_eq!(words.position(15, 15), Ok((7, 7)));
+        assert!(!words.take("flag"));
+        assert!(words.take("click"));
+        assert!(words.is_empty());
+        assert_eq!(words.finish(), Ok(()));
+
+        assert_eq!(
+            Words::new("i1").position(8, 8),
+            Err(ParseError::OutOfBounds)
+        );
+        assert_eq!(
+            Words::new("a9").position(8, 8),
+            Err(ParseError::OutOfBounds)
+        );
+        assert_eq!(
+            Words::new("a0").position(8, 8),
+            Err(ParseError::OutOfBounds)
+        );
+        assert_eq!(
+            Words::new("ab").position(8, 8),
+            Err(ParseError::InvalidMove)
+        );
+        assert_eq!(Words::new("1").position(8, 8), Err(ParseError::InvalidMove));
+        assert_eq!(Words::new("1 2 3").finish(), Err(ParseError::InvalidMove));
+
+        #[cfg(feature = "connect_four")]
+        {
+            use crate::connect_four::{ConnectFour, Move};
+
+            for mv in [Move::Put(3), Move::Flip] {
+                assert_eq!(ConnectFour::parse_move(&mv.to_string()), Ok(mv));
+            }
+        }
+
+        #[cfg(feature = "connect6")]
+        {
+            use crate::connect6::{Connect6, Move};
+
+            for mv in [
+                Move::Single(9, 9),
+                Move::Double {
+                    first: (0, 1),
+                    second: (18, 2),
+                },
+            ] {
+                assert_eq!(Connect6::parse_move(&mv.to_string()), Ok(mv));
+            }
+        }
+    }
+}
@@ -0,0 +1,672 @@
+//! Pente
+//!
+//! Check struct [`Pente`] for more information
+
+use crate::{
+    affect::{Affected, Affects, Effect},
+    invariant::{self, InvariantError},
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use core::{
+    convert::Infallible,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+use snafu::Snafu;
+
+pub(crate) const BOARD_WIDTH: usize = 19;
+pub(crate) const BOARD_HEIGHT: usize = 19;
+
+// the number of captured pairs a player wins at
+const CAPTURES_TO_WIN: usize = 5;
+
+// the first four directions cover each line through a position once
+const DIRECTIONS: [(isize, isize); 8] = [
+    (0, 1),
+    (1, 0),
+    (1, 1),
+    (1, -1),
+    (0, -1),
+    (-1, 0),
+    (-1, -1),
+    (-1, 1),
+];
+
+/// Pente
+///
+/// Placing a stone that flanks exactly two stones of the other player in a line captures them. A player wins by five or more stones in a row, or by capturing 5 pairs
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::pente::{Pente, Player};
+/// let mut game = Pente::new().unwrap();
+///
+/// game.put(9, 9).unwrap();
+/// game.put(9, 10).unwrap();
+/// game.put(0, 0).unwrap();
+/// game.put(9, 11).unwrap();
+/// game.put(9, 12).unwrap();
+///
+/// assert_eq!(game.get(9, 10), None);
+/// assert_eq!(game.captures(Player::Player0), 1);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Pente {
+    board: [[Option<Player>; BOARD_WIDTH]; BOARD_HEIGHT],
+    empties: usize,
+    captures: [usize; 2],
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(Pente);
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::canonical!(Pente, "pente", 1 {
+    board,
+    empties,
+    captures,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+});
+
+/// A rules event between two game states, see [`Pente::events`]
+#[cfg(feature = "events")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// A player captured pairs of stones of the other player
+    Captured { player: Player, pairs: usize },
+    /// A player won by the rules
+    Won(Player),
+}
+
+/// A move, which places a stone at a position
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    /// Positions are always in bounds
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            row: u.choose_index(BOARD_HEIGHT)?,
+            col: u.choose_index(BOARD_WIDTH)?,
+        })
+    }
+}
+
+/// Errors that can occur when placing a stone onto the board
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum PenteError {
+    #[snafu(display("position occupied"))]
+    PositionOccupied,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("position out of bounds"))]
+    OutOfBounds,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+}
+
+crate::code::codes!(PenteError {
+    PositionOccupied => "position_occupied",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+    NoDrawOffer => "no_draw_offer",
+});
+
+impl Pente {
+    /// Create a new Pente game
+    pub const fn new() -> Result<Self, Infallible> {
+        Ok(Self {
+            board: [[None; BOARD_WIDTH]; BOARD_HEIGHT],
+            empties: BOARD_WIDTH * BOARD_HEIGHT,
+            captures: [0; 2],
+            next_player: Player::Player0,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+        })
+    }
+
+    /// Create a Pente game by replaying a list of moves from an empty board
+    ///
+    /// Return the index of the first invalid move and the error it caused on failure
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::pente::{Pente, Player};
+    /// let game = Pente::from_moves(&[(9, 9), (9, 10)]).unwrap();
+    ///
+    /// assert_eq!(game.next_player(), Player::Player0);
+    /// assert!(Pente::from_moves(&[(9, 9), (9, 9)]).is_err());
+    /// ```
+    pub fn from_moves(moves: &[(usize, usize)]) -> Result<Self, (usize, PenteError)> {
+        let mut game = Self::new().unwrap();
+
+        for (idx, (row, col)) in moves.iter().enumerate() {
+            if *row >= BOARD_HEIGHT || *col >= BOARD_WIDTH {
+                return Err((idx, PenteError::OutOfBounds));
+            }
+
+            game.put(*row, *col).map_err(|err| (idx, err))?;
+        }
+
+        Ok(game)
+    }
+
+    /// Get a stone at a position
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Option<Player> {
+        self.board[row][col]
+    }
+
+    /// Get a stone at a position without bounds checking, for the hot loops of a search that keep the positions in bounds already
+    ///
+    /// The bounds are still checked in debug builds
+    ///
+    /// # Safety
+    ///
+    /// The target position must be in bounds, see [`get`](Self::get)
+    pub const unsafe fn get_unchecked(&self, row: usize, col: usize) -> Option<Player> {
+        debug_assert!(row < self.board.len() && col < self.board[0].len());
+
+        // SAFETY: the caller keeps the position in bounds
+        unsafe { *(*self.board.as_ptr().add(row)).as_ptr().add(col) }
+    }
+
+    /// Place a stone, capturing the pairs of the other player it flanks
+    ///
+    /// Placing a stone between two stones of the other player is safe, only the placing player captures
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn put(&mut self, row: usize, col: usize) -> Result<(), PenteError> {
+        self.put_capturing(row, col).map(|_| ())
+    }
+
+    /// Place a stone, same as [`put`](Self::put), and get the stones it captured, each being [`Effect::Captured`]
+    ///
+    /// The stones are collected while capturing them, in row-major order, rather than by comparing states with [`Affects`]
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn put_with_effects(
+        &mut self,
+        row: usize,
+        col: usize,
+    ) -> Result<impl Iterator<Item = Affected>, PenteError> {
+        let (mut captured, count) = self.put_capturing(row, col)?;
+        captured[..count].sort_unstable();
+
+        Ok(captured.into_iter().take(count).map(|(row, col)| Affected {
+            row,
+            col,
+            effect: Effect::Captured,
+        }))
+    }
+
+    /// Place a stone, return the stones captured and their count, at most a pair in each direction
+    fn put_capturing(
+        &mut self,
+        row: usize,
+        col: usize,
+    ) -> Result<([(usize, usize); 2 * DIRECTIONS.len()], usize), PenteError> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(PenteError::GameEnded);
+        }
+
+        if self.board[row][col].is_some() {
+            return Err(PenteError::PositionOccupied);
+        }
+
+        let player = self.next_player;
+
+        self.board[row][col] = Some(player);
+        self.empties -= 1;
+
+        let mut captured = [(0, 0); 2 * DIRECTIONS.len()];
+        let mut count = 0;
+
+        for (row_step, col_step) in DIRECTIONS {
+            let at = |distance: isize| {
+                (
+                    row as isize + row_step * distance,
+                    col as isize + col_step * distance,
+                )
+            };
+
+            let is_pair_flanked = self.get_signed(at(1)) == Some(Some(player.other()))
+                && self.get_signed(at(2)) == Some(Some(player.other()))
+                && self.get_signed(at(3)) == Some(Some(player));
+
+            if is_pair_flanked {
+                for (row, col) in [at(1), at(2)] {
+                    self.board[row as usize][col as usize] = None;
+                    captured[count] = (row as usize, col as usize);
+                    count += 1;
+                }
+
+                self.empties += 2;
+                self.captures[player as usize] += 1;
+            }
+        }
+
+        self.draw_offer = None;
+        self.next_player = player.other();
+
+        if self.captures[player as usize] >= CAPTURES_TO_WIN || self.is_five(row, col, player) {
+            self.status = Status::Win(player);
+        } else if self.empties == 0 {
+            self.status = Status::Draw;
+        }
+
+        Ok((captured, count))
+    }
+
+    /// Check if a stone can be put at a position, same as [`put`](Self::put) succeeding but without changing the game
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn can_put(&self, row: usize, col: usize) -> bool {
+        !matches!(self.status, Status::Win(_) | Status::Draw) && self.board[row][col].is_none()
+    }
+
+    /// Apply a move, same as [`put`](Self::put)
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn apply(&mut self, mv: Move) -> Result<(), PenteError> {
+        self.put(mv.row, mv.col)
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    ///
+    /// The game holds no allocation, so this is a single copy of the game followed by [`apply`](Self::apply)
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn preview(&self, mv: Move) -> Result<Self, PenteError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, a position as described in [`parse`](crate::parse)
+    pub fn parse_move(text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+        let (row, col) = words.position(BOARD_HEIGHT, BOARD_WIDTH)?;
+
+        words.finish()?;
+        Ok(Move { row, col })
+    }
+
+    /// Get the number of pairs captured by a player
+    pub const fn captures(&self, player: Player) -> usize {
+        self.captures[player as usize]
+    }
+
+    /// Get the rules events from this state to a later one, e.g. right before and after a move, for downstream apps to map to achievements
+    ///
+    /// The captures come first, then the win. A win is reported only when the game ended by the rules
+    #[cfg(feature = "events")]
+    pub fn events(&self, after: &Self) -> impl Iterator<Item = Event> {
+        let captured = [Player::Player0, Player::Player1].map(|player| {
+            let pairs = after.captures(player).saturating_sub(self.captures(player));
+            (pairs > 0).then_some(Event::Captured { player, pairs })
+        });
+
+        let won = match (&self.status, &after.status, after.end_reason) {
+            (Status::Ongoing, Status::Win(player), EndReason::Normal) => Some(Event::Won(*player)),
+            _ => None,
+        };
+
+        captured.into_iter().chain([won]).flatten()
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The stones placed by each player, on the board or captured, must match the next player, and a status ended by the rules must match the captures and the board
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let count = |player| {
+            self.board
+                .iter()
+                .flatten()
+                .filter(|cell| **cell == Some(player))
+                .count()
+        };
+
+        let (player0_count, player1_count) = (count(Player::Player0), count(Player::Player1));
+
+        if BOARD_HEIGHT * BOARD_WIDTH - player0_count - player1_count != self.empties {
+            return Err(InvariantError::Counter);
+        }
+
+        // each capture takes two stones of the other player
+        invariant::check_turns(
+            player0_count + 2 * self.captures[Player::Player1 as usize],
+            player1_count + 2 * self.captures[Player::Player0 as usize],
+            self.next_player == Player::Player0,
+        )?;
+
+        let is_winning = |player: Player| {
+            self.captures[player as usize] >= CAPTURES_TO_WIN
+                || (0..BOARD_HEIGHT)
+                    .flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+                    .any(|(row, col)| {
+                        self.board[row][col] == Some(player) && self.is_five(row, col, player)
+                    })
+        };
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => {
+                !is_winning(Player::Player0) && !is_winning(Player::Player1) && self.empties > 0
+            }
+            (Status::Ongoing, _) => false,
+            (Status::Win(player), EndReason::Normal) => is_winning(*player),
+            (Status::Draw, EndReason::Normal) => self.empties == 0,
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), PenteError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), PenteError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), PenteError> {
+        if self.status != Status::Ongoing {
+            return Err(PenteError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), PenteError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(PenteError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), PenteError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), PenteError> {
+        if self.status != Status::Ongoing {
+            return Err(PenteError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Check if the stone at a position is part of five or more stones of the player in a row
+    fn is_five(&self, row: usize, col: usize, player: Player) -> bool {
+        let count = |(row_step, col_step): (isize, isize)| {
+            (1..)
+                .map(|distance| {
+                    (
+                        row as isize + row_step * distance,
+                        col as isize + col_step * distance,
+                    )
+                })
+                .take_while(|position| self.get_signed(*position) == Some(Some(player)))
+                .count()
+        };
+
+        DIRECTIONS[..4].iter().any(|(row_step, col_step)| {
+            count((*row_step, *col_step)) + count((-row_step, -col_step)) + 1 >= 5
+        })
+    }
+
+    /// Get a stone at a signed position, `None` if out of bounds
+    fn get_signed(&self, (row, col): (isize, isize)) -> Option<Option<Player>> {
+        let is_in_bounds =
+            (0..BOARD_HEIGHT as isize).contains(&row) && (0..BOARD_WIDTH as isize).contains(&col);
+
+        is_in_bounds.then(|| self.get(row as usize, col as usize))
+    }
+}
+
+impl Affects for Pente {
+    /// The pieces of the other player captured, each being [`Effect::Captured`]
+    fn affected<'a>(&'a self, after: &'a Self) -> impl Iterator<Item = Affected> + 'a {
+        (0..BOARD_HEIGHT)
+            .flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+            .filter(|(row, col)| {
+                self.board[*row][*col] == Some(self.next_player.other())
+                    && after.board[*row][*col].is_none()
+            })
+            .map(|(row, col)| Affected {
+                row,
+                col,
+                effect: Effect::Captured,
+            })
+    }
+}
+
+impl Player {
+    /// Get the other player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} {}", self.row, self.col)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Pente {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    pub fn random_position<R: GameRng + ?Sized>(rng: &mut R, plies: usize) -> Self {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        game.play_random_position(rng, plies);
+        game
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for Pente {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let moves = testing::positions(BOARD_HEIGHT, BOARD_WIDTH)
+            .filter(|&(row, col)| self.can_put(row, col));
+        testing::choose(rng, moves).map(|(row, col)| Move { row, col })
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{affect::Affects, pente::*};
+
+    #[test]
+    fn test() {
+        // stepping between two stones of the other player is safe
+        let game = Pente::from_moves(&[(9, 9), (9, 10), (9, 12), (9, 11)]).unwrap();
+        assert_eq!(game.captures(Player::Player0), 0);
+        assert_eq!(game.get(9, 11), Some(Player::Player1));
+
+        // Player0 captures a pair in each of 5 rows
+        let mut game = Pente::new().unwrap();
+
+        for row in [0, 2, 4, 6, 8] {
+            game.put(row, 0).unwrap();
+            game.put(row, 1).unwrap();
+            game.put(18, row).unwrap();
+            game.put(row, 2).unwrap();
+            let before = game.clone();
+            game.put(row, 3).unwrap();
+
+            let captured = Event::Captured {
+                player: Player::Player0,
+                pairs: 1,
+            };
+            assert!(before.events(&game).eq(match row {
+                8 => [Some(captured), Some(Event::Won(Player::Player0))],
+                _ => [Some(captured), None],
+            }
+            .into_iter()
+            .flatten()));
+
+            assert_eq!(game.get(row, 1), None);
+            assert_eq!(game.get(row, 2), None);
+            assert!(before
+                .affected(&game)
+                .map(|affected| (affected.row, affected.col))
+                .eq([(row, 1), (row, 2)]));
+            assert!(before
+                .clone()
+                .put_with_effects(row, 3)
+                .unwrap()
+                .eq(before.affected(&game)));
+
+            if row < 8 {
+                game.put(17, row).unwrap();
+            }
+        }
+
+        assert_eq!(game.captures(Player::Player0), 5);
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+        assert_eq!(game.put(10, 10), Err(PenteError::GameEnded));
+
+        let mut game = Pente::new().unwrap();
+
+        for col in 0..4 {
+            game.put(5, col).unwrap();
+            game.put(6, col).unwrap();
+        }
+
+        game.apply(Move { row: 5, col: 4 }).unwrap();
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+    }
+}
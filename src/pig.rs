@@ -0,0 +1,450 @@
+//! Pig, the dice game of pushing your luck
+//!
+//! Check struct [`Pig`] for more information
+
+#[cfg(feature = "testing")]
+use crate::testing::RandomPlay;
+use crate::{dice::Die, invariant::InvariantError, rng::GameRng};
+use snafu::Snafu;
+
+/// The most players a game can have
+pub const MAX_PLAYERS: usize = 8;
+
+const DEFAULT_TARGET: usize = 100;
+const BIG_PIG_DOUBLE_ONES: usize = 25;
+
+/// Pig for 2 to 8 players, taking turns in order
+///
+/// On their turn, a player rolls as many times as they like, adding the rolls to a turn total, then holds to bank the turn total into their score. Rolling a `1` busts, losing the turn total and ending the turn. The first player to bank a score reaching the target, `100` by default, wins
+///
+/// The dice are rolled with [`roll`](Pig::roll) from a [`GameRng`], or set with [`roll_dice`](Pig::roll_dice) when they are rolled elsewhere, e.g. by physical dice or a server. Check enum [`Variant`] for the rules with two dice
+///
+/// Players are numbered from `0`
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::pig::{Dice, Outcome, Pig, Status};
+/// let mut game = Pig::new(2).unwrap();
+///
+/// assert_eq!(game.roll_dice(Dice::One(5)), Ok(Outcome::Scored(5)));
+/// assert_eq!(game.roll_dice(Dice::One(4)), Ok(Outcome::Scored(4)));
+/// game.hold().unwrap();
+/// assert_eq!(game.score(0), 9);
+///
+/// assert_eq!(game.roll_dice(Dice::One(1)), Ok(Outcome::Bust));
+/// assert_eq!(game.next_player(), 0);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Pig {
+    variant: Variant,
+    target: usize,
+    players: usize,
+    scores: [usize; MAX_PLAYERS],
+    turn_total: usize,
+    next_player: usize,
+    status: Status,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(Pig);
+
+/// The rules of a game
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Variant {
+    /// One die, a `1` busts
+    Pig,
+    /// Two dice, a single `1` busts, and double `1`s also lose the whole banked score of the player
+    TwoDice,
+    /// Two dice, a single `1` busts, double `1`s score `25`, and other doubles score twice their sum
+    BigPig,
+}
+
+/// The faces of the dice of a roll
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Dice {
+    One(usize),
+    Two(usize, usize),
+}
+
+/// The result of a roll
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Outcome {
+    /// Points added to the turn total
+    Scored(usize),
+    /// The turn total is lost and the turn ends
+    Bust,
+    /// The turn total and the banked score are lost and the turn ends, by double `1`s in [`Variant::TwoDice`]
+    LostScore,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Win(usize),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Variant {
+    0 => Pig,
+    1 => TwoDice,
+    2 => BigPig,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Win(player),
+});
+
+crate::canonical::canonical!(Pig, "pig", 1 {
+    variant,
+    target,
+    players,
+    scores,
+    turn_total,
+    next_player,
+    status,
+});
+
+/// A move of the next player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Move {
+    /// A roll with the faces of the dice
+    Roll(Dice),
+    Hold,
+}
+
+/// Errors that can occur when creating a game or making a move
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum PigError {
+    #[snafu(display("number of players out of range"))]
+    InvalidPlayers,
+    #[snafu(display("target must not be 0"))]
+    InvalidTarget,
+    #[snafu(display("dice do not match the variant"))]
+    InvalidDice,
+    #[snafu(display("game ended"))]
+    GameEnded,
+}
+
+crate::code::codes!(PigError {
+    InvalidPlayers => "invalid_players",
+    InvalidTarget => "invalid_target",
+    InvalidDice => "invalid_dice",
+    GameEnded => "game_ended",
+});
+
+impl Pig {
+    /// Create a new game of one die to `100` points
+    ///
+    /// Return `Err(PigError::InvalidPlayers)` if `players` is not from `2` to [`MAX_PLAYERS`]
+    pub const fn new(players: usize) -> Result<Self, PigError> {
+        Self::with_rules(players, Variant::Pig, DEFAULT_TARGET)
+    }
+
+    /// Create a new game of a variant to a target score
+    ///
+    /// Return `Err(PigError::InvalidPlayers)` if `players` is not from `2` to [`MAX_PLAYERS`], or `Err(PigError::InvalidTarget)` if `target` is `0`
+    pub const fn with_rules(
+        players: usize,
+        variant: Variant,
+        target: usize,
+    ) -> Result<Self, PigError> {
+        if players < 2 || players > MAX_PLAYERS {
+            return Err(PigError::InvalidPlayers);
+        }
+
+        if target == 0 {
+            return Err(PigError::InvalidTarget);
+        }
+
+        Ok(Self {
+            variant,
+            target,
+            players,
+            scores: [0; MAX_PLAYERS],
+            turn_total: 0,
+            next_player: 0,
+            status: Status::Ongoing,
+        })
+    }
+
+    /// Roll the dice of the variant for the next player with six-sided dice from `rng`
+    pub fn roll<R: GameRng + ?Sized>(&mut self, rng: &mut R) -> Result<Outcome, PigError> {
+        let dice = match self.variant {
+            Variant::Pig => Dice::One(Die::D6.roll(rng)),
+            Variant::TwoDice | Variant::BigPig => Dice::Two(Die::D6.roll(rng), Die::D6.roll(rng)),
+        };
+
+        self.roll_dice(dice)
+    }
+
+    /// Apply a roll of the next player with the faces of the dice, rolled elsewhere
+    ///
+    /// Return `Err(PigError::InvalidDice)` if the number of dice does not match the variant, or a face is not from `1` to `6`
+    pub const fn roll_dice(&mut self, dice: Dice) -> Result<Outcome, PigError> {
+        if matches!(self.status, Status::Win(_)) {
+            return Err(PigError::GameEnded);
+        }
+
+        let outcome = match (self.variant, dice) {
+            (Variant::Pig, Dice::One(face)) if is_face(face) => match face {
+                1 => Outcome::Bust,
+                face => Outcome::Scored(face),
+            },
+            (Variant::TwoDice | Variant::BigPig, Dice::Two(first, second))
+                if is_face(first) && is_face(second) =>
+            {
+                match (self.variant, first, second) {
+                    (Variant::TwoDice, 1, 1) => Outcome::LostScore,
+                    (Variant::BigPig, 1, 1) => Outcome::Scored(BIG_PIG_DOUBLE_ONES),
+                    (_, 1, _) | (_, _, 1) => Outcome::Bust,
+                    (Variant::BigPig, first, second) if first == second => {
+                        Outcome::Scored((first + second) * 2)
+                    }
+                    (_, first, second) => Outcome::Scored(first + second),
+                }
+            }
+            _ => return Err(PigError::InvalidDice),
+        };
+
+        match outcome {
+            Outcome::Scored(points) => self.turn_total += points,
+            Outcome::Bust => self.end_turn(),
+            Outcome::LostScore => {
+                self.scores[self.next_player] = 0;
+                self.end_turn();
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// Bank the turn total of the next player into their score, ending the turn
+    ///
+    /// The player wins if the score reaches the target
+    pub const fn hold(&mut self) -> Result<(), PigError> {
+        if matches!(self.status, Status::Win(_)) {
+            return Err(PigError::GameEnded);
+        }
+
+        self.scores[self.next_player] += self.turn_total;
+
+        if self.scores[self.next_player] >= self.target {
+            self.turn_total = 0;
+            self.status = Status::Win(self.next_player);
+        } else {
+            self.end_turn();
+        }
+
+        Ok(())
+    }
+
+    /// Apply a move of the next player
+    pub const fn apply(&mut self, mv: Move) -> Result<(), PigError> {
+        match mv {
+            Move::Roll(dice) => match self.roll_dice(dice) {
+                Ok(_) => Ok(()),
+                Err(err) => Err(err),
+            },
+            Move::Hold => self.hold(),
+        }
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    pub fn preview(&self, mv: Move) -> Result<Self, PigError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Get the variant
+    pub const fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// Get the target score
+    pub const fn target(&self) -> usize {
+        self.target
+    }
+
+    /// Get the number of players
+    pub const fn players(&self) -> usize {
+        self.players
+    }
+
+    /// Get the banked score of a player
+    ///
+    /// Panic if the player is out of range
+    pub const fn score(&self, player: usize) -> usize {
+        assert!(player < self.players);
+        self.scores[player]
+    }
+
+    /// Get the points rolled by the next player in the current turn, not banked yet
+    pub const fn turn_total(&self) -> usize {
+        self.turn_total
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The players and the target must be in range, and only the winner may have reached the target
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        if !(2..=MAX_PLAYERS).contains(&self.players)
+            || self.target == 0
+            || self.next_player >= self.players
+            || self.scores[self.players..].iter().any(|score| *score != 0)
+        {
+            return Err(InvariantError::Counter);
+        }
+
+        let is_status_valid = match self.status {
+            Status::Ongoing => self.scores.iter().all(|score| *score < self.target),
+            // the winner holds last, so the turn is not passed on
+            Status::Win(player) => {
+                player == self.next_player
+                    && self.turn_total == 0
+                    && self
+                        .scores
+                        .iter()
+                        .enumerate()
+                        .all(|(idx, score)| (idx == player) == (*score >= self.target))
+            }
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> usize {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    const fn end_turn(&mut self) {
+        self.turn_total = 0;
+        self.next_player = (self.next_player + 1) % self.players;
+    }
+}
+
+const fn is_face(face: usize) -> bool {
+    face >= 1 && face <= Die::D6.faces()
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for Pig {
+    type Move = Move;
+
+    /// Hold or roll with even odds, with the faces of a roll drawn from `rng`
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        if self.status != Status::Ongoing {
+            return None;
+        }
+
+        if rng.gen_below(2) == 0 {
+            return Some(Move::Hold);
+        }
+
+        Some(Move::Roll(match self.variant {
+            Variant::Pig => Dice::One(Die::D6.roll(rng)),
+            Variant::TwoDice | Variant::BigPig => Dice::Two(Die::D6.roll(rng), Die::D6.roll(rng)),
+        }))
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pig::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test() {
+        assert_eq!(Pig::new(1).unwrap_err(), PigError::InvalidPlayers);
+        assert_eq!(
+            Pig::with_rules(2, Variant::Pig, 0).unwrap_err(),
+            PigError::InvalidTarget
+        );
+
+        let mut game = Pig::with_rules(3, Variant::TwoDice, 20).unwrap();
+
+        assert_eq!(game.roll_dice(Dice::One(3)), Err(PigError::InvalidDice));
+        assert_eq!(game.roll_dice(Dice::Two(3, 7)), Err(PigError::InvalidDice));
+        assert_eq!(game.roll_dice(Dice::Two(6, 6)), Ok(Outcome::Scored(12)));
+        game.apply(Move::Hold).unwrap();
+
+        assert_eq!(game.roll_dice(Dice::Two(1, 4)), Ok(Outcome::Bust));
+        assert_eq!(game.next_player(), 2);
+        game.hold().unwrap();
+
+        game.roll_dice(Dice::Two(5, 5)).unwrap();
+        game.hold().unwrap();
+        assert_eq!(game.score(0), 22);
+        assert_eq!(game.status(), &Status::Win(0));
+        assert_eq!(game.validate(), Ok(()));
+        assert_eq!(game.hold(), Err(PigError::GameEnded));
+
+        let mut game = Pig::with_rules(2, Variant::TwoDice, 20).unwrap();
+        game.roll_dice(Dice::Two(2, 3)).unwrap();
+        game.hold().unwrap();
+        game.hold().unwrap();
+
+        // double ones lose the banked score too
+        assert_eq!(game.roll_dice(Dice::Two(1, 1)), Ok(Outcome::LostScore));
+        assert_eq!(game.score(0), 0);
+        assert_eq!(game.next_player(), 1);
+
+        let mut game = Pig::with_rules(2, Variant::BigPig, 100).unwrap();
+        assert_eq!(game.roll_dice(Dice::Two(1, 1)), Ok(Outcome::Scored(25)));
+        assert_eq!(game.roll_dice(Dice::Two(4, 4)), Ok(Outcome::Scored(16)));
+        assert_eq!(game.turn_total(), 41);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut game = Pig::new(4).unwrap();
+
+        while game.status() == &Status::Ongoing {
+            if game.turn_total() < 20 {
+                game.roll(&mut rng).unwrap();
+            } else {
+                game.hold().unwrap();
+            }
+
+            assert_eq!(game.validate(), Ok(()));
+        }
+    }
+}
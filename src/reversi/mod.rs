@@ -0,0 +1,2028 @@
+//! Reversi
+//!
+//! Check struct [`Reversi`] for more information
+
+pub mod openings;
+
+use crate::{
+    affect::{self, Affected, Affects, Effect},
+    invariant::InvariantError,
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use core::{
+    cmp::Ordering,
+    convert::Infallible,
+    fmt::{Display, Formatter, Result as FmtResult},
+    hash::{Hash, Hasher},
+};
+use snafu::Snafu;
+#[cfg(feature = "sgf")]
+use {
+    crate::sgf::{self, SgfError},
+    core::fmt::Write,
+};
+
+pub(crate) const BOARD_WIDTH: usize = 8;
+pub(crate) const BOARD_HEIGHT: usize = 8;
+
+// the number of pieces placed in the center before the first flip in the free setup
+const SETUP_MOVES: usize = 4;
+
+// the number of empty positions a game phase ends at
+const OPENING_END: usize = 44;
+const MIDGAME_END: usize = 20;
+
+const CORNERS: [(usize, usize); 4] = [
+    (0, 0),
+    (0, BOARD_WIDTH - 1),
+    (BOARD_HEIGHT - 1, 0),
+    (BOARD_HEIGHT - 1, BOARD_WIDTH - 1),
+];
+
+// the positions of the first and the last column, and the center 2x2 square, one bit each in row-major order
+const FIRST_COL: u64 = 0x0101_0101_0101_0101;
+const LAST_COL: u64 = FIRST_COL << (BOARD_WIDTH - 1);
+const CENTER: u64 = 0x0000_0018_1800_0000;
+
+// the first four directions cover each line through a position once
+const DIRECTIONS: [(isize, isize); 8] = [
+    (0, 1),
+    (1, 0),
+    (1, 1),
+    (1, -1),
+    (0, -1),
+    (-1, 0),
+    (-1, -1),
+    (-1, 1),
+];
+
+/// Reversi
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::reversi::{Player, Reversi};
+/// let mut game = Reversi::new().unwrap();
+///
+/// game.put(2, 4).unwrap();
+///
+///
+/// // The next player may not be able to place a piece onto any position, so manually check `next_player()` to determine the next player
+/// assert_eq!(game.next_player(), Player::Player1);
+///
+/// game.put(2, 3).unwrap();
+/// // ...
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Reversi {
+    board: [[Option<Player>; BOARD_HEIGHT]; BOARD_WIDTH],
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+    empties: usize,
+    move_number: usize,
+    order: [[u8; BOARD_WIDTH]; BOARD_HEIGHT],
+    komi: isize,
+    is_free_setup: bool,
+    // the starting pieces are on the other diagonals, after rotating or mirroring the board
+    is_setup_mirrored: bool,
+    stats: Stats,
+    // the starting position of a game composed by `from_board`, whose pieces are not numbered
+    #[cfg_attr(feature = "serde", serde(default))]
+    composition: Option<Composition>,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(Reversi);
+
+// games are compared by position, leaving out the order of the moves and the statistics of how they were played
+impl PartialEq for Reversi {
+    fn eq(&self, other: &Self) -> bool {
+        self.position_key() == other.position_key()
+    }
+}
+
+impl Eq for Reversi {}
+
+impl Hash for Reversi {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.position_key().hash(state);
+    }
+}
+
+/// The starting position of a composed game, see [`Reversi::from_board`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct Composition {
+    // the pieces of each player, one bit per position in row-major order
+    pieces: [u64; 2],
+    next_player: Player,
+}
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::canonical!(Reversi, "reversi", 1 {
+    board,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+    komi,
+    is_free_setup,
+    is_setup_mirrored,
+});
+
+/// Statistics of a game, counted as it is played, see [`Reversi::stats`]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stats {
+    flips: [usize; 2],
+    max_flips: [usize; 2],
+    passes: [usize; 2],
+    max_deficit: [usize; 2],
+}
+
+/// A rules event between two game states, see [`Reversi::events`]
+#[cfg(feature = "events")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// A player flipped pieces of the other player
+    Flipped { player: Player, count: usize },
+    /// A player took a corner
+    CornerTaken {
+        player: Player,
+        row: usize,
+        col: usize,
+    },
+    /// A player passed, as they could not move
+    Passed(Player),
+    /// A player won by the score, after trailing by at most `max_deficit` pieces, e.g. for comeback wins
+    Won { player: Player, max_deficit: usize },
+}
+
+/// A move, which places a piece at a position
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    /// Positions are always in bounds
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            row: u.choose_index(BOARD_HEIGHT)?,
+            col: u.choose_index(BOARD_WIDTH)?,
+        })
+    }
+}
+
+/// The change of a position between two game states
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CellDiff {
+    pub before: Option<Player>,
+    pub after: Option<Player>,
+}
+
+/// Differences between two game states, see [`Reversi::diff`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BoardDiff {
+    /// The change of each position, `None` if unchanged
+    pub cells: [[Option<CellDiff>; BOARD_WIDTH]; BOARD_HEIGHT],
+    /// The next player before and after, `None` if unchanged
+    pub next_player: Option<(Player, Player)>,
+    /// The game status before and after, `None` if unchanged
+    pub status: Option<(Status, Status)>,
+}
+
+/// Game phase, determined by the number of empty positions
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Phase {
+    /// More than 44 empty positions
+    Opening,
+    /// From 21 to 44 empty positions
+    Midgame,
+    /// 20 or fewer empty positions
+    Endgame,
+}
+
+/// A group of empty positions connected orthogonally or diagonally, see [`Reversi::regions`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Region {
+    // one bit per position, in row-major order
+    positions: u64,
+}
+
+/// Errors that can occur when placing a piece onto the board
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum ReversiError {
+    #[snafu(display("position occupied"))]
+    PositionOccupied,
+    #[snafu(display("invalid position, {reason}"))]
+    InvalidPosition { reason: InvalidReason },
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("position out of bounds"))]
+    OutOfBounds,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+}
+
+crate::code::codes!(ReversiError {
+    PositionOccupied => "position_occupied",
+    InvalidPosition => "invalid_position",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+    NoDrawOffer => "no_draw_offer",
+});
+
+/// Why a position is invalid for placing a piece, see [`ReversiError::InvalidPosition`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum InvalidReason {
+    /// No piece of the other player is next to the position
+    NoAdjacentPiece,
+    /// Pieces of the other player are next to the position, but no line of them ends with a piece of the next player
+    NoBracketingLine,
+    /// The position is outside the center 2x2 square during the setup phase, see [`Reversi::with_free_setup`]
+    OutsideCenter,
+}
+
+crate::code::codes!(InvalidReason {
+    NoAdjacentPiece => "no_adjacent_piece",
+    NoBracketingLine => "no_bracketing_line",
+    OutsideCenter => "outside_center",
+});
+
+impl Display for InvalidReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::NoAdjacentPiece => write!(f, "no adjacent piece of the other player"),
+            Self::NoBracketingLine => write!(f, "no line to flip"),
+            Self::OutsideCenter => write!(f, "outside the center during setup"),
+        }
+    }
+}
+
+impl Reversi {
+    /// Create a new Reversi game
+    pub const fn new() -> Result<Self, Infallible> {
+        Ok(Self::initial())
+    }
+
+    /// Create a new Reversi game, the same as [`new`](Self::new) without the `Result`
+    pub const fn initial() -> Self {
+        Self::with_setup(0, false)
+    }
+
+    /// Create a new Reversi game with komi, which is added to the piece count of [`Player::Player1`] when the game ends
+    ///
+    /// A negative komi is taken from [`Player::Player1`] instead, which can balance games between unequal players
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::reversi::Reversi;
+    /// let game = Reversi::with_komi(2).unwrap();
+    ///
+    /// assert_eq!(game.komi(), 2);
+    /// ```
+    pub const fn with_komi(komi: isize) -> Result<Self, Infallible> {
+        Ok(Self::with_setup(komi, false))
+    }
+
+    /// Create a new Reversi game with the free setup of the original rules, starting from an empty board
+    ///
+    /// The first 4 moves place pieces onto the empty positions of the center 2x2 square without flipping, so the players choose between the diagonal and the parallel arrangements. The game continues as usual afterwards. Check [`is_in_setup`](Self::is_in_setup) for the setup phase
+    ///
+    /// SGF games written by [`write_sgf`](Self::write_sgf) have no setup properties, and the setup moves are written as usual moves
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::reversi::{Player, Reversi};
+    /// let mut game = Reversi::with_free_setup().unwrap();
+    ///
+    /// assert!(game.put(2, 4).is_err());
+    ///
+    /// // the parallel arrangement
+    /// game.put(3, 3).unwrap();
+    /// game.put(4, 3).unwrap();
+    /// game.put(3, 4).unwrap();
+    /// game.put(4, 4).unwrap();
+    ///
+    /// assert!(!game.is_in_setup());
+    /// game.put(5, 3).unwrap();
+    /// assert_eq!(game.get(4, 3), Some(Player::Player0));
+    /// ```
+    pub const fn with_free_setup() -> Result<Self, Infallible> {
+        Ok(Self::with_setup(0, true))
+    }
+
+    const fn with_setup(komi: isize, is_free_setup: bool) -> Self {
+        let mut board = [[None; BOARD_HEIGHT]; BOARD_WIDTH];
+
+        if !is_free_setup {
+            board[3][3] = Some(Player::Player0);
+            board[4][4] = Some(Player::Player0);
+            board[3][4] = Some(Player::Player1);
+            board[4][3] = Some(Player::Player1);
+        }
+
+        let empties = if is_free_setup {
+            BOARD_WIDTH * BOARD_HEIGHT
+        } else {
+            BOARD_WIDTH * BOARD_HEIGHT - SETUP_MOVES
+        };
+
+        Self {
+            board,
+            next_player: Player::Player0,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+            empties,
+            move_number: 0,
+            order: [[0; BOARD_WIDTH]; BOARD_HEIGHT],
+            komi,
+            is_free_setup,
+            is_setup_mirrored: false,
+            stats: Stats {
+                flips: [0; 2],
+                max_flips: [0; 2],
+                passes: [0; 2],
+                max_deficit: [0; 2],
+            },
+            composition: None,
+        }
+    }
+
+    /// Create a Reversi game by replaying a list of moves from the starting position, e.g. an XOT opening
+    ///
+    /// Return the index of the first invalid move and the error it caused on failure
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::reversi::{Player, Reversi};
+    /// let game = Reversi::from_moves(&[(2, 4), (2, 3)]).unwrap();
+    ///
+    /// assert_eq!(game.next_player(), Player::Player0);
+    /// assert!(Reversi::from_moves(&[(2, 4), (2, 4)]).is_err());
+    /// ```
+    pub fn from_moves(moves: &[(usize, usize)]) -> Result<Self, (usize, ReversiError)> {
+        let mut game = Self::new().unwrap();
+
+        for (idx, (row, col)) in moves.iter().enumerate() {
+            if *row >= BOARD_HEIGHT || *col >= BOARD_WIDTH {
+                return Err((idx, ReversiError::OutOfBounds));
+            }
+
+            game.put(*row, *col).map_err(|err| (idx, err))?;
+        }
+
+        Ok(game)
+    }
+
+    /// Compose a Reversi game from a board and the next player, e.g. for a hand-authored endgame puzzle
+    ///
+    /// The four center positions must be occupied, as the pieces of the starting position are never removed. The status is read from the board, ended by the score once neither player can move
+    ///
+    /// The history of a composed game is unknown, so the board is its starting position: [`moves`](Self::moves) and the statistics only cover the moves played afterwards, and [`write_sgf`](Self::write_sgf) writes the board as setup properties
+    ///
+    /// Return `Err(InvariantError::Board)` if a center position is empty, or the error of [`validate`](Self::validate) if the position is not consistent, e.g. the next player cannot move while the game is not over
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::reversi::{Player, Reversi};
+    /// let mut board = [[None; 8]; 8];
+    ///
+    /// for (row, col) in [(3, 3), (3, 4), (4, 3), (4, 4), (2, 4)] {
+    ///     board[row][col] = Some(Player::Player0);
+    /// }
+    /// board[4][4] = Some(Player::Player1);
+    ///
+    /// let game = Reversi::from_board(board, Player::Player1).unwrap();
+    /// assert!(game.can_put(2, 2));
+    /// ```
+    pub fn from_board(
+        board: [[Option<Player>; BOARD_WIDTH]; BOARD_HEIGHT],
+        next_player: Player,
+    ) -> Result<Self, InvariantError> {
+        if bitboard(&board, None) & CENTER != 0 {
+            return Err(InvariantError::Board);
+        }
+
+        let mut game = Self::from_composition(Composition {
+            pieces: [
+                bitboard(&board, Some(Player::Player0)),
+                bitboard(&board, Some(Player::Player1)),
+            ],
+            next_player,
+        });
+
+        if game.mobility(Player::Player0) == 0 && game.mobility(Player::Player1) == 0 {
+            game.status = game.final_status();
+        }
+
+        game.validate()?;
+        Ok(game)
+    }
+
+    /// Create a Reversi game from the main line of an SGF game with `GM[2]`
+    ///
+    /// Black is [`Player::Player0`]. The starting position of this crate has the colors swapped compared to the standard one, so SGF columns are mirrored, where `a` is column `7`. Setup properties other than the usual starting position compose the game with [`from_board`](Self::from_board), and passes are skipped as they are implied by the board
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::reversi::{Player, Reversi};
+    /// let game = Reversi::from_sgf("(;FF[4]GM[2]SZ[8];B[fe];W[fd])").unwrap();
+    ///
+    /// assert_eq!(game.get(4, 2), Some(Player::Player0));
+    /// assert_eq!(game.get(3, 2), Some(Player::Player1));
+    /// ```
+    #[cfg(feature = "sgf")]
+    pub fn from_sgf(s: &str) -> Result<Self, SgfError> {
+        let mut board = [[None; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        let is_black_next = sgf::read_setup(s, BOARD_WIDTH, BOARD_HEIGHT, |is_black, row, col| {
+            board[row][BOARD_WIDTH - 1 - col] = Some(match is_black {
+                true => Player::Player0,
+                false => Player::Player1,
+            });
+        })?;
+
+        let mut game = Self::initial();
+
+        if board != [[None; BOARD_WIDTH]; BOARD_HEIGHT]
+            && (board != game.board || is_black_next == Some(false))
+        {
+            let next_player = match is_black_next {
+                Some(false) => Player::Player1,
+                _ => Player::Player0,
+            };
+
+            game = Self::from_board(board, next_player).map_err(|_| SgfError::InvalidSetup)?;
+        }
+
+        for (index, mv) in sgf::read_moves(s, 2, BOARD_WIDTH, BOARD_HEIGHT).enumerate() {
+            let sgf::Move { is_black, position } = mv?;
+
+            let Some((row, col)) = position else {
+                continue;
+            };
+
+            if is_black != (game.next_player == Player::Player0) {
+                return Err(SgfError::WrongPlayer { index });
+            }
+
+            game.put(row, BOARD_WIDTH - 1 - col)
+                .map_err(|_| SgfError::IllegalMove { index })?;
+        }
+
+        Ok(game)
+    }
+
+    /// Write the moves played so far as an SGF game with `GM[2]`, see [`Reversi::from_sgf`]
+    ///
+    /// The result is written if the game has ended. The board of a composed game is written as setup properties, with the player to move in `PL`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::reversi::Reversi;
+    /// let game = Reversi::from_moves(&[(2, 4), (2, 3)]).unwrap();
+    ///
+    /// let mut sgf = String::new();
+    /// game.write_sgf(&mut sgf).unwrap();
+    ///
+    /// assert_eq!(sgf, "(;FF[4]GM[2]SZ[8]AB[de][ed]AW[dd][ee];B[dc];W[ec])");
+    /// ```
+    #[cfg(feature = "sgf")]
+    pub fn write_sgf(&self, w: &mut impl Write) -> FmtResult {
+        write!(w, "(;FF[4]GM[2]SZ[{BOARD_WIDTH}]")?;
+
+        match (self.composition, self.is_free_setup, self.is_setup_mirrored) {
+            (Some(composition), _, _) => {
+                for (player, ident) in [(Player::Player0, "AB"), (Player::Player1, "AW")] {
+                    let pieces = composition.pieces[player as usize];
+
+                    if pieces != 0 {
+                        write!(w, "{ident}")?;
+                    }
+
+                    for (row, col) in self.positions() {
+                        if pieces & 1 << (row * BOARD_WIDTH + col) != 0 {
+                            let x = sgf::coordinate(BOARD_WIDTH - 1 - col);
+                            let y = sgf::coordinate(row);
+                            write!(w, "[{x}{y}]")?;
+                        }
+                    }
+                }
+
+                match composition.next_player {
+                    Player::Player0 => write!(w, "PL[B]")?,
+                    Player::Player1 => write!(w, "PL[W]")?,
+                }
+            }
+            (None, true, _) => {}
+            (None, false, false) => write!(w, "AB[de][ed]AW[dd][ee]")?,
+            (None, false, true) => write!(w, "AB[dd][ee]AW[de][ed]")?,
+        }
+
+        match self.status {
+            Status::Ongoing => {}
+            Status::Draw => write!(w, "RE[0]")?,
+            Status::Win(Player::Player0) => write!(w, "RE[B+]")?,
+            Status::Win(Player::Player1) => write!(w, "RE[W+]")?,
+        }
+
+        let mut game = match self.composition {
+            Some(composition) => Self::from_composition(composition),
+            None if self.is_setup_mirrored => Self::with_setup(0, false).mirror_horizontal(),
+            None => Self::with_setup(0, self.is_free_setup),
+        };
+
+        for Move { row, col } in self.moves() {
+            let color = match game.next_player {
+                Player::Player0 => 'B',
+                Player::Player1 => 'W',
+            };
+
+            let x = sgf::coordinate(BOARD_WIDTH - 1 - col);
+            let y = sgf::coordinate(row);
+            write!(w, ";{color}[{x}{y}]")?;
+
+            // the moves were legal when played from the starting position, unless the state was deserialized without validation
+            game.put(row, col).map_err(|_| core::fmt::Error)?;
+        }
+
+        write!(w, ")")
+    }
+
+    /// Get a piece at a position
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Option<Player> {
+        self.board[row][col]
+    }
+
+    /// Get a piece at a position without bounds checking, for the hot loops of a search that keep the positions in bounds already
+    ///
+    /// The bounds are still checked in debug builds
+    ///
+    /// # Safety
+    ///
+    /// The target position must be in bounds, see [`get`](Self::get)
+    pub const unsafe fn get_unchecked(&self, row: usize, col: usize) -> Option<Player> {
+        debug_assert!(row < self.board.len() && col < self.board[0].len());
+
+        // SAFETY: the caller keeps the position in bounds
+        unsafe { *(*self.board.as_ptr().add(row)).as_ptr().add(col) }
+    }
+
+    /// Put a piece
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn put(&mut self, row: usize, col: usize) -> Result<(), ReversiError> {
+        self.put_flipping(row, col).map(|_| ())
+    }
+
+    /// Put a piece, same as [`put`](Self::put), and get the pieces it flipped, each being [`Effect::Flipped`]
+    ///
+    /// The pieces are collected while flipping them, in row-major order, rather than by comparing states with [`Affects`]
+    ///
+    /// Panic if the target position is out of bounds
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gamie::{
+    ///     affect::{Affected, Effect},
+    ///     reversi::Reversi,
+    /// };
+    ///
+    /// let mut game = Reversi::new().unwrap();
+    ///
+    /// assert!(game.put_with_effects(2, 4).unwrap().eq([Affected {
+    ///     row: 3,
+    ///     col: 4,
+    ///     effect: Effect::Flipped,
+    /// }]));
+    /// ```
+    pub fn put_with_effects(
+        &mut self,
+        row: usize,
+        col: usize,
+    ) -> Result<impl Iterator<Item = Affected>, ReversiError> {
+        let flipped = self.put_flipping(row, col)?;
+        Ok(affect::bitboard_cells(
+            flipped.into(),
+            BOARD_WIDTH,
+            Effect::Flipped,
+        ))
+    }
+
+    /// Put a piece, return the pieces flipped as a bitboard
+    fn put_flipping(&mut self, row: usize, col: usize) -> Result<u64, ReversiError> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(ReversiError::GameEnded);
+        }
+
+        if self.board[row][col].is_some() {
+            return Err(ReversiError::PositionOccupied);
+        }
+
+        if self.is_in_setup() {
+            if !is_center(row, col) {
+                return Err(ReversiError::InvalidPosition {
+                    reason: InvalidReason::OutsideCenter,
+                });
+            }
+
+            // with two pieces of each player in the center, Player0 can always move after the setup
+            self.place(row, col);
+            return Ok(0);
+        }
+
+        let flipping_left_range = (0..col).rev();
+        let flipping_right_range = col + 1..BOARD_WIDTH;
+        let flipping_up_range = (0..row).rev();
+        let flipping_down_range = row + 1..BOARD_HEIGHT;
+
+        let mut flipped = 0;
+
+        // flip left
+        flipped |= self.flip_in_line(flipping_left_range.clone().map(|col| (row, col)));
+
+        // flip right
+        flipped |= self.flip_in_line(flipping_right_range.clone().map(|col| (row, col)));
+
+        // flip up
+        flipped |= self.flip_in_line(flipping_up_range.clone().map(|row| (row, col)));
+
+        // flip down
+        flipped |= self.flip_in_line(flipping_down_range.clone().map(|row| (row, col)));
+
+        // flip upper left
+        flipped |= self.flip_in_line(flipping_up_range.clone().zip(flipping_left_range.clone()));
+
+        // flip upper right
+        flipped |= self.flip_in_line(flipping_up_range.clone().zip(flipping_right_range.clone()));
+
+        // flip lower left
+        flipped |= self.flip_in_line(flipping_down_range.clone().zip(flipping_left_range.clone()));
+
+        // flip lower right
+        flipped |= self.flip_in_line(flipping_down_range.zip(flipping_right_range));
+
+        if flipped == 0 {
+            return Err(self.invalid_position(row, col));
+        }
+
+        self.stats
+            .record_flips(self.next_player, flipped.count_ones() as usize);
+        self.place(row, col);
+        self.stats.record_deficits(self.piece_counts());
+
+        if self.has_any_move(self.next_player) {
+            return Ok(flipped);
+        }
+
+        self.next_player = self.next_player.other();
+        if self.has_any_move(self.next_player) {
+            self.stats.passes[self.next_player.other() as usize] += 1;
+            return Ok(flipped);
+        }
+
+        // both players cannot move, game ends
+        self.status = self.final_status();
+
+        Ok(flipped)
+    }
+
+    /// Check if target position is valid for placing a piece
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn is_position_valid_for_put(&self, row: usize, col: usize) -> Result<(), ReversiError> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(ReversiError::GameEnded);
+        }
+
+        if self.board[row][col].is_some() {
+            return Err(ReversiError::PositionOccupied);
+        }
+
+        if self.is_in_setup() {
+            return if is_center(row, col) {
+                Ok(())
+            } else {
+                Err(ReversiError::InvalidPosition {
+                    reason: InvalidReason::OutsideCenter,
+                })
+            };
+        }
+
+        // check each direction for a valid move
+
+        let checking_left_range = (0..col).rev();
+        let checking_right_range = col + 1..BOARD_WIDTH;
+        let checking_up_range = (0..row).rev();
+        let checking_down_range = row + 1..BOARD_HEIGHT;
+
+        // check left
+        if self.is_clipping_in_line(checking_left_range.clone().map(|col| (row, col))) {
+            return Ok(());
+        }
+
+        // check right
+        if self.is_clipping_in_line(checking_right_range.clone().map(|col| (row, col))) {
+            return Ok(());
+        }
+
+        // check up
+        if self.is_clipping_in_line(checking_up_range.clone().map(|row| (row, col))) {
+            return Ok(());
+        }
+
+        // check down
+        if self.is_clipping_in_line(checking_down_range.clone().map(|row| (row, col))) {
+            return Ok(());
+        }
+
+        // check upper left
+        if self.is_clipping_in_line(checking_up_range.clone().zip(checking_left_range.clone())) {
+            return Ok(());
+        }
+
+        // check upper right
+        if self.is_clipping_in_line(checking_up_range.clone().zip(checking_right_range.clone())) {
+            return Ok(());
+        }
+
+        // check lower left
+        if self.is_clipping_in_line(checking_down_range.clone().zip(checking_left_range.clone())) {
+            return Ok(());
+        }
+
+        // check lower right
+        if self.is_clipping_in_line(checking_down_range.zip(checking_right_range)) {
+            return Ok(());
+        }
+
+        Err(self.invalid_position(row, col))
+    }
+
+    /// Check if a piece can be put at a position, same as [`is_position_valid_for_put`](Self::is_position_valid_for_put) but without constructing the error
+    ///
+    /// The bracketing is checked with bitwise operations on the board, like [`mobility`](Self::mobility), rather than by walking each direction
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn can_put(&self, row: usize, col: usize) -> bool {
+        if matches!(self.status, Status::Win(_) | Status::Draw) || self.board[row][col].is_some() {
+            return false;
+        }
+
+        if self.is_in_setup() {
+            return is_center(row, col);
+        }
+
+        let own = bitboard(&self.board, Some(self.next_player));
+        let other = bitboard(&self.board, Some(self.next_player.other()));
+
+        movable(own, other) & 1 << (row * BOARD_WIDTH + col) != 0
+    }
+
+    /// Apply a move, same as [`put`](Self::put)
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn apply(&mut self, mv: Move) -> Result<(), ReversiError> {
+        self.put(mv.row, mv.col)
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    ///
+    /// The game holds no allocation, so this is a single copy of the game followed by [`apply`](Self::apply)
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn preview(&self, mv: Move) -> Result<Self, ReversiError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, a position as described in [`parse`](crate::parse)
+    pub fn parse_move(text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+        let (row, col) = words.position(BOARD_HEIGHT, BOARD_WIDTH)?;
+
+        words.finish()?;
+        Ok(Move { row, col })
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The pieces on the board must match the counters and the move order, and a status ended by the rules must be the final score of a board where neither player can move. Passes are not recorded, so any player may be next as long as they can move
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let setup_pieces = match self.composition {
+            Some(Composition { pieces, .. }) => {
+                if self.is_free_setup || pieces[0] & pieces[1] != 0 {
+                    return Err(InvariantError::Board);
+                }
+
+                (pieces[0] | pieces[1]).count_ones() as usize
+            }
+            None if self.is_free_setup => 0,
+            None => SETUP_MOVES,
+        };
+        let empties = self
+            .positions()
+            .filter(|(row, col)| self.board[*row][*col].is_none())
+            .count();
+
+        if empties != self.empties
+            || BOARD_WIDTH * BOARD_HEIGHT - empties != self.move_number + setup_pieces
+        {
+            return Err(InvariantError::Counter);
+        }
+
+        let mut is_numbered = [false; BOARD_WIDTH * BOARD_HEIGHT + 1];
+
+        for (row, col) in self.positions() {
+            let number = self.order[row][col] as usize;
+
+            let is_order_valid = match (self.board[row][col], number) {
+                (None, number) => number == 0,
+                // the pieces of the starting position are not numbered
+                (Some(_), 0) => self.is_starting_position(row, col),
+                (Some(_), number)
+                    if number > self.move_number
+                        || is_numbered[number]
+                        || self.composition.is_some() && self.is_starting_position(row, col) =>
+                {
+                    false
+                }
+                (Some(_), number) => {
+                    number > SETUP_MOVES || !self.is_free_setup || is_center(row, col)
+                }
+            };
+
+            if !is_order_valid {
+                return Err(InvariantError::Board);
+            }
+
+            is_numbered[number] = true;
+        }
+
+        // every move after the setup flips at least one piece
+        let flipping_moves = match self.is_free_setup {
+            true => self.move_number.saturating_sub(SETUP_MOVES),
+            false => self.move_number,
+        };
+
+        if !self.stats.is_valid(flipping_moves) {
+            return Err(InvariantError::Counter);
+        }
+
+        let is_movable = |player| self.mobility(player) > 0;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => is_movable(self.next_player),
+            (Status::Ongoing, _) => false,
+            (status, EndReason::Normal) => {
+                !is_movable(Player::Player0)
+                    && !is_movable(Player::Player1)
+                    && *status == self.final_status()
+            }
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), ReversiError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), ReversiError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), ReversiError> {
+        if self.status != Status::Ongoing {
+            return Err(ReversiError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), ReversiError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(ReversiError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), ReversiError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), ReversiError> {
+        if self.status != Status::Ongoing {
+            return Err(ReversiError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Get the game with the board rotated 90 degrees clockwise, the rest of the state kept
+    ///
+    /// Rotating by 90 or 270 degrees, or mirroring, moves the starting pieces onto the other diagonals. The transformed game keeps track of it, so its moves can still be replayed, e.g. by [`write_sgf`](Self::write_sgf)
+    pub fn rotate90(&self) -> Self {
+        self.transform(|row, col| (col, BOARD_WIDTH - 1 - row))
+    }
+
+    /// Get the game with the board rotated 180 degrees, the rest of the state kept
+    pub fn rotate180(&self) -> Self {
+        self.transform(|row, col| (BOARD_WIDTH - 1 - row, BOARD_WIDTH - 1 - col))
+    }
+
+    /// Get the game with the board rotated 270 degrees clockwise, the rest of the state kept
+    pub fn rotate270(&self) -> Self {
+        self.transform(|row, col| (BOARD_WIDTH - 1 - col, row))
+    }
+
+    /// Get the game with the board mirrored left to right, the rest of the state kept
+    pub fn mirror_horizontal(&self) -> Self {
+        self.transform(|row, col| (row, BOARD_WIDTH - 1 - col))
+    }
+
+    /// Get the game with the board mirrored top to bottom, the rest of the state kept
+    pub fn mirror_vertical(&self) -> Self {
+        self.transform(|row, col| (BOARD_WIDTH - 1 - row, col))
+    }
+
+    /// Compare the game with a later state of it
+    ///
+    /// The returned diff describes how to get from `self` to `other`
+    pub fn diff(&self, other: &Self) -> BoardDiff {
+        let mut cells = [[None; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        for (row, cells) in cells.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                let (before, after) = (self.get(row, col), other.get(row, col));
+
+                if before != after {
+                    *cell = Some(CellDiff { before, after });
+                }
+            }
+        }
+
+        BoardDiff {
+            cells,
+            next_player: (self.next_player != other.next_player)
+                .then_some((self.next_player, other.next_player)),
+            status: (self.status != other.status)
+                .then(|| (self.status.clone(), other.status.clone())),
+        }
+    }
+
+    /// Get the komi, see [`Reversi::with_komi`]
+    pub const fn komi(&self) -> isize {
+        self.komi
+    }
+
+    /// Get the number of empty positions
+    pub const fn empties(&self) -> usize {
+        self.empties
+    }
+
+    /// Check if the game is of the free setup, see [`Reversi::with_free_setup`]
+    pub const fn is_free_setup(&self) -> bool {
+        self.is_free_setup
+    }
+
+    /// Check if the game is in the setup phase of the free setup, where pieces are placed onto the center without flipping
+    pub const fn is_in_setup(&self) -> bool {
+        self.is_free_setup && self.move_number < SETUP_MOVES
+    }
+
+    /// Get the number of pieces placed since the game started, passes not included
+    pub const fn move_number(&self) -> usize {
+        self.move_number
+    }
+
+    /// Get the moves played so far, in order
+    pub fn moves(&self) -> impl Iterator<Item = Move> + '_ {
+        (1..=self.move_number).filter_map(|number| {
+            self.positions()
+                .find(|(row, col)| self.order[*row][*col] as usize == number)
+                .map(|(row, col)| Move { row, col })
+        })
+    }
+
+    /// Get the statistics of the game, e.g. for achievements
+    ///
+    /// The statistics are part of the game state, so they are kept when the game is serialized
+    pub const fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Get the rules events from this state to a later one, e.g. right before and after a move, for downstream apps to map to achievements
+    ///
+    /// The events are in the order of flips, corners, passes and the win. A win is reported only when the game ended by the rules
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::reversi::{Event, Move, Player, Reversi};
+    /// let game = Reversi::new().unwrap();
+    /// let after = game.preview(Move { row: 2, col: 4 }).unwrap();
+    ///
+    /// assert!(game.events(&after).eq([Event::Flipped {
+    ///     player: Player::Player0,
+    ///     count: 1,
+    /// }]));
+    /// ```
+    #[cfg(feature = "events")]
+    pub fn events(&self, after: &Self) -> impl Iterator<Item = Event> {
+        let players = [Player::Player0, Player::Player1];
+
+        let flipped = players.map(|player| {
+            let count = self
+                .positions()
+                .filter(|(row, col)| {
+                    self.get(*row, *col) == Some(player.other())
+                        && after.get(*row, *col) == Some(player)
+                })
+                .count();
+
+            (count > 0).then_some(Event::Flipped { player, count })
+        });
+
+        let corners = CORNERS.map(
+            |(row, col)| match (self.get(row, col), after.get(row, col)) {
+                (None, Some(player)) => Some(Event::CornerTaken { player, row, col }),
+                _ => None,
+            },
+        );
+
+        let passes = players.map(|player| {
+            (after.stats.passes(player) > self.stats.passes(player))
+                .then_some(Event::Passed(player))
+        });
+
+        let won = match (&self.status, &after.status, after.end_reason) {
+            (Status::Ongoing, Status::Win(player), EndReason::Normal) => Some(Event::Won {
+                player: *player,
+                max_deficit: after.stats.max_deficit(*player),
+            }),
+            _ => None,
+        };
+
+        flipped
+            .into_iter()
+            .chain(corners)
+            .chain(passes)
+            .chain([won])
+            .flatten()
+    }
+
+    /// Get the game phase
+    pub const fn phase(&self) -> Phase {
+        if self.empties > OPENING_END {
+            Phase::Opening
+        } else if self.empties > MIDGAME_END {
+            Phase::Midgame
+        } else {
+            Phase::Endgame
+        }
+    }
+
+    /// Get the number of positions a player can put a piece at, as if it were their turn
+    ///
+    /// The moves of both players are found at once for each direction with bitwise operations on the board, so this is cheap enough to call at every node of a search. In the setup phase of the free setup, these are the empty positions in the center
+    pub fn mobility(&self, player: Player) -> usize {
+        if self.is_in_setup() {
+            return (bitboard(&self.board, None) & CENTER).count_ones() as usize;
+        }
+
+        let own = bitboard(&self.board, Some(player));
+        let other = bitboard(&self.board, Some(player.other()));
+
+        movable(own, other).count_ones() as usize
+    }
+
+    /// Check if a player can put a piece anywhere, as if it were their turn, without checking each position
+    ///
+    /// Always `false` once the game has ended, e.g. by a timeout. In the setup phase of the free setup, this holds for both players while the center has empty positions, as either can put a piece there on their turn
+    ///
+    /// [`put`](Self::put) passes on its own for a player without any move, so the next player always has one while the game is ongoing. A pass happened in the last move when the player who made it is the next player again, and to prompt it, check that `has_any_move(game.next_player().other())` is `false`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::reversi::Reversi;
+    /// let game = Reversi::new().unwrap();
+    ///
+    /// assert!(game.has_any_move(game.next_player()));
+    /// assert!(game.has_any_move(game.next_player().other()));
+    /// ```
+    pub fn has_any_move(&self, player: Player) -> bool {
+        self.status == Status::Ongoing && self.mobility(player) > 0
+    }
+
+    /// Iterate over the regions of empty positions, in row-major order of their first position
+    ///
+    /// The parity of the regions guides the endgame: the player who moves last in a region with an odd number of positions usually gains from it
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::reversi::Reversi;
+    /// let game = Reversi::new().unwrap();
+    ///
+    /// assert_eq!(game.regions().count(), 1);
+    /// assert_eq!(game.odd_region_count(), 0);
+    /// ```
+    pub fn regions(&self) -> impl Iterator<Item = Region> {
+        let empty = bitboard(&self.board, None);
+        let mut remaining = empty;
+
+        core::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+
+            // grow the lowest remaining position until the region stops growing
+            let mut positions = remaining & remaining.wrapping_neg();
+
+            loop {
+                let grown = DIRECTIONS.iter().fold(positions, |grown, direction| {
+                    grown | shift(positions, *direction)
+                }) & empty;
+
+                if grown == positions {
+                    break;
+                }
+
+                positions = grown;
+            }
+
+            remaining &= !positions;
+            Some(Region { positions })
+        })
+    }
+
+    /// Get the number of regions with an odd number of empty positions, see [`Reversi::regions`]
+    pub fn odd_region_count(&self) -> usize {
+        self.regions().filter(Region::is_odd).count()
+    }
+
+    /// Check if the piece at a position is stable, which means it can never be flipped for the rest of the game
+    ///
+    /// A piece is considered stable if, along each of the four lines through it, the line is full, or it is next to the edge or a stable piece of the same player. This can miss some stable pieces, but never reports a piece that can still be flipped
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn is_stable(&self, row: usize, col: usize) -> bool {
+        self.stable_pieces()[row][col]
+    }
+
+    /// Get the number of stable pieces of a player, see [`Reversi::is_stable`]
+    pub fn stable_count(&self, player: Player) -> usize {
+        let stable = self.stable_pieces();
+
+        self.positions()
+            .filter(|(row, col)| stable[*row][*col] && self.get(*row, *col) == Some(player))
+            .count()
+    }
+
+    /// Get the number of frontier pieces of a player, which are the pieces adjacent to an empty position
+    pub fn frontier_count(&self, player: Player) -> usize {
+        self.positions()
+            .filter(|(row, col)| self.get(*row, *col) == Some(player))
+            .filter(|(row, col)| {
+                DIRECTIONS.iter().any(|direction| {
+                    let (row, col) = (*row as isize + direction.0, *col as isize + direction.1);
+                    matches!(self.get_signed(row, col), Some(None))
+                })
+            })
+            .count()
+    }
+
+    /// Get the number of corners occupied by a player
+    pub fn corner_count(&self, player: Player) -> usize {
+        CORNERS
+            .into_iter()
+            .filter(|(row, col)| self.get(*row, *col) == Some(player))
+            .count()
+    }
+
+    /// Get the number of edge positions occupied by a player, corners excluded
+    pub fn edge_count(&self, player: Player) -> usize {
+        self.positions()
+            .filter(|(row, col)| {
+                let is_row_edge = *row == 0 || *row == BOARD_HEIGHT - 1;
+                let is_col_edge = *col == 0 || *col == BOARD_WIDTH - 1;
+                is_row_edge != is_col_edge
+            })
+            .filter(|(row, col)| self.get(*row, *col) == Some(player))
+            .count()
+    }
+
+    fn stable_pieces(&self) -> [[bool; BOARD_WIDTH]; BOARD_HEIGHT] {
+        let mut stable = [[false; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        // a piece becoming stable may anchor its neighbors, so repeat until nothing changes
+        loop {
+            let mut is_changed = false;
+
+            for (row, col) in self.positions() {
+                let Some(player) = self.get(row, col) else {
+                    continue;
+                };
+
+                if stable[row][col] {
+                    continue;
+                }
+
+                let is_stable = DIRECTIONS[..4].iter().all(|&(row_step, col_step)| {
+                    let is_anchored = |row_step: isize, col_step: isize| {
+                        let (row, col) = (row as isize + row_step, col as isize + col_step);
+
+                        match self.get_signed(row, col) {
+                            None => true,
+                            Some(piece) => {
+                                piece == Some(player) && stable[row as usize][col as usize]
+                            }
+                        }
+                    };
+
+                    let is_full = |row_step: isize, col_step: isize| {
+                        (1..)
+                            .map(|distance| {
+                                self.get_signed(
+                                    row as isize + row_step * distance,
+                                    col as isize + col_step * distance,
+                                )
+                            })
+                            .take_while(Option::is_some)
+                            .all(|piece| piece.flatten().is_some())
+                    };
+
+                    is_anchored(row_step, col_step)
+                        || is_anchored(-row_step, -col_step)
+                        || (is_full(row_step, col_step) && is_full(-row_step, -col_step))
+                });
+
+                if is_stable {
+                    stable[row][col] = true;
+                    is_changed = true;
+                }
+            }
+
+            if !is_changed {
+                return stable;
+            }
+        }
+    }
+
+    /// Move each piece and its move number to the position mapped by `to`, which is a rotation or a mirroring
+    fn transform(&self, to: impl Fn(usize, usize) -> (usize, usize)) -> Self {
+        let mut game = self.clone();
+
+        if let Some(composition) = &mut game.composition {
+            composition.pieces = [0; 2];
+        }
+
+        for (row, col) in self.positions() {
+            let (to_row, to_col) = to(row, col);
+            game.board[to_row][to_col] = self.board[row][col];
+            game.order[to_row][to_col] = self.order[row][col];
+
+            if let (Some(from), Some(composition)) = (self.composition, &mut game.composition) {
+                for (to_pieces, pieces) in composition.pieces.iter_mut().zip(from.pieces) {
+                    *to_pieces |= (pieces >> (row * BOARD_WIDTH + col) & 1)
+                        << (to_row * BOARD_WIDTH + to_col);
+                }
+            }
+        }
+
+        // only rotating by 180 degrees keeps both diagonals of the center in place
+        let (center_row, center_col) = to(BOARD_HEIGHT / 2, BOARD_WIDTH / 2);
+        let is_diagonal_kept = center_row == center_col;
+
+        if !self.is_free_setup && self.composition.is_none() && !is_diagonal_kept {
+            game.is_setup_mirrored = !self.is_setup_mirrored;
+        }
+
+        game
+    }
+
+    /// The fields telling positions apart, see the `PartialEq` implementation
+    #[allow(clippy::type_complexity)]
+    const fn position_key(
+        &self,
+    ) -> (
+        &[[Option<Player>; BOARD_HEIGHT]; BOARD_WIDTH],
+        Player,
+        &Status,
+        EndReason,
+        Option<Player>,
+        isize,
+        bool,
+        bool,
+    ) {
+        (
+            &self.board,
+            self.next_player,
+            &self.status,
+            self.end_reason,
+            self.draw_offer,
+            self.komi,
+            self.is_free_setup,
+            self.is_setup_mirrored,
+        )
+    }
+
+    /// Get a piece at a signed position, `None` if out of bounds
+    fn get_signed(&self, row: isize, col: isize) -> Option<Option<Player>> {
+        let is_in_bounds =
+            (0..BOARD_HEIGHT as isize).contains(&row) && (0..BOARD_WIDTH as isize).contains(&col);
+
+        is_in_bounds.then(|| self.get(row as usize, col as usize))
+    }
+
+    /// Place a piece of the next player and pass the turn, flipping is done by the caller
+    fn place(&mut self, row: usize, col: usize) {
+        self.board[row][col] = Some(self.next_player);
+        self.empties -= 1;
+        self.move_number += 1;
+        self.order[row][col] = self.move_number as u8;
+
+        self.draw_offer = None;
+        self.next_player = self.next_player.other();
+    }
+
+    /// Create a composed game from its starting position
+    fn from_composition(composition: Composition) -> Self {
+        let mut game = Self::initial();
+
+        for (row, col) in game.positions() {
+            let bit = 1 << (row * BOARD_WIDTH + col);
+
+            game.board[row][col] = [Player::Player0, Player::Player1]
+                .into_iter()
+                .find(|player| composition.pieces[*player as usize] & bit != 0);
+        }
+
+        game.empties = bitboard(&game.board, None).count_ones() as usize;
+        game.next_player = composition.next_player;
+        game.composition = Some(composition);
+        game
+    }
+
+    /// Check if a position holds a piece of the starting position, which is not numbered
+    fn is_starting_position(&self, row: usize, col: usize) -> bool {
+        match self.composition {
+            Some(Composition { pieces, .. }) => {
+                (pieces[0] | pieces[1]) & 1 << (row * BOARD_WIDTH + col) != 0
+            }
+            None => !self.is_free_setup && is_center(row, col),
+        }
+    }
+
+    fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        (0..BOARD_HEIGHT).flat_map(|row| (0..BOARD_WIDTH).map(move |col| (row, col)))
+    }
+
+    /// The number of pieces of each player on the board
+    fn piece_counts(&self) -> [usize; 2] {
+        let mut counts = [0; 2];
+
+        for (row, col) in self.positions() {
+            if let Some(player) = self.get(row, col) {
+                counts[player as usize] += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// The status by the score, once both players cannot move
+    fn final_status(&self) -> Status {
+        let [player0_count, player1_count] = self.piece_counts().map(|count| count as isize);
+
+        match player0_count.cmp(&(player1_count + self.komi)) {
+            Ordering::Greater => Status::Win(Player::Player0),
+            Ordering::Less => Status::Win(Player::Player1),
+            Ordering::Equal => Status::Draw,
+        }
+    }
+
+    /// Flip the pieces of the other player bracketed along a line, return the pieces flipped as a bitboard
+    fn flip_in_line(&mut self, line: impl Iterator<Item = (usize, usize)> + Clone) -> u64 {
+        let mut skipped = 0;
+
+        let Some((row, col)) = line.clone().find(|(row, col)| {
+            let is_other_player = self.get(*row, *col) == Some(self.next_player().other());
+            skipped += is_other_player as usize;
+            !is_other_player
+        }) else {
+            return 0;
+        };
+
+        if skipped == 0 || self.get(row, col) != Some(self.next_player()) {
+            return 0;
+        }
+
+        let mut flipped = 0;
+
+        for (row, col) in line.take(skipped) {
+            self.board[row][col] = Some(self.next_player());
+            flipped |= 1 << (row * BOARD_WIDTH + col);
+        }
+
+        flipped
+    }
+
+    /// The error of a position where no piece is flipped
+    fn invalid_position(&self, row: usize, col: usize) -> ReversiError {
+        let rows = row.saturating_sub(1)..=(row + 1).min(BOARD_HEIGHT - 1);
+        let cols = col.saturating_sub(1)..=(col + 1).min(BOARD_WIDTH - 1);
+
+        let has_adjacent_piece = rows
+            .flat_map(|row| cols.clone().map(move |col| (row, col)))
+            .any(|(row, col)| self.get(row, col) == Some(self.next_player.other()));
+
+        let reason = if has_adjacent_piece {
+            InvalidReason::NoBracketingLine
+        } else {
+            InvalidReason::NoAdjacentPiece
+        };
+
+        ReversiError::InvalidPosition { reason }
+    }
+
+    fn is_clipping_in_line(&self, mut line: impl Iterator<Item = (usize, usize)>) -> bool {
+        let mut skipped = false;
+
+        let Some((row, col)) = line.find(|(row, col)| {
+            let is_other_player = self.get(*row, *col) == Some(self.next_player().other());
+            skipped |= is_other_player;
+            !is_other_player
+        }) else {
+            return false;
+        };
+
+        skipped && self.get(row, col) == Some(self.next_player())
+    }
+}
+
+impl Affects for Reversi {
+    /// The pieces flipped, each being [`Effect::Flipped`]
+    fn affected<'a>(&'a self, after: &'a Self) -> impl Iterator<Item = Affected> + 'a {
+        self.positions()
+            .filter(
+                |(row, col)| match (self.board[*row][*col], after.board[*row][*col]) {
+                    (Some(before), Some(after)) => before != after,
+                    _ => false,
+                },
+            )
+            .map(|(row, col)| Affected {
+                row,
+                col,
+                effect: Effect::Flipped,
+            })
+    }
+}
+
+impl Default for Reversi {
+    fn default() -> Self {
+        Self::initial()
+    }
+}
+
+/// Check if a position is in the center 2x2 square, where the pieces of the setup are placed
+const fn is_center(row: usize, col: usize) -> bool {
+    (row == BOARD_HEIGHT / 2 - 1 || row == BOARD_HEIGHT / 2)
+        && (col == BOARD_WIDTH / 2 - 1 || col == BOARD_WIDTH / 2)
+}
+
+/// Get the positions of a player as one bit each in row-major order, or the empty positions for `None`
+fn bitboard(board: &[[Option<Player>; BOARD_WIDTH]; BOARD_HEIGHT], piece: Option<Player>) -> u64 {
+    board
+        .iter()
+        .flatten()
+        .enumerate()
+        .filter(|(_, cell)| **cell == piece)
+        .fold(0, |bits, (idx, _)| bits | 1 << idx)
+}
+
+/// Move every position one step in a direction, dropping the positions stepping off the board
+const fn shift(bits: u64, (row_step, col_step): (isize, isize)) -> u64 {
+    let amount = row_step * BOARD_WIDTH as isize + col_step;
+
+    let shifted = if amount > 0 {
+        bits << amount
+    } else {
+        bits >> -amount
+    };
+
+    // a position stepping off the side wraps around to the other side of the next row
+    match col_step {
+        1 => shifted & !FIRST_COL,
+        -1 => shifted & !LAST_COL,
+        _ => shifted,
+    }
+}
+
+/// Get the empty positions where a piece of `own` brackets pieces of `other` along a line
+fn movable(own: u64, other: u64) -> u64 {
+    let empty = !(own | other);
+
+    DIRECTIONS.iter().fold(0, |moves, direction| {
+        // a line holds at most 6 pieces of the other player between the two ends
+        let mut bracketed = shift(own, *direction) & other;
+
+        for _ in 0..BOARD_WIDTH - 3 {
+            bracketed |= shift(bracketed, *direction) & other;
+        }
+
+        moves | shift(bracketed, *direction) & empty
+    })
+}
+
+impl Region {
+    /// Get the number of empty positions in the region
+    pub const fn size(&self) -> usize {
+        self.positions.count_ones() as usize
+    }
+
+    /// Check if the region has an odd number of empty positions
+    pub const fn is_odd(&self) -> bool {
+        self.size() % 2 == 1
+    }
+
+    /// Check if a position is in the region
+    ///
+    /// Panic if the position is out of bounds
+    pub const fn contains(&self, row: usize, col: usize) -> bool {
+        assert!(row < BOARD_HEIGHT && col < BOARD_WIDTH);
+        self.positions & 1 << (row * BOARD_WIDTH + col) != 0
+    }
+
+    /// Iterate over the positions in the region as `(row, col)`, in row-major order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let positions = self.positions;
+
+        (0..BOARD_HEIGHT * BOARD_WIDTH)
+            .filter(move |idx| positions & 1 << idx != 0)
+            .map(|idx| (idx / BOARD_WIDTH, idx % BOARD_WIDTH))
+    }
+}
+
+impl BoardDiff {
+    /// Iterate over the changed positions as `(row, col, diff)`, in row-major order
+    pub fn changes(&self) -> impl Iterator<Item = (usize, usize, CellDiff)> + '_ {
+        self.cells.iter().enumerate().flat_map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .filter_map(move |(col, cell)| cell.map(|cell| (row, col, cell)))
+        })
+    }
+
+    /// Check if the two game states are identical
+    pub fn is_empty(&self) -> bool {
+        self.changes().next().is_none() && self.next_player.is_none() && self.status.is_none()
+    }
+}
+
+impl Stats {
+    /// Get the total number of pieces flipped by a player
+    pub const fn flips(&self, player: Player) -> usize {
+        self.flips[player as usize]
+    }
+
+    /// Get the most pieces flipped by a single move of a player
+    pub const fn max_flips(&self, player: Player) -> usize {
+        self.max_flips[player as usize]
+    }
+
+    /// Get the number of turns a player passed, as they could not move
+    pub const fn passes(&self, player: Player) -> usize {
+        self.passes[player as usize]
+    }
+
+    /// Get the most pieces a player trailed by on the board after a move, komi not included
+    pub const fn max_deficit(&self, player: Player) -> usize {
+        self.max_deficit[player as usize]
+    }
+
+    const fn record_flips(&mut self, player: Player, flips: usize) {
+        self.flips[player as usize] += flips;
+
+        if flips > self.max_flips[player as usize] {
+            self.max_flips[player as usize] = flips;
+        }
+    }
+
+    const fn record_deficits(&mut self, counts: [usize; 2]) {
+        let mut player = 0;
+
+        while player < 2 {
+            let deficit = counts[1 - player].saturating_sub(counts[player]);
+
+            if deficit > self.max_deficit[player] {
+                self.max_deficit[player] = deficit;
+            }
+
+            player += 1;
+        }
+    }
+
+    fn is_valid(&self, flipping_moves: usize) -> bool {
+        (0..2).all(|player| {
+            self.max_flips[player] <= self.flips[player]
+                && (self.max_flips[player] == 0) == (self.flips[player] == 0)
+                && self.max_deficit[player] < BOARD_WIDTH * BOARD_HEIGHT
+        }) && self.flips[0] + self.flips[1] >= flipping_moves
+    }
+}
+
+impl Player {
+    /// Get the other player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} {}", self.row, self.col)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Reversi {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    pub fn random_position<R: GameRng + ?Sized>(rng: &mut R, plies: usize) -> Self {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        game.play_random_position(rng, plies);
+        game
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for Reversi {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let moves = testing::positions(BOARD_HEIGHT, BOARD_WIDTH)
+            .filter(|&(row, col)| self.can_put(row, col));
+        testing::choose(rng, moves).map(|(row, col)| Move { row, col })
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{code::Code, reversi::*};
+
+    #[test]
+    fn test() {
+        assert_eq!(Reversi::default(), Reversi::new().unwrap());
+
+        let mut game = Reversi::initial();
+
+        game.is_position_valid_for_put(2, 4).unwrap();
+
+        let before = game.clone();
+
+        game.put(2, 4).unwrap();
+
+        let diff = before.diff(&game);
+        assert_eq!(
+            diff.cells[3][4],
+            Some(CellDiff {
+                before: Some(Player::Player1),
+                after: Some(Player::Player0),
+            })
+        );
+        assert_eq!(diff.changes().count(), 2);
+        assert!(before.affected(&game).eq([Affected {
+            row: 3,
+            col: 4,
+            effect: Effect::Flipped,
+        }]));
+        assert!(before
+            .clone()
+            .put_with_effects(2, 4)
+            .unwrap()
+            .eq(before.affected(&game)));
+
+        game.apply(Move { row: 2, col: 3 }).unwrap();
+        assert_eq!(game.stats().flips(Player::Player0), 1);
+        assert_eq!(game.stats().max_flips(Player::Player1), 1);
+        assert_eq!(game.stats().passes(Player::Player0), 0);
+
+        assert_eq!(game.put(2, 3), Err(ReversiError::PositionOccupied));
+        assert_eq!(
+            game.put(1, 3),
+            Err(ReversiError::InvalidPosition {
+                reason: InvalidReason::NoBracketingLine
+            })
+        );
+        assert_eq!(
+            game.put(2, 6),
+            Err(ReversiError::InvalidPosition {
+                reason: InvalidReason::NoAdjacentPiece
+            })
+        );
+        assert_eq!(InvalidReason::NoBracketingLine.code(), "no_bracketing_line");
+
+        assert_eq!(
+            Reversi::from_moves(&[(2, 4), (2, 3), (8, 0)]).unwrap_err(),
+            (2, ReversiError::OutOfBounds)
+        );
+
+        assert_eq!(game.empties(), 58);
+        assert_eq!(game.move_number(), 2);
+        assert_eq!(game.phase(), Phase::Opening);
+
+        assert_eq!(game.stable_count(Player::Player0), 0);
+        assert_eq!(game.frontier_count(Player::Player1), 3);
+        assert_eq!(game.corner_count(Player::Player0), 0);
+        assert_eq!(game.edge_count(Player::Player0), 0);
+
+        // the moves are found at once with bitwise operations, which must agree with checking each position
+        for player in [Player::Player0, Player::Player1] {
+            let mut turn = game.clone();
+            turn.next_player = player;
+
+            for (row, col) in turn.positions() {
+                assert_eq!(
+                    turn.can_put(row, col),
+                    turn.is_position_valid_for_put(row, col).is_ok()
+                );
+            }
+
+            let movable = turn
+                .positions()
+                .filter(|(row, col)| turn.is_position_valid_for_put(*row, *col).is_ok());
+            assert_eq!(game.mobility(player), movable.count());
+        }
+        assert_eq!(game.mobility(Player::Player0), 5);
+
+        for (row, col) in game.positions() {
+            assert_eq!(unsafe { game.get_unchecked(row, col) }, game.get(row, col));
+        }
+
+        assert!(game
+            .moves()
+            .eq([Move { row: 2, col: 4 }, Move { row: 2, col: 3 }]));
+
+        // the shortest game, where Player0 wins 13 to 0
+        let wipeout = [
+            (2, 4),
+            (2, 3),
+            (1, 2),
+            (1, 5),
+            (1, 4),
+            (2, 5),
+            (4, 2),
+            (1, 3),
+            (1, 6),
+        ];
+
+        for (komi, status) in [
+            (12, Status::Win(Player::Player0)),
+            (13, Status::Draw),
+            (14, Status::Win(Player::Player1)),
+        ] {
+            let mut game = Reversi::with_komi(komi).unwrap();
+            let mut before = game.clone();
+
+            for (row, col) in wipeout {
+                before = game.clone();
+                game.put(row, col).unwrap();
+            }
+
+            assert_eq!(game.status(), &status);
+
+            if komi == 12 {
+                let composed = Reversi::from_board(game.board, game.next_player).unwrap();
+                assert!(composed.diff(&game).is_empty());
+
+                assert_eq!(game.stats().max_deficit(Player::Player0), 0);
+                assert_eq!(game.mobility(Player::Player1), 0);
+                assert!(!game.has_any_move(Player::Player0));
+                assert_eq!(game.odd_region_count(), 1);
+                assert!(before.events(&game).any(|event| event
+                    == Event::Won {
+                        player: Player::Player0,
+                        max_deficit: 0
+                    }));
+            }
+        }
+
+        let read = Reversi::from_sgf("(;GM[2];B[dc];W[ec])").unwrap();
+        assert!(read.diff(&game).is_empty());
+        assert_eq!(
+            Reversi::from_sgf("(;GM[2];W[dc])").unwrap_err(),
+            SgfError::WrongPlayer { index: 0 }
+        );
+
+        let rotated = game.rotate90();
+        assert_eq!(rotated.get(4, 5), Some(Player::Player0));
+        assert_eq!(rotated.validate(), Ok(()));
+        assert!(rotated.rotate270().diff(&game).is_empty());
+        assert!(rotated.rotate90().diff(&game.rotate180()).is_empty());
+        assert!(game
+            .mirror_vertical()
+            .diff(&game.rotate90().mirror_vertical().rotate90())
+            .is_empty());
+
+        let mut sgf = alloc::string::String::new();
+        rotated.write_sgf(&mut sgf).unwrap();
+        assert_eq!(sgf, "(;FF[4]GM[2]SZ[8]AB[dd][ee]AW[de][ed];B[ce];W[cd])");
+
+        // a composed game starts from its board, which is written as setup properties
+        let mut composed = Reversi::from_board(game.board, game.next_player).unwrap();
+        assert_eq!(composed.moves().count(), 0);
+
+        let (row, col) = composed
+            .positions()
+            .find(|(row, col)| composed.can_put(*row, *col))
+            .unwrap();
+        composed.put(row, col).unwrap();
+        assert!(composed.moves().eq([Move { row, col }]));
+        assert_eq!(composed.validate(), Ok(()));
+        assert_eq!(composed.rotate90().validate(), Ok(()));
+
+        let mut sgf = alloc::string::String::new();
+        composed.write_sgf(&mut sgf).unwrap();
+        assert_eq!(
+            sgf,
+            "(;FF[4]GM[2]SZ[8]AB[dc][dd][de]AW[ec][ed][ee]PL[B];B[fb])"
+        );
+
+        let read = Reversi::from_sgf(&sgf).unwrap();
+        assert!(read.diff(&composed).is_empty());
+        assert!(read.moves().eq(composed.moves()));
+
+        let mut game = Reversi::with_free_setup().unwrap();
+        assert!(game.is_in_setup());
+        assert_eq!(game.empties(), 64);
+        assert_eq!(
+            game.put(2, 3),
+            Err(ReversiError::InvalidPosition {
+                reason: InvalidReason::OutsideCenter
+            })
+        );
+
+        // the diagonal arrangement with the colors swapped
+        for (row, col) in [(3, 4), (3, 3), (4, 3), (4, 4)] {
+            game.put(row, col).unwrap();
+        }
+
+        assert!(!game.is_in_setup());
+        assert_eq!(game.get(3, 3), Some(Player::Player1));
+        assert!(matches!(
+            game.put(2, 4),
+            Err(ReversiError::InvalidPosition { .. })
+        ));
+        game.put(2, 3).unwrap();
+        assert_eq!(game.get(3, 3), Some(Player::Player0));
+    }
+}
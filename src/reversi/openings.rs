@@ -0,0 +1,140 @@
+//! Named Reversi openings
+//!
+//! Check constant [`OPENINGS`] and method [`Reversi::opening`] for more information
+
+use super::{Move, Reversi, BOARD_HEIGHT, BOARD_WIDTH};
+
+/// A named opening
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Opening {
+    pub name: &'static str,
+    /// The moves of the opening in the standard Othello notation, starting with `f5`
+    ///
+    /// The standard starting position has the colors swapped compared to the one of this crate, so the columns are mirrored, where column `a` is column `7`
+    pub moves: &'static str,
+}
+
+/// Well-known openings, as listed by most opening books
+pub const OPENINGS: &[Opening] = &[
+    Opening {
+        name: "Diagonal Opening",
+        moves: "f5f6",
+    },
+    Opening {
+        name: "Perpendicular Opening",
+        moves: "f5d6",
+    },
+    Opening {
+        name: "Parallel Opening",
+        moves: "f5f4",
+    },
+    Opening {
+        name: "Tiger",
+        moves: "f5d6c3d3c4",
+    },
+    Opening {
+        name: "Buffalo",
+        moves: "f5d6c3d3c4f4c5b3c2",
+    },
+];
+
+type Symmetry = fn(usize, usize) -> (usize, usize);
+
+// the symmetries of the starting position, which map the four possible first moves onto each other
+const SYMMETRIES: [Symmetry; 4] = [
+    |row, col| (row, col),
+    |row, col| (col, row),
+    |row, col| (BOARD_HEIGHT - 1 - row, BOARD_WIDTH - 1 - col),
+    |row, col| (BOARD_WIDTH - 1 - col, BOARD_HEIGHT - 1 - row),
+];
+
+impl Opening {
+    /// Iterate over the moves of the opening
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::reversi::{openings::OPENINGS, Move, Reversi};
+    /// let tiger = OPENINGS
+    ///     .iter()
+    ///     .find(|opening| opening.name == "Tiger")
+    ///     .unwrap();
+    /// let moves = tiger
+    ///     .moves()
+    ///     .map(|Move { row, col }| (row, col))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let game = Reversi::from_moves(&moves).unwrap();
+    /// assert_eq!(game.opening(), Some(tiger));
+    /// ```
+    pub fn moves(&self) -> impl Iterator<Item = Move> {
+        self.moves.as_bytes().chunks(2).map(|mv| Move {
+            row: (mv[1] - b'1') as usize,
+            col: BOARD_WIDTH - 1 - (mv[0] - b'a') as usize,
+        })
+    }
+}
+
+impl Reversi {
+    /// Get the longest named opening the moves played so far follow, in any of the four symmetric orientations of the board, `None` for a composed game
+    ///
+    /// Check [`OPENINGS`] for the known openings
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::reversi::Reversi;
+    /// // `d3` `e3`, the Parallel Opening turned over a diagonal
+    /// let game = Reversi::from_moves(&[(2, 4), (2, 3)]).unwrap();
+    ///
+    /// assert_eq!(game.opening().unwrap().name, "Parallel Opening");
+    /// ```
+    pub fn opening(&self) -> Option<&'static Opening> {
+        // a composed game did not start from the usual position
+        if self.composition.is_some() {
+            return None;
+        }
+
+        let first = self.moves().next()?;
+
+        // exactly one symmetry maps the first move onto `f5`
+        let symmetry = SYMMETRIES.into_iter().find(|symmetry| {
+            let f5 = OPENINGS[0].moves().next().unwrap();
+            symmetry(first.row, first.col) == (f5.row, f5.col)
+        })?;
+
+        OPENINGS
+            .iter()
+            .filter(|opening| {
+                opening.moves().count() <= self.move_number
+                    && opening.moves().zip(self.moves()).all(|(expected, mv)| {
+                        symmetry(mv.row, mv.col) == (expected.row, expected.col)
+                    })
+            })
+            .max_by_key(|opening| opening.moves.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reversi::{openings::*, Reversi};
+
+    #[test]
+    fn test() {
+        for opening in OPENINGS {
+            let mut game = Reversi::new().unwrap();
+
+            for Move { row, col } in opening.moves() {
+                game.put(row, col).unwrap();
+            }
+
+            assert_eq!(game.opening(), Some(opening));
+        }
+
+        // `f5` `d6` `c3` `d3` turned by 180 degrees, one move short of the Tiger
+        let game = Reversi::from_moves(&[(3, 5), (2, 3), (5, 2), (5, 3)]).unwrap();
+        assert_eq!(game.opening().unwrap().name, "Perpendicular Opening");
+
+        assert_eq!(Reversi::new().unwrap().opening(), None);
+    }
+}
@@ -0,0 +1,51 @@
+//! Random number sources for the chance-based games
+//!
+//! Check trait [`GameRng`] for more information
+
+/// A source of random numbers, as used to place mines and shuffle boards
+///
+/// With the `rand` feature, this is implemented for every [`rand::Rng`]. Without it, a custom generator, e.g. a deterministic one seeded by a server, only needs to implement [`gen_below`](GameRng::gen_below)
+///
+/// # Examples
+///
+/// ```rust
+/// use gamie::rng::GameRng;
+///
+/// // a linear congruential generator, for illustration only
+/// struct Lcg(u64);
+///
+/// impl GameRng for Lcg {
+///     fn gen_below(&mut self, bound: usize) -> usize {
+///         self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1);
+///         ((self.0 >> 33) % bound as u64) as usize
+///     }
+/// }
+///
+/// let mut cells = [0, 1, 2, 3, 4];
+/// Lcg(0).shuffle(&mut cells);
+///
+/// cells.sort();
+/// assert_eq!(cells, [0, 1, 2, 3, 4]);
+/// ```
+pub trait GameRng {
+    /// Get a random number in `0..bound`
+    ///
+    /// `bound` is never `0`
+    fn gen_below(&mut self, bound: usize) -> usize;
+
+    /// Shuffle a slice in place with the Fisher-Yates shuffle
+    fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for idx in (1..slice.len()).rev() {
+            slice.swap(idx, self.gen_below(idx + 1));
+        }
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<R: rand::Rng + ?Sized> GameRng for R {
+    fn gen_below(&mut self, bound: usize) -> usize {
+        use rand::distributions::{Distribution, Uniform};
+
+        Uniform::from(0..bound).sample(self)
+    }
+}
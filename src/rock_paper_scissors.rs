@@ -0,0 +1,530 @@
+//! Rock Paper Scissors
+//!
+//! Check struct [`RockPaperScissors`] for more information
+
+use crate::{
+    invariant::InvariantError,
+    outcome::{AdjudicationReason, EndReason},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use snafu::Snafu;
+
+const DEFAULT_BEST_OF: usize = 3;
+
+/// Rock Paper Scissors, played as the best of a number of rounds
+///
+/// The moves are simultaneous. Both players submit a hand in any order, and the round is resolved once both have. A tied round does not count, and the first player to win more than half of the rounds wins the game
+///
+/// The submitted hand of a player is part of the game state until the round is resolved, so a server should not send the whole game to the other player
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::rock_paper_scissors::{Hand, Player, RockPaperScissors, Round};
+/// let mut game = RockPaperScissors::new().unwrap();
+///
+/// assert_eq!(game.submit(Player::Player1, Hand::Paper), Ok(None));
+/// assert_eq!(
+///     game.submit(Player::Player0, Hand::Rock),
+///     Ok(Some(Round {
+///         hands: [Hand::Rock, Hand::Paper],
+///         winner: Some(Player::Player1),
+///     }))
+/// );
+/// assert_eq!(game.wins(Player::Player1), 1);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RockPaperScissors {
+    best_of: usize,
+    wins: [usize; 2],
+    ties: usize,
+    hands: [Option<Hand>; 2],
+    last_round: Option<Round>,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(RockPaperScissors);
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// A hand a player can show
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Hand {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    /// Only reached by agreement or adjudication, as tied rounds are replayed
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Hand {
+    0 => Rock,
+    1 => Paper,
+    2 => Scissors,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::encode_struct!(Round { hands, winner });
+
+crate::canonical::canonical!(RockPaperScissors, "rock_paper_scissors", 1 {
+    best_of,
+    wins,
+    ties,
+    hands,
+    last_round,
+    status,
+    end_reason,
+    draw_offer,
+});
+
+/// A resolved round
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Round {
+    /// The hands of [`Player::Player0`] and [`Player::Player1`]
+    pub hands: [Hand; 2],
+    /// The winner of the round, `None` for a tie
+    pub winner: Option<Player>,
+}
+
+/// A move, which submits the hand of a player for the current round
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Move {
+    pub player: Player,
+    pub hand: Hand,
+}
+
+/// Errors that can occur when submitting a hand
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum RockPaperScissorsError {
+    #[snafu(display("hand already submitted"))]
+    AlreadySubmitted,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("number of rounds must be odd"))]
+    EvenRounds,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+}
+
+crate::code::codes!(RockPaperScissorsError {
+    AlreadySubmitted => "already_submitted",
+    GameEnded => "game_ended",
+    EvenRounds => "even_rounds",
+    NoDrawOffer => "no_draw_offer",
+});
+
+impl RockPaperScissors {
+    /// Create a new game of the best of 3 rounds
+    pub const fn new() -> Result<Self, RockPaperScissorsError> {
+        Self::with_best_of(DEFAULT_BEST_OF)
+    }
+
+    /// Create a new game of the best of `rounds` rounds
+    ///
+    /// Return `Err(RockPaperScissorsError::EvenRounds)` if `rounds` is even, including `0`, as the winner would not always be decided by more than half of the rounds
+    pub const fn with_best_of(rounds: usize) -> Result<Self, RockPaperScissorsError> {
+        if rounds.is_multiple_of(2) {
+            return Err(RockPaperScissorsError::EvenRounds);
+        }
+
+        Ok(Self {
+            best_of: rounds,
+            wins: [0; 2],
+            ties: 0,
+            hands: [None; 2],
+            last_round: None,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+        })
+    }
+
+    /// Submit the hand of a player for the current round
+    ///
+    /// Return the resolved round if the other player has already submitted, `None` otherwise
+    pub const fn submit(
+        &mut self,
+        player: Player,
+        hand: Hand,
+    ) -> Result<Option<Round>, RockPaperScissorsError> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(RockPaperScissorsError::GameEnded);
+        }
+
+        if self.hands[player as usize].is_some() {
+            return Err(RockPaperScissorsError::AlreadySubmitted);
+        }
+
+        self.hands[player as usize] = Some(hand);
+
+        let [Some(player0_hand), Some(player1_hand)] = self.hands else {
+            return Ok(None);
+        };
+
+        let winner = if player0_hand.beats(player1_hand) {
+            Some(Player::Player0)
+        } else if player1_hand.beats(player0_hand) {
+            Some(Player::Player1)
+        } else {
+            None
+        };
+
+        match winner {
+            Some(player) => {
+                self.wins[player as usize] += 1;
+
+                if self.wins[player as usize] * 2 > self.best_of {
+                    self.status = Status::Win(player);
+                }
+            }
+            None => self.ties += 1,
+        }
+
+        let round = Round {
+            hands: [player0_hand, player1_hand],
+            winner,
+        };
+
+        self.hands = [None; 2];
+        self.last_round = Some(round);
+        self.draw_offer = None;
+
+        Ok(Some(round))
+    }
+
+    /// Check if a player can submit a hand, same as [`submit`](Self::submit) succeeding but without changing the game
+    pub const fn can_submit(&self, player: Player) -> bool {
+        !matches!(self.status, Status::Win(_) | Status::Draw)
+            && self.hands[player as usize].is_none()
+    }
+
+    /// Apply a move, same as [`submit`](Self::submit) without returning the round
+    pub const fn apply(&mut self, mv: Move) -> Result<(), RockPaperScissorsError> {
+        match self.submit(mv.player, mv.hand) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    pub fn preview(&self, mv: Move) -> Result<Self, RockPaperScissorsError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Check if a player has submitted a hand for the current round
+    pub const fn has_submitted(&self, player: Player) -> bool {
+        self.hands[player as usize].is_some()
+    }
+
+    /// Get the number of rounds the game is the best of
+    pub const fn best_of(&self) -> usize {
+        self.best_of
+    }
+
+    /// Get the number of rounds won by a player
+    pub const fn wins(&self, player: Player) -> usize {
+        self.wins[player as usize]
+    }
+
+    /// Get the number of tied rounds
+    pub const fn ties(&self) -> usize {
+        self.ties
+    }
+
+    /// Get the number of resolved rounds, ties included
+    pub const fn rounds_played(&self) -> usize {
+        self.wins[0] + self.wins[1] + self.ties
+    }
+
+    /// Get the last resolved round
+    pub const fn last_round(&self) -> Option<Round> {
+        self.last_round
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// A round with both hands submitted must have been resolved, the last round must match the counters, and a status ended by the rules must match the wins
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        if self.best_of.is_multiple_of(2) {
+            return Err(InvariantError::Counter);
+        }
+
+        if self.hands[0].is_some() && self.hands[1].is_some() {
+            return Err(InvariantError::Board);
+        }
+
+        let is_last_round_valid = match self.last_round {
+            None => self.rounds_played() == 0,
+            Some(Round { hands, winner }) => {
+                let expected = match (hands[0].beats(hands[1]), hands[1].beats(hands[0])) {
+                    (true, _) => Some(Player::Player0),
+                    (_, true) => Some(Player::Player1),
+                    _ => None,
+                };
+
+                winner == expected
+                    && match winner {
+                        Some(player) => self.wins[player as usize] > 0,
+                        None => self.ties > 0,
+                    }
+            }
+        };
+
+        if !is_last_round_valid {
+            return Err(InvariantError::Counter);
+        }
+
+        let is_winning = |player: Player| self.wins[player as usize] * 2 > self.best_of;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => {
+                !is_winning(Player::Player0) && !is_winning(Player::Player1)
+            }
+            (Status::Ongoing, _) => false,
+            // the round is resolved when the game is won, so the winning round is the last one
+            (Status::Win(player), EndReason::Normal) => {
+                self.wins[*player as usize] * 2 == self.best_of + 1
+                    && !is_winning(player.other())
+                    && self
+                        .last_round
+                        .is_some_and(|round| round.winner == Some(*player))
+            }
+            (Status::Draw, EndReason::Normal) => false,
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), RockPaperScissorsError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, e.g. without submitting a hand in time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), RockPaperScissorsError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a round is resolved
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), RockPaperScissorsError> {
+        if self.status != Status::Ongoing {
+            return Err(RockPaperScissorsError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), RockPaperScissorsError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(RockPaperScissorsError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), RockPaperScissorsError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), RockPaperScissorsError> {
+        if self.status != Status::Ongoing {
+            return Err(RockPaperScissorsError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+}
+
+impl Hand {
+    /// Check if the hand beats another one
+    pub const fn beats(self, other: Self) -> bool {
+        matches!(
+            (self, other),
+            (Hand::Rock, Hand::Scissors)
+                | (Hand::Paper, Hand::Rock)
+                | (Hand::Scissors, Hand::Paper)
+        )
+    }
+}
+
+impl Player {
+    /// Get the other player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for RockPaperScissors {
+    type Move = Move;
+
+    /// Submit for [`Player::Player0`] first, then for [`Player::Player1`]
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let player = [Player::Player0, Player::Player1]
+            .into_iter()
+            .find(|player| self.can_submit(*player))?;
+        let hands = [Hand::Rock, Hand::Paper, Hand::Scissors].into_iter();
+
+        testing::choose(rng, hands).map(|hand| Move { player, hand })
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rock_paper_scissors::*;
+
+    #[test]
+    fn test() {
+        assert_eq!(
+            RockPaperScissors::with_best_of(4).unwrap_err(),
+            RockPaperScissorsError::EvenRounds
+        );
+
+        let mut game = RockPaperScissors::with_best_of(3).unwrap();
+
+        assert_eq!(game.submit(Player::Player0, Hand::Rock), Ok(None));
+        assert!(game.has_submitted(Player::Player0));
+        assert!(!game.can_submit(Player::Player0));
+        assert_eq!(
+            game.submit(Player::Player0, Hand::Paper),
+            Err(RockPaperScissorsError::AlreadySubmitted)
+        );
+
+        // a tie does not count
+        let round = game.submit(Player::Player1, Hand::Rock).unwrap().unwrap();
+        assert_eq!(round.winner, None);
+        assert_eq!(game.ties(), 1);
+        assert!(!game.has_submitted(Player::Player0));
+
+        for _ in 0..2 {
+            game.apply(Move {
+                player: Player::Player1,
+                hand: Hand::Scissors,
+            })
+            .unwrap();
+            assert_eq!(game.validate(), Ok(()));
+            game.submit(Player::Player0, Hand::Rock).unwrap();
+        }
+
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+        assert_eq!(game.rounds_played(), 3);
+        assert_eq!(game.end_reason(), Some(EndReason::Normal));
+        assert_eq!(game.validate(), Ok(()));
+        assert_eq!(
+            game.submit(Player::Player1, Hand::Rock),
+            Err(RockPaperScissorsError::GameEnded)
+        );
+
+        let mut game = RockPaperScissors::new().unwrap();
+        game.submit(Player::Player1, Hand::Paper).unwrap();
+        game.time_out(Player::Player0).unwrap();
+        assert_eq!(game.status(), &Status::Win(Player::Player1));
+    }
+}
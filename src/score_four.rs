@@ -0,0 +1,572 @@
+//! Score Four
+//!
+//! Check struct [`ScoreFour`] for more information
+
+use crate::{
+    invariant::{self, InvariantError},
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use core::{
+    convert::Infallible,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+use snafu::Snafu;
+
+pub(crate) const BOARD_SIZE: usize = 4;
+
+// one direction for each of the 13 lines through a position, covering rows, columns, levels, and the planar and space diagonals
+const DIRECTIONS: [(isize, isize, isize); 13] = [
+    (0, 0, 1),
+    (0, 1, 0),
+    (1, 0, 0),
+    (0, 1, 1),
+    (0, 1, -1),
+    (1, 0, 1),
+    (1, 0, -1),
+    (1, 1, 0),
+    (1, -1, 0),
+    (1, 1, 1),
+    (1, 1, -1),
+    (1, -1, 1),
+    (1, -1, -1),
+];
+
+/// Score Four, a Connect Four played in three dimensions on a 4x4 grid of vertical pins, each holding up to 4 beads
+///
+/// Four beads of a player in a line, in any of the 76 lines through the 4x4x4 cube, win the game
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::score_four::{Player, ScoreFour, Status};
+/// let mut game = ScoreFour::new().unwrap();
+///
+/// for _ in 0..3 {
+///     game.put(0, 0).unwrap();
+///     game.put(1, 1).unwrap();
+/// }
+///
+/// game.put(0, 0).unwrap();
+/// assert_eq!(game.get(0, 0, 3), Some(Player::Player0));
+/// assert_eq!(game.status(), &Status::Win(Player::Player0));
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ScoreFour {
+    board: [[[Option<Player>; BOARD_SIZE]; BOARD_SIZE]; BOARD_SIZE],
+    move_count: usize,
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(ScoreFour);
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::canonical!(ScoreFour, "score_four", 1 {
+    board,
+    move_count,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+});
+
+/// A move, which drops a bead onto a pin
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    /// Positions are always in bounds
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            row: u.choose_index(BOARD_SIZE)?,
+            col: u.choose_index(BOARD_SIZE)?,
+        })
+    }
+}
+
+/// Errors that can occur when dropping a bead onto a pin
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum ScoreFourError {
+    #[snafu(display("pin filled"))]
+    PinFilled,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("pin out of bounds"))]
+    OutOfBounds,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+}
+
+crate::code::codes!(ScoreFourError {
+    PinFilled => "pin_filled",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+    NoDrawOffer => "no_draw_offer",
+});
+
+impl ScoreFour {
+    /// Create a new Score Four game
+    pub const fn new() -> Result<Self, Infallible> {
+        Ok(Self {
+            board: [[[None; BOARD_SIZE]; BOARD_SIZE]; BOARD_SIZE],
+            move_count: 0,
+            next_player: Player::Player0,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+        })
+    }
+
+    /// Create a Score Four game by replaying a list of moves from an empty board, each move being the pin to drop a bead onto
+    ///
+    /// Return the index of the first invalid move and the error it caused on failure
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::score_four::{Player, ScoreFour};
+    /// let game = ScoreFour::from_moves(&[(1, 1), (1, 1)]).unwrap();
+    ///
+    /// assert_eq!(game.get(1, 1, 1), Some(Player::Player1));
+    /// assert!(ScoreFour::from_moves(&[(1, 4)]).is_err());
+    /// ```
+    pub fn from_moves(moves: &[(usize, usize)]) -> Result<Self, (usize, ScoreFourError)> {
+        let mut game = Self::new().unwrap();
+
+        for (idx, (row, col)) in moves.iter().enumerate() {
+            if *row >= BOARD_SIZE || *col >= BOARD_SIZE {
+                return Err((idx, ScoreFourError::OutOfBounds));
+            }
+
+            game.put(*row, *col).map_err(|err| (idx, err))?;
+        }
+
+        Ok(game)
+    }
+
+    /// Get a bead at a position, level `0` being the bottom of a pin
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn get(&self, row: usize, col: usize, level: usize) -> Option<Player> {
+        self.board[row][col][level]
+    }
+
+    /// Get a bead at a position without bounds checking, for the hot loops of a search that keep the positions in bounds already
+    ///
+    /// The bounds are still checked in debug builds
+    ///
+    /// # Safety
+    ///
+    /// The target position must be in bounds, see [`get`](Self::get)
+    pub const unsafe fn get_unchecked(
+        &self,
+        row: usize,
+        col: usize,
+        level: usize,
+    ) -> Option<Player> {
+        debug_assert!(row < BOARD_SIZE && col < BOARD_SIZE && level < BOARD_SIZE);
+
+        // SAFETY: the caller keeps the position in bounds
+        unsafe {
+            *(*(*self.board.as_ptr().add(row)).as_ptr().add(col))
+                .as_ptr()
+                .add(level)
+        }
+    }
+
+    /// Get the number of beads on a pin
+    ///
+    /// Panic if the target pin is out of bounds
+    pub fn height(&self, row: usize, col: usize) -> usize {
+        self.board[row][col]
+            .iter()
+            .take_while(|bead| bead.is_some())
+            .count()
+    }
+
+    /// Drop a bead onto a pin
+    ///
+    /// Panic if the target pin is out of bounds
+    pub fn put(&mut self, row: usize, col: usize) -> Result<(), ScoreFourError> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(ScoreFourError::GameEnded);
+        }
+
+        let level = self.height(row, col);
+
+        if level == BOARD_SIZE {
+            return Err(ScoreFourError::PinFilled);
+        }
+
+        self.board[row][col][level] = Some(self.next_player);
+        self.move_count += 1;
+
+        self.update_status(row, col, level);
+        self.draw_offer = None;
+        self.next_player = self.next_player.other();
+
+        Ok(())
+    }
+
+    /// Check if a bead can be dropped onto a pin, same as [`put`](Self::put) succeeding but without changing the game
+    ///
+    /// Panic if the target pin is out of bounds
+    pub const fn can_put(&self, row: usize, col: usize) -> bool {
+        !matches!(self.status, Status::Win(_) | Status::Draw)
+            && self.board[row][col][BOARD_SIZE - 1].is_none()
+    }
+
+    /// Apply a move, same as [`put`](Self::put)
+    ///
+    /// Panic if the target pin is out of bounds
+    pub fn apply(&mut self, mv: Move) -> Result<(), ScoreFourError> {
+        self.put(mv.row, mv.col)
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    ///
+    /// The game holds no allocation, so this is a single copy of the game followed by [`apply`](Self::apply)
+    ///
+    /// Panic if the target pin is out of bounds
+    pub fn preview(&self, mv: Move) -> Result<Self, ScoreFourError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, a position as described in [`parse`](crate::parse)
+    pub fn parse_move(text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+        let (row, col) = words.position(BOARD_SIZE, BOARD_SIZE)?;
+
+        words.finish()?;
+        Ok(Move { row, col })
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// Beads must rest on each other from the bottom of each pin, the bead counts must match the move count and the next player, and a status ended by the rules must match the lines in the cube
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let is_stacked = self.board.iter().flatten().all(|pin| {
+            pin.windows(2)
+                .all(|beads| beads[0].is_some() || beads[1].is_none())
+        });
+
+        if !is_stacked {
+            return Err(InvariantError::Board);
+        }
+
+        let count = |player| {
+            self.board
+                .iter()
+                .flatten()
+                .flatten()
+                .filter(|bead| **bead == Some(player))
+                .count()
+        };
+
+        let (player0_count, player1_count) = (count(Player::Player0), count(Player::Player1));
+
+        if player0_count + player1_count != self.move_count {
+            return Err(InvariantError::Counter);
+        }
+
+        invariant::check_turns(
+            player0_count,
+            player1_count,
+            self.next_player == Player::Player0,
+        )?;
+
+        let has_line = |player| {
+            (0..BOARD_SIZE * BOARD_SIZE * BOARD_SIZE).any(|idx| {
+                let (row, col, level) = (
+                    idx / (BOARD_SIZE * BOARD_SIZE),
+                    idx / BOARD_SIZE % BOARD_SIZE,
+                    idx % BOARD_SIZE,
+                );
+
+                self.get(row, col, level) == Some(player) && self.is_line(row, col, level, player)
+            })
+        };
+        let has_no_line = !has_line(Player::Player0) && !has_line(Player::Player1);
+        let is_full = self.move_count == BOARD_SIZE * BOARD_SIZE * BOARD_SIZE;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => has_no_line && !is_full,
+            (Status::Ongoing, _) => false,
+            (Status::Win(player), EndReason::Normal) => {
+                has_line(*player) && !has_line(player.other())
+            }
+            (Status::Draw, EndReason::Normal) => has_no_line && is_full,
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), ScoreFourError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), ScoreFourError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), ScoreFourError> {
+        if self.status != Status::Ongoing {
+            return Err(ScoreFourError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), ScoreFourError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(ScoreFourError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), ScoreFourError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), ScoreFourError> {
+        if self.status != Status::Ongoing {
+            return Err(ScoreFourError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    fn update_status(&mut self, row: usize, col: usize, level: usize) {
+        if self.is_line(row, col, level, self.next_player) {
+            self.status = Status::Win(self.next_player);
+        } else if self.move_count == BOARD_SIZE * BOARD_SIZE * BOARD_SIZE {
+            self.status = Status::Draw;
+        }
+    }
+
+    /// Check if a bead of a player completes a line through a position
+    fn is_line(&self, row: usize, col: usize, level: usize, player: Player) -> bool {
+        // every line spans the whole cube, so a line through the last move wins if all 4 positions on it are taken by the player
+        let count = |(row_step, col_step, level_step): (isize, isize, isize)| {
+            (1..BOARD_SIZE as isize)
+                .map(|distance| {
+                    (
+                        row as isize + row_step * distance,
+                        col as isize + col_step * distance,
+                        level as isize + level_step * distance,
+                    )
+                })
+                .take_while(|(row, col, level)| {
+                    [row, col, level]
+                        .into_iter()
+                        .all(|value| (0..BOARD_SIZE as isize).contains(value))
+                        && self.get(*row as usize, *col as usize, *level as usize) == Some(player)
+                })
+                .count()
+        };
+
+        DIRECTIONS
+            .into_iter()
+            .any(|(row_step, col_step, level_step)| {
+                count((row_step, col_step, level_step))
+                    + count((-row_step, -col_step, -level_step))
+                    + 1
+                    >= BOARD_SIZE
+            })
+    }
+}
+
+impl Player {
+    /// Get the other player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} {}", self.row, self.col)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl ScoreFour {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    pub fn random_position<R: GameRng + ?Sized>(rng: &mut R, plies: usize) -> Self {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        game.play_random_position(rng, plies);
+        game
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for ScoreFour {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let moves =
+            testing::positions(BOARD_SIZE, BOARD_SIZE).filter(|&(row, col)| self.can_put(row, col));
+        testing::choose(rng, moves).map(|(row, col)| Move { row, col })
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::score_four::*;
+
+    #[test]
+    fn test() {
+        // a space diagonal from (0, 0, 0) to (3, 3, 3), with Player1 filling the pins below
+        let mut game = ScoreFour::from_moves(&[
+            (0, 0),
+            (1, 1),
+            (1, 1),
+            (2, 2),
+            (3, 0),
+            (2, 2),
+            (2, 2),
+            (3, 3),
+            (0, 3),
+            (3, 3),
+            (0, 1),
+            (3, 3),
+        ])
+        .unwrap();
+
+        assert_eq!(game.status(), &Status::Ongoing);
+        assert_eq!(game.height(3, 3), 3);
+
+        game.apply(Move { row: 3, col: 3 }).unwrap();
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+        assert_eq!(game.put(0, 0), Err(ScoreFourError::GameEnded));
+
+        let mut game = ScoreFour::new().unwrap();
+        for _ in 0..4 {
+            game.put(2, 1).unwrap();
+        }
+        assert_eq!(game.put(2, 1), Err(ScoreFourError::PinFilled));
+    }
+}
@@ -0,0 +1,241 @@
+//! Sessions bundling several games with their metadata, e.g. the games of a room in a save file
+//!
+//! Check struct [`Session`] for more information
+
+use crate::invariant::InvariantError;
+use alloc::{string::String, vec::Vec};
+use serde::{
+    de::{Error as DeError, Unexpected},
+    Deserialize, Deserializer, Serialize,
+};
+
+/// The version of the session format, written into every serialized session
+///
+/// Deserializing a session of any other version fails
+pub const VERSION: u32 = 1;
+
+/// A versioned container of games of different types, each with its metadata
+///
+/// The session is saved and loaded through its `serde` traits, in any format supported by `serde`
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(all(feature = "reversi", feature = "tictactoe"))]
+/// # fn session() {
+/// use gamie::{
+///     reversi::Reversi,
+///     session::{Entry, Game, Metadata, Session},
+///     tictactoe::TicTacToe,
+/// };
+///
+/// let mut session = Session::new();
+///
+/// session.entries.push(Entry {
+///     game: TicTacToe::new().unwrap().into(),
+///     metadata: Metadata {
+///         players: vec!["alice".into(), "bob".into()],
+///         created_at: Some(1_700_000_000),
+///         ..Default::default()
+///     },
+/// });
+/// session.entries.push(Entry::new(Reversi::new().unwrap()));
+///
+/// let saved = serde_json::to_string(&session).unwrap();
+/// let loaded: Session = serde_json::from_str(&saved).unwrap();
+///
+/// assert!(matches!(loaded.entries[1].game, Game::Reversi(_)));
+/// assert_eq!(loaded.entries[0].metadata.players[1], "bob");
+/// # }
+/// # #[cfg(all(feature = "reversi", feature = "tictactoe"))]
+/// # session();
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Session {
+    #[serde(deserialize_with = "deserialize_version")]
+    version: u32,
+    /// The games of the session, in the order chosen by the caller
+    pub entries: Vec<Entry>,
+}
+
+/// A game in a session with its metadata
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub game: Game,
+    pub metadata: Metadata,
+}
+
+/// Metadata of a game in a session
+///
+/// Timestamps and clocks are provided by the caller, in any unit it chooses, e.g. seconds since the Unix epoch and milliseconds
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Metadata {
+    /// The names or IDs of the players, in the order of the players of the game
+    pub players: Vec<String>,
+    /// When the game was created
+    pub created_at: Option<u64>,
+    /// When the game was last played
+    pub updated_at: Option<u64>,
+    /// The time left on the clock of each player, in the order of the players of the game
+    pub clocks: Vec<u64>,
+}
+
+macro_rules! games {
+    ($($feature:literal, $variant:ident, $game:path;)*) => {
+        /// A game of any module, `Minesweeper` left out as it holds a random number generator
+        ///
+        /// Each game converts into it with `From`
+        #[derive(Clone, Debug, Serialize, Deserialize)]
+        #[non_exhaustive]
+        pub enum Game {
+            $(
+                #[cfg(feature = $feature)]
+                $variant($game),
+            )*
+        }
+
+        impl Game {
+            /// Check if the game could have been reached by playing, see the `validate()` method of each game
+            pub fn validate(&self) -> Result<(), InvariantError> {
+                match *self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(ref game) => game.validate(),
+                    )*
+                }
+            }
+        }
+
+        $(
+            #[cfg(feature = $feature)]
+            impl From<$game> for Game {
+                fn from(game: $game) -> Self {
+                    Self::$variant(game)
+                }
+            }
+        )*
+    };
+}
+
+games! {
+    "connect_four", ConnectFour, crate::connect_four::ConnectFour;
+    "connect6", Connect6, crate::connect6::Connect6;
+    "dominoes", Dominoes, crate::dominoes::Dominoes;
+    "drop_three", DropThree, crate::drop_three::DropThree;
+    "fox_and_geese", FoxAndGeese, crate::fox_and_geese::FoxAndGeese;
+    "gomoku", Gomoku, crate::gomoku::Gomoku;
+    "hasami_shogi", HasamiShogi, crate::hasami_shogi::HasamiShogi;
+    "liars_dice", LiarsDice, crate::liars_dice::LiarsDice;
+    "order_and_chaos", OrderAndChaos, crate::order_and_chaos::OrderAndChaos;
+    "pente", Pente, crate::pente::Pente;
+    "pig", Pig, crate::pig::Pig;
+    "reversi", Reversi, crate::reversi::Reversi;
+    "rock_paper_scissors", RockPaperScissors, crate::rock_paper_scissors::RockPaperScissors;
+    "score_four", ScoreFour, crate::score_four::ScoreFour;
+    "shut_the_box", ShutTheBox, crate::shut_the_box::ShutTheBox;
+    "sos", Sos, crate::sos::Sos;
+    "tictactoe", TicTacToe, crate::tictactoe::TicTacToe;
+}
+
+impl Session {
+    /// Create an empty session
+    pub const fn new() -> Self {
+        Self {
+            version: VERSION,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Check every game of the session, see [`Game::validate`]
+    ///
+    /// Return the index of the first inconsistent game with its error
+    pub fn validate(&self) -> Result<(), (usize, InvariantError)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .try_for_each(|(idx, entry)| entry.game.validate().map_err(|err| (idx, err)))
+    }
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Entry {
+    /// Create an entry of a game with empty metadata
+    pub fn new(game: impl Into<Game>) -> Self {
+        Self {
+            game: game.into(),
+            metadata: Metadata::default(),
+        }
+    }
+}
+
+/// Reject sessions written in another version of the format
+fn deserialize_version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let version = u32::deserialize(deserializer)?;
+
+    if version != VERSION {
+        return Err(D::Error::invalid_value(
+            Unexpected::Unsigned(version as u64),
+            &"a supported session version",
+        ));
+    }
+
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        reversi::Reversi,
+        session::*,
+        sos::Sos,
+        tictactoe::{Player, TicTacToe},
+    };
+
+    #[test]
+    fn test() {
+        let mut tictactoe = TicTacToe::new().unwrap();
+        tictactoe.put(1, 1).unwrap();
+
+        let mut session = Session::new();
+        session.entries.push(Entry {
+            game: tictactoe.into(),
+            metadata: Metadata {
+                players: ["alice", "bob"].map(String::from).to_vec(),
+                updated_at: Some(42),
+                clocks: [60_000, 59_000].to_vec(),
+                ..Default::default()
+            },
+        });
+        session.entries.push(Entry::new(Reversi::new().unwrap()));
+        session.entries.push(Entry::new(Sos::new(3, 3).unwrap()));
+
+        let saved = serde_json::to_string(&session).unwrap();
+        let loaded: Session = serde_json::from_str(&saved).unwrap();
+
+        assert_eq!(loaded.validate(), Ok(()));
+        assert!(matches!(loaded.entries[2].game, Game::Sos(_)));
+        assert_eq!(loaded.entries[0].metadata, session.entries[0].metadata);
+        assert_eq!(loaded.entries[1].metadata, Metadata::default());
+
+        let Game::TicTacToe(ref game) = loaded.entries[0].game else {
+            panic!("wrong game type");
+        };
+        assert_eq!(game.get(1, 1), Some(Player::Player0));
+
+        let newer = saved.replacen("\"version\":1", "\"version\":2", 1);
+        assert!(serde_json::from_str::<Session>(&newer).is_err());
+
+        // metadata written without optional fields is filled in with defaults
+        let game = serde_json::to_string(&Game::from(Reversi::new().unwrap())).unwrap();
+        let sparse =
+            alloc::format!(r#"{{"version":1,"entries":[{{"game":{game},"metadata":{{}}}}]}}"#);
+        let loaded: Session = serde_json::from_str(&sparse).unwrap();
+        assert_eq!(loaded.entries[0].metadata, Metadata::default());
+    }
+}
@@ -0,0 +1,369 @@
+//! Smart Game Format (SGF)
+//!
+//! Only the main line of the first game tree is read, variations are skipped. Games are read with `from_sgf()` and written with `write_sgf()` of the supported games:
+//!
+//! - [`Gomoku`](crate::gomoku::Gomoku), `GM[4]`
+//! - [`Reversi`](crate::reversi::Reversi), `GM[2]`
+//!
+//! The first player is always black
+
+// the move reader is only used by the supported games
+#![cfg_attr(not(any(feature = "gomoku", feature = "reversi")), allow(dead_code))]
+
+use snafu::Snafu;
+
+/// Errors that can occur when reading an SGF game
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum SgfError {
+    #[snafu(display("syntax error at byte {offset}"))]
+    Syntax { offset: usize },
+    #[snafu(display("unsupported game type"))]
+    GameType,
+    #[snafu(display("unsupported board size"))]
+    BoardSize,
+    #[snafu(display("invalid coordinate at move {index}"))]
+    InvalidCoordinate { index: usize },
+    #[snafu(display("wrong player at move {index}"))]
+    WrongPlayer { index: usize },
+    #[snafu(display("illegal move {index}"))]
+    IllegalMove { index: usize },
+    #[snafu(display("invalid setup"))]
+    InvalidSetup,
+}
+
+crate::code::codes!(SgfError {
+    Syntax => "syntax",
+    GameType => "game_type",
+    BoardSize => "board_size",
+    InvalidCoordinate => "invalid_coordinate",
+    WrongPlayer => "wrong_player",
+    IllegalMove => "illegal_move",
+    InvalidSetup => "invalid_setup",
+});
+
+/// A property on the main line of an SGF game
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Property<'a> {
+    /// The index of the node the property belongs to, `0` being the root node
+    pub node: usize,
+    pub ident: &'a str,
+    /// The raw value, with escapes kept
+    pub value: &'a str,
+}
+
+/// A move read from an SGF game
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Move {
+    pub(crate) is_black: bool,
+    /// `None` for a pass
+    pub(crate) position: Option<(usize, usize)>,
+}
+
+/// Iterate over the properties on the main line of the first game tree
+///
+/// Properties with multiple values are yielded once per value
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::sgf::{self, Property};
+/// let mut properties = sgf::main_line("(;GM[4](;B[hh];W[ii])(;B[aa]))");
+///
+/// assert_eq!(
+///     properties.nth(2),
+///     Some(Ok(Property {
+///         node: 2,
+///         ident: "W",
+///         value: "ii",
+///     }))
+/// );
+/// ```
+pub fn main_line(s: &str) -> MainLine<'_> {
+    MainLine {
+        s,
+        offset: 0,
+        node: None,
+        ident: None,
+        is_started: false,
+        is_ended: false,
+    }
+}
+
+/// Iterator over the properties on the main line, see [`main_line`]
+#[derive(Clone, Debug)]
+pub struct MainLine<'a> {
+    s: &'a str,
+    offset: usize,
+    node: Option<usize>,
+    ident: Option<&'a str>,
+    is_started: bool,
+    is_ended: bool,
+}
+
+impl<'a> Iterator for MainLine<'a> {
+    type Item = Result<Property<'a>, SgfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_ended {
+            return None;
+        }
+
+        let result = self.next_property();
+
+        if !matches!(result, Some(Ok(_))) {
+            self.is_ended = true;
+        }
+
+        result
+    }
+}
+
+impl<'a> MainLine<'a> {
+    fn next_property(&mut self) -> Option<Result<Property<'a>, SgfError>> {
+        let bytes = self.s.as_bytes();
+
+        loop {
+            self.skip_whitespace();
+
+            let Some(byte) = bytes.get(self.offset) else {
+                return self.error();
+            };
+
+            match (byte, self.is_started) {
+                (b'(', _) => {
+                    // the first variation is the main line, the rest are never reached
+                    self.is_started = true;
+                    self.ident = None;
+                    self.offset += 1;
+                }
+                (_, false) => return self.error(),
+                (b')', _) => return None,
+                (b';', _) => {
+                    self.node = Some(self.node.map_or(0, |node| node + 1));
+                    self.ident = None;
+                    self.offset += 1;
+                }
+                (b'A'..=b'Z', _) if self.node.is_some() => {
+                    let start = self.offset;
+
+                    while bytes.get(self.offset).is_some_and(u8::is_ascii_uppercase) {
+                        self.offset += 1;
+                    }
+
+                    self.ident = Some(&self.s[start..self.offset]);
+                    self.skip_whitespace();
+
+                    if bytes.get(self.offset) != Some(&b'[') {
+                        return self.error();
+                    }
+                }
+                (b'[', _) => {
+                    let Some(ident) = self.ident else {
+                        return self.error();
+                    };
+
+                    let start = self.offset + 1;
+                    let mut end = start;
+
+                    loop {
+                        match bytes.get(end) {
+                            None => return self.error(),
+                            Some(b'\\') => end += 2,
+                            Some(b']') => break,
+                            Some(_) => end += 1,
+                        }
+                    }
+
+                    self.offset = end + 1;
+
+                    return Some(Ok(Property {
+                        node: self.node.unwrap(),
+                        ident,
+                        value: &self.s[start..end],
+                    }));
+                }
+                _ => return self.error(),
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .s
+            .as_bytes()
+            .get(self.offset)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.offset += 1;
+        }
+    }
+
+    fn error<T>(&self) -> Option<Result<T, SgfError>> {
+        Some(Err(SgfError::Syntax {
+            offset: self.offset,
+        }))
+    }
+}
+
+/// Read the moves on the main line, checking the game type and the board size of the root node
+pub(crate) fn read_moves(
+    s: &str,
+    game_type: usize,
+    width: usize,
+    height: usize,
+) -> impl Iterator<Item = Result<Move, SgfError>> + '_ {
+    let mut properties = main_line(s);
+    let mut is_game_type_checked = false;
+    let mut index = 0;
+
+    core::iter::from_fn(move || loop {
+        let property = match properties.next()? {
+            Ok(property) => property,
+            Err(err) => return Some(Err(err)),
+        };
+
+        if property.node == 0 {
+            match property.ident {
+                "GM" if property.value.trim().parse() == Ok(game_type) => {
+                    is_game_type_checked = true;
+                }
+                "GM" => return Some(Err(SgfError::GameType)),
+                "SZ" if !is_size(property.value, width, height) => {
+                    return Some(Err(SgfError::BoardSize));
+                }
+                _ => {}
+            }
+
+            continue;
+        }
+
+        if !is_game_type_checked {
+            return Some(Err(SgfError::GameType));
+        }
+
+        let is_black = match property.ident {
+            "B" => true,
+            "W" => false,
+            _ => continue,
+        };
+
+        let position = match property.value.as_bytes() {
+            [] => None,
+            [x, y] if (*x, *y) == (b't', b't') && width <= 19 && height <= 19 => None,
+            _ => match point(property.value, width, height) {
+                Some(position) => Some(position),
+                None => return Some(Err(SgfError::InvalidCoordinate { index })),
+            },
+        };
+
+        index += 1;
+
+        return Some(Ok(Move { is_black, position }));
+    })
+}
+
+/// Read the stones placed with `AB` and `AW` in the root node, e.g. of a composed position, and the player to move set with `PL`
+///
+/// Each stone is passed to `place` as `(is_black, row, col)`. Return whether black moves next, `None` without `PL`
+pub(crate) fn read_setup(
+    s: &str,
+    width: usize,
+    height: usize,
+    mut place: impl FnMut(bool, usize, usize),
+) -> Result<Option<bool>, SgfError> {
+    let mut is_black_next = None;
+
+    for property in main_line(s) {
+        let property = property?;
+
+        if property.node > 0 {
+            break;
+        }
+
+        match property.ident {
+            "AB" | "AW" => {
+                let (row, col) =
+                    point(property.value, width, height).ok_or(SgfError::InvalidSetup)?;
+                place(property.ident == "AB", row, col);
+            }
+            "PL" => {
+                is_black_next = match property.value.trim() {
+                    "B" => Some(true),
+                    "W" => Some(false),
+                    _ => return Err(SgfError::InvalidSetup),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    Ok(is_black_next)
+}
+
+/// Get the letter of an SGF coordinate
+pub(crate) fn coordinate(value: usize) -> char {
+    (b'a' + value as u8) as char
+}
+
+/// Read a point as `(row, col)`, e.g. `hh`
+fn point(value: &str, width: usize, height: usize) -> Option<(usize, usize)> {
+    match value.as_bytes() {
+        [x @ b'a'..=b'z', y @ b'a'..=b'z']
+            if ((x - b'a') as usize) < width && ((y - b'a') as usize) < height =>
+        {
+            Some(((y - b'a') as usize, (x - b'a') as usize))
+        }
+        _ => None,
+    }
+}
+
+/// Check the value of the `SZ` property, e.g. `15` or `15:17`
+fn is_size(value: &str, width: usize, height: usize) -> bool {
+    let (value_width, value_height) = value.split_once(':').unwrap_or((value, value));
+
+    value_width.trim().parse() == Ok(width) && value_height.trim().parse() == Ok(height)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sgf::*;
+
+    #[test]
+    fn test() {
+        let properties = main_line("(;C[a \\] b]PB[x][y](;B[aa])(;B[bb]))")
+            .map(Result::unwrap)
+            .map(|property| (property.node, property.ident, property.value));
+
+        assert!(properties.eq([
+            (0, "C", "a \\] b"),
+            (0, "PB", "x"),
+            (0, "PB", "y"),
+            (1, "B", "aa"),
+        ]));
+
+        assert_eq!(
+            main_line("(;B[aa").last(),
+            Some(Err(SgfError::Syntax { offset: 3 }))
+        );
+
+        let moves = read_moves("(;GM[4]SZ[15];B[hh];W[])", 4, 15, 15).map(Result::unwrap);
+
+        assert!(moves.eq([
+            Move {
+                is_black: true,
+                position: Some((7, 7)),
+            },
+            Move {
+                is_black: false,
+                position: None,
+            },
+        ]));
+
+        assert_eq!(
+            read_moves("(;GM[1];B[hh])", 4, 15, 15).next(),
+            Some(Err(SgfError::GameType))
+        );
+    }
+}
@@ -0,0 +1,484 @@
+//! Shut the Box
+//!
+//! Check struct [`ShutTheBox`] for more information
+
+#[cfg(feature = "testing")]
+use crate::testing::{self, RandomPlay};
+use crate::{dice::Die, invariant::InvariantError, rng::GameRng};
+use snafu::Snafu;
+
+const DEFAULT_TILES: usize = 9;
+const MIN_TILES: usize = 9;
+const MAX_TILES: usize = 12;
+// with every tile from 7 up shut, the player may roll a single die
+const ONE_DIE_FROM: usize = 7;
+
+/// Shut the Box, a single-player round of tiles numbered from `1` to `9`, or up to `12`
+///
+/// Each turn, the dice are rolled, and the player shuts any open tiles adding up to the roll. The round ends when all tiles are shut, or when no open tiles add up to a roll, and scores the sum of the tiles left open, so lower is better. Once the tiles from `7` up are all shut, the player may roll a single die instead of two
+///
+/// The dice are rolled with [`roll`](ShutTheBox::roll) from a [`GameRng`], or set with [`roll_dice`](ShutTheBox::roll_dice) when they are rolled elsewhere
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::shut_the_box::{Dice, ShutTheBox, Status};
+/// let mut game = ShutTheBox::new().unwrap();
+///
+/// game.roll_dice(Dice::Two(3, 5)).unwrap();
+/// assert_eq!(game.moves().count(), 6);
+///
+/// game.shut(&[1, 7]).unwrap();
+/// assert_eq!(game.score(), 37);
+/// assert_eq!(game.status(), &Status::Ongoing);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ShutTheBox {
+    tile_count: usize,
+    open: Tiles,
+    roll: Option<usize>,
+    status: Status,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(ShutTheBox);
+
+/// A set of tiles, by their numbers
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Tiles(u16);
+
+/// The faces of the dice of a roll
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Dice {
+    One(usize),
+    Two(usize, usize),
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    /// All tiles are shut
+    Shut,
+    /// No open tiles add up to the last roll
+    Stuck,
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Shut => "shut",
+    Stuck => "stuck",
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Shut,
+    2 => Stuck,
+});
+
+crate::canonical::encode_struct!(Tiles { 0 });
+
+crate::canonical::canonical!(ShutTheBox, "shut_the_box", 1 {
+    tile_count,
+    open,
+    roll,
+    status,
+});
+
+/// A move of the player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Move {
+    /// A roll with the faces of the dice
+    Roll(Dice),
+    Shut(Tiles),
+}
+
+/// Errors that can occur when rolling or shutting tiles
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum ShutTheBoxError {
+    #[snafu(display("number of tiles out of range"))]
+    InvalidTiles,
+    #[snafu(display("dice not allowed or out of range"))]
+    InvalidDice,
+    #[snafu(display("already rolled"))]
+    AlreadyRolled,
+    #[snafu(display("not rolled yet"))]
+    NotRolled,
+    #[snafu(display("tile not open"))]
+    TileNotOpen,
+    #[snafu(display("tiles do not add up to the roll"))]
+    SumMismatch,
+    #[snafu(display("game ended"))]
+    GameEnded,
+}
+
+crate::code::codes!(ShutTheBoxError {
+    InvalidTiles => "invalid_tiles",
+    InvalidDice => "invalid_dice",
+    AlreadyRolled => "already_rolled",
+    NotRolled => "not_rolled",
+    TileNotOpen => "tile_not_open",
+    SumMismatch => "sum_mismatch",
+    GameEnded => "game_ended",
+});
+
+impl ShutTheBox {
+    /// Create a new round with tiles from `1` to `9`
+    pub const fn new() -> Result<Self, ShutTheBoxError> {
+        Self::with_tiles(DEFAULT_TILES)
+    }
+
+    /// Create a new round with tiles from `1` to `tile_count`
+    ///
+    /// Return `Err(ShutTheBoxError::InvalidTiles)` if `tile_count` is not from `9` to `12`
+    pub const fn with_tiles(tile_count: usize) -> Result<Self, ShutTheBoxError> {
+        if tile_count < MIN_TILES || tile_count > MAX_TILES {
+            return Err(ShutTheBoxError::InvalidTiles);
+        }
+
+        Ok(Self {
+            tile_count,
+            open: Tiles::up_to(tile_count),
+            roll: None,
+            status: Status::Ongoing,
+        })
+    }
+
+    /// Roll the dice with six-sided dice from `rng`, a single die if allowed and `one_die` is `true`
+    ///
+    /// Return the sum of the roll
+    pub fn roll<R: GameRng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        one_die: bool,
+    ) -> Result<usize, ShutTheBoxError> {
+        let dice = match one_die && self.can_roll_one_die() {
+            true => Dice::One(Die::D6.roll(rng)),
+            false => Dice::Two(Die::D6.roll(rng), Die::D6.roll(rng)),
+        };
+
+        self.roll_dice(dice)
+    }
+
+    /// Apply a roll with the faces of the dice, rolled elsewhere
+    ///
+    /// Return the sum of the roll, the round ends if no open tiles add up to it
+    ///
+    /// Return `Err(ShutTheBoxError::InvalidDice)` if a face is not from `1` to `6`, or a single die is rolled when not allowed
+    pub fn roll_dice(&mut self, dice: Dice) -> Result<usize, ShutTheBoxError> {
+        if self.status != Status::Ongoing {
+            return Err(ShutTheBoxError::GameEnded);
+        }
+
+        if self.roll.is_some() {
+            return Err(ShutTheBoxError::AlreadyRolled);
+        }
+
+        let is_face = |face: usize| (1..=Die::D6.faces()).contains(&face);
+
+        let sum = match dice {
+            Dice::One(face) if is_face(face) && self.can_roll_one_die() => face,
+            Dice::Two(first, second) if is_face(first) && is_face(second) => first + second,
+            _ => return Err(ShutTheBoxError::InvalidDice),
+        };
+
+        self.roll = Some(sum);
+
+        if self.moves().next().is_none() {
+            self.status = Status::Stuck;
+        }
+
+        Ok(sum)
+    }
+
+    /// Shut open tiles adding up to the roll
+    pub fn shut(&mut self, tiles: &[usize]) -> Result<(), ShutTheBoxError> {
+        let mut shut = Tiles::default();
+
+        for tile in tiles {
+            if !self.is_open(*tile) || shut.contains(*tile) {
+                return Err(ShutTheBoxError::TileNotOpen);
+            }
+
+            shut.0 |= Tiles::bit(*tile);
+        }
+
+        self.shut_tiles(shut)
+    }
+
+    /// Check if a single die may be rolled, which is when the tiles from `7` up are all shut
+    pub fn can_roll_one_die(&self) -> bool {
+        (ONE_DIE_FROM..=self.tile_count).all(|tile| !self.is_open(tile))
+    }
+
+    /// Apply a move
+    pub fn apply(&mut self, mv: Move) -> Result<(), ShutTheBoxError> {
+        match mv {
+            Move::Roll(dice) => self.roll_dice(dice).map(|_| ()),
+            Move::Shut(tiles) => {
+                if tiles.0 & !self.open.0 != 0 {
+                    return Err(ShutTheBoxError::TileNotOpen);
+                }
+
+                self.shut_tiles(tiles)
+            }
+        }
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    pub fn preview(&self, mv: Move) -> Result<Self, ShutTheBoxError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Get every set of open tiles adding up to the roll, which are the valid moves, empty before rolling
+    ///
+    /// The sets are enumerated as the subsets of the open tiles, from the ones with the highest tiles
+    pub fn moves(&self) -> impl Iterator<Item = Tiles> + '_ {
+        let open = self.open.0;
+        let roll = self.roll.unwrap_or(0);
+
+        // every non-empty subset of the open tiles, by counting down within the mask
+        let mut next = self.roll.map(|_| open);
+
+        core::iter::from_fn(move || {
+            let subset = next.filter(|subset| *subset != 0)?;
+            next = Some((subset - 1) & open);
+            Some(Tiles(subset))
+        })
+        .filter(move |tiles| tiles.sum() == roll)
+    }
+
+    /// Get the number of tiles of the box
+    pub const fn tile_count(&self) -> usize {
+        self.tile_count
+    }
+
+    /// Get the open tiles
+    pub const fn open_tiles(&self) -> Tiles {
+        self.open
+    }
+
+    /// Check if a tile is open, `false` if it is out of range
+    pub fn is_open(&self, tile: usize) -> bool {
+        self.open.contains(tile)
+    }
+
+    /// Get the sum of the roll waiting for tiles to be shut
+    pub const fn pending_roll(&self) -> Option<usize> {
+        self.roll
+    }
+
+    /// Get the score of the round, which is the sum of the open tiles, `0` if the box is shut
+    pub fn score(&self) -> usize {
+        self.open.sum()
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The open tiles and the roll must be in range, and the status must match the open tiles and the roll
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        if !(MIN_TILES..=MAX_TILES).contains(&self.tile_count)
+            || self.open.0 & !Tiles::up_to(self.tile_count).0 != 0
+        {
+            return Err(InvariantError::Board);
+        }
+
+        // a single die rolls from 1, two dice from 2
+        if self
+            .roll
+            .is_some_and(|roll| roll == 0 || roll > Die::D6.faces() * 2)
+        {
+            return Err(InvariantError::Counter);
+        }
+
+        let has_moves = self.moves().next().is_some();
+
+        let is_status_valid = match self.status {
+            Status::Ongoing => self.open.0 != 0 && (self.roll.is_none() || has_moves),
+            Status::Shut => self.open.0 == 0 && self.roll.is_none(),
+            Status::Stuck => self.roll.is_some() && !has_moves,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn shut_tiles(&mut self, tiles: Tiles) -> Result<(), ShutTheBoxError> {
+        if self.status != Status::Ongoing {
+            return Err(ShutTheBoxError::GameEnded);
+        }
+
+        let Some(roll) = self.roll else {
+            return Err(ShutTheBoxError::NotRolled);
+        };
+
+        if tiles.sum() != roll {
+            return Err(ShutTheBoxError::SumMismatch);
+        }
+
+        self.open.0 &= !tiles.0;
+        self.roll = None;
+
+        if self.open.0 == 0 {
+            self.status = Status::Shut;
+        }
+
+        Ok(())
+    }
+}
+
+impl Tiles {
+    /// Check if a tile is in the set
+    pub fn contains(self, tile: usize) -> bool {
+        (1..=MAX_TILES).contains(&tile) && self.0 & Self::bit(tile) != 0
+    }
+
+    /// Get the tiles in the set, in ascending order
+    pub fn iter(self) -> impl Iterator<Item = usize> {
+        (1..=MAX_TILES).filter(move |tile| self.contains(*tile))
+    }
+
+    /// Get the sum of the tiles
+    pub fn sum(self) -> usize {
+        self.iter().sum()
+    }
+
+    /// Get the number of tiles in the set
+    pub const fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Check if the set is empty
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    const fn up_to(tile_count: usize) -> Self {
+        Self(((1 << tile_count) - 1) << 1)
+    }
+
+    const fn bit(tile: usize) -> u16 {
+        1 << tile
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for ShutTheBox {
+    type Move = Move;
+
+    /// Roll a single die with even odds when allowed, with the faces drawn from `rng`
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        if self.status != Status::Ongoing {
+            return None;
+        }
+
+        if self.roll.is_some() {
+            return testing::choose(rng, self.moves()).map(Move::Shut);
+        }
+
+        let dice = match self.can_roll_one_die() && rng.gen_below(2) == 0 {
+            true => Dice::One(Die::D6.roll(rng)),
+            false => Dice::Two(Die::D6.roll(rng), Die::D6.roll(rng)),
+        };
+
+        Some(Move::Roll(dice))
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shut_the_box::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test() {
+        assert_eq!(
+            ShutTheBox::with_tiles(13).unwrap_err(),
+            ShutTheBoxError::InvalidTiles
+        );
+
+        let mut game = ShutTheBox::new().unwrap();
+
+        assert_eq!(game.shut(&[4]), Err(ShutTheBoxError::NotRolled));
+        assert_eq!(
+            game.roll_dice(Dice::One(4)),
+            Err(ShutTheBoxError::InvalidDice)
+        );
+        assert_eq!(game.roll_dice(Dice::Two(2, 2)), Ok(4));
+        assert_eq!(
+            game.roll_dice(Dice::Two(2, 2)),
+            Err(ShutTheBoxError::AlreadyRolled)
+        );
+
+        assert!(game.moves().map(Tiles::len).eq([1, 2]));
+        assert!(game.moves().all(|tiles| tiles.sum() == 4));
+
+        assert_eq!(game.shut(&[1, 2]), Err(ShutTheBoxError::SumMismatch));
+        assert_eq!(game.shut(&[2, 2]), Err(ShutTheBoxError::TileNotOpen));
+        game.shut(&[1, 3]).unwrap();
+        assert_eq!(game.validate(), Ok(()));
+
+        for (roll, tiles) in [(9, &[9][..]), (8, &[8]), (7, &[7])] {
+            game.roll_dice(Dice::Two(roll - 3, 3)).unwrap();
+            game.shut(tiles).unwrap();
+        }
+
+        assert!(game.can_roll_one_die());
+        game.apply(Move::Roll(Dice::One(2))).unwrap();
+        game.shut(&[2]).unwrap();
+        assert_eq!(game.score(), 4 + 5 + 6);
+
+        // 15 is left in three tiles, no pair of them adds up to 12
+        game.roll_dice(Dice::Two(6, 6)).unwrap();
+        assert_eq!(game.status(), &Status::Stuck);
+        assert_eq!(game.validate(), Ok(()));
+        assert_eq!(game.shut(&[6]), Err(ShutTheBoxError::GameEnded));
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut game = ShutTheBox::with_tiles(12).unwrap();
+
+        while game.status() == &Status::Ongoing {
+            match game.pending_roll() {
+                None => game.roll(&mut rng, true).map(|_| ()),
+                Some(_) => {
+                    let tiles = game.moves().next().unwrap();
+                    game.apply(Move::Shut(tiles))
+                }
+            }
+            .unwrap();
+
+            assert_eq!(game.validate(), Ok(()));
+        }
+    }
+}
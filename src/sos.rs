@@ -0,0 +1,535 @@
+//! SOS
+//!
+//! Check struct [`Sos`] for more information
+
+use crate::{
+    invariant::InvariantError,
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+    util::LINES,
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use snafu::Snafu;
+
+/// The largest height or width of a board
+pub const MAX_SIZE: usize = 10;
+
+/// SOS
+///
+/// Two players take turns putting either an S or an O on an empty position of the board. A player scores a point for each S-O-S sequence the letter completes horizontally, vertically or diagonally, and takes another turn after scoring. Once the board is full, the player with the higher score wins
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::sos::{Letter, Player, Sos};
+/// let mut game = Sos::new(3, 3).unwrap();
+///
+/// game.put(0, 0, Letter::S).unwrap();
+/// game.put(0, 2, Letter::S).unwrap();
+///
+/// // Player0 completes the sequence, and goes again
+/// assert_eq!(game.put(0, 1, Letter::O).unwrap(), 1);
+/// assert_eq!(game.score(Player::Player0), 1);
+/// assert_eq!(game.next_player(), Player::Player0);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Sos {
+    board: [[Option<Letter>; MAX_SIZE]; MAX_SIZE],
+    height: usize,
+    width: usize,
+    scores: [usize; 2],
+    move_count: usize,
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(Sos);
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// A letter, which either player may put
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Letter {
+    S,
+    O,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    /// The board is full with equal scores, or the game was drawn otherwise, see [`Sos::end_reason`]
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Letter {
+    0 => S,
+    1 => O,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::canonical!(Sos, "sos", 1 {
+    board,
+    height,
+    width,
+    scores,
+    move_count,
+    next_player,
+    status,
+    end_reason,
+});
+
+/// A move, which places a letter at a position
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+    pub letter: Letter,
+}
+
+/// Errors that can occur when creating a game or placing a letter onto the board
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum SosError {
+    #[snafu(display("invalid board size"))]
+    InvalidSize,
+    #[snafu(display("position occupied"))]
+    PositionOccupied,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("position out of bounds"))]
+    OutOfBounds,
+}
+
+crate::code::codes!(SosError {
+    InvalidSize => "invalid_size",
+    PositionOccupied => "position_occupied",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+});
+
+impl Sos {
+    /// Create a new SOS game on an empty board
+    ///
+    /// Return `Err(SosError::InvalidSize)` if the height or the width is not from `1` to [`MAX_SIZE`]
+    pub const fn new(height: usize, width: usize) -> Result<Self, SosError> {
+        if height == 0 || height > MAX_SIZE || width == 0 || width > MAX_SIZE {
+            return Err(SosError::InvalidSize);
+        }
+
+        Ok(Self {
+            board: [[None; MAX_SIZE]; MAX_SIZE],
+            height,
+            width,
+            scores: [0; 2],
+            move_count: 0,
+            next_player: Player::Player0,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+        })
+    }
+
+    /// Get the height of the board
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the width of the board
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get a letter at a position
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Option<Letter> {
+        assert!(row < self.height && col < self.width);
+        self.board[row][col]
+    }
+
+    /// Get the score of a player, the number of sequences the player completed
+    pub const fn score(&self, player: Player) -> usize {
+        self.scores[player as usize]
+    }
+
+    /// Put a letter on behalf of the next player
+    ///
+    /// Return the number of sequences the letter completed. The same player moves again if it is not `0`
+    pub fn put(&mut self, row: usize, col: usize, letter: Letter) -> Result<usize, SosError> {
+        if self.status != Status::Ongoing {
+            return Err(SosError::GameEnded);
+        }
+
+        if row >= self.height || col >= self.width {
+            return Err(SosError::OutOfBounds);
+        }
+
+        if self.board[row][col].is_some() {
+            return Err(SosError::PositionOccupied);
+        }
+
+        self.board[row][col] = Some(letter);
+        self.move_count += 1;
+
+        let points = self.sequences_through(row, col);
+        self.scores[self.next_player as usize] += points;
+
+        if points == 0 {
+            self.next_player = self.next_player.other();
+        }
+
+        if self.move_count == self.height * self.width {
+            self.status = self.status_of_full_board();
+        }
+
+        Ok(points)
+    }
+
+    /// Check if a letter can be put at a position, same as [`put`](Self::put) succeeding but without changing the game
+    ///
+    /// Either letter can be put at an empty position
+    pub fn can_put(&self, row: usize, col: usize) -> bool {
+        self.status == Status::Ongoing
+            && row < self.height
+            && col < self.width
+            && self.board[row][col].is_none()
+    }
+
+    /// Apply a move, same as [`put`](Self::put)
+    pub fn apply(&mut self, mv: Move) -> Result<usize, SosError> {
+        self.put(mv.row, mv.col, mv.letter)
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    pub fn preview(&self, mv: Move) -> Result<Self, SosError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, a position as described in [`parse`](crate::parse) followed by the letter, `s` or `o`
+    pub fn parse_move(&self, text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+        let (row, col) = words.position(self.height, self.width)?;
+
+        let letter = if words.take("s") {
+            Letter::S
+        } else if words.take("o") {
+            Letter::O
+        } else {
+            return Err(ParseError::InvalidMove);
+        };
+
+        words.finish()?;
+        Ok(Move { row, col, letter })
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The board size must be valid with no letter outside it, the letter count must match the move count, the scores must add up to the sequences on the board, and a status ended by the rules must match the scores of a full board
+    ///
+    /// The next player is not checked, as scoring players move again
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        if Self::new(self.height, self.width).is_err() {
+            return Err(InvariantError::Board);
+        }
+
+        for (row, cells) in self.board.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                if cell.is_some() && (row >= self.height || col >= self.width) {
+                    return Err(InvariantError::Board);
+                }
+            }
+        }
+
+        if self.board.iter().flatten().flatten().count() != self.move_count {
+            return Err(InvariantError::Counter);
+        }
+
+        if self.scores[0] + self.scores[1] != self.sequence_count() {
+            return Err(InvariantError::Counter);
+        }
+
+        let is_full = self.move_count == self.height * self.width;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => !is_full,
+            (Status::Ongoing, _) => false,
+            (status, EndReason::Normal) => is_full && *status == self.status_of_full_board(),
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), SosError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), SosError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), SosError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), SosError> {
+        if self.status != Status::Ongoing {
+            return Err(SosError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        Ok(())
+    }
+
+    fn status_of_full_board(&self) -> Status {
+        match self.scores[0].cmp(&self.scores[1]) {
+            core::cmp::Ordering::Greater => Status::Win(Player::Player0),
+            core::cmp::Ordering::Less => Status::Win(Player::Player1),
+            core::cmp::Ordering::Equal => Status::Draw,
+        }
+    }
+
+    /// Count the sequences through a position, which the letter there is part of
+    fn sequences_through(&self, row: usize, col: usize) -> usize {
+        let letter_at = |distance: isize, (row_step, col_step): (isize, isize)| {
+            self.get_signed(
+                row as isize + row_step * distance,
+                col as isize + col_step * distance,
+            )
+        };
+
+        LINES
+            .into_iter()
+            .map(|step| match self.board[row][col] {
+                // the O in the middle
+                Some(Letter::O) => usize::from(
+                    letter_at(-1, step) == Some(Letter::S) && letter_at(1, step) == Some(Letter::S),
+                ),
+                // an S at either end, in both directions of the line
+                Some(Letter::S) => [-1, 1]
+                    .into_iter()
+                    .filter(|sign| {
+                        letter_at(*sign, step) == Some(Letter::O)
+                            && letter_at(sign * 2, step) == Some(Letter::S)
+                    })
+                    .count(),
+                None => 0,
+            })
+            .sum()
+    }
+
+    /// Count the sequences on the board, by their middle letter
+    fn sequence_count(&self) -> usize {
+        (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.board[row][col] == Some(Letter::O))
+            .map(|(row, col)| self.sequences_through(row, col))
+            .sum()
+    }
+
+    fn get_signed(&self, row: isize, col: isize) -> Option<Letter> {
+        let is_in_bounds =
+            (0..self.height as isize).contains(&row) && (0..self.width as isize).contains(&col);
+
+        if is_in_bounds {
+            self.board[row as usize][col as usize]
+        } else {
+            None
+        }
+    }
+}
+
+impl Player {
+    /// Get the opposite player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let letter = match self.letter {
+            Letter::S => 's',
+            Letter::O => 'o',
+        };
+
+        write!(f, "{} {} {letter}", self.row, self.col)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Sos {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    ///
+    /// Return `Err(SosError::InvalidSize)` if the board size is invalid, see [`new`](Self::new)
+    pub fn random_position<R: GameRng + ?Sized>(
+        height: usize,
+        width: usize,
+        rng: &mut R,
+        plies: usize,
+    ) -> Result<Self, SosError> {
+        let mut game = Self::new(height, width)?;
+        game.play_random_position(rng, plies);
+        Ok(game)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for Sos {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let moves = testing::positions(self.height, self.width)
+            .filter(|&(row, col)| self.can_put(row, col))
+            .flat_map(|(row, col)| [Letter::S, Letter::O].map(|letter| Move { row, col, letter }));
+        testing::choose(rng, moves)
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sos::*;
+
+    #[test]
+    fn test() {
+        assert_eq!(Sos::new(0, 3).unwrap_err(), SosError::InvalidSize);
+        assert_eq!(
+            Sos::new(3, MAX_SIZE + 1).unwrap_err(),
+            SosError::InvalidSize
+        );
+
+        let mut game = Sos::new(3, 3).unwrap();
+
+        assert_eq!(game.put(1, 1, Letter::O), Ok(0));
+        assert_eq!(game.put(1, 1, Letter::S), Err(SosError::PositionOccupied));
+        assert_eq!(game.put(3, 0, Letter::S), Err(SosError::OutOfBounds));
+        assert_eq!(game.put(0, 0, Letter::S), Ok(0));
+        assert_eq!(game.put(2, 0, Letter::S), Ok(0));
+        assert_eq!(game.next_player(), Player::Player1);
+
+        // a diagonal through the O
+        assert_eq!(game.put(2, 2, Letter::S), Ok(1));
+        assert_eq!(game.next_player(), Player::Player1);
+
+        assert_eq!(game.put(0, 1, Letter::O), Ok(0));
+        assert_eq!(game.next_player(), Player::Player0);
+
+        // two sequences at once, a row and a diagonal
+        assert_eq!(game.put(0, 2, Letter::S), Ok(2));
+        assert_eq!(game.score(Player::Player0), 2);
+        assert_eq!(game.score(Player::Player1), 1);
+        assert_eq!(game.validate(), Ok(()));
+
+        // the columns at both sides
+        assert_eq!(game.put(1, 0, Letter::O), Ok(1));
+        assert_eq!(game.put(1, 2, Letter::O), Ok(1));
+        assert_eq!(game.put(2, 1, Letter::S), Ok(0));
+
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+        assert_eq!(game.validate(), Ok(()));
+        assert_eq!(game.put(0, 0, Letter::S), Err(SosError::GameEnded));
+
+        let mut game = Sos::new(1, 3).unwrap();
+        game.put(0, 0, Letter::S).unwrap();
+        game.scores[0] = 1;
+        assert_eq!(game.validate(), Err(InvariantError::Counter));
+
+        let mut game = Sos::new(1, 1).unwrap();
+        game.put(0, 0, Letter::S).unwrap();
+        assert_eq!(game.status(), &Status::Draw);
+        assert_eq!(game.end_reason(), Some(EndReason::Normal));
+    }
+}
@@ -0,0 +1,196 @@
+//! Random games for property testing and fuzzing
+//!
+//! Every game implements [`RandomPlay`], which picks uniformly random legal moves with any [`GameRng`]. Drive it with a generator seeded by the test framework, e.g. a `u64` strategy of proptest or quickcheck, to get shrinkable random games
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(all(feature = "reversi", feature = "rand"))]
+//! # fn reversi() {
+//! use gamie::{reversi::Reversi, testing::RandomPlay};
+//! use rand::{rngs::StdRng, SeedableRng};
+//!
+//! let mut rng = StdRng::seed_from_u64(0);
+//! let mut game = Reversi::new().unwrap();
+//!
+//! assert_eq!(game.play_random(&mut rng, 10), 10);
+//!
+//! // the game is played to its end
+//! game.play_random(&mut rng, usize::MAX);
+//! assert!(game.random_move(&mut rng).is_none());
+//! # }
+//! # #[cfg(all(feature = "reversi", feature = "rand"))]
+//! # reversi();
+//! ```
+
+// the helpers are only used by the games
+#![cfg_attr(
+    not(any(
+        feature = "connect_four",
+        feature = "connect6",
+        feature = "fox_and_geese",
+        feature = "gomoku",
+        feature = "hasami_shogi",
+        feature = "minesweeper",
+        feature = "pente",
+        feature = "reversi",
+        feature = "score_four",
+        feature = "tictactoe"
+    )),
+    allow(dead_code)
+)]
+
+use crate::rng::GameRng;
+
+// the random moves tried for each ply of a random position before giving up on keeping the game going
+const MOVE_ATTEMPTS: usize = 8;
+
+/// A game which can be played with random legal moves
+pub trait RandomPlay {
+    /// The move of the game
+    type Move;
+
+    /// Pick a uniformly random legal move, `None` if there is no legal move, e.g. the game has ended
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Self::Move>;
+
+    /// Apply a move picked by [`random_move`](RandomPlay::random_move)
+    ///
+    /// Panic if the move is illegal
+    fn apply_move(&mut self, mv: Self::Move);
+
+    /// Play up to `max_moves` random legal moves, return the number of moves played
+    fn play_random<R: GameRng + ?Sized>(&mut self, rng: &mut R, max_moves: usize) -> usize {
+        let mut played = 0;
+
+        while played < max_moves {
+            let Some(mv) = self.random_move(rng) else {
+                break;
+            };
+
+            self.apply_move(mv);
+            played += 1;
+        }
+
+        played
+    }
+
+    /// Play up to `plies` random legal moves, avoiding the moves that end the game, return the number of moves played
+    ///
+    /// A few random moves are tried for each ply, and playing stops early when each of them ends the game, so the game is left ongoing whenever it can be. This makes varied positions for benchmarks, training data or screenshots
+    fn play_random_position<R: GameRng + ?Sized>(&mut self, rng: &mut R, plies: usize) -> usize
+    where
+        Self: Clone,
+    {
+        let mut played = 0;
+
+        while played < plies {
+            let next = (0..MOVE_ATTEMPTS).find_map(|_| {
+                let mut next = self.clone();
+                next.apply_move(self.random_move(rng)?);
+                next.random_move(rng).is_some().then_some(next)
+            });
+
+            let Some(next) = next else {
+                break;
+            };
+
+            *self = next;
+            played += 1;
+        }
+
+        played
+    }
+}
+
+/// Pick a uniformly random item with reservoir sampling, without collecting the items
+pub(crate) fn choose<R, T>(rng: &mut R, items: impl Iterator<Item = T>) -> Option<T>
+where
+    R: GameRng + ?Sized,
+{
+    let mut chosen = None;
+
+    for (count, item) in items.enumerate() {
+        if rng.gen_below(count + 1) == 0 {
+            chosen = Some(item);
+        }
+    }
+
+    chosen
+}
+
+/// Iterate over the positions of a board in row-major order
+pub(crate) fn positions(height: usize, width: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        connect6::Connect6,
+        connect_four,
+        connect_four::ConnectFour,
+        dominoes::Dominoes,
+        drop_three::{self, DropThree},
+        fox_and_geese::FoxAndGeese,
+        gomoku::Gomoku,
+        hasami_shogi::HasamiShogi,
+        liars_dice::LiarsDice,
+        minesweeper::Minesweeper,
+        order_and_chaos::OrderAndChaos,
+        pente::Pente,
+        pig::Pig,
+        reversi::Reversi,
+        rock_paper_scissors::RockPaperScissors,
+        score_four::ScoreFour,
+        shut_the_box::ShutTheBox,
+        sos::Sos,
+        testing::*,
+        tictactoe::{self, TicTacToe},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn play_to_end(mut game: impl RandomPlay, rng: &mut StdRng) {
+        game.play_random(rng, usize::MAX);
+        assert!(game.random_move(rng).is_none());
+    }
+
+    #[test]
+    fn test() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..4 {
+            play_to_end(TicTacToe::new().unwrap(), &mut rng);
+            play_to_end(ConnectFour::with_flip().unwrap(), &mut rng);
+            play_to_end(Gomoku::new().unwrap(), &mut rng);
+            play_to_end(Connect6::new().unwrap(), &mut rng);
+            play_to_end(Reversi::with_free_setup().unwrap(), &mut rng);
+            play_to_end(ScoreFour::new().unwrap(), &mut rng);
+            play_to_end(OrderAndChaos::new().unwrap(), &mut rng);
+            play_to_end(Sos::new(5, 5).unwrap(), &mut rng);
+            play_to_end(
+                DropThree::new(drop_three::Rules::default()).unwrap(),
+                &mut rng,
+            );
+            play_to_end(RockPaperScissors::with_best_of(5).unwrap(), &mut rng);
+            play_to_end(Dominoes::new(&mut rng).unwrap(), &mut rng);
+            play_to_end(ShutTheBox::with_tiles(12).unwrap(), &mut rng);
+            play_to_end(LiarsDice::new(3).unwrap(), &mut rng);
+
+            let game = Minesweeper::new(8, 8, 10, StdRng::seed_from_u64(0)).unwrap();
+            play_to_end(game, &mut rng);
+
+            // captures may keep these going for long
+            assert_eq!(Pente::new().unwrap().play_random(&mut rng, 20), 20);
+            HasamiShogi::new().unwrap().play_random(&mut rng, 100);
+            Pig::new(4).unwrap().play_random(&mut rng, 100);
+            FoxAndGeese::new().unwrap().play_random(&mut rng, 100);
+
+            // a position is kept ongoing, even when the board fills up
+            let game = TicTacToe::random_position(&mut rng, 9);
+            assert_eq!(game.status(), &tictactoe::Status::Ongoing);
+            assert_eq!(Reversi::random_position(&mut rng, 20).move_number(), 20);
+            let game = ConnectFour::random_position(&mut rng, 42);
+            assert_eq!(game.status(), &connect_four::Status::Ongoing);
+        }
+    }
+}
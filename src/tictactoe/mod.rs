@@ -0,0 +1,813 @@
+//! Tic-Tac-Toe
+//!
+//! Check struct [`TicTacToe`] for more information
+
+#[cfg(feature = "tablebase")]
+pub mod tablebase;
+
+use crate::{
+    invariant::{self, InvariantError},
+    outcome::{AdjudicationReason, EndReason},
+    parse::{ParseError, Words},
+    util::{KInARow, LINES},
+};
+#[cfg(feature = "testing")]
+use crate::{
+    rng::GameRng,
+    testing::{self, RandomPlay},
+};
+use core::{
+    convert::Infallible,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+use snafu::Snafu;
+
+pub(crate) const BOARD_WIDTH: usize = 3;
+pub(crate) const BOARD_HEIGHT: usize = 3;
+
+// the number of pieces in a row a player wins with
+const LINE_LENGTH: usize = 3;
+
+/// Tic-Tac-Toe
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::tictactoe::TicTacToe;
+/// let mut game = TicTacToe::new().unwrap();
+/// game.put(1, 0).unwrap();
+/// game.put(0, 1).unwrap();
+/// // ...
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-validate", serde(remote = "Self"))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TicTacToe {
+    board: [[Option<Player>; BOARD_HEIGHT]; BOARD_WIDTH],
+    move_count: usize,
+    next_player: Player,
+    status: Status,
+    end_reason: EndReason,
+    draw_offer: Option<Player>,
+}
+
+#[cfg(feature = "serde-validate")]
+crate::invariant::validated_serde!(TicTacToe);
+
+/// Player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Player {
+    Player0,
+    Player1,
+}
+
+/// Game status
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Status {
+    Ongoing,
+    Draw,
+    Win(Player),
+}
+
+crate::code::codes!(Status {
+    Ongoing => "ongoing",
+    Draw => "draw",
+    Win => "win",
+});
+
+crate::canonical::encode_enum!(Player {
+    0 => Player0,
+    1 => Player1,
+});
+
+crate::canonical::encode_enum!(Status {
+    0 => Ongoing,
+    1 => Draw,
+    2 => Win(player),
+});
+
+crate::canonical::canonical!(TicTacToe, "tictactoe", 1 {
+    board,
+    move_count,
+    next_player,
+    status,
+    end_reason,
+    draw_offer,
+});
+
+/// A move, which places a piece at a position
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    /// Positions are always in bounds
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            row: u.choose_index(BOARD_HEIGHT)?,
+            col: u.choose_index(BOARD_WIDTH)?,
+        })
+    }
+}
+
+/// The change of a position between two game states
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CellDiff {
+    pub before: Option<Player>,
+    pub after: Option<Player>,
+}
+
+/// Differences between two game states, see [`TicTacToe::diff`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BoardDiff {
+    /// The change of each position, `None` if unchanged
+    pub cells: [[Option<CellDiff>; BOARD_WIDTH]; BOARD_HEIGHT],
+    /// The next player before and after, `None` if unchanged
+    pub next_player: Option<(Player, Player)>,
+    /// The game status before and after, `None` if unchanged
+    pub status: Option<(Status, Status)>,
+}
+
+/// Errors that can occur when placing a piece onto the board
+#[derive(Debug, Eq, PartialEq, Snafu)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum TicTacToeError {
+    #[snafu(display("position occupied"))]
+    PositionOccupied,
+    #[snafu(display("game ended"))]
+    GameEnded,
+    #[snafu(display("position out of bounds"))]
+    OutOfBounds,
+    #[snafu(display("no draw offer"))]
+    NoDrawOffer,
+}
+
+crate::code::codes!(TicTacToeError {
+    PositionOccupied => "position_occupied",
+    GameEnded => "game_ended",
+    OutOfBounds => "out_of_bounds",
+    NoDrawOffer => "no_draw_offer",
+});
+
+struct LastMove {
+    player: Player,
+    row: usize,
+    col: usize,
+}
+
+impl TicTacToe {
+    /// Create a new Tic-Tac-Toe game
+    pub const fn new() -> Result<Self, Infallible> {
+        Ok(Self::initial())
+    }
+
+    /// Create a new Tic-Tac-Toe game, the same as [`new`](Self::new) without the `Result`
+    pub const fn initial() -> Self {
+        Self {
+            board: [[None; BOARD_HEIGHT]; BOARD_WIDTH],
+            move_count: 0,
+            next_player: Player::Player0,
+            status: Status::Ongoing,
+            end_reason: EndReason::Normal,
+            draw_offer: None,
+        }
+    }
+
+    /// Create a Tic-Tac-Toe game by replaying a list of moves from an empty board
+    ///
+    /// Return the index of the first invalid move and the error it caused on failure
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::tictactoe::{Player, TicTacToe};
+    /// let game = TicTacToe::from_moves(&[(1, 1), (0, 0)]).unwrap();
+    ///
+    /// assert_eq!(game.next_player(), Player::Player0);
+    /// assert!(TicTacToe::from_moves(&[(1, 1), (1, 1)]).is_err());
+    /// ```
+    ///
+    /// Positions can be built at compile time:
+    ///
+    /// ```rust
+    /// # use gamie::tictactoe::{Player, Status, TicTacToe};
+    /// const WON: TicTacToe = match TicTacToe::from_moves(&[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]) {
+    ///     Ok(game) => game,
+    ///     Err(_) => panic!("invalid moves"),
+    /// };
+    ///
+    /// assert_eq!(WON.status(), &Status::Win(Player::Player0));
+    /// ```
+    pub const fn from_moves(moves: &[(usize, usize)]) -> Result<Self, (usize, TicTacToeError)> {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        let mut idx = 0;
+
+        while idx < moves.len() {
+            let (row, col) = moves[idx];
+
+            if row >= BOARD_HEIGHT || col >= BOARD_WIDTH {
+                return Err((idx, TicTacToeError::OutOfBounds));
+            }
+
+            if let Err(err) = game.put(row, col) {
+                return Err((idx, err));
+            }
+
+            idx += 1;
+        }
+
+        Ok(game)
+    }
+
+    /// Compose a Tic-Tac-Toe game from a board and the next player, e.g. for a hand-authored puzzle
+    ///
+    /// The status is read from the board. Return the error of [`validate`](Self::validate) if the position could not have been reached by playing
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::tictactoe::{Player, TicTacToe};
+    /// const X: Option<Player> = Some(Player::Player0);
+    /// const O: Option<Player> = Some(Player::Player1);
+    ///
+    /// let game = TicTacToe::from_board(
+    ///     [[X, O, None], [None, X, None], [None, None, O]],
+    ///     Player::Player0,
+    /// )
+    /// .unwrap();
+    /// assert!(game.can_put(0, 2));
+    ///
+    /// // Player0 moves first, so it can never be behind
+    /// assert!(TicTacToe::from_board([[O, O, None], [None; 3], [None; 3]], Player::Player0).is_err());
+    /// ```
+    pub fn from_board(
+        board: [[Option<Player>; BOARD_WIDTH]; BOARD_HEIGHT],
+        next_player: Player,
+    ) -> Result<Self, InvariantError> {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        game.board = board;
+        game.move_count = board.iter().flatten().filter(|cell| cell.is_some()).count();
+        game.next_player = next_player;
+
+        let has_line = |player| {
+            Self::lines().any(|line| {
+                line.iter()
+                    .all(|&(row, col)| board[row][col] == Some(player))
+            })
+        };
+
+        game.status = if has_line(Player::Player0) {
+            Status::Win(Player::Player0)
+        } else if has_line(Player::Player1) {
+            Status::Win(Player::Player1)
+        } else if game.move_count == BOARD_HEIGHT * BOARD_WIDTH {
+            Status::Draw
+        } else {
+            Status::Ongoing
+        };
+
+        game.validate()?;
+        Ok(game)
+    }
+
+    /// Get a piece at a position
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn get(&self, row: usize, col: usize) -> Option<Player> {
+        self.board[row][col]
+    }
+
+    /// Get a piece at a position without bounds checking, for the hot loops of a search that keep the positions in bounds already
+    ///
+    /// The bounds are still checked in debug builds
+    ///
+    /// # Safety
+    ///
+    /// The target position must be in bounds, see [`get`](Self::get)
+    pub const unsafe fn get_unchecked(&self, row: usize, col: usize) -> Option<Player> {
+        debug_assert!(row < self.board.len() && col < self.board[0].len());
+
+        // SAFETY: the caller keeps the position in bounds
+        unsafe { *(*self.board.as_ptr().add(row)).as_ptr().add(col) }
+    }
+
+    /// Put a piece
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn put(&mut self, row: usize, col: usize) -> Result<(), TicTacToeError> {
+        if matches!(self.status, Status::Win(_) | Status::Draw) {
+            return Err(TicTacToeError::GameEnded);
+        }
+
+        if self.board[row][col].is_some() {
+            return Err(TicTacToeError::PositionOccupied);
+        }
+
+        self.board[row][col] = Some(self.next_player);
+
+        let last_move = LastMove {
+            player: self.next_player,
+            row,
+            col,
+        };
+
+        self.move_count += 1;
+        self.draw_offer = None;
+        self.next_player = self.next_player.other();
+
+        self.update_status(last_move);
+
+        Ok(())
+    }
+
+    /// Check if a piece can be put at a position, same as [`put`](Self::put) succeeding but without changing the game
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn can_put(&self, row: usize, col: usize) -> bool {
+        !matches!(self.status, Status::Win(_) | Status::Draw) && self.board[row][col].is_none()
+    }
+
+    /// Apply a move, same as [`put`](Self::put)
+    ///
+    /// Panic if the target position is out of bounds
+    pub const fn apply(&mut self, mv: Move) -> Result<(), TicTacToeError> {
+        self.put(mv.row, mv.col)
+    }
+
+    /// Get the game after applying a move, leaving this game unchanged
+    ///
+    /// The game holds no allocation, so this is a single copy of the game followed by [`apply`](Self::apply)
+    ///
+    /// Panic if the target position is out of bounds
+    pub fn preview(&self, mv: Move) -> Result<Self, TicTacToeError> {
+        let mut game = self.clone();
+        game.apply(mv)?;
+        Ok(game)
+    }
+
+    /// Read a move from text, a position as described in [`parse`](crate::parse)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::tictactoe::{Move, TicTacToe};
+    /// assert_eq!(TicTacToe::parse_move("b2"), Ok(Move { row: 1, col: 1 }));
+    /// assert_eq!(TicTacToe::parse_move("(0, 2)"), Ok(Move { row: 0, col: 2 }));
+    ///
+    /// assert_eq!(Move { row: 0, col: 2 }.to_string(), "0 2");
+    /// ```
+    pub fn parse_move(text: &str) -> Result<Move, ParseError> {
+        let mut words = Words::new(text);
+        let (row, col) = words.position(BOARD_HEIGHT, BOARD_WIDTH)?;
+
+        words.finish()?;
+        Ok(Move { row, col })
+    }
+
+    /// Check if the state could have been reached by playing, e.g. after deserializing it from an untrusted source
+    ///
+    /// The piece counts must match the move count and the next player, and a status ended by the rules must match the lines on the board
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        let count = |player| {
+            self.board
+                .iter()
+                .flatten()
+                .filter(|cell| **cell == Some(player))
+                .count()
+        };
+
+        let (player0_count, player1_count) = (count(Player::Player0), count(Player::Player1));
+
+        if player0_count + player1_count != self.move_count {
+            return Err(InvariantError::Counter);
+        }
+
+        invariant::check_turns(
+            player0_count,
+            player1_count,
+            self.next_player == Player::Player0,
+        )?;
+
+        let has_line = |player| {
+            Self::lines().any(|line| {
+                line.iter()
+                    .all(|&(row, col)| self.get(row, col) == Some(player))
+            })
+        };
+        let has_no_line = !has_line(Player::Player0) && !has_line(Player::Player1);
+        let is_full = self.move_count == BOARD_HEIGHT * BOARD_WIDTH;
+
+        let is_status_valid = match (&self.status, self.end_reason) {
+            (Status::Ongoing, EndReason::Normal) => has_no_line && !is_full,
+            (Status::Ongoing, _) => false,
+            (Status::Win(player), EndReason::Normal) => {
+                has_line(*player) && !has_line(player.other())
+            }
+            (Status::Draw, EndReason::Normal) => has_no_line && is_full,
+            // the players or an arbiter may end the game at any time
+            _ => true,
+        };
+
+        if !is_status_valid {
+            return Err(InvariantError::Status);
+        }
+
+        Ok(())
+    }
+
+    /// Resign the game on behalf of a player, the other player wins
+    pub fn resign(&mut self, player: Player) -> Result<(), TicTacToeError> {
+        self.end_by(Status::Win(player.other()), EndReason::Resignation)
+    }
+
+    /// End the game as a player ran out of time, the other player wins
+    pub fn time_out(&mut self, player: Player) -> Result<(), TicTacToeError> {
+        self.end_by(Status::Win(player.other()), EndReason::Timeout)
+    }
+
+    /// Offer a draw on behalf of a player
+    ///
+    /// The offer stands until the other player accepts it with [`accept_draw`](Self::accept_draw), or a move is made
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), TicTacToeError> {
+        if self.status != Status::Ongoing {
+            return Err(TicTacToeError::GameEnded);
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    /// Accept the draw offered by the other player
+    pub fn accept_draw(&mut self, player: Player) -> Result<(), TicTacToeError> {
+        if self.status == Status::Ongoing && self.draw_offer != Some(player.other()) {
+            return Err(TicTacToeError::NoDrawOffer);
+        }
+
+        self.end_by(Status::Draw, EndReason::Agreement)
+    }
+
+    /// End the game administratively, e.g. by a server or an arbiter, `None` as the winner for a draw
+    ///
+    /// The result is recorded as [`EndReason::Adjudication`], distinct from endings by the rules
+    pub fn adjudicate(
+        &mut self,
+        winner: Option<Player>,
+        reason: AdjudicationReason,
+    ) -> Result<(), TicTacToeError> {
+        let status = match winner {
+            Some(player) => Status::Win(player),
+            None => Status::Draw,
+        };
+
+        self.end_by(status, EndReason::Adjudication(reason))
+    }
+
+    /// Get the player with a pending draw offer
+    pub const fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    /// Get how the game ended, `None` if it is still ongoing
+    pub const fn end_reason(&self) -> Option<EndReason> {
+        match self.status {
+            Status::Ongoing => None,
+            _ => Some(self.end_reason),
+        }
+    }
+
+    /// Get the next player
+    pub const fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// Get game status
+    pub const fn status(&self) -> &Status {
+        &self.status
+    }
+
+    fn end_by(&mut self, status: Status, reason: EndReason) -> Result<(), TicTacToeError> {
+        if self.status != Status::Ongoing {
+            return Err(TicTacToeError::GameEnded);
+        }
+
+        self.status = status;
+        self.end_reason = reason;
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// Compare the game with a later state of it
+    ///
+    /// The returned diff describes how to get from `self` to `other`
+    pub fn diff(&self, other: &Self) -> BoardDiff {
+        let mut cells = [[None; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        for (row, cells) in cells.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                let (before, after) = (self.get(row, col), other.get(row, col));
+
+                if before != after {
+                    *cell = Some(CellDiff { before, after });
+                }
+            }
+        }
+
+        BoardDiff {
+            cells,
+            next_player: (self.next_player != other.next_player)
+                .then_some((self.next_player, other.next_player)),
+            status: (self.status != other.status)
+                .then(|| (self.status.clone(), other.status.clone())),
+        }
+    }
+
+    /// Iterate over all lines of 3 positions a player can win with, each position being `(row, col)`
+    pub fn lines() -> impl Iterator<Item = [(usize, usize); LINE_LENGTH]> {
+        (0..BOARD_HEIGHT as isize)
+            .flat_map(|row| (0..BOARD_WIDTH as isize).map(move |col| (row, col)))
+            .flat_map(|(row, col)| {
+                LINES.into_iter().filter_map(move |(row_step, col_step)| {
+                    let end_row = row + row_step * (LINE_LENGTH as isize - 1);
+                    let end_col = col + col_step * (LINE_LENGTH as isize - 1);
+
+                    let is_in_bounds = (0..BOARD_HEIGHT as isize).contains(&end_row)
+                        && (0..BOARD_WIDTH as isize).contains(&end_col);
+
+                    is_in_bounds.then(|| {
+                        core::array::from_fn(|distance| {
+                            (
+                                (row + row_step * distance as isize) as usize,
+                                (col + col_step * distance as isize) as usize,
+                            )
+                        })
+                    })
+                })
+            })
+    }
+
+    /// Get the number of lines through each empty position that a player can still win with, which are the lines without a piece of the other player
+    ///
+    /// Occupied positions are `0`. See [`lines`](Self::lines)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use gamie::tictactoe::{Player, TicTacToe};
+    /// let mut game = TicTacToe::new().unwrap();
+    /// game.put(1, 1).unwrap();
+    ///
+    /// // every line through a corner of Player1 passes the center or another occupied position
+    /// assert_eq!(game.heatmap(Player::Player1)[0][0], 2);
+    /// assert_eq!(game.heatmap(Player::Player0)[0][0], 3);
+    /// ```
+    pub fn heatmap(&self, player: Player) -> [[usize; BOARD_WIDTH]; BOARD_HEIGHT] {
+        let mut heatmap = [[0; BOARD_WIDTH]; BOARD_HEIGHT];
+
+        let open_lines = Self::lines().filter(|line| {
+            line.iter()
+                .all(|(row, col)| self.get(*row, *col) != Some(player.other()))
+        });
+
+        for line in open_lines {
+            for (row, col) in line {
+                if self.get(row, col).is_none() {
+                    heatmap[row][col] += 1;
+                }
+            }
+        }
+
+        heatmap
+    }
+
+    /// Check if a position holds a piece of a player, as `==` is not available in const contexts
+    const fn is_piece_of(&self, row: usize, col: usize, player: Player) -> bool {
+        matches!(
+            (self.get(row, col), player),
+            (Some(Player::Player0), Player::Player0) | (Some(Player::Player1), Player::Player1)
+        )
+    }
+
+    const fn update_status(&mut self, last_move: LastMove) {
+        let player = last_move.player;
+        let mut scan =
+            KInARow::<BOARD_HEIGHT, BOARD_WIDTH>::new(last_move.row, last_move.col, LINE_LENGTH);
+
+        while let Some((row, col)) = scan.next() {
+            scan.record(self.is_piece_of(row, col, player));
+        }
+
+        if scan.is_found() {
+            self.status = Status::Win(player);
+            return;
+        }
+
+        // check draw
+        if self.move_count == BOARD_HEIGHT * BOARD_WIDTH {
+            self.status = Status::Draw;
+        }
+    }
+}
+
+impl Default for TicTacToe {
+    fn default() -> Self {
+        Self::initial()
+    }
+}
+
+impl BoardDiff {
+    /// Iterate over the changed positions as `(row, col, diff)`, in row-major order
+    pub fn changes(&self) -> impl Iterator<Item = (usize, usize, CellDiff)> + '_ {
+        self.cells.iter().enumerate().flat_map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .filter_map(move |(col, cell)| cell.map(|cell| (row, col, cell)))
+        })
+    }
+
+    /// Check if the two game states are identical
+    pub fn is_empty(&self) -> bool {
+        self.changes().next().is_none() && self.next_player.is_none() && self.status.is_none()
+    }
+}
+
+impl Player {
+    /// Get the opposite player
+    pub const fn other(self) -> Self {
+        match self {
+            Player::Player0 => Player::Player1,
+            Player::Player1 => Player::Player0,
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} {}", self.row, self.col)
+    }
+}
+
+#[cfg(feature = "testing")]
+impl TicTacToe {
+    /// Get a position after up to `plies` random legal moves from the start, kept ongoing whenever it can be, e.g. for benchmarks, see [`RandomPlay::play_random_position`]
+    pub fn random_position<R: GameRng + ?Sized>(rng: &mut R, plies: usize) -> Self {
+        let mut game = match Self::new() {
+            Ok(game) => game,
+            Err(err) => match err {},
+        };
+
+        game.play_random_position(rng, plies);
+        game
+    }
+}
+
+#[cfg(feature = "testing")]
+impl RandomPlay for TicTacToe {
+    type Move = Move;
+
+    fn random_move<R: GameRng + ?Sized>(&self, rng: &mut R) -> Option<Move> {
+        let moves = testing::positions(BOARD_HEIGHT, BOARD_WIDTH)
+            .filter(|&(row, col)| self.can_put(row, col));
+        testing::choose(rng, moves).map(|(row, col)| Move { row, col })
+    }
+
+    fn apply_move(&mut self, mv: Move) {
+        self.apply(mv).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tictactoe::*;
+
+    #[test]
+    fn test() {
+        assert_eq!(TicTacToe::default(), TicTacToe::new().unwrap());
+
+        let mut game = TicTacToe::initial();
+
+        game.put(1, 1).unwrap();
+
+        assert_eq!(game.next_player(), Player::Player1);
+
+        game.apply(Move { row: 1, col: 0 }).unwrap();
+
+        assert_eq!(game.next_player(), Player::Player0);
+        assert_eq!(game.put(1, 1), Err(TicTacToeError::PositionOccupied));
+        assert!(!game.can_put(1, 1));
+        assert!(game.can_put(2, 2));
+
+        let previewed = game.preview(Move { row: 2, col: 2 }).unwrap();
+        assert_eq!(previewed.get(2, 2), Some(Player::Player0));
+        assert_eq!(game.get(2, 2), None);
+
+        let before = game.clone();
+
+        game.put(2, 2).unwrap();
+        game.put(2, 0).unwrap();
+        game.put(0, 0).unwrap();
+
+        let diff = before.diff(&game);
+        assert_eq!(diff.changes().count(), 3);
+        assert_eq!(diff.next_player, Some((Player::Player0, Player::Player1)));
+        assert!(game.diff(&game).is_empty());
+
+        let composed = TicTacToe::from_board(game.board, Player::Player1).unwrap();
+        assert!(composed.diff(&game).is_empty());
+        assert_eq!(
+            TicTacToe::from_board(game.board, Player::Player0).unwrap_err(),
+            InvariantError::NextPlayer
+        );
+
+        assert_eq!(game.status(), &Status::Win(Player::Player0));
+        assert_eq!(game.put(0, 2), Err(TicTacToeError::GameEnded));
+        assert!(!game.can_put(0, 2));
+
+        assert_eq!(
+            TicTacToe::from_moves(&[(1, 1), (1, 0), (2, 2), (2, 0), (0, 0), (0, 2)]).unwrap_err(),
+            (5, TicTacToeError::GameEnded)
+        );
+        assert_eq!(
+            TicTacToe::from_moves(&[(3, 0)]).unwrap_err(),
+            (0, TicTacToeError::OutOfBounds)
+        );
+
+        assert_eq!(game.end_reason(), Some(EndReason::Normal));
+        assert_eq!(game.resign(Player::Player1), Err(TicTacToeError::GameEnded));
+
+        assert_eq!(TicTacToe::lines().count(), 8);
+        assert_eq!(
+            TicTacToe::from_moves(&[(1, 1)])
+                .unwrap()
+                .heatmap(Player::Player1),
+            [[2, 1, 2], [1, 0, 1], [2, 1, 2]]
+        );
+
+        let mut game = TicTacToe::new().unwrap();
+        assert_eq!(game.end_reason(), None);
+
+        game.offer_draw(Player::Player0).unwrap();
+        assert_eq!(
+            game.accept_draw(Player::Player0),
+            Err(TicTacToeError::NoDrawOffer)
+        );
+
+        // a move withdraws the offer
+        game.put(1, 1).unwrap();
+        assert_eq!(game.draw_offer(), None);
+
+        game.offer_draw(Player::Player1).unwrap();
+        game.accept_draw(Player::Player0).unwrap();
+        assert_eq!(game.status(), &Status::Draw);
+        assert_eq!(game.end_reason(), Some(EndReason::Agreement));
+
+        let mut game = TicTacToe::new().unwrap();
+        game.time_out(Player::Player0).unwrap();
+        assert_eq!(game.status(), &Status::Win(Player::Player1));
+        assert_eq!(game.end_reason(), Some(EndReason::Timeout));
+
+        let mut game = TicTacToe::new().unwrap();
+        game.adjudicate(None, AdjudicationReason::Arbiter).unwrap();
+        assert_eq!(game.status(), &Status::Draw);
+        assert_eq!(
+            game.end_reason(),
+            Some(EndReason::Adjudication(AdjudicationReason::Arbiter))
+        );
+
+        let mut game = TicTacToe::from_moves(&[(1, 1), (0, 0)]).unwrap();
+        assert_eq!(game.validate(), Ok(()));
+
+        game.next_player = Player::Player1;
+        assert_eq!(game.validate(), Err(InvariantError::NextPlayer));
+
+        game.next_player = Player::Player0;
+        game.status = Status::Win(Player::Player0);
+        assert_eq!(game.validate(), Err(InvariantError::Status));
+    }
+}
@@ -0,0 +1,234 @@
+//! Perfect play for Tic-Tac-Toe
+//!
+//! Every position reachable from the empty board is solved at compile time into a table of 2 bits per position, so looking up the [`value`] and the [`best_moves`] of a game never searches
+//!
+//! # Examples
+//!
+//! ```rust
+//! use gamie::tictactoe::{
+//!     tablebase::{self, Value},
+//!     TicTacToe,
+//! };
+//!
+//! let game = TicTacToe::new().unwrap();
+//! assert_eq!(tablebase::value(&game), Value::Draw);
+//!
+//! // a corner reply is the only way to hold the draw after a center opening
+//! let game = TicTacToe::from_moves(&[(1, 1)]).unwrap();
+//! assert!(tablebase::best_moves(&game).all(|(row, col)| row != 1 && col != 1));
+//! ```
+
+use super::{Player, Status, TicTacToe, BOARD_HEIGHT, BOARD_WIDTH};
+
+const CELLS: usize = BOARD_HEIGHT * BOARD_WIDTH;
+
+// each position is indexed by its cells in base 3, see `index()`
+const POSITIONS: usize = 3usize.pow(CELLS as u32);
+
+// the value of each position packed in 2 bits, 4 positions a byte
+static TABLE: [u8; POSITIONS.div_ceil(4)] = solve();
+
+// marks a position not reachable from the empty board
+const UNKNOWN: u8 = 0b11;
+
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+/// The game-theoretic value of a position for the next player, assuming perfect play from both players
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Value {
+    Win,
+    Draw,
+    Loss,
+}
+
+/// Get the value of a game for its next player
+///
+/// An ended game is valued by its status, including games ended by resignation or agreement
+pub const fn value(game: &TicTacToe) -> Value {
+    match game.status {
+        Status::Ongoing => lookup(index(game)),
+        Status::Draw => Value::Draw,
+        Status::Win(winner) => {
+            if matches!(
+                (winner, game.next_player),
+                (Player::Player0, Player::Player0) | (Player::Player1, Player::Player1)
+            ) {
+                Value::Win
+            } else {
+                Value::Loss
+            }
+        }
+    }
+}
+
+/// Iterate over the moves keeping the best value for the next player, each move being `(row, col)`
+///
+/// Nothing is yielded if the game has ended
+pub fn best_moves(game: &TicTacToe) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let is_ongoing = game.status == Status::Ongoing;
+    let index = index(game);
+    let piece = piece(game.next_player);
+
+    let moves = (0..CELLS)
+        .filter(move |cell| is_ongoing && (index / 3usize.pow(*cell as u32)).is_multiple_of(3))
+        .map(move |cell| (cell, lookup(index + piece * 3usize.pow(cell as u32))));
+
+    // the best move leaves the other player the worst value
+    let worst = moves
+        .clone()
+        .map(|(_, value)| value as u8)
+        .max()
+        .unwrap_or_default();
+
+    moves
+        .filter(move |(_, value)| *value as u8 == worst)
+        .map(|(cell, _)| (cell / BOARD_WIDTH, cell % BOARD_WIDTH))
+}
+
+const fn piece(player: Player) -> usize {
+    match player {
+        Player::Player0 => 1,
+        Player::Player1 => 2,
+    }
+}
+
+/// The cells of a game in base 3, `0` for an empty cell and [`piece()`] for a piece, with cell `(0, 0)` as the least significant digit
+const fn index(game: &TicTacToe) -> usize {
+    let mut index = 0;
+    let mut cell = CELLS;
+
+    while cell > 0 {
+        cell -= 1;
+
+        index *= 3;
+
+        if let Some(player) = game.get(cell / BOARD_WIDTH, cell % BOARD_WIDTH) {
+            index += piece(player);
+        }
+    }
+
+    index
+}
+
+const fn lookup(index: usize) -> Value {
+    match (TABLE[index / 4] >> (index % 4 * 2)) & 0b11 {
+        0 => Value::Win,
+        1 => Value::Draw,
+        2 => Value::Loss,
+        _ => unreachable!(),
+    }
+}
+
+const fn solve() -> [u8; POSITIONS.div_ceil(4)] {
+    let mut table = [u8::MAX; POSITIONS.div_ceil(4)];
+    solve_position(&mut table, [0; CELLS], 0, 0);
+    table
+}
+
+/// Solve a position and every position following it, return the value for the next player as stored in the table
+const fn solve_position(
+    table: &mut [u8; POSITIONS.div_ceil(4)],
+    mut cells: [usize; CELLS],
+    index: usize,
+    move_count: usize,
+) -> u8 {
+    let stored = (table[index / 4] >> (index % 4 * 2)) & 0b11;
+
+    if stored != UNKNOWN {
+        return stored;
+    }
+
+    // only the player who just moved can have a line
+    let mut line = 0;
+    let mut value = Value::Draw as u8;
+
+    while line < LINES.len() {
+        let [a, b, c] = LINES[line];
+
+        if cells[a] != 0 && cells[a] == cells[b] && cells[b] == cells[c] {
+            value = Value::Loss as u8;
+        }
+
+        line += 1;
+    }
+
+    if value != Value::Loss as u8 && move_count < CELLS {
+        let piece = move_count % 2 + 1;
+        let mut cell = 0;
+
+        value = Value::Loss as u8;
+
+        while cell < CELLS {
+            if cells[cell] == 0 {
+                cells[cell] = piece;
+
+                let next_index = index + piece * 3usize.pow(cell as u32);
+                let next_value = solve_position(table, cells, next_index, move_count + 1);
+
+                // the value of the other player is mirrored
+                let value_after = Value::Loss as u8 - next_value;
+
+                if value_after < value {
+                    value = value_after;
+                }
+
+                cells[cell] = 0;
+            }
+
+            cell += 1;
+        }
+    }
+
+    table[index / 4] &= !(0b11 << (index % 4 * 2));
+    table[index / 4] |= value << (index % 4 * 2);
+
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tictactoe::{tablebase::*, Status, TicTacToe};
+
+    #[test]
+    fn test() {
+        // Player0 completes the bottom row at once
+        let game =
+            TicTacToe::from_moves(&[(0, 0), (1, 1), (2, 2), (0, 2), (2, 0), (1, 0)]).unwrap();
+        assert_eq!(value(&game), Value::Win);
+        assert!(best_moves(&game).all(|mv| mv == (2, 1)));
+
+        // Player0 threatens two lines
+        let game = TicTacToe::from_moves(&[(0, 0), (1, 1), (2, 2), (0, 2), (2, 0)]).unwrap();
+        assert_eq!(value(&game), Value::Loss);
+
+        let game = TicTacToe::from_moves(&[(1, 1), (0, 1)]).unwrap();
+        assert_eq!(value(&game), Value::Win);
+
+        let mut game = TicTacToe::from_moves(&[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]).unwrap();
+        assert_eq!(value(&game), Value::Loss);
+        assert_eq!(best_moves(&game).count(), 0);
+
+        // the table agrees with the status of every game played along the best moves
+        game = TicTacToe::new().unwrap();
+
+        while game.status() == &Status::Ongoing {
+            assert_eq!(value(&game), Value::Draw);
+
+            let (row, col) = best_moves(&game).next().unwrap();
+            game.put(row, col).unwrap();
+        }
+
+        assert_eq!(game.status(), &Status::Draw);
+    }
+}
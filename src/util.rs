@@ -0,0 +1,423 @@
+//! Building blocks for board games, without allocation
+//!
+//! [`UnionFind`] tracks connected groups, e.g. for the sides joined in Hex or the chains in Go, [`Bfs`] finds the shortest paths on a grid with blockers, e.g. for the walls in Quoridor, and [`KInARow`] finds the lines of pieces through a position, e.g. for the five in a row of Gomoku
+//!
+//! # Examples
+//!
+//! ```rust
+//! use gamie::util::{k_in_a_row, Bfs, UnionFind, ORTHOGONAL};
+//!
+//! let mut groups = UnionFind::<4>::new();
+//! groups.union(0, 1);
+//! groups.union(2, 3);
+//! assert!(!groups.is_connected(0, 3));
+//!
+//! // a wall between the two left cells
+//! let bfs = Bfs::<2, 2>::new((0, 0), &ORTHOGONAL, |from, to| {
+//!     (from, to) != ((0, 0), (1, 0)) && (from, to) != ((1, 0), (0, 0))
+//! });
+//! assert_eq!(bfs.distance(1, 0), Some(3));
+//!
+//! // a diagonal of three on a 3 by 3 board
+//! let pieces = [(0, 0), (2, 2)];
+//! assert!(k_in_a_row::<3, 3>(1, 1, 3, |row, col| pieces.contains(&(row, col))));
+//! ```
+
+/// The four orthogonal directions as `(row, col)` steps
+pub const ORTHOGONAL: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+/// The eight orthogonal and diagonal directions as `(row, col)` steps
+pub const ALL: [(isize, isize); 8] = [
+    (0, 1),
+    (1, 0),
+    (1, 1),
+    (1, -1),
+    (0, -1),
+    (-1, 0),
+    (-1, -1),
+    (-1, 1),
+];
+
+/// The six directions of a hexagonal grid stored as a rhombus, as `(row, col)` steps
+///
+/// Each row is shifted half a cell further than the one above, so a cell touches the cells left and right of it, the two above it to the right, and the two below it to the left
+pub const HEXAGONAL: [(isize, isize); 6] = [(0, 1), (1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1)];
+
+/// The four directions a line runs in as `(row, col)` steps: horizontal, vertical, and the two diagonals
+///
+/// Each one stands for itself and its opposite in [`ALL`]
+pub const LINES: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Disjoint sets of `N` elements, numbered from `0`
+///
+/// Finding merges the path to the representative of a set as it goes, and union attaches the smaller set to the larger one, so both are close to constant time
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct UnionFind<const N: usize> {
+    parents: [usize; N],
+    sizes: [usize; N],
+}
+
+impl<const N: usize> UnionFind<N> {
+    /// Create `N` sets of a single element each
+    pub const fn new() -> Self {
+        let mut parents = [0; N];
+        let mut idx = 0;
+
+        while idx < N {
+            parents[idx] = idx;
+            idx += 1;
+        }
+
+        Self {
+            parents,
+            sizes: [1; N],
+        }
+    }
+
+    /// Get the representative element of the set of an element
+    ///
+    /// Panic if the element is out of bounds
+    pub fn find(&mut self, mut element: usize) -> usize {
+        while self.parents[element] != element {
+            // path halving, each element on the path skips its parent
+            self.parents[element] = self.parents[self.parents[element]];
+            element = self.parents[element];
+        }
+
+        element
+    }
+
+    /// Merge the sets of two elements
+    ///
+    /// Return `false` if they were already in the same set
+    ///
+    /// Panic if an element is out of bounds
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+
+        if a == b {
+            return false;
+        }
+
+        let (larger, smaller) = if self.sizes[a] >= self.sizes[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+
+        self.parents[smaller] = larger;
+        self.sizes[larger] += self.sizes[smaller];
+        true
+    }
+
+    /// Check if two elements are in the same set
+    ///
+    /// Panic if an element is out of bounds
+    pub fn is_connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Get the number of elements in the set of an element
+    ///
+    /// Panic if the element is out of bounds
+    pub fn size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.sizes[root]
+    }
+}
+
+impl<const N: usize> Default for UnionFind<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A breadth-first search on a grid of `H` rows and `W` columns from a start position, which holds the shortest path to every reachable position
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Bfs<const H: usize, const W: usize> {
+    distances: [[Option<usize>; W]; H],
+    parents: [[(usize, usize); W]; H],
+}
+
+impl<const H: usize, const W: usize> Bfs<H, W> {
+    /// Search from a start position, stepping in the given directions, e.g. [`ORTHOGONAL`]
+    ///
+    /// `is_passable` is called with each step as `(from, to)` positions in bounds, and blocks the step by returning `false`. This covers both occupied positions and walls between two positions
+    ///
+    /// Panic if the start position is out of bounds
+    pub fn new(
+        start: (usize, usize),
+        directions: &[(isize, isize)],
+        mut is_passable: impl FnMut((usize, usize), (usize, usize)) -> bool,
+    ) -> Self {
+        assert!(start.0 < H && start.1 < W);
+
+        let mut bfs = Self {
+            distances: [[None; W]; H],
+            parents: [[start; W]; H],
+        };
+
+        // each position is queued at most once, so the queue fits in a grid of the same size
+        let mut queue = [[(0, 0); W]; H];
+        let (mut head, mut tail) = (0, 1);
+
+        queue[0][0] = start;
+        bfs.distances[start.0][start.1] = Some(0);
+
+        while head < tail {
+            let from = queue[head / W][head % W];
+            let distance = bfs.distances[from.0][from.1].unwrap_or(0) + 1;
+            head += 1;
+
+            for (row_step, col_step) in directions {
+                let (row, col) = (from.0 as isize + row_step, from.1 as isize + col_step);
+
+                if !(0..H as isize).contains(&row) || !(0..W as isize).contains(&col) {
+                    continue;
+                }
+
+                let to = (row as usize, col as usize);
+
+                if bfs.distances[to.0][to.1].is_some() || !is_passable(from, to) {
+                    continue;
+                }
+
+                bfs.distances[to.0][to.1] = Some(distance);
+                bfs.parents[to.0][to.1] = from;
+                queue[tail / W][tail % W] = to;
+                tail += 1;
+            }
+        }
+
+        bfs
+    }
+
+    /// Get the number of steps from the start to a position, `None` if unreachable
+    ///
+    /// Panic if the position is out of bounds
+    pub const fn distance(&self, row: usize, col: usize) -> Option<usize> {
+        self.distances[row][col]
+    }
+
+    /// Get a shortest path from a position back to the start, both included, or an empty path if the position is unreachable
+    ///
+    /// Panic if the position is out of bounds
+    pub fn path_from(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let steps = self.distances[row][col].map_or(0, |distance| distance + 1);
+
+        core::iter::successors(Some((row, col)), |&(row, col)| Some(self.parents[row][col]))
+            .take(steps)
+    }
+}
+
+/// A scan for `k` or more pieces in a row through a position on a grid of `H` rows and `W` columns, counted in each of the [`LINES`]
+///
+/// The position itself is counted as a piece, so the scan also tells if putting a piece there would make a line. It is driven by the caller, which makes it usable in const contexts: [`next`](Self::next) yields the position to look at, and [`record`](Self::record) tells whether it holds a piece. A line only counts consecutive pieces, the scan of a direction stops at the first position without one
+///
+/// See [`k_in_a_row`] for the same check with a closure
+///
+/// # Examples
+///
+/// ```rust
+/// # use gamie::util::KInARow;
+/// // a gap breaks the row
+/// let board = [[true, true, false, true, true]];
+///
+/// let mut scan = KInARow::<1, 5>::new(0, 1, 3);
+/// while let Some((row, col)) = scan.next() {
+///     scan.record(board[row][col]);
+/// }
+/// assert!(!scan.is_found());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct KInARow<const H: usize, const W: usize> {
+    row: usize,
+    col: usize,
+    k: usize,
+    line_idx: usize,
+    is_backward: bool,
+    distance: usize,
+    count: usize,
+    is_found: bool,
+}
+
+impl<const H: usize, const W: usize> KInARow<H, W> {
+    /// Start a scan for `k` pieces in a row through a position
+    ///
+    /// Panic if the position is out of bounds
+    pub const fn new(row: usize, col: usize, k: usize) -> Self {
+        assert!(row < H && col < W);
+
+        Self {
+            row,
+            col,
+            k,
+            line_idx: 0,
+            is_backward: false,
+            distance: 1,
+            count: 1,
+            is_found: k <= 1,
+        }
+    }
+
+    /// Get the next position to look at, `None` if the scan is over
+    ///
+    /// The same position is returned until it is [`record`](Self::record)ed
+    #[allow(clippy::should_implement_trait)]
+    pub const fn next(&mut self) -> Option<(usize, usize)> {
+        while !self.is_found && self.line_idx < LINES.len() {
+            let (row_step, col_step) = LINES[self.line_idx];
+            let distance = if self.is_backward {
+                -(self.distance as isize)
+            } else {
+                self.distance as isize
+            };
+
+            let row = self.row as isize + row_step * distance;
+            let col = self.col as isize + col_step * distance;
+
+            if row >= 0 && row < H as isize && col >= 0 && col < W as isize {
+                return Some((row as usize, col as usize));
+            }
+
+            self.end_direction();
+        }
+
+        None
+    }
+
+    /// Tell if the position returned by [`next`](Self::next) holds a piece
+    pub const fn record(&mut self, is_piece: bool) {
+        if !is_piece {
+            self.end_direction();
+            return;
+        }
+
+        self.count += 1;
+        self.distance += 1;
+
+        if self.count >= self.k {
+            self.is_found = true;
+        }
+    }
+
+    /// Check if `k` or more pieces in a row were found
+    pub const fn is_found(&self) -> bool {
+        self.is_found
+    }
+
+    const fn end_direction(&mut self) {
+        self.distance = 1;
+
+        // a line is scanned forward then backward from the position, so its count carries over between the two
+        if self.is_backward {
+            self.line_idx += 1;
+            self.is_backward = false;
+            self.count = 1;
+        } else {
+            self.is_backward = true;
+        }
+    }
+}
+
+/// Check if there are `k` or more pieces in a row through a position on a grid of `H` rows and `W` columns, with the position itself counted as a piece
+///
+/// `is_piece` is called with positions in bounds, see [`KInARow`]
+///
+/// Panic if the position is out of bounds
+pub fn k_in_a_row<const H: usize, const W: usize>(
+    row: usize,
+    col: usize,
+    k: usize,
+    mut is_piece: impl FnMut(usize, usize) -> bool,
+) -> bool {
+    let mut scan = KInARow::<H, W>::new(row, col, k);
+
+    while let Some((row, col)) = scan.next() {
+        scan.record(is_piece(row, col));
+    }
+
+    scan.is_found()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::*;
+
+    #[test]
+    fn test() {
+        // the two sides of a 3 by 3 Hex board, joined by the elements 9 and 10
+        let mut groups = UnionFind::<11>::new();
+        let stones = [(0, 1), (1, 1), (2, 0)];
+
+        for &(row, col) in &stones {
+            let idx = row * 3 + col;
+
+            match row {
+                0 => groups.union(idx, 9),
+                2 => groups.union(idx, 10),
+                _ => false,
+            };
+
+            for (row_step, col_step) in HEXAGONAL {
+                let neighbor = (row as isize + row_step, col as isize + col_step);
+
+                if stones.contains(&(neighbor.0 as usize, neighbor.1 as usize)) {
+                    groups.union(idx, neighbor.0 as usize * 3 + neighbor.1 as usize);
+                }
+            }
+        }
+
+        assert!(groups.is_connected(9, 10));
+        assert_eq!(groups.size(9), 5);
+        assert!(!groups.union(1, 6));
+        assert!(!groups.is_connected(0, 9));
+
+        // a maze with a single gap in the middle row
+        let blocked = [
+            [false, false, false],
+            [true, true, false],
+            [false, false, false],
+        ];
+        let bfs = Bfs::<3, 3>::new((0, 0), &ORTHOGONAL, |_, to| !blocked[to.0][to.1]);
+
+        assert_eq!(bfs.distance(2, 0), Some(6));
+        assert_eq!(bfs.distance(1, 0), None);
+        assert!(bfs
+            .path_from(2, 0)
+            .eq([(2, 0), (2, 1), (2, 2), (1, 2), (0, 2), (0, 1), (0, 0)]));
+        assert_eq!(bfs.path_from(1, 1).count(), 0);
+
+        // diagonal steps cut through the corner
+        let bfs = Bfs::<3, 3>::new((0, 0), &ALL, |_, _| true);
+        assert_eq!(bfs.distance(2, 2), Some(2));
+
+        // lines through the middle of a row, with a gap at either end
+        let board = [[false, true, true, true, false, true]];
+        let is_piece = |row: usize, col: usize| board[row][col];
+
+        assert!(k_in_a_row::<1, 6>(0, 2, 3, is_piece));
+        assert!(!k_in_a_row::<1, 6>(0, 2, 4, is_piece));
+        assert!(k_in_a_row::<1, 6>(0, 4, 5, is_piece));
+        assert!(!k_in_a_row::<1, 6>(0, 4, 6, is_piece));
+        assert!(k_in_a_row::<1, 6>(0, 0, 1, |_, _| false));
+
+        // both diagonals and the column, cut off by the edges
+        let board = [
+            [true, false, true, true],
+            [false, true, true, false],
+            [true, true, true, false],
+            [false, false, true, false],
+        ];
+        let is_piece = |row: usize, col: usize| board[row][col];
+
+        assert!(k_in_a_row::<4, 4>(1, 1, 3, is_piece));
+        assert!(!k_in_a_row::<4, 4>(1, 1, 4, is_piece));
+        assert!(k_in_a_row::<4, 4>(0, 3, 3, is_piece));
+        assert!(!k_in_a_row::<4, 4>(0, 3, 4, is_piece));
+        assert!(k_in_a_row::<4, 4>(3, 2, 4, is_piece));
+    }
+}